@@ -0,0 +1,10 @@
+// Used for testing breakpoint sites. The tracee stops on the first `int3`,
+// after which breakpoints are set on the following `nop` instructions.
+
+use std::arch::asm;
+
+fn main() {
+    unsafe {
+        asm!("int3", "nop", "nop", "nop", "int3", "mov r12, 1", out("r12") _);
+    }
+}
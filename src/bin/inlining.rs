@@ -0,0 +1,20 @@
+// Used for testing the debugging information of inlined functions. Functions
+// marked `#[inline(always)]` are inlined even without optimizations, so `main`
+// holds the code of `add_two`, which itself holds that of `add_one` twice.
+
+use std::hint::black_box;
+
+#[inline(always)]
+fn add_one(x: u64) -> u64 {
+    black_box(x) + 1
+}
+
+#[inline(always)]
+fn add_two(x: u64) -> u64 {
+    add_one(add_one(x))
+}
+
+fn main() {
+    let value = add_two(black_box(40));
+    black_box(value);
+}
@@ -0,0 +1,23 @@
+// Used for testing register reads. The tracee stops on each `int3` after
+// populating a register with a known value.
+
+use std::arch::asm;
+
+fn main() {
+    unsafe {
+        asm!(
+            "mov r13, 0xcafecafe",
+            "int3",
+            "mov r13b, 42",
+            "int3",
+            "mov rax, 0xba5eba11",
+            "movq xmm0, rax",
+            "int3",
+            "mov rax, 0x1122334455667788",
+            "int3",
+            out("rax") _,
+            out("r13") _,
+            out("xmm0") _,
+        );
+    }
+}
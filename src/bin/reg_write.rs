@@ -0,0 +1,17 @@
+// Used for testing register writes. The tracee stops on `int3`, after which the
+// debugger writes `r13` and `xmm0`, whose sum is returned as the exit status.
+
+use std::arch::asm;
+
+fn main() {
+    unsafe {
+        asm!(
+            "int3",
+            "movq rdi, xmm0",
+            "add rdi, r13",
+            "mov eax, 60",
+            "syscall",
+            options(noreturn)
+        );
+    }
+}
@@ -0,0 +1,10 @@
+// Used for testing x87 register reads. The tracee stops on `int3` after
+// pushing 1.0 and pi onto the x87 register stack, leaving `TOP` at 6.
+
+use std::arch::asm;
+
+fn main() {
+    unsafe {
+        asm!("fld1", "fldpi", "int3", "fstp st(0)", "fstp st(0)");
+    }
+}
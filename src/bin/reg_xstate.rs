@@ -0,0 +1,29 @@
+// Used for testing reads and writes of the AVX-512 extended state. The tracee
+// populates `zmm1` and `k1` then stops on `int3`, after which the debugger writes
+// `ymm2` and `k2`, whose (upper lane, low qword) sum is returned as the exit status.
+
+use std::arch::asm;
+
+fn main() {
+    if !is_x86_feature_detected!("avx512f") {
+        return;
+    }
+
+    unsafe {
+        asm!(
+            "mov eax, 0x11111111",
+            "vmovd xmm1, eax",
+            "vpbroadcastd zmm1, xmm1",
+            "mov eax, 0xf0f0",
+            "kmovw k1, eax",
+            "int3",
+            "vextractf128 xmm3, ymm2, 1",
+            "vmovq rdi, xmm3",
+            "kmovw eax, k2",
+            "add rdi, rax",
+            "mov eax, 60",
+            "syscall",
+            options(noreturn)
+        );
+    }
+}
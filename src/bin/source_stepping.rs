@@ -0,0 +1,32 @@
+// Used for testing source-level stepping. The tracee stops on `int3` at the
+// start of `main`, then calls functions returning integer and floating-point
+// values, one of which drops a heap allocation and recurses.
+
+use std::arch::asm;
+use std::hint::black_box;
+
+fn square(x: u64) -> u64 {
+    let result = x * x;
+    black_box(result)
+}
+
+fn half(x: f64) -> f64 {
+    let result = x / 2.0;
+    black_box(result)
+}
+
+fn countdown(n: u64) -> u64 {
+    let values = black_box(vec![n; 4]);
+    if n == 0 {
+        return values.len() as u64;
+    }
+    countdown(n - 1) + values[0]
+}
+
+fn main() {
+    unsafe { asm!("int3") };
+    let squared = square(6);
+    let halved = half(squared as f64);
+    let total = countdown(3);
+    black_box((squared, halved, total));
+}
@@ -0,0 +1,31 @@
+// Used for testing instruction stepping. The tracee stops on `int3`, just
+// before calling a function with a standard frame pointer prologue.
+
+use std::arch::{asm, global_asm};
+
+global_asm!(
+    ".globl stepping_callee",
+    "stepping_callee:",
+    "push rbp",
+    "mov rbp, rsp",
+    "nop",
+    "pop rbp",
+    "ret",
+);
+
+unsafe extern "C" {
+    fn stepping_callee();
+}
+
+fn main() {
+    unsafe {
+        asm!(
+            "int3",
+            "call {callee}",
+            "nop",
+            "nop",
+            callee = sym stepping_callee,
+            clobber_abi("C"),
+        );
+    }
+}
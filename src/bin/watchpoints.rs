@@ -0,0 +1,15 @@
+// Used for testing watchpoints. The tracee stops on `int3` with the address of
+// `VALUE` in `rdi`, then writes to it.
+
+use std::arch::asm;
+
+static mut VALUE: u64 = 0;
+
+fn main() {
+    let ptr = &raw mut VALUE;
+
+    unsafe {
+        asm!("int3", in("rdi") ptr);
+        ptr.write_volatile(42);
+    }
+}
@@ -0,0 +1,82 @@
+use std::fs;
+
+use crate::Result;
+
+use libc::{AT_NULL, pid_t};
+
+/// Auxiliary vector of a process, passed by the kernel to the program when it
+/// is executed (e.g., the address of its entry point, `AT_ENTRY`).
+#[derive(Debug, Clone)]
+pub struct AuxVector {
+    /// Entries of the vector, as pairs of types (e.g., [`libc::AT_PHDR`]) and
+    /// values.
+    entries: Vec<(u64, u64)>,
+}
+
+impl AuxVector {
+    /// Read the auxiliary vector of the process with the given `pid` from
+    /// `/proc/<pid>/auxv`.
+    pub fn read(pid: pid_t) -> Result<Self> {
+        let path = format!("/proc/{pid}/auxv");
+        let data = fs::read(&path).map_err(|err| format!("failed to read {path}: {err}"))?;
+
+        Ok(Self::parse(&data))
+    }
+
+    /// Parse the raw auxiliary vector `data`, consisting of pairs of native
+    /// words terminated by an `AT_NULL` entry.
+    pub fn parse(data: &[u8]) -> Self {
+        let entries = data
+            .chunks_exact(16)
+            .map(|entry| {
+                let (kind, value) = entry.split_at(8);
+
+                (
+                    u64::from_ne_bytes(kind.try_into().expect("entry is 16 bytes")),
+                    u64::from_ne_bytes(value.try_into().expect("entry is 16 bytes")),
+                )
+            })
+            .take_while(|&(kind, _)| kind != AT_NULL)
+            .collect();
+
+        Self { entries }
+    }
+
+    /// Return the value of the entry with the given `kind` (e.g.,
+    /// [`libc::AT_ENTRY`]).
+    pub fn get(&self, kind: u64) -> Option<u64> {
+        self.entries
+            .iter()
+            .find(|&&(entry, _)| entry == kind)
+            .map(|&(_, value)| value)
+    }
+
+    /// Return the entries of the vector, as pairs of types and values.
+    pub fn entries(&self) -> &[(u64, u64)] {
+        &self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use libc::{AT_ENTRY, AT_PAGESZ};
+
+    #[test]
+    fn aux_vector_parsing() {
+        let data: Vec<u8> = [AT_PAGESZ, 4096, AT_ENTRY, 0x1040, AT_NULL, 0, AT_ENTRY, 1]
+            .iter()
+            .flat_map(|word| word.to_ne_bytes())
+            .collect();
+
+        let auxv = AuxVector::parse(&data);
+        assert_eq!(auxv.entries().len(), 2);
+        assert_eq!(auxv.get(AT_ENTRY), Some(0x1040));
+        assert_eq!(auxv.get(AT_PAGESZ), Some(4096));
+
+        let auxv = AuxVector::read(unsafe { libc::getpid() }).unwrap();
+        assert_eq!(auxv.get(AT_PAGESZ), Some(4096));
+        assert!(auxv.get(AT_ENTRY).is_some());
+    }
+}
@@ -0,0 +1,145 @@
+use super::hardware::{clear_hardware_stoppoint, set_hardware_stoppoint};
+use super::memory::{peek_data, poke_data};
+use super::{Registers, Stoppoint, StoppointMode, Stoppoints};
+use crate::Result;
+use crate::elf::VirtAddr;
+
+use libc::pid_t;
+
+/// Opcode of the `int3` instruction, which raises `SIGTRAP` when executed.
+const INT3: u8 = 0xcc;
+
+/// Location within the tracee at which execution halts. Software sites replace
+/// the first byte of the instruction at `address` with `int3`, while hardware
+/// sites occupy one of the debug register slots.
+#[derive(Debug)]
+pub struct BreakpointSite {
+    /// Identifier of the breakpoint site, unique within a [`BreakpointSites`].
+    id: usize,
+    /// Process ID of the tracee the breakpoint site belongs to.
+    pid: pid_t,
+    /// Virtual address of the breakpoint site.
+    address: VirtAddr,
+    /// Indicates whether the breakpoint site is currently installed.
+    is_enabled: bool,
+    /// Indicates whether the breakpoint site uses a debug register.
+    is_hardware: bool,
+    /// Debug register slot of an enabled hardware breakpoint site.
+    hardware_slot: Option<usize>,
+    /// Original byte replaced by `int3`, valid while an enabled software site.
+    saved_data: u8,
+}
+
+/// Collection of the [`BreakpointSite`]s of a tracee.
+pub type BreakpointSites = Stoppoints<BreakpointSite>;
+
+impl BreakpointSite {
+    /// Creates a new, disabled [`BreakpointSite`].
+    pub(crate) fn new(id: usize, pid: pid_t, address: VirtAddr, is_hardware: bool) -> Self {
+        Self {
+            id,
+            pid,
+            address,
+            is_enabled: false,
+            is_hardware,
+            hardware_slot: None,
+            saved_data: 0,
+        }
+    }
+
+    /// Return `true` if the given [`BreakpointSite`] uses a debug register.
+    pub fn is_hardware(&self) -> bool {
+        self.is_hardware
+    }
+
+    /// Return the debug register slot of an enabled hardware [`BreakpointSite`].
+    pub fn hardware_slot(&self) -> Option<usize> {
+        self.hardware_slot
+    }
+
+    /// Return the original byte replaced by `int3` in an enabled software
+    /// [`BreakpointSite`].
+    pub(crate) fn saved_data(&self) -> u8 {
+        self.saved_data
+    }
+
+    /// Install the breakpoint site, either by patching `int3` into the tracee
+    /// (saving the original byte) or by allocating a debug register slot.
+    pub(crate) fn enable(&mut self, regs: &mut Registers) -> Result<()> {
+        if self.is_enabled {
+            return Ok(());
+        }
+
+        if self.is_hardware {
+            self.hardware_slot = Some(set_hardware_stoppoint(
+                regs,
+                self.address.0,
+                StoppointMode::Execute,
+                1,
+            )?);
+        } else {
+            let word = peek_data(self.pid, self.address.0)?;
+
+            self.saved_data = (word & 0xff) as u8;
+            poke_data(self.pid, self.address.0, (word & !0xff) | INT3 as u64)?;
+        }
+
+        self.is_enabled = true;
+
+        Ok(())
+    }
+
+    /// Uninstall the breakpoint site, restoring the original byte of the tracee
+    /// or freeing its debug register slot.
+    pub(crate) fn disable(&mut self, regs: &mut Registers) -> Result<()> {
+        if !self.is_enabled {
+            return Ok(());
+        }
+
+        if let Some(slot) = self.hardware_slot.take() {
+            clear_hardware_stoppoint(regs, slot)?;
+        } else {
+            let word = peek_data(self.pid, self.address.0)?;
+            poke_data(
+                self.pid,
+                self.address.0,
+                (word & !0xff) | self.saved_data as u64,
+            )?;
+        }
+
+        self.is_enabled = false;
+
+        Ok(())
+    }
+}
+
+impl Stoppoint for BreakpointSite {
+    const KIND: &'static str = "breakpoint";
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn address(&self) -> VirtAddr {
+        self.address
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.is_enabled
+    }
+}
+
+impl BreakpointSites {
+    /// Return `true` if an enabled software [`BreakpointSite`] (i.e., `int3`)
+    /// exists at `address`.
+    pub fn software_enabled_at(&self, address: VirtAddr) -> bool {
+        self.get_by_address(address)
+            .is_some_and(|site| site.is_enabled && !site.is_hardware)
+    }
+
+    /// Return the enabled hardware [`BreakpointSite`] using the given debug
+    /// register `slot`.
+    pub(crate) fn get_by_slot(&self, slot: usize) -> Option<&BreakpointSite> {
+        self.iter().find(|site| site.hardware_slot == Some(slot))
+    }
+}
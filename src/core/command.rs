@@ -1,19 +1,1438 @@
-use crate::Result;
-use crate::core::Process;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::{fs, mem};
+
+use crate::core::{
+    F80, FormatLetter, LaneFormat, Listing, MemoryFormat, MemoryMap, PartialRead, Process,
+    ProcessState, Regex, RegisterFormat, RegisterInfo, RegisterType, RegisterValue, StopReason,
+    Stoppoint, StoppointMode, TrapKind, escape, hexdump,
+};
+use crate::disasm::{Instruction, MAX_INSTRUCTION_LEN, Syntax};
+use crate::dwarf::Scope;
+use crate::elf::{Elf, VirtAddr};
+use crate::utils::log_err;
+use crate::{Error, Result};
 
 /// Process an input command for a given [`Process`].
 pub fn handle_command(proc: &mut Process, input: &str) -> Result<()> {
-    let mut parts = input.split(' ');
+    let mut parts = input.split_whitespace();
 
     let command = parts.next().unwrap_or("");
+    let args: Vec<&str> = parts.collect();
 
     if "continue".starts_with(command) {
         proc.resume()?;
         let reason = proc.wait_on_signal()?;
-        reason.log_stop_reason(proc);
+        print_stop(proc, &reason);
+    } else if command == "syscall" {
+        proc.resume_syscall()?;
+        let reason = proc.wait_on_signal()?;
+        print_stop(proc, &reason);
+    } else if matches!(command, "stepi" | "si") {
+        let count = match args.as_slice() {
+            [] => 1,
+            [count] => count
+                .parse::<usize>()
+                .map_err(|_| format!("invalid instruction count '{count}'"))?,
+            _ => return Err("usage: stepi [count]".into()),
+        };
+
+        let mut reason = proc.step_instruction()?;
+
+        // Stop early if anything other than the step halted the tracee.
+        for _ in 1..count {
+            if reason.trap != Some(TrapKind::SingleStep) {
+                break;
+            }
+            reason = proc.step_instruction()?;
+        }
+
+        print_stop(proc, &reason);
+    } else if matches!(command, "nexti" | "ni") {
+        let reason = proc.step_over()?;
+        print_stop(proc, &reason);
+    } else if "step".starts_with(command) {
+        let reason = proc.step_line()?;
+        print_stop(proc, &reason);
+    } else if "next".starts_with(command) {
+        let reason = proc.next_line()?;
+        print_stop(proc, &reason);
+    } else if "finish".starts_with(command) {
+        let reason = proc.finish()?;
+        print_stop(proc, &reason);
+
+        // The function only returned if nothing else stopped the tracee.
+        if reason.trap == Some(TrapKind::SingleStep) {
+            print_return_value(proc)?;
+        }
+    } else if "until".starts_with(command) {
+        let [address] = args.as_slice() else {
+            return Err("usage: until <address>".into());
+        };
+
+        let address = parse_address(proc, address)?;
+        let reason = proc.run_until(address)?;
+        print_stop(proc, &reason);
+    } else if "register".starts_with(command) {
+        handle_register_command(proc, &args)?;
+    } else if "break".starts_with(command) {
+        handle_break_command(proc, &args)?;
+    } else if command == "rbreak" {
+        handle_rbreak_command(proc, &args)?;
+    } else if "watchpoint".starts_with(command) {
+        handle_watchpoint_command(proc, &args)?;
+    } else if "info".starts_with(command) {
+        handle_info_command(proc, &args)?;
+    } else if "memory".starts_with(command) {
+        handle_memory_command(proc, &args)?;
+    } else if "disassemble".starts_with(command) {
+        handle_disassemble_command(proc, &args)?;
+    } else if "list".starts_with(command) {
+        handle_list_command(proc, &args)?;
+    } else if "directory".starts_with(command) {
+        handle_directory_command(proc, &args)?;
+    } else if command == "set" {
+        handle_set_command(proc, &args)?;
+    } else if let Some(spec) = command.strip_prefix("x")
+        && (spec.is_empty() || spec.starts_with('/'))
+    {
+        // GDB-style alias for `memory read` (e.g., `x/4xg $rsp`).
+        read_memory(proc, spec.strip_prefix('/'), &args)?;
     } else {
         return Err(format!("unrecognized command '{command}'").into());
     }
 
     Ok(())
 }
+
+/// Process a `register` sub-command for a given [`Process`].
+fn handle_register_command(proc: &mut Process, args: &[&str]) -> Result<()> {
+    const USAGE: &str = "usage: register read [all | <name>[/<lane>] | <name>.<lane>[<index>]] \
+                         | register write <name>[.<lane>[<index>]] <value> \
+                         | register set <name> <flag> | register clear <name> <flag> \
+                         | register diff";
+
+    match args {
+        [sub] if "read".starts_with(sub) => {
+            print_registers(proc, |info| info.reg_type == RegisterType::GeneralPurpose);
+        }
+        [sub, "all"] if "read".starts_with(sub) => {
+            print_registers(proc, |_| true);
+        }
+        [sub, spec] if "read".starts_with(sub) && spec.contains('.') => {
+            let (info, format, index) = parse_lane_spec(proc, spec)?;
+
+            let view = proc
+                .registers()
+                .read(info)
+                .lanes(format)
+                .ok_or_else(|| format!("register '{}' is not a vector register", info.name))?;
+
+            let lane = view
+                .lanes
+                .get(index)
+                .ok_or_else(|| format!("lane index {index} is out of range for {}", view.name()))?;
+
+            println!("{spec}:\t{lane}");
+        }
+        [sub, spec] if "read".starts_with(sub) => {
+            let (name, format) = match spec.split_once('/') {
+                Some((name, format)) => (name, Some(lane_format(format)?)),
+                None => (*spec, None),
+            };
+
+            let info = available_register_info(proc, name)?;
+            let value = proc.registers().read(info);
+
+            match format {
+                Some(format) => {
+                    let view = value.lanes(format).ok_or_else(|| {
+                        format!("register '{}' is not a vector register", info.name)
+                    })?;
+
+                    println!("{}:\t{} {view}", info.name, view.name());
+                }
+                None => {
+                    println!("{}:\t{}", info.name, format_register(proc, info));
+
+                    let views: Vec<_> = LaneFormat::ALL
+                        .iter()
+                        .filter_map(|format| value.lanes(*format))
+                        .collect();
+
+                    let width = views
+                        .iter()
+                        .map(|view| view.name().len())
+                        .max()
+                        .unwrap_or(0);
+
+                    for view in views {
+                        println!("  {:<width$}  {view}", view.name());
+                    }
+                }
+            }
+        }
+        [sub, spec, value] if "write".starts_with(sub) && spec.contains('.') => {
+            let (info, format, index) = parse_lane_spec(proc, spec)?;
+            let lane = parse_lane(format, value)?;
+
+            let value = proc.registers().read(info).with_lane(format, index, lane)?;
+            proc.registers_mut().write(info, value)?;
+        }
+        [sub, name, value] if "write".starts_with(sub) => {
+            let info = register_info(name)?;
+            let value = parse_register(info, value)?;
+
+            proc.registers_mut().write(info, value)?;
+        }
+        [sub] if "diff".starts_with(sub) => {
+            let delta = proc.register_delta();
+
+            if delta.is_empty() {
+                println!("no registers changed since the previous stop");
+                return Ok(());
+            }
+
+            let width = delta
+                .changes()
+                .iter()
+                .map(|change| change.info.name.len())
+                .max()
+                .unwrap_or(0);
+
+            for change in delta.changes() {
+                println!("  {:<width$}  {change}", change.info.name);
+            }
+        }
+        [sub, name, flag] if "set".starts_with(sub) => {
+            let info = register_info(name)?;
+            proc.registers_mut().set_flag(info, flag, true)?;
+        }
+        [sub, name, flag] if "clear".starts_with(sub) => {
+            let info = register_info(name)?;
+            proc.registers_mut().set_flag(info, flag, false)?;
+        }
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
+
+/// Process a `break` sub-command for a given [`Process`].
+fn handle_break_command(proc: &mut Process, args: &[&str]) -> Result<()> {
+    const USAGE: &str = "usage: break set <address>|<file>:<line> [-h] | break list \
+                         | break enable <id> | break disable <id> | break delete <id>";
+
+    match args {
+        [sub, address, flags @ ..] if "set".starts_with(sub) => {
+            let hardware = match flags {
+                [] => false,
+                ["-h"] => true,
+                _ => return Err(USAGE.into()),
+            };
+
+            // Source locations are given as `<file>:<line>` (e.g.,
+            // `src/main.rs:42`), and may have code at several addresses
+            // (e.g., a line of a generic or inlined function).
+            let addresses = match parse_source_line(address) {
+                Some((path, line)) => {
+                    let addresses = proc.find_lines(Path::new(path), line);
+
+                    if addresses.is_empty() {
+                        return Err(format!("no code found for '{address}'").into());
+                    }
+
+                    addresses
+                }
+                None => vec![parse_address(proc, address)?],
+            };
+
+            // Every address of a location is set, or none of them.
+            let mut ids = Vec::new();
+
+            for &address in &addresses {
+                match set_breakpoint(proc, address, hardware) {
+                    Ok(id) => ids.push(id),
+                    Err(err) => {
+                        for id in ids {
+                            proc.remove_breakpoint_site(id)?;
+                        }
+
+                        return Err(err);
+                    }
+                }
+            }
+
+            for (id, address) in ids.into_iter().zip(addresses) {
+                let source = match proc.source_location(address) {
+                    Some(source) => format!(" ({source})"),
+                    None => String::new(),
+                };
+
+                println!(
+                    "breakpoint {id} set at {}{source}",
+                    format_location(proc, address)
+                );
+            }
+        }
+        [sub] if "list".starts_with(sub) => {
+            if proc.breakpoint_sites().is_empty() {
+                println!("no breakpoints set");
+            }
+
+            for site in proc.breakpoint_sites().iter() {
+                println!(
+                    "{}: address = {}, {}{}",
+                    site.id(),
+                    format_location(proc, site.address()),
+                    enabled_str(site.is_enabled()),
+                    if site.is_hardware() { ", hardware" } else { "" }
+                );
+            }
+        }
+        [sub, id] if "enable".starts_with(sub) => proc.enable_breakpoint_site(parse_id(id)?)?,
+        [sub, id] if "disable".starts_with(sub) => proc.disable_breakpoint_site(parse_id(id)?)?,
+        [sub, id] if "delete".starts_with(sub) => proc.remove_breakpoint_site(parse_id(id)?)?,
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
+
+/// Parse a source location of the form `<file>:<line>` (e.g., `main.rs:42`),
+/// returning `None` for anything else (e.g., `main` or `std::process::exit`).
+fn parse_source_line(location: &str) -> Option<(&str, u64)> {
+    let (path, line) = location.rsplit_once(':')?;
+
+    if path.is_empty() || path.ends_with(':') || !line.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((path, line.parse().ok()?))
+}
+
+/// Create and enable a software (or `hardware`) breakpoint site at `address`,
+/// returning its identifier.
+fn set_breakpoint(proc: &mut Process, address: VirtAddr, hardware: bool) -> Result<usize> {
+    let id = proc.create_breakpoint_site(address, hardware)?;
+
+    // Discard the breakpoint site if it could not be installed.
+    if let Err(err) = proc.enable_breakpoint_site(id) {
+        proc.remove_breakpoint_site(id)?;
+        return Err(err);
+    }
+
+    Ok(id)
+}
+
+/// Process an `rbreak` command for a given [`Process`], setting a breakpoint
+/// on every function whose demangled name matches a regular expression.
+fn handle_rbreak_command(proc: &mut Process, args: &[&str]) -> Result<()> {
+    if args.is_empty() {
+        return Err("usage: rbreak <regex>".into());
+    }
+
+    // Demangled names may contain spaces (e.g., `<T as Trait>::f`).
+    let pattern = args.join(" ");
+    let regex = Regex::new(&pattern)?;
+
+    if proc.elf().is_none() {
+        return Err("no symbols are loaded for the executable".into());
+    }
+
+    let mut functions: Vec<(VirtAddr, String)> = proc
+        .objects()
+        .flat_map(|elf| {
+            elf.symbol_index()
+                .iter()
+                .filter(|symbol| symbol.is_function() && regex.is_match(&symbol.name))
+                .map(|symbol| (symbol.address.to_virt(elf), symbol.name.clone()))
+        })
+        .collect();
+
+    // Symbols of each object are sorted by address, with aliases adjacent.
+    functions.dedup_by_key(|(address, _)| *address);
+
+    if functions.is_empty() {
+        return Err(format!("no functions match '{pattern}'").into());
+    }
+
+    for (address, name) in functions {
+        if let Some(site) = proc.breakpoint_sites().get_by_address(address) {
+            println!("breakpoint {} already set at {name}", site.id());
+            continue;
+        }
+
+        match set_breakpoint(proc, address, false) {
+            Ok(id) => println!("breakpoint {id} set at {address:#018x} <{name}>"),
+            Err(err) => log_err("rdbg", format!("{name}: {err}")),
+        }
+    }
+
+    Ok(())
+}
+
+/// Process a `watchpoint` sub-command for a given [`Process`].
+fn handle_watchpoint_command(proc: &mut Process, args: &[&str]) -> Result<()> {
+    const USAGE: &str = "usage: watchpoint set <address> <write | rw | execute> <1 | 2 | 4 | 8> \
+                         | watchpoint list | watchpoint enable <id> | watchpoint disable <id> \
+                         | watchpoint delete <id>";
+
+    match args {
+        [sub, address, mode, size] if "set".starts_with(sub) => {
+            let address = parse_address(proc, address)?;
+            let mode = StoppointMode::mode_by_name(mode).ok_or(USAGE)?;
+            let size = size.parse::<usize>().map_err(|_| USAGE)?;
+
+            let id = proc.create_watchpoint(address, mode, size)?;
+
+            // Discard the watchpoint if it could not be installed.
+            if let Err(err) = proc.enable_watchpoint(id) {
+                proc.remove_watchpoint(id)?;
+                return Err(err);
+            }
+
+            println!("watchpoint {id} set at {}", format_location(proc, address));
+        }
+        [sub] if "list".starts_with(sub) => {
+            if proc.watchpoints().is_empty() {
+                println!("no watchpoints set");
+            }
+
+            for watchpoint in proc.watchpoints().iter() {
+                println!(
+                    "{}: address = {:#018x}, {}, mode = {}, size = {}",
+                    watchpoint.id(),
+                    watchpoint.address(),
+                    enabled_str(watchpoint.is_enabled()),
+                    watchpoint.mode(),
+                    watchpoint.size()
+                );
+            }
+        }
+        [sub, id] if "enable".starts_with(sub) => proc.enable_watchpoint(parse_id(id)?)?,
+        [sub, id] if "disable".starts_with(sub) => proc.disable_watchpoint(parse_id(id)?)?,
+        [sub, id] if "delete".starts_with(sub) => proc.remove_watchpoint(parse_id(id)?)?,
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
+
+/// Log the [`StopReason`] of the given [`Process`], followed by the source line
+/// and next instruction to execute if it is still stopped. The stop is recorded
+/// as the one later register changes are compared against.
+fn print_stop(proc: &mut Process, reason: &StopReason) {
+    proc.record_stop();
+    reason.log_stop_reason(proc);
+
+    // The next listing is centered on the new location.
+    proc.sources_mut().set_listing(None);
+
+    if proc.state() != ProcessState::Stopped {
+        return;
+    }
+
+    // Code inlined into the function is described by the innermost function.
+    if let Some((elf, scopes)) = proc.scopes_at(proc.pc())
+        && let Some(scope) = scopes.last().filter(|scope| scope.is_inlined())
+    {
+        let call_site = match call_site_location(elf, scope) {
+            Some(location) => format!(", called from {location}"),
+            None => String::new(),
+        };
+
+        println!(
+            "in inlined {}{call_site}",
+            scope.name.as_deref().unwrap_or("??")
+        );
+    }
+
+    // Sources are often unavailable (e.g., for libraries), so a missing one
+    // is not reported.
+    if let Ok((path, line)) = stop_line(proc) {
+        let _ = print_source(proc, &path, line..=line);
+    }
+
+    if let Err(err) = print_instructions(proc, proc.pc(), 1, Syntax::Intel) {
+        log_err("rdbg", err);
+    }
+}
+
+/// Display the registers which hold the return value of a function that just
+/// returned: `rax` for integers and pointers, and `xmm0` for floating-point
+/// values. The type of the value is unknown, so both are shown.
+fn print_return_value(proc: &Process) -> Result<()> {
+    let registers = proc.registers();
+
+    let rax = registers
+        .read(register_info("rax")?)
+        .to_u64()
+        .unwrap_or_default();
+    let xmm0 = registers.read(register_info("xmm0")?).to_bytes();
+    let double = f64::from_le_bytes(xmm0[..8].try_into()?);
+
+    println!(
+        "return value: rax = {rax:#x} ({}), xmm0 = {double:?}",
+        rax as i64
+    );
+
+    Ok(())
+}
+
+/// Process a `disassemble` command for a given [`Process`], which displays
+/// instructions starting at the given address, or the program counter.
+fn handle_disassemble_command(proc: &mut Process, args: &[&str]) -> Result<()> {
+    const USAGE: &str = "usage: disassemble [address] [-c <count>] [-s intel|att]";
+
+    let mut address = None;
+    let mut count = 8;
+    let mut syntax = Syntax::Intel;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match *arg {
+            "-c" => {
+                let arg = args.next().ok_or(USAGE)?;
+                count = arg
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid instruction count '{arg}'"))?;
+            }
+            "-s" => {
+                syntax = match args.next() {
+                    Some(&"intel") => Syntax::Intel,
+                    Some(&"att") => Syntax::Att,
+                    Some(arg) => return Err(format!("unknown syntax '{arg}'").into()),
+                    None => return Err(USAGE.into()),
+                };
+            }
+            arg if address.is_none() && !arg.starts_with('-') => {
+                address = Some(parse_address(proc, arg)?);
+            }
+            _ => return Err(USAGE.into()),
+        }
+    }
+
+    print_instructions(proc, address.unwrap_or(proc.pc()), count, syntax)
+}
+
+/// Display `count` instructions decoded from the memory of the [`Process`]
+/// starting at `address`, marking the instruction at the program counter and
+/// naming the symbols of branch targets.
+fn print_instructions(
+    proc: &Process,
+    address: VirtAddr,
+    count: usize,
+    syntax: Syntax,
+) -> Result<()> {
+    let symbolize = |address| proc.symbolize(VirtAddr(address));
+    let mut address = address;
+
+    for _ in 0..count {
+        let instruction = proc.decode_instruction(address)?;
+
+        let marker = if address == proc.pc() { "=>" } else { "  " };
+
+        println!(
+            "{marker} {}: {:<21} {}",
+            format_location(proc, address),
+            format_bytes(&instruction),
+            instruction.format(syntax, &symbolize)
+        );
+
+        address = VirtAddr(instruction.next_address());
+    }
+
+    Ok(())
+}
+
+/// Format the encoding of `instruction` as hexadecimal bytes.
+fn format_bytes(instruction: &Instruction) -> String {
+    let bytes: Vec<String> = instruction
+        .bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+
+    bytes.join(" ")
+}
+
+/// Process a `list` command for a given [`Process`], which displays the source
+/// lines around a location, or the program counter. Without a location, the
+/// lines following the previous listing are displayed, or those preceding it
+/// given `-`.
+fn handle_list_command(proc: &mut Process, args: &[&str]) -> Result<()> {
+    const USAGE: &str = "usage: list [- | <line> | <file>:<line> | <function>]";
+    /// Number of lines displayed by a listing.
+    const COUNT: u64 = 10;
+
+    // Center `line` within the lines displayed.
+    let around = |line: u64| {
+        let first = line.saturating_sub(COUNT / 2).max(1);
+        first..=first + COUNT - 1
+    };
+
+    let (path, lines) = match args {
+        [] => match proc.sources().listing() {
+            Some(listing) => {
+                let first = listing.lines.end() + 1;
+                (listing.path.clone(), first..=first + COUNT - 1)
+            }
+            None => {
+                let (path, line) = stop_line(proc)?;
+                (path, around(line))
+            }
+        },
+        ["-"] => {
+            let listing = proc.sources().listing().ok_or("no lines listed yet")?;
+            let last = listing.lines.start().saturating_sub(1);
+
+            if last == 0 {
+                return Err(format!("already at the start of '{}'", listing.path.display()).into());
+            }
+
+            let first = last.saturating_sub(COUNT - 1).max(1);
+            (listing.path.clone(), first..=last)
+        }
+        [location] => match parse_source_location(proc, location)? {
+            (_, 0) => return Err(format!("no source line for '{location}'").into()),
+            (path, line) => (path, around(line)),
+        },
+        _ => return Err(USAGE.into()),
+    };
+
+    let lines = print_source(proc, &path, lines)?;
+    proc.sources_mut()
+        .set_listing(Some(Listing { path, lines }));
+
+    Ok(())
+}
+
+/// Resolve a `list` location of the form `<line>` (within the file being
+/// listed), `<file>:<line>` or `<function>` to a line of a source file, as
+/// named by the line number information.
+fn parse_source_location(proc: &Process, location: &str) -> Result<(PathBuf, u64)> {
+    if let Some((file, line)) = parse_source_line(location) {
+        let path = proc
+            .find_source_file(Path::new(file))
+            .ok_or_else(|| format!("no source file named '{file}'"))?;
+
+        return Ok((path.to_path_buf(), line));
+    }
+
+    if let Ok(line) = location.parse::<u64>() {
+        let path = match proc.sources().listing() {
+            Some(listing) => listing.path.clone(),
+            None => stop_line(proc)?.0,
+        };
+
+        return Ok((path, line));
+    }
+
+    let address = parse_address(proc, location)?;
+    let (elf, row) = proc
+        .line_at(address)
+        .ok_or_else(|| format!("no line information for '{location}'"))?;
+    let file = elf
+        .line_table()
+        .file(row)
+        .ok_or_else(|| format!("no source file for '{location}'"))?;
+
+    Ok((file.path.clone(), row.line))
+}
+
+/// Return the source file, as named by the line number information, and line
+/// of the program counter, unless it corresponds to no source line.
+fn stop_line(proc: &Process) -> Result<(PathBuf, u64)> {
+    let pc = proc.pc();
+
+    let (elf, row) = proc
+        .line_at(pc)
+        .ok_or_else(|| format!("no line information for {pc:#x}"))?;
+
+    if row.line == 0 {
+        return Err(format!("no source line for {pc:#x}").into());
+    }
+
+    let file = elf
+        .line_table()
+        .file(row)
+        .ok_or_else(|| format!("no source file for {pc:#x}"))?;
+
+    Ok((file.path.clone(), row.line))
+}
+
+/// Display the `lines` of the source file named `path` by the line number
+/// information which exist, marking the line of the program counter, and
+/// return the range displayed.
+fn print_source(
+    proc: &Process,
+    path: &Path,
+    lines: RangeInclusive<u64>,
+) -> Result<RangeInclusive<u64>> {
+    let source = proc.sources().read_lines(path, proc.comp_dir(path))?;
+    let count = source.len() as u64;
+
+    if *lines.start() == 0 {
+        return Err("line numbers start from 1".into());
+    }
+
+    if *lines.start() > count {
+        return Err(format!("'{}' only has {count} lines", path.display()).into());
+    }
+
+    let lines = *lines.start()..=(*lines.end()).min(count);
+
+    let current = match stop_line(proc) {
+        Ok((stop_path, line)) if stop_path == path => Some(line),
+        _ => None,
+    };
+
+    for line in lines.clone() {
+        let marker = if Some(line) == current { "=>" } else { "  " };
+        println!("{marker} {line:>4}  {}", source[line as usize - 1]);
+    }
+
+    Ok(lines)
+}
+
+/// Process a `directory` command for a given [`Process`], which adds
+/// directories searched for source files, or displays them.
+fn handle_directory_command(proc: &mut Process, args: &[&str]) -> Result<()> {
+    if args.is_empty() {
+        let directories = proc.sources().directories();
+
+        if directories.is_empty() {
+            println!("no source directories");
+        }
+
+        for directory in directories {
+            println!("{}", directory.display());
+        }
+
+        return Ok(());
+    }
+
+    // The first directory given is searched first.
+    for directory in args.iter().rev() {
+        let directory = fs::canonicalize(directory)
+            .map_err(|err| format!("invalid directory '{directory}': {err}"))?;
+
+        if !directory.is_dir() {
+            return Err(format!("'{}' is not a directory", directory.display()).into());
+        }
+
+        proc.sources_mut().add_directory(directory);
+    }
+
+    Ok(())
+}
+
+/// Process a `set` command for a given [`Process`], which changes a setting
+/// of the debugger.
+fn handle_set_command(proc: &mut Process, args: &[&str]) -> Result<()> {
+    const USAGE: &str = "usage: set substitute-path [<from> <to>]";
+
+    match args {
+        ["substitute-path"] => {
+            let substitutions = proc.sources().substitutions();
+
+            if substitutions.is_empty() {
+                println!("no source path substitutions");
+            }
+
+            for (from, to) in substitutions {
+                println!("'{}' -> '{}'", from.display(), to.display());
+            }
+        }
+        ["substitute-path", from, to] => {
+            proc.sources_mut()
+                .set_substitution(PathBuf::from(from), PathBuf::from(to));
+        }
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
+
+/// Process an `info` sub-command for a given [`Process`].
+fn handle_info_command(proc: &mut Process, args: &[&str]) -> Result<()> {
+    const USAGE: &str = "usage: info mappings [-s] | info mappings <address> \
+                         | info symbol <address> | info address <name> \
+                         | info function [address]";
+
+    match args {
+        [sub, address] if "symbol".starts_with(sub) => {
+            let address = parse_address(proc, address)?;
+
+            match (proc.symbolize(address), proc.object_containing(address)) {
+                (Some(symbol), Some((elf, _))) => {
+                    println!("{symbol} in {}", elf.path().display());
+                }
+                _ => println!("no symbol matches {address:#x}"),
+            }
+        }
+        [sub, name @ ..] if "address".starts_with(sub) && !name.is_empty() => {
+            let name = name.join(" ");
+            let symbols = proc.find_symbols(&name);
+
+            if symbols.is_empty() {
+                return Err(format!("no symbol '{name}' found").into());
+            }
+
+            for (address, elf, symbol) in symbols {
+                let kind = if symbol.is_function() {
+                    "a function"
+                } else {
+                    "data"
+                };
+
+                println!(
+                    "symbol '{}' is {kind} at {address:#018x} in {}",
+                    symbol.name,
+                    elf.path().display()
+                );
+            }
+        }
+        [sub, address @ ..] if "function".starts_with(sub) && address.len() <= 1 => {
+            let address = match address {
+                [address] => parse_address(proc, address)?,
+                _ => proc.pc(),
+            };
+
+            let scopes = match proc.scopes_at(address) {
+                Some((elf, scopes)) if !scopes.is_empty() => (elf, scopes),
+                _ => return Err(format!("no function information for {address:#x}").into()),
+            };
+
+            print_scopes(scopes.0, &scopes.1);
+        }
+        [sub, flags @ ..] if "mappings".starts_with(sub) => match flags {
+            [] => print_mappings(&proc.memory_map()?, false),
+            ["-s"] => print_mappings(&MemoryMap::read_smaps(proc.pid())?, true),
+            [address] => {
+                let address = parse_address(proc, address)?;
+                println!("{}", proc.memory_map()?.describe(address.0));
+            }
+            _ => return Err(USAGE.into()),
+        },
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
+
+/// Print the function containing an address, followed by the functions inlined
+/// into it which do, each with its address ranges and the location it was
+/// called from.
+fn print_scopes(elf: &Elf, scopes: &[Scope<'_>]) {
+    for (depth, scope) in scopes.iter().enumerate() {
+        let kind = if scope.is_inlined() {
+            "inlined"
+        } else {
+            "function"
+        };
+
+        let ranges: Vec<String> = scope
+            .ranges
+            .iter()
+            .map(|range| {
+                format!(
+                    "{:#018x}-{:#018x}",
+                    range.start.to_virt(elf).0,
+                    range.end.to_virt(elf).0
+                )
+            })
+            .collect();
+
+        let call_site = match call_site_location(elf, scope) {
+            Some(location) => format!(", called from {location}"),
+            None => String::new(),
+        };
+
+        println!(
+            "{:indent$}{kind} {} at {}{call_site}",
+            "",
+            scope.name.as_deref().unwrap_or("??"),
+            ranges.join(", "),
+            indent = depth * 2
+        );
+    }
+}
+
+/// Describe the location an inlined function was called from (e.g.,
+/// `src/main.rs:42:5`), omitting the column if it is unknown.
+fn call_site_location(elf: &Elf, scope: &Scope<'_>) -> Option<String> {
+    let call_site = scope.call_site()?;
+    let file = elf
+        .line_table()
+        .program_file(scope.unit.stmt_list?, call_site.file)?;
+
+    Some(match call_site.column {
+        0 => format!("{}:{}", file.path.display(), call_site.line),
+        column => format!("{}:{}:{column}", file.path.display(), call_site.line),
+    })
+}
+
+/// Print each region of the [`MemoryMap`], optionally including the resident
+/// (`Rss`) and proportional (`Pss`) memory usage read from `smaps`.
+fn print_mappings(map: &MemoryMap, usage: bool) {
+    let usage_header = if usage { "       rss       pss" } else { "" };
+
+    println!(
+        "{:<18} {:<18} perms {:<10} {:<10}{usage_header}  backing",
+        "start", "end", "offset", "size"
+    );
+
+    for region in map.regions() {
+        let usage = if usage {
+            format!(
+                " {:>6} kB {:>6} kB",
+                region.usage("Rss").unwrap_or_default(),
+                region.usage("Pss").unwrap_or_default()
+            )
+        } else {
+            String::new()
+        };
+
+        println!(
+            "{:#018x} {:#018x} {}  {:<#10x} {:<#10x}{usage}  {}",
+            region.start,
+            region.end,
+            region.permissions,
+            region.offset,
+            region.size(),
+            region.backing
+        );
+    }
+}
+
+/// Process a `memory` sub-command for a given [`Process`].
+fn handle_memory_command(proc: &mut Process, args: &[&str]) -> Result<()> {
+    const USAGE: &str = "usage: memory read[/<format>] <address> [count] \
+                         | memory write <address> <byte>... \
+                         | memory write/<format> <address> <value>";
+
+    let [sub, args @ ..] = args else {
+        return Err(USAGE.into());
+    };
+
+    // The format is attached to the sub-command (e.g., `read/4xg`).
+    let (sub, spec) = match sub.split_once('/') {
+        Some((sub, spec)) => (sub, Some(spec)),
+        None => (*sub, None),
+    };
+
+    if !sub.is_empty() && "read".starts_with(sub) {
+        read_memory(proc, spec, args)
+    } else if !sub.is_empty() && "write".starts_with(sub) {
+        write_memory(proc, spec, args)
+    } else {
+        Err(USAGE.into())
+    }
+}
+
+/// Display the memory of the [`Process`] at the address given by `args`,
+/// either as a hexdump of `count` bytes, or `count` units of the GDB `x`-style
+/// format given by `spec`.
+fn read_memory(proc: &Process, spec: Option<&str>, args: &[&str]) -> Result<()> {
+    const USAGE: &str = "usage: memory read[/<format>] <address> [count]";
+
+    let (address, count) = match args {
+        [address] => (address, None),
+        [address, count] => (
+            address,
+            Some(
+                count
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid count '{count}'"))?,
+            ),
+        ),
+        _ => return Err(USAGE.into()),
+    };
+
+    let address = parse_address(proc, address)?;
+
+    let Some(spec) = spec else {
+        let len = read_len(count.unwrap_or(64), 1)?;
+        let (data, err) = read_partial(proc, address, len);
+
+        for line in hexdump(address.0, &data) {
+            println!("{line}");
+        }
+
+        return err.map_or(Ok(()), Err);
+    };
+
+    let mut format = MemoryFormat::parse(spec)?;
+
+    if let Some(count) = count {
+        format.count = count;
+    }
+
+    // Strings and instructions are read one bounded unit at a time.
+    let len = match format.letter {
+        FormatLetter::String => read_len(format.count, MAX_STRING_LEN)?,
+        FormatLetter::Instruction => read_len(format.count, MAX_INSTRUCTION_LEN)?,
+        _ => read_len(format.count, format.size.bytes())?,
+    };
+
+    match format.letter {
+        FormatLetter::String => {
+            let mut address = address;
+
+            for _ in 0..format.count {
+                let (data, err) = read_partial(proc, address, MAX_STRING_LEN);
+
+                let len = data.iter().position(|&byte| byte == 0);
+                let string = &data[..len.unwrap_or(data.len())];
+
+                println!(
+                    "{address:#018x}: \"{}\"{}",
+                    escape(string),
+                    if len.is_none() { "..." } else { "" }
+                );
+
+                if let Some(err) = err {
+                    return Err(err);
+                }
+
+                match len {
+                    Some(len) => address += len as u64 + 1,
+                    None => address += data.len() as u64,
+                }
+            }
+
+            Ok(())
+        }
+        FormatLetter::Instruction => print_instructions(proc, address, format.count, Syntax::Intel),
+        _ => {
+            let (data, err) = read_partial(proc, address, len);
+
+            for line in format.format_units(address.0, &data) {
+                println!("{line}");
+            }
+
+            err.map_or(Ok(()), Err)
+        }
+    }
+}
+
+/// Write the values given by `args` into the memory of the [`Process`], either
+/// as individual bytes, or as a single value of the GDB `x`-style format given
+/// by `spec` (e.g., `g` for a 64-bit integer, or `fw` for an `f32`).
+fn write_memory(proc: &mut Process, spec: Option<&str>, args: &[&str]) -> Result<()> {
+    const USAGE: &str = "usage: memory write <address> <byte>... \
+                         | memory write/<format> <address> <value>";
+
+    let data = match (spec, args) {
+        (None, [_, bytes @ ..]) if !bytes.is_empty() => bytes
+            .iter()
+            .map(|byte| Ok(parse_sized_int(byte, 1, "byte")?.to_bytes()[0]))
+            .collect::<Result<Vec<u8>>>()?,
+        (Some(spec), [_, value]) => {
+            let format = MemoryFormat::parse(spec)?;
+            let size = format.size.bytes();
+
+            match format.letter {
+                FormatLetter::Float if size == 4 => {
+                    (parse_float(value)? as f32).to_le_bytes().to_vec()
+                }
+                FormatLetter::Float => parse_float(value)?.to_le_bytes().to_vec(),
+                FormatLetter::Hex | FormatLetter::Signed | FormatLetter::Unsigned => {
+                    parse_sized_int(value, size, &format!("{size}-byte value"))?.to_bytes()
+                }
+                _ => return Err(format!("cannot write values of format '{spec}'").into()),
+            }
+        }
+        _ => return Err(USAGE.into()),
+    };
+
+    let address = parse_address(proc, args[0])?;
+    proc.write_memory(address, &data)?;
+
+    println!("wrote {} bytes at {address:#018x}", data.len());
+
+    Ok(())
+}
+
+/// Maximum number of bytes read for a single string.
+const MAX_STRING_LEN: usize = 256;
+
+/// Maximum number of bytes read by a single `memory read`, like the default
+/// `max-value-size` of GDB.
+const MAX_READ_LEN: usize = 65536;
+
+/// Return the number of bytes in `count` units of `unit` bytes, ensuring it is
+/// at most [`MAX_READ_LEN`].
+fn read_len(count: usize, unit: usize) -> Result<usize> {
+    count
+        .checked_mul(unit)
+        .filter(|&len| len <= MAX_READ_LEN)
+        .ok_or_else(|| {
+            let units = match unit {
+                1 => format!("{count} bytes"),
+                _ => format!("{count} units of {unit} bytes"),
+            };
+
+            format!("cannot read {units}: at most {MAX_READ_LEN} bytes can be read at once").into()
+        })
+}
+
+/// Read `len` bytes from the memory of the [`Process`] at `address`, returning
+/// the readable bytes along with the error if only part of the range could be
+/// read.
+fn read_partial(proc: &Process, address: VirtAddr, len: usize) -> (Vec<u8>, Option<Error>) {
+    match proc.read_memory_without_traps(address, len) {
+        Ok(data) => (data, None),
+        Err(err) => match err.downcast::<PartialRead>() {
+            Ok(mut partial) => (mem::take(&mut partial.data), Some(partial)),
+            Err(err) => (Vec::new(), Some(err)),
+        },
+    }
+}
+
+/// Parse an address expression, consisting of integer literals, registers
+/// (e.g., `$rsp`), and symbols (e.g., `main`) joined by `+` or `-` (e.g.,
+/// `$rsp+0x10` or `main+4`).
+fn parse_address(proc: &Process, expr: &str) -> Result<VirtAddr> {
+    let mut address = 0u64;
+    let mut rest = expr;
+    let mut negate = false;
+
+    loop {
+        let end = find_operator(rest).unwrap_or(rest.len());
+        let term = rest[..end].trim();
+
+        let value = if let Some(name) = term.strip_prefix('$') {
+            let info = available_register_info(proc, name)?;
+
+            proc.registers()
+                .read(info)
+                .to_u64()
+                .ok_or_else(|| format!("register '{name}' is not an integer register"))?
+        } else if term.is_empty() {
+            return Err(format!("invalid address expression '{expr}'").into());
+        } else if !term.starts_with(|c: char| c.is_ascii_digit()) {
+            resolve_symbol(proc, term)?
+        } else {
+            let (int, signed) = parse_int(term)?;
+
+            if signed {
+                return Err(format!("invalid address expression '{expr}'").into());
+            }
+
+            int
+        };
+
+        address = if negate {
+            address.wrapping_sub(value)
+        } else {
+            address.wrapping_add(value)
+        };
+
+        if end == rest.len() {
+            return Ok(VirtAddr(address));
+        }
+
+        negate = rest[end..].starts_with('-');
+        rest = &rest[end + 1..];
+    }
+}
+
+/// Return the offset of the first `+` or `-` operator of an address
+/// expression. Operators within angle brackets, which appear in the names of
+/// generic functions (e.g., `f::<fn() -> u8>`), are skipped.
+fn find_operator(expr: &str) -> Option<usize> {
+    let mut depth = 0usize;
+
+    for (idx, c) in expr.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            '+' | '-' if depth == 0 => return Some(idx),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Resolve the symbol `name` to its address in memory, failing if it is
+/// unknown or ambiguous.
+fn resolve_symbol(proc: &Process, name: &str) -> Result<u64> {
+    let mut addresses: Vec<u64> = proc
+        .find_symbols(name)
+        .into_iter()
+        .map(|(address, _, _)| address.into())
+        .collect();
+    addresses.sort_unstable();
+    addresses.dedup();
+
+    match addresses.as_slice() {
+        [] => Err(format!("no symbol '{name}' found").into()),
+        [address] => Ok(*address),
+        _ => Err(format!(
+            "symbol '{name}' is ambiguous: it is defined at {} addresses",
+            addresses.len()
+        )
+        .into()),
+    }
+}
+
+/// Format `address`, followed by the symbol containing it if any (e.g.,
+/// `0x0000555555555139 <main+0x4>`).
+fn format_location(proc: &Process, address: VirtAddr) -> String {
+    match proc.symbolize(address) {
+        Some(symbol) => format!("{address:#018x} <{symbol}>"),
+        None => format!("{address:#018x}"),
+    }
+}
+
+fn enabled_str(enabled: bool) -> &'static str {
+    if enabled { "enabled" } else { "disabled" }
+}
+
+/// Parse a breakpoint or watchpoint identifier.
+fn parse_id(id: &str) -> Result<usize> {
+    id.parse::<usize>()
+        .map_err(|_| format!("invalid id '{id}'").into())
+}
+
+/// Look up the [`RegisterInfo`] for the register with the given `name`.
+fn register_info(name: &str) -> Result<&'static RegisterInfo> {
+    RegisterInfo::register_info_by_name(name)
+        .ok_or_else(|| format!("no such register '{name}'").into())
+}
+
+/// Look up the [`RegisterInfo`] for the register with the given `name`,
+/// ensuring it is supported on this system.
+fn available_register_info(proc: &Process, name: &str) -> Result<&'static RegisterInfo> {
+    let info = register_info(name)?;
+
+    if !proc.registers().is_available(info) {
+        return Err(format!("register '{name}' is not supported on this system").into());
+    }
+
+    Ok(info)
+}
+
+/// Look up the [`LaneFormat`] with the given `name`.
+fn lane_format(name: &str) -> Result<LaneFormat> {
+    LaneFormat::format_by_name(name).ok_or_else(|| {
+        let names: Vec<_> = LaneFormat::ALL.iter().map(|format| format.name()).collect();
+        format!(
+            "no such lane format '{name}' (expected {})",
+            names.join(", ")
+        )
+        .into()
+    })
+}
+
+/// Parse a lane specifier of a vector register (e.g., `xmm0.f32[2]`).
+fn parse_lane_spec(
+    proc: &Process,
+    spec: &str,
+) -> Result<(&'static RegisterInfo, LaneFormat, usize)> {
+    let invalid = || format!("invalid lane '{spec}': expected <name>.<lane>[<index>]");
+
+    let (name, lane) = spec.split_once('.').ok_or_else(invalid)?;
+    let (format, index) = lane
+        .strip_suffix(']')
+        .and_then(|lane| lane.split_once('['))
+        .ok_or_else(invalid)?;
+
+    let info = available_register_info(proc, name)?;
+
+    if info.format != RegisterFormat::Vector {
+        return Err(format!("register '{name}' is not a vector register").into());
+    }
+
+    let index = index.parse::<usize>().map_err(|_| invalid())?;
+
+    Ok((info, lane_format(format)?, index))
+}
+
+/// Print an aligned table of every register matching `filter`, grouped by
+/// [`RegisterType`].
+fn print_registers<F>(proc: &Process, filter: F)
+where
+    F: Fn(&RegisterInfo) -> bool,
+{
+    const GROUPS: &[(RegisterType, &str)] = &[
+        (RegisterType::GeneralPurpose, "General Purpose"),
+        (RegisterType::SubRegister, "Sub-Registers"),
+        (RegisterType::FloatingPoint, "Floating Point"),
+        (RegisterType::Debug, "Debug"),
+        (RegisterType::Extended, "Extended"),
+    ];
+
+    for (reg_type, title) in GROUPS {
+        let infos: Vec<&RegisterInfo> = RegisterInfo::all()
+            .iter()
+            .filter(|info| {
+                info.reg_type == *reg_type && proc.registers().is_available(info) && filter(info)
+            })
+            .collect();
+
+        if infos.is_empty() {
+            continue;
+        }
+
+        let width = infos.iter().map(|info| info.name.len()).max().unwrap_or(0);
+
+        println!("\x1b[1m{title}:\x1b[0m");
+
+        for info in infos {
+            println!("  {:<width$}  {}", info.name, format_register(proc, info));
+        }
+    }
+}
+
+/// Format the value of the register described by `info`, followed by its
+/// decoded representation for status and control registers. Values which
+/// changed since the previous stop are highlighted.
+fn format_register(proc: &Process, info: &'static RegisterInfo) -> String {
+    let value = proc.registers().read(info);
+
+    let value = if proc.register_change(info).is_some() {
+        format!("\x1b[1;33m{value}\x1b[0m")
+    } else {
+        value.to_string()
+    };
+
+    match proc.registers().decode(info) {
+        Some(decoded) => format!("{value}  {decoded}"),
+        None => value,
+    }
+}
+
+/// Parse the string `value` into a [`RegisterValue`] according to the
+/// [`RegisterFormat`] of the register described by `info`.
+fn parse_register(info: &RegisterInfo, value: &str) -> Result<RegisterValue> {
+    match info.format {
+        RegisterFormat::UInt => {
+            parse_sized_int(value, info.size, &format!("register '{}'", info.name))
+        }
+        RegisterFormat::DoubleFloat => Ok(RegisterValue::F64(parse_float(value)?)),
+        // Parsed directly into extended precision, without rounding to `f64`.
+        RegisterFormat::LongDouble => Ok(RegisterValue::LongDouble(value.parse::<F80>()?)),
+        RegisterFormat::Vector => {
+            let inner = value
+                .strip_prefix('[')
+                .and_then(|v| v.strip_suffix(']'))
+                .ok_or_else(|| {
+                    format!("invalid vector literal '{value}': expected [0x1,0x2,...]")
+                })?;
+
+            let bytes = inner
+                .split(',')
+                .map(|byte| {
+                    let byte = byte.trim();
+                    let (int, signed) = parse_int(byte)?;
+
+                    if signed || int > u8::MAX as u64 {
+                        return Err(
+                            format!("vector element '{byte}' does not fit in a byte").into()
+                        );
+                    }
+
+                    Ok(int as u8)
+                })
+                .collect::<Result<Vec<u8>>>()?;
+
+            if bytes.len() > info.size {
+                return Err(format!(
+                    "vector literal has {} elements, but register '{}' holds {} bytes",
+                    bytes.len(),
+                    info.name,
+                    info.size
+                )
+                .into());
+            }
+
+            let mut vector = [0u8; 64];
+            vector[..bytes.len()].copy_from_slice(&bytes);
+
+            Ok(match info.size {
+                8 => RegisterValue::Byte64(vector[..8].try_into()?),
+                16 => RegisterValue::Byte128(vector[..16].try_into()?),
+                32 => RegisterValue::Byte256(vector[..32].try_into()?),
+                _ => RegisterValue::Byte512(vector),
+            })
+        }
+    }
+}
+
+/// Parse the string `value` into a [`RegisterValue`] for a single lane of the
+/// given [`LaneFormat`].
+fn parse_lane(format: LaneFormat, value: &str) -> Result<RegisterValue> {
+    match format {
+        LaneFormat::F32 => value
+            .parse::<f32>()
+            .map(RegisterValue::F32)
+            .map_err(|err| format!("invalid floating-point literal '{value}': {err}").into()),
+        LaneFormat::F64 => Ok(RegisterValue::F64(parse_float(value)?)),
+        _ => parse_sized_int(value, format.size(), &format!("{format} lane")),
+    }
+}
+
+/// Parse an integer literal into a [`RegisterValue`] of `size` bytes, which is
+/// signed if the literal was negative. The `target` (e.g., `register 'rax'`)
+/// is described when the value does not fit.
+fn parse_sized_int(value: &str, size: usize, target: &str) -> Result<RegisterValue> {
+    let (int, signed) = parse_int(value)?;
+    let bits = size * 8;
+
+    // Negative values are accepted as long as their two's complement
+    // representation fits within the given size.
+    let fits = bits == 64
+        || if signed {
+            ((int as i64) >> (bits - 1)) == -1
+        } else {
+            (int >> bits) == 0
+        };
+
+    if !fits {
+        return Err(format!("value '{value}' does not fit in {bits}-bit {target}").into());
+    }
+
+    Ok(match (size, signed) {
+        (1, false) => RegisterValue::U8(int as u8),
+        (2, false) => RegisterValue::U16(int as u16),
+        (4, false) => RegisterValue::U32(int as u32),
+        (_, false) => RegisterValue::U64(int),
+        (1, true) => RegisterValue::I8(int as i8),
+        (2, true) => RegisterValue::I16(int as i16),
+        (4, true) => RegisterValue::I32(int as i32),
+        (_, true) => RegisterValue::I64(int as i64),
+    })
+}
+
+/// Parse a hexadecimal (`0x` prefixed) or decimal integer literal, returning
+/// its two's complement representation and whether it was negative.
+fn parse_int(value: &str) -> Result<(u64, bool)> {
+    let parsed = if let Some(hex) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        u64::from_str_radix(hex, 16).ok().map(|int| (int, false))
+    } else if let Some(neg) = value.strip_prefix('-') {
+        neg.parse::<u64>()
+            .ok()
+            .filter(|&int| int <= i64::MIN.unsigned_abs())
+            .map(|int| (int.wrapping_neg(), true))
+    } else {
+        value.parse::<u64>().ok().map(|int| (int, false))
+    };
+
+    parsed.ok_or_else(|| format!("invalid integer literal '{value}'").into())
+}
+
+/// Parse a floating-point literal.
+fn parse_float(value: &str) -> Result<f64> {
+    value
+        .parse::<f64>()
+        .map_err(|err| format!("invalid floating-point literal '{value}': {err}").into())
+}
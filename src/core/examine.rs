@@ -0,0 +1,333 @@
+use std::ascii;
+
+use crate::Result;
+
+/// Number of bytes shown on each line of a hexdump.
+const HEXDUMP_WIDTH: usize = 16;
+
+/// Letter selecting how each unit of memory is displayed, following the GDB
+/// `x` command.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum FormatLetter {
+    /// Hexadecimal integers (`x`).
+    Hex,
+    /// Signed decimal integers (`d`).
+    Signed,
+    /// Unsigned decimal integers (`u`).
+    Unsigned,
+    /// IEEE 754 floating-point values (`f`).
+    Float,
+    /// Characters, along with their numeric value (`c`).
+    Char,
+    /// NUL-terminated C strings (`s`).
+    String,
+    /// Machine instructions (`i`).
+    Instruction,
+}
+
+/// Size of each unit of memory, following the GDB `x` command.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum UnitSize {
+    /// Bytes (`b`).
+    Byte = 1,
+    /// Half-words, of two bytes (`h`).
+    HalfWord = 2,
+    /// Words, of four bytes (`w`).
+    Word = 4,
+    /// Giant words, of eight bytes (`g`).
+    Giant = 8,
+}
+
+impl UnitSize {
+    /// Return the size of the unit in bytes.
+    pub fn bytes(self) -> usize {
+        self as usize
+    }
+
+    /// Look up the [`UnitSize`] with the given `letter` (i.e., `b`, `h`, `w`,
+    /// or `g`).
+    pub fn size_by_letter(letter: char) -> Option<Self> {
+        match letter {
+            'b' => Some(Self::Byte),
+            'h' => Some(Self::HalfWord),
+            'w' => Some(Self::Word),
+            'g' => Some(Self::Giant),
+            _ => None,
+        }
+    }
+}
+
+/// Display format of a range of memory, given by a GDB `x`-style
+/// specification of a repeat count, followed by format and size letters in
+/// either order (e.g., `4xg`).
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct MemoryFormat {
+    /// Number of units (or strings, or instructions) to display.
+    pub count: usize,
+    /// How each unit is displayed.
+    pub letter: FormatLetter,
+    /// Size of each unit.
+    pub size: UnitSize,
+}
+
+impl MemoryFormat {
+    /// Parse a format specification such as `4xg`, `s`, or `2fw`. The count
+    /// defaults to one, the format to hexadecimal, and the size to words (or
+    /// giant words for floating-point values, and bytes for characters).
+    pub fn parse(spec: &str) -> Result<Self> {
+        let digits = spec.len() - spec.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+        let count = match &spec[..digits] {
+            "" => 1,
+            count => count
+                .parse::<usize>()
+                .map_err(|_| format!("invalid count in format '{spec}'"))?,
+        };
+
+        let mut letter = None;
+        let mut size = None;
+
+        for c in spec[digits..].chars() {
+            if let Some(unit) = UnitSize::size_by_letter(c) {
+                if size.replace(unit).is_some() {
+                    return Err(format!("multiple size letters in format '{spec}'").into());
+                }
+
+                continue;
+            }
+
+            let format = match c {
+                'x' => FormatLetter::Hex,
+                'd' => FormatLetter::Signed,
+                'u' => FormatLetter::Unsigned,
+                'f' => FormatLetter::Float,
+                'c' => FormatLetter::Char,
+                's' => FormatLetter::String,
+                'i' => FormatLetter::Instruction,
+                _ => return Err(format!("invalid format letter '{c}' in format '{spec}'").into()),
+            };
+
+            if letter.replace(format).is_some() {
+                return Err(format!("multiple format letters in format '{spec}'").into());
+            }
+        }
+
+        let letter = letter.unwrap_or(FormatLetter::Hex);
+
+        let size = match (letter, size) {
+            (FormatLetter::Char, _) => UnitSize::Byte,
+            (FormatLetter::Float, Some(UnitSize::Byte | UnitSize::HalfWord)) => {
+                return Err("floating-point values must be words or giant words".into());
+            }
+            (FormatLetter::Float, None) => UnitSize::Giant,
+            (_, size) => size.unwrap_or(UnitSize::Word),
+        };
+
+        if count == 0 {
+            return Err(format!("count in format '{spec}' must be non-zero").into());
+        }
+
+        Ok(Self {
+            count,
+            letter,
+            size,
+        })
+    }
+
+    /// Return the number of units displayed on each line.
+    fn units_per_line(&self) -> usize {
+        match self.size {
+            UnitSize::Byte | UnitSize::HalfWord => 8,
+            UnitSize::Word => 4,
+            UnitSize::Giant => 2,
+        }
+    }
+
+    /// Format the units of `data`, read from `address`, into lines prefixed
+    /// by the address of their first unit. Trailing bytes which do not form a
+    /// whole unit are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format displays strings or instructions, which are not a
+    /// fixed number of units.
+    pub fn format_units(&self, address: u64, data: &[u8]) -> Vec<String> {
+        assert!(
+            !matches!(
+                self.letter,
+                FormatLetter::String | FormatLetter::Instruction
+            ),
+            "strings and instructions are not formatted as units"
+        );
+
+        let size = self.size.bytes();
+        let line_bytes = size * self.units_per_line();
+
+        data.chunks(line_bytes)
+            .enumerate()
+            .filter(|(_, line)| line.len() >= size)
+            .map(|(idx, line)| {
+                let units: Vec<String> = line
+                    .chunks_exact(size)
+                    .map(|unit| self.format_unit(unit))
+                    .collect();
+
+                format!(
+                    "{:#018x}: {}",
+                    address + (idx * line_bytes) as u64,
+                    units.join("  ")
+                )
+            })
+            .collect()
+    }
+
+    /// Format a single unit of memory.
+    fn format_unit(&self, unit: &[u8]) -> String {
+        let mut bytes = [0u8; 8];
+        bytes[..unit.len()].copy_from_slice(unit);
+
+        let raw = u64::from_le_bytes(bytes);
+        let bits = unit.len() * 8;
+
+        match self.letter {
+            FormatLetter::Hex => format!("{raw:#0width$x}", width = unit.len() * 2 + 2),
+            FormatLetter::Signed => {
+                // Sign-extend the unit into a full 64-bit value.
+                let shift = 64 - bits;
+                format!("{}", ((raw << shift) as i64) >> shift)
+            }
+            FormatLetter::Unsigned => format!("{raw}"),
+            FormatLetter::Float if unit.len() == 4 => format!("{:?}", f32::from_bits(raw as u32)),
+            FormatLetter::Float => format!("{:?}", f64::from_bits(raw)),
+            FormatLetter::Char => format!("{raw} '{}'", escape(unit)),
+            FormatLetter::String | FormatLetter::Instruction => unreachable!(),
+        }
+    }
+}
+
+/// Format `data`, read from `address`, as a hexdump of sixteen bytes per line
+/// followed by their printable ASCII characters.
+pub fn hexdump(address: u64, data: &[u8]) -> Vec<String> {
+    data.chunks(HEXDUMP_WIDTH)
+        .enumerate()
+        .map(|(idx, line)| {
+            let mut hex = String::new();
+
+            for col in 0..HEXDUMP_WIDTH {
+                // Separate the two halves of the line.
+                if col == HEXDUMP_WIDTH / 2 {
+                    hex.push(' ');
+                }
+
+                match line.get(col) {
+                    Some(byte) => hex.push_str(&format!("{byte:02x} ")),
+                    None => hex.push_str("   "),
+                }
+            }
+
+            let ascii: String = line
+                .iter()
+                .map(|&byte| {
+                    if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+
+            format!(
+                "{:#018x}: {hex} |{ascii}|",
+                address + (idx * HEXDUMP_WIDTH) as u64
+            )
+        })
+        .collect()
+}
+
+/// Escape `bytes` for display within quotes, as in a Rust byte string.
+pub(crate) fn escape(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|&byte| ascii::escape_default(byte))
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_format_parsing() {
+        let format = MemoryFormat::parse("4xg").unwrap();
+        assert_eq!(format.count, 4);
+        assert_eq!(format.letter, FormatLetter::Hex);
+        assert_eq!(format.size, UnitSize::Giant);
+
+        // Letters may appear in either order, with defaults for those omitted.
+        assert_eq!(MemoryFormat::parse("bd").unwrap().size, UnitSize::Byte);
+        assert_eq!(MemoryFormat::parse("").unwrap().size, UnitSize::Word);
+        assert_eq!(MemoryFormat::parse("f").unwrap().size, UnitSize::Giant);
+        assert_eq!(MemoryFormat::parse("3cg").unwrap().size, UnitSize::Byte);
+        assert_eq!(MemoryFormat::parse("12s").unwrap().count, 12);
+
+        assert!(MemoryFormat::parse("fh").is_err());
+        assert!(MemoryFormat::parse("xd").is_err());
+        assert!(MemoryFormat::parse("bg").is_err());
+        assert!(MemoryFormat::parse("4z").is_err());
+        assert!(MemoryFormat::parse("0x").is_err());
+    }
+
+    #[test]
+    fn memory_format_units() {
+        let data: Vec<u8> = (0xf8..=0xff).chain(0..8).collect();
+
+        let lines = MemoryFormat::parse("2xg")
+            .unwrap()
+            .format_units(0x1000, &data);
+        assert_eq!(
+            lines,
+            ["0x0000000000001000: 0xfffefdfcfbfaf9f8  0x0706050403020100"]
+        );
+
+        let lines = MemoryFormat::parse("4dw")
+            .unwrap()
+            .format_units(0x1000, &data);
+        assert_eq!(
+            lines,
+            ["0x0000000000001000: -67438088  -66052  50462976  117835012"]
+        );
+
+        let lines = MemoryFormat::parse("ub")
+            .unwrap()
+            .format_units(0x1000, &data);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1], "0x0000000000001008: 0  1  2  3  4  5  6  7");
+
+        let lines = MemoryFormat::parse("fw")
+            .unwrap()
+            .format_units(0, &1.5f32.to_le_bytes());
+        assert_eq!(lines, ["0x0000000000000000: 1.5"]);
+
+        let lines = MemoryFormat::parse("c").unwrap().format_units(0, b"A\n");
+        assert_eq!(lines, ["0x0000000000000000: 65 'A'  10 '\\n'"]);
+    }
+
+    #[test]
+    fn hexdump_lines() {
+        let data: Vec<u8> = (b'0'..=b'9')
+            .chain([0, 0x7f, b' ', b'~', 0xff, b'z', b'!'])
+            .collect();
+
+        let lines = hexdump(0x10, &data);
+        assert_eq!(
+            lines,
+            [
+                "0x0000000000000010: 30 31 32 33 34 35 36 37  38 39 00 7f 20 7e ff 7a  \
+                 |0123456789.. ~.z|",
+                "0x0000000000000020: 21                                                \
+                 |!|",
+            ]
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+
+use super::{RegisterInfo, RegisterValue, Registers};
+use crate::Result;
+
+/// Number of hardware stoppoint slots, given by the address registers `dr0`
+/// to `dr3`.
+const SLOTS: usize = 4;
+
+/// Kind of access which triggers a hardware stoppoint.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum StoppointMode {
+    /// Data writes.
+    Write,
+    /// Data reads or writes.
+    ReadWrite,
+    /// Instruction execution.
+    Execute,
+}
+
+impl StoppointMode {
+    /// Look up the [`StoppointMode`] with the given `name` (i.e., `write`, `rw`,
+    /// or `execute`).
+    pub fn mode_by_name(name: &str) -> Option<Self> {
+        match name {
+            "write" => Some(Self::Write),
+            "rw" => Some(Self::ReadWrite),
+            "execute" => Some(Self::Execute),
+            _ => None,
+        }
+    }
+
+    /// Return the `R/W` field of `dr7` for the given [`StoppointMode`].
+    fn rw_bits(self) -> u64 {
+        match self {
+            Self::Execute => 0b00,
+            Self::Write => 0b01,
+            Self::ReadWrite => 0b11,
+        }
+    }
+}
+
+impl fmt::Display for StoppointMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self {
+            Self::Write => "write",
+            Self::ReadWrite => "rw",
+            Self::Execute => "execute",
+        };
+
+        write!(f, "{mode}")
+    }
+}
+
+/// Return the `LEN` field of `dr7` for a stoppoint covering `size` bytes.
+fn len_bits(size: usize) -> Result<u64> {
+    match size {
+        1 => Ok(0b00),
+        2 => Ok(0b01),
+        4 => Ok(0b11),
+        8 => Ok(0b10),
+        _ => Err(format!("invalid hardware stoppoint size {size}: expected 1, 2, 4, or 8").into()),
+    }
+}
+
+/// Program a free debug register slot to trigger on `mode` accesses to the
+/// `size` bytes at `address`, returning the allocated slot.
+pub(crate) fn set_hardware_stoppoint(
+    regs: &mut Registers,
+    address: u64,
+    mode: StoppointMode,
+    size: usize,
+) -> Result<usize> {
+    let len = len_bits(size)?;
+    let dr7 = read_debug_reg(regs, 7);
+
+    // A slot is in use if either its local or global enable bit is set.
+    let slot = (0..SLOTS)
+        .find(|slot| dr7 & (0b11 << (slot * 2)) == 0)
+        .ok_or("no free hardware stoppoint slots (at most 4 may be enabled)")?;
+
+    // The address must be set before the slot is enabled.
+    write_debug_reg(regs, slot, address)?;
+
+    // Each slot has a 4-bit field of `R/W` and `LEN`, starting at bit 16.
+    let shift = 16 + slot * 4;
+    let dr7 = (dr7 & !(0b1111 << shift))
+        | (1 << (slot * 2))
+        | (mode.rw_bits() << shift)
+        | (len << (shift + 2));
+
+    write_debug_reg(regs, 7, dr7)?;
+
+    Ok(slot)
+}
+
+/// Disable and clear the given debug register `slot`.
+pub(crate) fn clear_hardware_stoppoint(regs: &mut Registers, slot: usize) -> Result<()> {
+    let dr7 = read_debug_reg(regs, 7);
+    let dr7 = dr7 & !(0b11 << (slot * 2)) & !(0b1111 << (16 + slot * 4));
+
+    write_debug_reg(regs, 7, dr7)?;
+    write_debug_reg(regs, slot, 0)
+}
+
+/// Return the debug register slot whose condition was met, as reported by the
+/// `B0` to `B3` bits of `dr6`, clearing them so later traps are not
+/// misattributed.
+pub(crate) fn take_triggered_slot(regs: &mut Registers) -> Result<Option<usize>> {
+    let dr6 = read_debug_reg(regs, 6);
+    let slot = (0..SLOTS).find(|slot| dr6 & (1 << slot) != 0);
+
+    if slot.is_some() {
+        write_debug_reg(regs, 6, dr6 & !0b1111)?;
+    }
+
+    Ok(slot)
+}
+
+fn debug_reg_info(idx: usize) -> &'static RegisterInfo {
+    const NAMES: [&str; 8] = ["dr0", "dr1", "dr2", "dr3", "dr4", "dr5", "dr6", "dr7"];
+
+    RegisterInfo::register_info_by_name(NAMES[idx]).expect("debug registers should be known")
+}
+
+fn read_debug_reg(regs: &Registers, idx: usize) -> u64 {
+    regs.read(debug_reg_info(idx)).to_u64().unwrap_or_default()
+}
+
+fn write_debug_reg(regs: &mut Registers, idx: usize, value: u64) -> Result<()> {
+    regs.write(debug_reg_info(idx), RegisterValue::U64(value))
+}
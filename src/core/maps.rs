@@ -0,0 +1,377 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::Result;
+
+use libc::pid_t;
+
+/// Access permissions of a [`MemoryRegion`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct Permissions {
+    /// The region can be read.
+    pub read: bool,
+    /// The region can be written.
+    pub write: bool,
+    /// The region can be executed.
+    pub execute: bool,
+    /// The region is shared with other processes, rather than private
+    /// (copy-on-write).
+    pub shared: bool,
+}
+
+impl Permissions {
+    /// Parse permissions in the format of `/proc/<pid>/maps` (e.g., `r-xp`).
+    fn parse(perms: &str) -> Option<Self> {
+        let &[read, write, execute, shared] = perms.as_bytes() else {
+            return None;
+        };
+
+        let flag = |byte: u8, set: u8| match byte {
+            b'-' => Some(false),
+            _ if byte == set => Some(true),
+            _ => None,
+        };
+
+        Some(Self {
+            read: flag(read, b'r')?,
+            write: flag(write, b'w')?,
+            execute: flag(execute, b'x')?,
+            shared: match shared {
+                b's' => true,
+                b'p' => false,
+                _ => return None,
+            },
+        })
+    }
+}
+
+impl fmt::Display for Permissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}",
+            if self.read { 'r' } else { '-' },
+            if self.write { 'w' } else { '-' },
+            if self.execute { 'x' } else { '-' },
+            if self.shared { 's' } else { 'p' }
+        )
+    }
+}
+
+/// What backs the memory of a [`MemoryRegion`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RegionBacking {
+    /// A file mapped into memory.
+    File(PathBuf),
+    /// A region named by the kernel (e.g., `[stack]`, `[heap]`, or `[vdso]`).
+    Pseudo(String),
+    /// Anonymous memory.
+    Anonymous,
+}
+
+impl fmt::Display for RegionBacking {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Pseudo(name) => write!(f, "{name}"),
+            Self::Anonymous => write!(f, "[anonymous]"),
+        }
+    }
+}
+
+/// Contiguous range of virtual memory of a tracee, as listed in
+/// `/proc/<pid>/maps`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MemoryRegion {
+    /// Address of the first byte of the region.
+    pub start: u64,
+    /// Address one past the last byte of the region.
+    pub end: u64,
+    /// Access permissions of the region.
+    pub permissions: Permissions,
+    /// Offset into the backing file at which the region starts.
+    pub offset: u64,
+    /// Device of the backing file, as `major:minor`.
+    pub device: String,
+    /// Inode of the backing file, or zero.
+    pub inode: u64,
+    /// What backs the memory of the region.
+    pub backing: RegionBacking,
+    /// Memory usage fields from `/proc/<pid>/smaps` (e.g., `Rss`), in
+    /// kilobytes. Empty unless read with [`MemoryMap::read_smaps`].
+    pub usage: Vec<(String, u64)>,
+    /// Kernel flags of the region from `/proc/<pid>/smaps` (e.g., `rd`).
+    /// Empty unless read with [`MemoryMap::read_smaps`].
+    pub vm_flags: Vec<String>,
+}
+
+impl MemoryRegion {
+    /// Parse a single line of `/proc/<pid>/maps`.
+    fn parse(line: &str) -> Option<Self> {
+        let mut rest = line;
+        let mut next_field = || {
+            let trimmed = rest.trim_start();
+            let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+            let (field, remaining) = trimmed.split_at(end);
+
+            rest = remaining;
+            Some(field).filter(|field| !field.is_empty())
+        };
+
+        let (start, end) = next_field()?.split_once('-')?;
+        let permissions = Permissions::parse(next_field()?)?;
+        let offset = u64::from_str_radix(next_field()?, 16).ok()?;
+        let device = next_field()?.to_string();
+        let inode = next_field()?.parse::<u64>().ok()?;
+
+        // The path may contain spaces, so is the remainder of the line.
+        let backing = match rest.trim() {
+            "" => RegionBacking::Anonymous,
+            name if name.starts_with('[') => RegionBacking::Pseudo(name.to_string()),
+            path => RegionBacking::File(PathBuf::from(path)),
+        };
+
+        Some(Self {
+            start: u64::from_str_radix(start, 16).ok()?,
+            end: u64::from_str_radix(end, 16).ok()?,
+            permissions,
+            offset,
+            device,
+            inode,
+            backing,
+            usage: Vec::new(),
+            vm_flags: Vec::new(),
+        })
+    }
+
+    /// Return the size of the region in bytes.
+    pub fn size(&self) -> u64 {
+        self.end - self.start
+    }
+
+    /// Return `true` if `address` lies within the region.
+    pub fn contains(&self, address: u64) -> bool {
+        (self.start..self.end).contains(&address)
+    }
+
+    /// Return the value of the `smaps` usage field with the given `key` (e.g.,
+    /// `Rss`), in kilobytes.
+    pub fn usage(&self, key: &str) -> Option<u64> {
+        self.usage
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|&(_, value)| value)
+    }
+
+    /// Return a short name for the backing of the region: the file name of a
+    /// mapped file, or the name of a pseudo or anonymous region.
+    pub fn name(&self) -> String {
+        match &self.backing {
+            RegionBacking::File(path) => path
+                .file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .into_owned(),
+            backing => backing.to_string(),
+        }
+    }
+
+    /// Describe where `address` lies within the region (e.g., `libc.so.6
+    /// r-xp +0x2a1f0`), giving its offset into the backing file, or into the
+    /// region itself if not file-backed.
+    pub fn describe(&self, address: u64) -> String {
+        let offset = match self.backing {
+            RegionBacking::File(_) => address - self.start + self.offset,
+            _ => address - self.start,
+        };
+
+        format!("{} {} +{offset:#x}", self.name(), self.permissions)
+    }
+}
+
+/// Virtual memory layout of a tracee, as listed in `/proc/<pid>/maps`, ordered
+/// by address.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryMap {
+    /// Regions of the memory map, ordered by address.
+    regions: Vec<MemoryRegion>,
+}
+
+impl MemoryMap {
+    /// Read the memory map of the process with the given `pid`.
+    pub fn read(pid: pid_t) -> Result<Self> {
+        Self::parse(&read_proc_file(pid, "maps")?)
+    }
+
+    /// Read the memory map of the process with the given `pid`, including the
+    /// memory usage of each region from `/proc/<pid>/smaps`.
+    pub fn read_smaps(pid: pid_t) -> Result<Self> {
+        Self::parse(&read_proc_file(pid, "smaps")?)
+    }
+
+    /// Parse the contents of `/proc/<pid>/maps` or `/proc/<pid>/smaps`.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut regions: Vec<MemoryRegion> = Vec::new();
+
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            // Each region of `smaps` is followed by `Key: value` lines.
+            if let Some((key, value)) = line.split_once(':')
+                && !key.contains(char::is_whitespace)
+                && !key.contains('-')
+            {
+                let region = regions
+                    .last_mut()
+                    .ok_or_else(|| format!("unexpected field '{key}' before any region"))?;
+
+                if key == "VmFlags" {
+                    region.vm_flags = value.split_whitespace().map(String::from).collect();
+                } else if let Some(value) = value.split_whitespace().next()
+                    && let Ok(value) = value.parse::<u64>()
+                {
+                    region.usage.push((key.to_string(), value));
+                }
+
+                continue;
+            }
+
+            let region = MemoryRegion::parse(line)
+                .ok_or_else(|| format!("invalid memory map entry '{line}'"))?;
+
+            regions.push(region);
+        }
+
+        Ok(Self { regions })
+    }
+
+    /// Return the regions of the memory map, ordered by address.
+    pub fn regions(&self) -> &[MemoryRegion] {
+        &self.regions
+    }
+
+    /// Return the [`MemoryRegion`] containing `address`, if it is mapped.
+    pub fn region_at(&self, address: u64) -> Option<&MemoryRegion> {
+        let idx = self.regions.partition_point(|region| region.end <= address);
+
+        self.regions
+            .get(idx)
+            .filter(|region| region.contains(address))
+    }
+
+    /// Return `true` if every byte of the `len` bytes at `address` is mapped.
+    pub fn is_mapped(&self, address: u64, len: u64) -> bool {
+        let end = address.saturating_add(len);
+        let mut address = address;
+
+        while address < end {
+            match self.region_at(address) {
+                Some(region) => address = region.end,
+                None => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Describe where `address` lies within the memory map (e.g., `0x7ff... is
+    /// in libc.so.6 r-xp +0x2a1f0`).
+    pub fn describe(&self, address: u64) -> String {
+        match self.region_at(address) {
+            Some(region) => format!("{address:#x} is in {}", region.describe(address)),
+            None => format!("{address:#x} is not mapped"),
+        }
+    }
+}
+
+/// Read the file with the given `name` from `/proc/<pid>`.
+fn read_proc_file(pid: pid_t, name: &str) -> Result<String> {
+    let path = Path::new("/proc").join(pid.to_string()).join(name);
+
+    fs::read_to_string(&path)
+        .map_err(|err| format!("failed to read '{}': {err}", path.display()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAPS: &str = "\
+55d0c8a00000-55d0c8a02000 r--p 00000000 08:01 1234                       /usr/bin/my prog
+55d0c8a02000-55d0c8a05000 r-xp 00002000 08:01 1234                       /usr/bin/my prog
+55d0c9c3e000-55d0c9c5f000 rw-p 00000000 00:00 0                          [heap]
+7f1e2a200000-7f1e2a228000 r--p 00000000 08:01 5678                       /usr/lib/libc.so.6
+7f1e2a228000-7f1e2a3bd000 r-xp 00028000 08:01 5678                       /usr/lib/libc.so.6
+7f1e2a3c0000-7f1e2a3c4000 rw-p 00000000 00:00 0
+7ffd3a6a1000-7ffd3a6c2000 rw-p 00000000 00:00 0                          [stack]
+";
+
+    #[test]
+    fn memory_map_parsing() {
+        let map = MemoryMap::parse(MAPS).unwrap();
+        assert_eq!(map.regions().len(), 7);
+
+        let text = &map.regions()[1];
+        assert_eq!(text.start, 0x55d0c8a02000);
+        assert_eq!(text.size(), 0x3000);
+        assert_eq!(text.offset, 0x2000);
+        assert_eq!(text.device, "08:01");
+        assert_eq!(text.inode, 1234);
+        assert_eq!(text.permissions.to_string(), "r-xp");
+        assert!(text.permissions.execute && !text.permissions.write);
+        assert_eq!(
+            text.backing,
+            RegionBacking::File(PathBuf::from("/usr/bin/my prog"))
+        );
+
+        assert_eq!(
+            map.regions()[2].backing,
+            RegionBacking::Pseudo("[heap]".to_string())
+        );
+        assert_eq!(map.regions()[5].backing, RegionBacking::Anonymous);
+
+        assert!(MemoryMap::parse("not a mapping").is_err());
+        assert!(MemoryMap::parse("1000-2000 rwzp 0 00:00 0").is_err());
+    }
+
+    #[test]
+    fn memory_map_lookup() {
+        let map = MemoryMap::parse(MAPS).unwrap();
+
+        assert_eq!(
+            map.describe(0x7f1e2a2521f0),
+            "0x7f1e2a2521f0 is in libc.so.6 r-xp +0x521f0"
+        );
+        assert_eq!(
+            map.describe(0x7ffd3a6c1ff8),
+            "0x7ffd3a6c1ff8 is in [stack] rw-p +0x20ff8"
+        );
+        assert_eq!(map.describe(0x7f1e2a3bd000), "0x7f1e2a3bd000 is not mapped");
+        assert!(map.region_at(0).is_none());
+
+        // Ranges may span adjacent regions, but not gaps between them.
+        assert!(map.is_mapped(0x55d0c8a01ff0, 0x20));
+        assert!(!map.is_mapped(0x55d0c8a04ff0, 0x20));
+        assert!(map.is_mapped(0x55d0c8a04ff0, 0x10));
+    }
+
+    #[test]
+    fn smaps_parsing() {
+        let smaps = "\
+7ffd3a6a1000-7ffd3a6c2000 rw-p 00000000 00:00 0                          [stack]
+Size:                132 kB
+KernelPageSize:        4 kB
+Rss:                  16 kB
+THPeligible:    0
+VmFlags: rd wr mr mw me gd ac
+";
+
+        let map = MemoryMap::parse(smaps).unwrap();
+        let stack = &map.regions()[0];
+
+        assert_eq!(stack.usage("Size"), Some(132));
+        assert_eq!(stack.usage("Rss"), Some(16));
+        assert_eq!(stack.usage("THPeligible"), Some(0));
+        assert_eq!(stack.usage("Pss"), None);
+        assert_eq!(stack.vm_flags, ["rd", "wr", "mr", "mw", "me", "gd", "ac"]);
+    }
+}
@@ -0,0 +1,223 @@
+use std::{error, fmt, io, ptr};
+
+use crate::Result;
+use crate::utils::errno;
+
+use libc::{_SC_PAGESIZE, PTRACE_PEEKDATA, PTRACE_POKEDATA, c_void, iovec, pid_t};
+
+/// Error returned when only part of a range of tracee memory could be read,
+/// holding the bytes which were readable.
+#[derive(Debug)]
+pub struct PartialRead {
+    /// Bytes read from the start of the range, up to `address`.
+    pub data: Vec<u8>,
+    /// Address of the first byte which could not be read.
+    pub address: u64,
+    /// Number of bytes requested.
+    pub requested: usize,
+    /// Reason the byte at `address` could not be read.
+    pub source: io::Error,
+}
+
+impl fmt::Display for PartialRead {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "read {} of {} bytes: failed to read tracee memory at {:#x}: {}",
+            self.data.len(),
+            self.requested,
+            self.address,
+            self.source
+        )
+    }
+}
+
+impl error::Error for PartialRead {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Read `len` bytes from the memory of the tracee with the given `pid` at
+/// `address`.
+///
+/// The range is read in bulk using `process_vm_readv`, falling back to reading
+/// each page individually (using `PTRACE_PEEKDATA` for pages which are mapped
+/// but not readable, such as execute-only text). If any page is unmapped, a
+/// [`PartialRead`] holding the bytes preceding it is returned.
+pub(crate) fn read_memory(pid: pid_t, address: u64, len: usize) -> Result<Vec<u8>> {
+    check_range(address, len, "read")?;
+
+    let mut data = vec![0u8; len];
+
+    // A bulk read stops at the first page which could not be read.
+    let mut offset = vm_read(pid, address, &mut data).unwrap_or(0);
+    let page_size = page_size();
+
+    while offset < len {
+        let page_address = address + offset as u64;
+        let chunk = (len - offset).min(page_size - (page_address % page_size as u64) as usize);
+        let buf = &mut data[offset..offset + chunk];
+
+        if vm_read(pid, page_address, buf).is_ok_and(|read| read == chunk) {
+            offset += chunk;
+            continue;
+        }
+
+        if let Err(source) = peek_range(pid, page_address, buf) {
+            data.truncate(offset);
+
+            return Err(Box::new(PartialRead {
+                data,
+                address: page_address,
+                requested: len,
+                source,
+            }));
+        }
+
+        offset += chunk;
+    }
+
+    Ok(data)
+}
+
+/// Write `data` into the memory of the tracee with the given `pid` at
+/// `address`.
+///
+/// `PTRACE_POKEDATA` ignores page protections, so read-only pages (e.g., text)
+/// can be patched. Words only partially covered by `data` are read first, so
+/// the surrounding bytes are preserved.
+pub(crate) fn write_memory(pid: pid_t, address: u64, data: &[u8]) -> Result<()> {
+    let end = check_range(address, data.len(), "write")?;
+    let mut word_address = address & !0b111;
+
+    while word_address < end {
+        let start = word_address.max(address);
+        let stop = word_address.saturating_add(8).min(end);
+
+        let mut bytes = if stop - start == 8 {
+            [0; 8]
+        } else {
+            peek_data(pid, word_address)?.to_le_bytes()
+        };
+
+        bytes[(start - word_address) as usize..(stop - word_address) as usize]
+            .copy_from_slice(&data[(start - address) as usize..(stop - address) as usize]);
+
+        poke_data(pid, word_address, u64::from_le_bytes(bytes))?;
+
+        let Some(next) = word_address.checked_add(8) else {
+            break;
+        };
+        word_address = next;
+    }
+
+    Ok(())
+}
+
+/// Return the end of the range of `len` bytes at `address`, or an error naming
+/// the `access` (e.g., `read`) if the range wraps around the address space.
+pub(crate) fn check_range(address: u64, len: usize, access: &str) -> Result<u64> {
+    address.checked_add(len as u64).ok_or_else(|| {
+        format!("cannot {access} {len} bytes at {address:#x}: address range wraps around").into()
+    })
+}
+
+/// Read into `buf` from the memory of the tracee using `process_vm_readv`,
+/// returning the number of bytes read.
+fn vm_read(pid: pid_t, address: u64, buf: &mut [u8]) -> io::Result<usize> {
+    let local = iovec {
+        iov_base: buf.as_mut_ptr() as *mut c_void,
+        iov_len: buf.len(),
+    };
+    let remote = iovec {
+        iov_base: address as *mut c_void,
+        iov_len: buf.len(),
+    };
+
+    let read = unsafe { libc::process_vm_readv(pid, &local, 1, &remote, 1, 0) };
+
+    if read < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(read as usize)
+}
+
+/// Read into `buf` from the memory of the tracee one word at a time using
+/// `PTRACE_PEEKDATA`.
+fn peek_range(pid: pid_t, address: u64, buf: &mut [u8]) -> io::Result<()> {
+    let end = address + buf.len() as u64;
+    let mut word_address = address & !0b111;
+
+    while word_address < end {
+        let word = peek_word(pid, word_address)?.to_le_bytes();
+
+        let start = word_address.max(address);
+        let stop = word_address.saturating_add(8).min(end);
+
+        buf[(start - address) as usize..(stop - address) as usize].copy_from_slice(
+            &word[(start - word_address) as usize..(stop - word_address) as usize],
+        );
+
+        let Some(next) = word_address.checked_add(8) else {
+            break;
+        };
+        word_address = next;
+    }
+
+    Ok(())
+}
+
+/// Return the size of a page of memory.
+pub(crate) fn page_size() -> usize {
+    match unsafe { libc::sysconf(_SC_PAGESIZE) } {
+        size if size > 0 => size as usize,
+        _ => 4096,
+    }
+}
+
+/// Read a word from the memory of the tracee with the given `pid` at `address`.
+pub(crate) fn peek_data(pid: pid_t, address: u64) -> Result<u64> {
+    peek_word(pid, address)
+        .map_err(|err| format!("failed to read tracee memory at {address:#x}: {err}").into())
+}
+
+fn peek_word(pid: pid_t, address: u64) -> io::Result<u64> {
+    // `PTRACE_PEEKDATA` can legitimately return -1, so `errno` must be cleared
+    // beforehand to distinguish errors.
+    unsafe { *libc::__errno_location() = 0 };
+
+    let word = unsafe {
+        libc::ptrace(
+            PTRACE_PEEKDATA,
+            pid,
+            address as *mut c_void,
+            ptr::null_mut::<c_void>(),
+        )
+    };
+
+    if word == -1 && unsafe { *libc::__errno_location() } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(word as u64)
+}
+
+/// Write a word into the memory of the tracee with the given `pid` at
+/// `address`.
+pub(crate) fn poke_data(pid: pid_t, address: u64, word: u64) -> Result<()> {
+    if unsafe {
+        libc::ptrace(
+            PTRACE_POKEDATA,
+            pid,
+            address as *mut c_void,
+            word as *mut c_void,
+        )
+    } < 0
+    {
+        return Err(errno!("failed to write tracee memory at {address:#x}"));
+    }
+
+    Ok(())
+}
@@ -3,12 +3,48 @@
 mod command;
 pub use command::handle_command;
 
+mod stoppoint;
+pub use stoppoint::{Stoppoint, Stoppoints};
+
+mod auxv;
+pub use auxv::AuxVector;
+
+mod breakpoint;
+pub use breakpoint::{BreakpointSite, BreakpointSites};
+
+mod watchpoint;
+pub use watchpoint::{Watchpoint, Watchpoints};
+
+mod hardware;
+pub use hardware::StoppointMode;
+
+mod examine;
+pub(crate) use examine::escape;
+pub use examine::{FormatLetter, MemoryFormat, UnitSize, hexdump};
+
+mod maps;
+pub use maps::{MemoryMap, MemoryRegion, Permissions, RegionBacking};
+
+mod memory;
+pub use memory::PartialRead;
+
 mod process;
-pub use process::{Process, StopReason};
+pub use process::{Process, ProcessState, StopReason, TrapKind};
+
+mod source;
+pub use source::{Listing, Sources};
 
 mod pipe;
 pub(crate) use pipe::Pipe;
 
+mod regex;
+pub(crate) use regex::Regex;
+
 mod register;
-#[allow(unused_imports)]
-pub(crate) use register::{RegisterFormat, RegisterInfo, RegisterType};
+pub(crate) use register::UserArea;
+pub use register::{
+    DecodedRegister, Eflags, F80, F80Category, FpuControlWord, FpuStatusWord, FpuTag, FpuTagWord,
+    LaneFormat, LaneView, Mxcsr, PrecisionControl, RegisterChange, RegisterDelta, RegisterFlag,
+    RegisterFormat, RegisterInfo, RegisterType, RegisterValue, Registers, RoundingMode,
+    X87StackSlot,
+};
@@ -1,13 +1,31 @@
-use std::{ffi, ptr};
-
-use super::Pipe;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::{ffi, fs, mem, ptr};
+
+use super::hardware::take_triggered_slot;
+use super::memory::{self, peek_data};
+use super::{
+    AuxVector, BreakpointSite, BreakpointSites, MemoryMap, PartialRead, Pipe, RegionBacking,
+    RegisterChange, RegisterDelta, RegisterInfo, RegisterValue, Registers, Sources, Stoppoint,
+    StoppointMode, UserArea, Watchpoint, Watchpoints,
+};
 use crate::Result;
+use crate::disasm::{self, Instruction, MAX_INSTRUCTION_LEN};
+use crate::dwarf::{LineRow, Scope};
+use crate::elf::{Elf, FileAddr, IndexedSymbol, PT_PHDR, VirtAddr};
 use crate::utils::{errno, log_err};
 
 use libc::{
-    PTRACE_ATTACH, PTRACE_CONT, PTRACE_DETACH, PTRACE_TRACEME, SIGCONT, SIGKILL, SIGSTOP,
-    WEXITSTATUS, WIFEXITED, WIFSIGNALED, WIFSTOPPED, WSTOPSIG, WTERMSIG, c_char, c_int, c_void,
-    pid_t,
+    AT_ENTRY, AT_PHDR, PTRACE_ATTACH, PTRACE_CONT, PTRACE_DETACH, PTRACE_GET_SYSCALL_INFO,
+    PTRACE_GETSIGINFO, PTRACE_O_TRACEEXEC, PTRACE_O_TRACESYSGOOD, PTRACE_SETOPTIONS,
+    PTRACE_SINGLESTEP, PTRACE_SYSCALL, PTRACE_SYSCALL_INFO_ENTRY, PTRACE_SYSCALL_INFO_EXIT,
+    PTRACE_TRACEME, SI_KERNEL, SIGCONT, SIGKILL, SIGSTOP, SIGTRAP, TRAP_HWBKPT, TRAP_TRACE,
+    WEXITSTATUS, WIFEXITED, WIFSIGNALED, WIFSTOPPED, WSTOPSIG, WTERMSIG, c_char, c_int, c_uint,
+    c_void, pid_t, ptrace_syscall_info, siginfo_t,
+};
+use libc::{
+    PTRACE_EVENT_CLONE, PTRACE_EVENT_EXEC, PTRACE_EVENT_EXIT, PTRACE_EVENT_FORK,
+    PTRACE_EVENT_SECCOMP, PTRACE_EVENT_STOP, PTRACE_EVENT_VFORK, PTRACE_EVENT_VFORK_DONE,
 };
 
 /// Represents a tracee [`Process`] the debugger can interact with.
@@ -22,14 +40,49 @@ pub struct Process {
     state: ProcessState,
     /// Indicates whether the process has been attached to (used during cleanup).
     is_attached: bool,
+    /// Registers of the tracee, refreshed each time it is stopped.
+    registers: Registers,
+    /// Register state at the previous recorded stop, compared against the
+    /// state at the most recent recorded stop when the changes between them
+    /// are requested.
+    previous_registers: Option<Box<UserArea>>,
+    /// Register state at the most recent recorded stop.
+    stop_registers: Option<Box<UserArea>>,
+    /// Breakpoint sites of the tracee.
+    breakpoint_sites: BreakpointSites,
+    /// Watchpoints of the tracee.
+    watchpoints: Watchpoints,
+    /// Internal software breakpoint site used to run the tracee up to an
+    /// address (e.g., when stepping over a `call`).
+    temporary_site: Option<BreakpointSite>,
+    /// Internal software breakpoint site at the function the dynamic loader
+    /// calls each time it changes the set of loaded shared libraries.
+    loader_site: Option<BreakpointSite>,
+    /// Parsed executable of the tracee, loaded once it is first stopped.
+    elf: Option<Elf>,
+    /// Parsed shared libraries of the tracee, refreshed whenever the dynamic
+    /// loader changes them.
+    libraries: Vec<Elf>,
+    /// Files mapped into the tracee which are not ELF objects, and so are not
+    /// parsed again.
+    non_elf_files: HashSet<PathBuf>,
+    /// Locations of the source files of the tracee on the local filesystem.
+    sources: Sources,
+    /// Wait status of a tracee which exited while stepping over a breakpoint
+    /// site, reported by the next wait instead of calling `waitpid` again.
+    pending_status: Option<c_int>,
 }
 
 /// Represents the current state of a [`Process`].
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum ProcessState {
+    /// Halted by the delivery of a signal.
     Stopped,
+    /// Executing.
     Running,
+    /// Exited normally.
     Exited,
+    /// Terminated by a signal.
     Terminated,
 }
 
@@ -41,6 +94,46 @@ pub struct StopReason {
     pub reason: ProcessState,
     /// Additional code associated with the stop, such as a signal or exit code.
     pub info: c_int,
+    /// Cause of a `SIGTRAP` stop, if it could be determined.
+    pub trap: Option<TrapKind>,
+    /// Program counter (`rip`) of the [`Process`] when it stopped.
+    pub pc: Option<VirtAddr>,
+    /// Symbolized program counter (e.g., `main+0x14`), if it lies within a
+    /// known symbol.
+    pub symbol: Option<String>,
+    /// Source location of the program counter (e.g., `src/main.rs:42:5`), if
+    /// it is described by line number information.
+    pub source: Option<String>,
+}
+
+/// Cause of a `SIGTRAP` stop, derived from the `si_code` of the signal, the
+/// `dr6` debug status register, and the wait status of the tracee.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TrapKind {
+    /// An enabled software breakpoint site (i.e., `int3`) with the given id.
+    SoftwareBreakpoint(usize),
+    /// An enabled hardware breakpoint site with the given id.
+    HardwareBreakpoint(usize),
+    /// A watchpoint with the given id, along with the watched data before and
+    /// after the access.
+    Watchpoint {
+        /// Identifier of the watchpoint.
+        id: usize,
+        /// Value of the watched data before the access.
+        old: RegisterValue,
+        /// Value of the watched data after the access.
+        new: RegisterValue,
+    },
+    /// Completion of a single-stepped instruction.
+    SingleStep,
+    /// The internal breakpoint site used to run the tracee up to an address.
+    TemporaryBreakpoint,
+    /// Entry into the system call with the given number.
+    SyscallEntry(u64),
+    /// Exit from a system call, with its number and return value.
+    SyscallExit(u64, u64),
+    /// A `PTRACE_EVENT_*` stop with the given event number.
+    PtraceEvent(c_int),
 }
 
 impl StopReason {
@@ -59,9 +152,10 @@ impl StopReason {
             reason = ProcessState::Terminated;
             info = WTERMSIG(wait_status);
         } else if WIFSTOPPED(wait_status) {
-            // Child process was stopped by delivery of a signal.
+            // Child process was stopped by delivery of a signal. System call
+            // stops are reported as `SIGTRAP | 0x80` (see `PTRACE_O_TRACESYSGOOD`).
             reason = ProcessState::Stopped;
-            info = WSTOPSIG(wait_status);
+            info = WSTOPSIG(wait_status) & !SYSCALL_TRAP;
         } else {
             log_err(
                 "rdbg",
@@ -72,7 +166,14 @@ impl StopReason {
             info = -1;
         }
 
-        Self { reason, info }
+        Self {
+            reason,
+            info,
+            trap: None,
+            pc: None,
+            symbol: None,
+            source: None,
+        }
     }
 
     /// Log details of the [`StopReason`] for the given [`Process`].
@@ -82,35 +183,48 @@ impl StopReason {
                 println!("process {} exited with status {}", proc.pid, self.info);
             }
             ProcessState::Terminated => {
-                let signal = {
-                    // Returns a string describing the signal number provided.
-                    let ptr = unsafe { libc::strsignal(self.info) };
-
-                    if ptr.is_null() {
-                        "UNKNOWN"
-                    } else {
-                        let c_str = unsafe { ffi::CStr::from_ptr(ptr as *const c_char) };
-                        c_str.to_str().unwrap_or("UNKNOWN")
+                println!(
+                    "process {} terminated with signal {}",
+                    proc.pid,
+                    signal_name(self.info)
+                );
+            }
+            ProcessState::Stopped => {
+                let message = match &self.trap {
+                    Some(TrapKind::SoftwareBreakpoint(id) | TrapKind::HardwareBreakpoint(id)) => {
+                        format!("hit breakpoint {id}")
                     }
+                    Some(TrapKind::Watchpoint { id, .. }) => format!("hit watchpoint {id}"),
+                    Some(TrapKind::SingleStep) => "stepped".to_string(),
+                    Some(TrapKind::TemporaryBreakpoint) => {
+                        "reached temporary breakpoint".to_string()
+                    }
+                    Some(TrapKind::SyscallEntry(number)) => format!("entered syscall {number}"),
+                    Some(TrapKind::SyscallExit(number, _)) => format!("exited syscall {number}"),
+                    Some(TrapKind::PtraceEvent(event)) => {
+                        format!("stopped at ptrace event {}", ptrace_event_name(*event))
+                    }
+                    None => format!("stopped with signal {}", signal_name(self.info)),
                 };
 
-                println!("process {} terminated with signal {}", proc.pid, signal);
-            }
-            ProcessState::Stopped => {
-                let signal = {
-                    // Returns a string describing the signal number provided.
-                    let ptr = unsafe { libc::strsignal(self.info) };
+                let location = match (&self.symbol, self.pc) {
+                    (Some(symbol), _) => format!(" at {symbol}"),
+                    (None, Some(pc)) => format!(" at {pc:#018x}"),
+                    (None, None) => String::new(),
+                };
 
-                    if ptr.is_null() {
-                        "UNKNOWN"
-                    } else {
-                        let c_str = unsafe { ffi::CStr::from_ptr(ptr as *const c_char) };
+                let detail = match &self.trap {
+                    Some(TrapKind::Watchpoint { old, new, .. }) => format!(": {old} → {new}"),
+                    Some(TrapKind::SyscallExit(_, ret)) => format!(": returned {ret:#x}"),
+                    _ => String::new(),
+                };
 
-                        c_str.to_str().unwrap_or("UNKNOWN")
-                    }
+                let source = match &self.source {
+                    Some(source) => format!(" ({source})"),
+                    None => String::new(),
                 };
 
-                println!("process {} stopped with signal {}", proc.pid, signal);
+                println!("process {} {message}{location}{source}{detail}", proc.pid);
             }
             _ => {
                 log_err(
@@ -122,6 +236,39 @@ impl StopReason {
     }
 }
 
+/// Bit set in the stop signal of system call stops when the
+/// `PTRACE_O_TRACESYSGOOD` option is set.
+const SYSCALL_TRAP: c_int = 0x80;
+
+/// Return a string describing the given `signal` number.
+fn signal_name(signal: c_int) -> String {
+    // Returns a string describing the signal number provided.
+    let ptr = unsafe { libc::strsignal(signal) };
+
+    if ptr.is_null() {
+        "UNKNOWN".to_string()
+    } else {
+        let c_str = unsafe { ffi::CStr::from_ptr(ptr as *const c_char) };
+
+        c_str.to_str().unwrap_or("UNKNOWN").to_string()
+    }
+}
+
+/// Return the name of the given `PTRACE_EVENT_*` number.
+fn ptrace_event_name(event: c_int) -> &'static str {
+    match event {
+        PTRACE_EVENT_FORK => "fork",
+        PTRACE_EVENT_VFORK => "vfork",
+        PTRACE_EVENT_CLONE => "clone",
+        PTRACE_EVENT_EXEC => "exec",
+        PTRACE_EVENT_VFORK_DONE => "vfork-done",
+        PTRACE_EVENT_EXIT => "exit",
+        PTRACE_EVENT_SECCOMP => "seccomp",
+        PTRACE_EVENT_STOP => "stop",
+        _ => "unknown",
+    }
+}
+
 impl Process {
     /// Begin tracing a program given it's path, returning a new [`Process`].
     ///
@@ -215,12 +362,27 @@ impl Process {
             terminate: true,
             state: ProcessState::Stopped,
             is_attached: debug,
+            registers: Registers::new(pid),
+            previous_registers: None,
+            stop_registers: None,
+            breakpoint_sites: BreakpointSites::new(),
+            watchpoints: Watchpoints::new(),
+            temporary_site: None,
+            loader_site: None,
+            elf: None,
+            libraries: Vec::new(),
+            non_elf_files: HashSet::new(),
+            sources: Sources::new(),
+            pending_status: None,
         };
 
         // Guard the `wait_on_signal` call so it only runs when requested
         if debug {
             // Wait for the child process to halt.
             proc.wait_on_signal()?;
+            proc.set_ptrace_options()?;
+            proc.load_elf();
+            proc.record_stop();
         }
 
         Ok(proc)
@@ -247,20 +409,119 @@ impl Process {
             terminate: true,
             state: ProcessState::Stopped,
             is_attached: true,
+            registers: Registers::new(pid),
+            previous_registers: None,
+            stop_registers: None,
+            breakpoint_sites: BreakpointSites::new(),
+            watchpoints: Watchpoints::new(),
+            temporary_site: None,
+            loader_site: None,
+            elf: None,
+            libraries: Vec::new(),
+            non_elf_files: HashSet::new(),
+            sources: Sources::new(),
+            pending_status: None,
         };
 
-        // Wait for the child process to halt.
-        proc.wait_on_signal()?;
+        // Wait for the child process to halt. Stops reported before the
+        // `SIGSTOP` (e.g., the tracee finishing an `exec`) are passed over, so
+        // the tracee is not stopped again once resumed.
+        while proc.wait_on_signal()?.info != SIGSTOP && proc.state == ProcessState::Stopped {
+            proc.resume()?;
+        }
+
+        proc.set_ptrace_options()?;
+        proc.load_elf();
+        proc.record_stop();
 
         Ok(proc)
     }
 
     /// Continue execution for the halted [`Process`].
+    ///
+    /// If stopped at an enabled software breakpoint site (or the internal site
+    /// of the dynamic loader), the original instruction is executed first by
+    /// temporarily disabling the site and single-stepping. Hardware breakpoint
+    /// sites are not re-triggered, as the kernel sets the resume flag (`RF`)
+    /// when they are hit.
+    ///
+    /// Signals arriving while stepping over a site are delivered with the site
+    /// still disabled, so it is not hit again before its instruction executes.
     pub fn resume(&mut self) -> Result<()> {
+        self.resume_with(PTRACE_CONT)
+    }
+
+    /// Continue execution for the halted [`Process`] until the next system
+    /// call entry or exit, stepping over any breakpoint site like
+    /// [`Process::resume`].
+    pub fn resume_syscall(&mut self) -> Result<()> {
+        self.resume_with(PTRACE_SYSCALL)
+    }
+
+    /// Restart the halted [`Process`] with the given ptrace `request`, after
+    /// stepping over the breakpoint site at the program counter, if any.
+    fn resume_with(&mut self, request: c_uint) -> Result<()> {
+        let pc = self.pc();
+
+        let site = if self.breakpoint_sites.software_enabled_at(pc) {
+            self.breakpoint_sites.get_by_address_mut(pc)
+        } else if self.loader_site_enabled_at(pc) {
+            self.loader_site.as_mut()
+        } else {
+            None
+        };
+
+        if let Some(site) = site {
+            site.disable(&mut self.registers)?;
+
+            // A signal may arrive before the step completes, and must not be
+            // lost. It is delivered by stepping again, with the site still
+            // disabled, until the step itself traps.
+            let mut signal = 0;
+
+            loop {
+                if unsafe {
+                    libc::ptrace(
+                        PTRACE_SINGLESTEP,
+                        self.pid,
+                        ptr::null_mut::<c_void>(),
+                        signal as *mut c_void,
+                    )
+                } < 0
+                {
+                    return Err(errno!("failed to step over breakpoint site"));
+                }
+
+                let mut wait_status = 0;
+
+                if unsafe { libc::waitpid(self.pid, &mut wait_status, 0) } < 0 {
+                    return Err(errno!("failed to wait on tracee"));
+                }
+
+                // The tracee may have exited or been killed by the stepped
+                // instruction or the delivered signal, which is reported by the
+                // next wait.
+                if !WIFSTOPPED(wait_status) {
+                    self.pending_status = Some(wait_status);
+                    self.state = ProcessState::Running;
+
+                    return Ok(());
+                }
+
+                signal = WSTOPSIG(wait_status);
+
+                if signal == SIGTRAP {
+                    break;
+                }
+            }
+
+            site.enable(&mut self.registers)?;
+        }
+
         // Restart the stopped tracee process. `addr` argument is ignored.
         if unsafe {
             libc::ptrace(
-                PTRACE_CONT,
+                request,
                 self.pid,
                 ptr::null_mut::<c_void>(),
                 ptr::null_mut::<c_void>(),
@@ -275,196 +536,2312 @@ impl Process {
         Ok(())
     }
 
-    /// Wait on a state change for the given [`Process`], returning a new [`StopReason`]
-    pub fn wait_on_signal(&mut self) -> Result<StopReason> {
-        let mut wait_status = 0;
-        let options = 0;
+    /// Execute a single instruction of the halted [`Process`].
+    ///
+    /// If stopped at an enabled software breakpoint site (or the internal site
+    /// of the dynamic loader), the site is temporarily disabled so the
+    /// original instruction is executed.
+    pub fn step_instruction(&mut self) -> Result<StopReason> {
+        let pc = self.pc();
+        let at_site = self.breakpoint_sites.software_enabled_at(pc);
+        let at_loader_site = self.loader_site_enabled_at(pc);
+
+        if at_site && let Some(site) = self.breakpoint_sites.get_by_address_mut(pc) {
+            site.disable(&mut self.registers)?;
+        }
+
+        if at_loader_site && let Some(site) = &mut self.loader_site {
+            site.disable(&mut self.registers)?;
+        }
+
+        // Execute the next instruction of the tracee, then stop. `addr` argument
+        // is ignored.
+        if unsafe {
+            libc::ptrace(
+                PTRACE_SINGLESTEP,
+                self.pid,
+                ptr::null_mut::<c_void>(),
+                ptr::null_mut::<c_void>(),
+            )
+        } < 0
+        {
+            return Err(errno!("failed to single-step tracee"));
+        }
+
+        self.state = ProcessState::Running;
+        let mut reason = self.wait_on_signal()?;
+
+        // Stepping over `syscall` reports `TRAP_BRKPT` rather than `TRAP_TRACE`.
+        if reason.reason == ProcessState::Stopped && reason.info == SIGTRAP && reason.trap.is_none()
+        {
+            reason.trap = Some(TrapKind::SingleStep);
+        }
 
-        // Wait for state changes in the child process.
-        if unsafe { libc::waitpid(self.pid, &mut wait_status, options) } < 0 {
-            return Err(errno!("failed to wait on tracee"));
+        // The site can only be restored while the tracee is still alive.
+        if at_site
+            && self.state == ProcessState::Stopped
+            && let Some(site) = self.breakpoint_sites.get_by_address_mut(pc)
+        {
+            site.enable(&mut self.registers)?;
         }
 
-        let reason = StopReason::new(wait_status);
-        self.state = reason.reason;
+        if at_loader_site
+            && self.state == ProcessState::Stopped
+            && let Some(site) = &mut self.loader_site
+        {
+            site.enable(&mut self.registers)?;
+        }
 
         Ok(reason)
     }
 
-    /// Return the process ID of the given [`Process`].
-    pub fn pid(&self) -> pid_t {
-        self.pid
+    /// Execute a single instruction of the halted [`Process`], running any
+    /// function called by the instruction to completion.
+    ///
+    /// A `call` is stepped over using a temporary breakpoint site at its
+    /// return address, so the tracee may stop earlier within the function
+    /// (e.g., at a breakpoint site). Recursive calls which return to the same
+    /// address do not stop the tracee.
+    pub fn step_over(&mut self) -> Result<StopReason> {
+        let instruction = match self.decode_instruction(self.pc()) {
+            Ok(instruction) if instruction.is_call() => instruction,
+            // An instruction which cannot be decoded (e.g., one using an
+            // unsupported extension) is executed like any other non-`call`.
+            _ => return self.step_instruction(),
+        };
+
+        // The return address is pushed just below the current stack pointer.
+        let slot = self.read_u64("rsp").wrapping_sub(8);
+        let mut reason = self.run_to(VirtAddr(instruction.next_address()), Some(slot))?;
+
+        if reason.trap == Some(TrapKind::TemporaryBreakpoint) {
+            reason.trap = Some(TrapKind::SingleStep);
+        }
+
+        Ok(reason)
     }
 
-    /// Return the current state of the given [`Process`].
-    pub fn state(&self) -> ProcessState {
-        self.state
+    /// Run the halted [`Process`] until the current function returns, using a
+    /// temporary breakpoint site at the saved return address.
+    ///
+    /// The return address is located using the call frame information of the
+    /// object, so the function may have been compiled without a frame
+    /// pointer. Functions without call frame information are instead
+    /// single-stepped, stepping over any calls, until just after their `ret`.
+    /// Either way, the tracee may stop earlier (e.g., at a breakpoint site).
+    pub fn finish(&mut self) -> Result<StopReason> {
+        let Some(slot) = self.frame_return_address_slot() else {
+            return self.finish_by_stepping();
+        };
+
+        let return_address = VirtAddr(peek_data(self.pid, slot)?);
+        let mut reason = self.run_to(return_address, Some(slot))?;
+
+        if reason.trap == Some(TrapKind::TemporaryBreakpoint) {
+            reason.trap = Some(TrapKind::SingleStep);
+        }
+
+        Ok(reason)
     }
-}
 
-impl Drop for Process {
-    fn drop(&mut self) {
-        if self.pid != 0 {
-            let pid = self.pid;
-            let mut status = 0;
+    fn finish_by_stepping(&mut self) -> Result<StopReason> {
+        loop {
+            let returning = self
+                .decode_instruction(self.pc())
+                .is_ok_and(|instruction| instruction.is_return());
+            let reason = self.step_over()?;
 
-            unsafe {
-                if self.is_attached {
-                    if self.state == ProcessState::Running {
-                        // Send a signal to the tracee to stop execution.
-                        libc::kill(pid, SIGSTOP);
-                        // Wait for a state change from the tracee.
-                        libc::waitpid(pid, &mut status, 0);
-                    }
+            if returning || reason.trap != Some(TrapKind::SingleStep) {
+                return Ok(reason);
+            }
+        }
+    }
 
-                    // Detach from the tracee, then restart execution.
-                    libc::ptrace(
-                        PTRACE_DETACH,
-                        pid,
-                        ptr::null_mut::<c_void>(),
-                        ptr::null_mut::<c_void>(),
-                    );
-                    // Send a signal to tracee to ensure it continues execution.
-                    libc::kill(pid, SIGCONT);
+    /// Return the address of the stack slot holding the return address of the
+    /// current function, found from the canonical frame address (CFA) given by
+    /// the call frame information, or `None` if there is none.
+    fn frame_return_address_slot(&self) -> Option<u64> {
+        let (elf, address) = self.object_containing(self.pc())?;
+        let rule = elf.call_frame_info().cfa_rule(address)?;
+
+        let info = RegisterInfo::register_info_by_dwarf(i32::try_from(rule.register).ok()?)?;
+        let cfa = self
+            .registers
+            .read(info)
+            .to_u64()?
+            .wrapping_add_signed(rule.offset);
+
+        // The return address is pushed by the `call`, just below the CFA.
+        Some(cfa.wrapping_sub(8))
+    }
+
+    /// Execute the halted [`Process`] until it reaches the start of a
+    /// statement on a different source line, entering any function called on
+    /// the way which has line number information.
+    ///
+    /// A function entered is stopped in just past its prologue, while calls
+    /// to functions without line number information (e.g., through the PLT)
+    /// or to drop glue are run to completion. Returning from the current
+    /// function stops the tracee in the caller.
+    pub fn step_line(&mut self) -> Result<StopReason> {
+        self.step_source_line(true)
+    }
+
+    /// Execute the halted [`Process`] until it reaches the start of a
+    /// statement on a different source line, running any function called on
+    /// the way to completion (see [`Process::step_line`]).
+    pub fn next_line(&mut self) -> Result<StopReason> {
+        self.step_source_line(false)
+    }
+
+    fn step_source_line(&mut self, enter_calls: bool) -> Result<StopReason> {
+        // Code without line number information is left by returning from it.
+        let Some(start) = self.source_line(self.pc()) else {
+            return self.finish();
+        };
+
+        loop {
+            // An instruction which cannot be decoded is neither a `call` nor a
+            // `ret`, as with `step_over`.
+            let instruction = self.decode_instruction(self.pc()).ok();
+            let entering = enter_calls && instruction.as_ref().is_some_and(Instruction::is_call);
+            let returning = instruction.as_ref().is_some_and(Instruction::is_return);
+
+            let reason = if entering {
+                self.step_instruction()?
+            } else {
+                self.step_over()?
+            };
+
+            if reason.trap != Some(TrapKind::SingleStep) || returning {
+                return Ok(reason);
+            }
+
+            let pc = self.pc();
+
+            if entering {
+                if !self.is_skipped_function(pc) {
+                    return self.skip_prologue(reason);
                 }
 
-                // Terminate tracee if it was spawned due to [`Process::launch`].
-                if self.terminate {
-                    libc::kill(pid, SIGKILL);
-                    libc::waitpid(pid, &mut status, 0);
+                // Skipped functions are run until they return, from just
+                // after the `call`.
+                let slot = self.read_u64("rsp");
+                let return_address = VirtAddr(peek_data(self.pid, slot)?);
+                let reason = self.run_to(return_address, Some(slot))?;
+
+                if reason.trap != Some(TrapKind::TemporaryBreakpoint) {
+                    return Ok(reason);
                 }
+
+                continue;
+            }
+
+            if let Some((elf, row)) = self.line_at(pc)
+                && row.address.to_virt(elf) == pc
+                && row.is_stmt
+                && self.source_line(pc).is_some_and(|line| line != start)
+            {
+                return Ok(reason);
             }
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::fs;
-    use std::io;
+    /// Run the halted [`Process`] from the entry of a function past its
+    /// prologue, returning the [`StopReason`] of the single step which entered
+    /// the function if there is no prologue to skip.
+    fn skip_prologue(&mut self, reason: StopReason) -> Result<StopReason> {
+        let pc = self.pc();
 
-    use super::*;
+        let Some((elf, symbol, 0)) = self.symbol_at(pc) else {
+            return Ok(reason);
+        };
 
-    fn get_process_status(pid: pid_t) -> char {
-        // The `/proc` directory in Linux is a virtual filesystem that stores
-        // information about processes and other systems activities (Linux `procfs`),
-        // organized as files.
-        //
-        // The `/proc/[pid]/stat` file gives high-level information on the
-        // current state of a given process.
-        let stats = fs::read_to_string(format!("/proc/{pid}/stat"))
-            .unwrap_or_else(|err| panic!("failed to read proc stats for /proc/{pid}/stat': {err}"));
+        let function = symbol.address..FileAddr(symbol.address.0 + symbol.size.max(1));
 
-        let last_paren_idx = stats
-            .rfind(')')
-            .unwrap_or_else(|| panic!("unexpected format for /proc/{pid}/stat"));
+        let Some(end) = elf.line_table().prologue_end(function) else {
+            return Ok(reason);
+        };
 
-        // Return the state indicator.
-        stats
-            .chars()
-            .nth(last_paren_idx + 2) // located right after the closing parenthesis + 2
-            .unwrap_or_else(|| panic!("process state indicator missing for /proc/{pid}/stat"))
-    }
+        let mut reason = self.run_to(end.to_virt(elf), None)?;
 
-    fn check_pid(pid: pid_t) -> bool {
-        // If signal is 0, then no signal is sent, but existence and permission
-        // checks are still performed on the given `pid`.
-        let ret = unsafe { libc::kill(pid, 0) };
+        if reason.trap == Some(TrapKind::TemporaryBreakpoint) {
+            reason.trap = Some(TrapKind::SingleStep);
+        }
 
-        let errno = io::Error::last_os_error().to_string();
+        Ok(reason)
+    }
 
-        ret != -1 && !errno.contains("No such process")
+    /// Return `true` if the function at `address` is stepped over by
+    /// source-level stepping: either it has no line number information, or it
+    /// is drop glue generated by the Rust compiler (i.e.,
+    /// `core::ptr::drop_in_place<T>`), whose rows only locate the declaration
+    /// of `drop_in_place`.
+    fn is_skipped_function(&self, address: VirtAddr) -> bool {
+        if self.source_line(address).is_none() {
+            return true;
+        }
+
+        self.symbol_at(address)
+            .is_some_and(|(_, symbol, _)| symbol.name.starts_with("core::ptr::drop_in_place<"))
     }
 
-    #[test]
-    fn process_exists() {
-        let proc = Process::launch("yes".to_string(), true);
-        assert_eq!(proc.is_ok(), true);
+    /// Return the path of the source file and the line number of the
+    /// instruction at `address`, or `None` if it has no line number
+    /// information (or corresponds to no source line).
+    fn source_line(&self, address: VirtAddr) -> Option<(PathBuf, u64)> {
+        let (elf, row) = self.line_at(address)?;
+        let file = elf.line_table().file(row)?;
 
-        assert_eq!(check_pid(proc.unwrap().pid()), true);
+        (row.line != 0).then(|| (file.path.clone(), row.line))
     }
 
-    #[test]
-    fn process_not_exists() {
-        let proc = Process::launch("this_program_does_not_exist".to_string(), true);
-        assert_eq!(proc.is_err(), true);
+    /// Run the halted [`Process`] until the program counter reaches `address`,
+    /// using a temporary breakpoint site.
+    ///
+    /// The tracee may stop earlier (e.g., at a breakpoint site or on exit).
+    pub fn run_until(&mut self, address: VirtAddr) -> Result<StopReason> {
+        self.run_to(address, None)
     }
 
-    #[test]
-    fn process_attach_valid() {
-        // Does not request to trace the process.
-        let target = Process::launch("target/debug/run".to_string(), false);
-        assert_eq!(target.is_ok(), true);
+    /// Run the halted [`Process`] until it reaches `address`, optionally only
+    /// once the stack pointer is above the given return address `slot`.
+    fn run_to(&mut self, address: VirtAddr, slot: Option<u64>) -> Result<StopReason> {
+        // Only one `int3` can be patched in at an address, so the internal site
+        // of the dynamic loader gives way to the temporary site.
+        let at_loader_site = self.loader_site_enabled_at(address);
+
+        // An enabled software breakpoint site at the address already halts the
+        // tracee, so no temporary site is needed.
+        if !self.breakpoint_sites.software_enabled_at(address) {
+            if at_loader_site && let Some(site) = &mut self.loader_site {
+                site.disable(&mut self.registers)?;
+            }
 
-        let target = target.unwrap();
+            let mut site = BreakpointSite::new(0, self.pid, address, false);
+            site.enable(&mut self.registers)?;
 
-        let proc = Process::attach(target.pid());
-        assert_eq!(proc.is_ok(), true);
+            self.temporary_site = Some(site);
+        }
 
-        // 't' indicates tracing has stopped for the process
-        // (since `attach` sends SIGSTOP signal).
-        assert_eq!(get_process_status(target.pid()), 't')
-    }
+        let reason = self.run_to_temporary_site(address, slot);
 
-    #[test]
-    fn process_attach_invalid_pid() {
-        let proc = Process::attach(0);
-        assert_eq!(proc.is_err(), true);
+        // The site can only be removed while the tracee is still alive.
+        if let Some(mut site) = self.temporary_site.take()
+            && self.state == ProcessState::Stopped
+        {
+            site.disable(&mut self.registers)?;
+
+            if at_loader_site && let Some(site) = &mut self.loader_site {
+                site.enable(&mut self.registers)?;
+            }
+        }
+
+        reason
     }
 
-    #[test]
-    fn process_resume_valid() {
-        // Test: launch process and trace, then resume.
-        {
-            let proc = Process::launch("target/debug/run".to_string(), true);
-            assert_eq!(proc.is_ok(), true);
+    fn run_to_temporary_site(
+        &mut self,
+        address: VirtAddr,
+        slot: Option<u64>,
+    ) -> Result<StopReason> {
+        loop {
+            // Step over the temporary site if the tracee is already stopped on
+            // it (e.g., after returning from a recursive call).
+            if self.pc() == address
+                && let Some(site) = &mut self.temporary_site
+            {
+                site.disable(&mut self.registers)?;
+                let reason = self.step_instruction()?;
 
-            let mut proc = proc.unwrap();
+                if self.state != ProcessState::Stopped {
+                    return Ok(reason);
+                }
 
-            assert_eq!(proc.resume().is_ok(), true);
+                if let Some(site) = &mut self.temporary_site {
+                    site.enable(&mut self.registers)?;
+                }
 
-            // 'R' indicates the process is running and 'S' indicates the process
-            // is sleeping in an interruptible wait (waiting to be scheduled by OS).
-            assert_eq!(
-                get_process_status(proc.pid()) == 'R' || get_process_status(proc.pid()) == 'S',
-                true
-            )
+                if reason.trap != Some(TrapKind::SingleStep) {
+                    return Ok(reason);
+                }
+            }
+
+            self.resume()?;
+            let reason = self.wait_on_signal()?;
+
+            // The stack pointer is just above the return address once the
+            // function has returned.
+            let returned = slot.is_none_or(|slot| self.read_u64("rsp") > slot);
+
+            if reason.trap != Some(TrapKind::TemporaryBreakpoint) || returned {
+                return Ok(reason);
+            }
         }
+    }
 
-        // Test: launch process, attach, then resume.
-        {
-            // Does not request to trace the process.
-            let target = Process::launch("target/debug/run".to_string(), false);
-            assert_eq!(target.is_ok(), true);
+    /// Wait on a state change for the given [`Process`], returning a new [`StopReason`]
+    ///
+    /// Stops at the internal site of the dynamic loader are not reported: the
+    /// shared libraries are refreshed, and the tracee is resumed.
+    pub fn wait_on_signal(&mut self) -> Result<StopReason> {
+        loop {
+            let mut wait_status = 0;
+            let options = 0;
+
+            // Wait for state changes in the child process, unless one was
+            // already collected while resuming.
+            if let Some(status) = self.pending_status.take() {
+                wait_status = status;
+            } else if unsafe { libc::waitpid(self.pid, &mut wait_status, options) } < 0 {
+                return Err(errno!("failed to wait on tracee"));
+            }
 
-            let target = target.unwrap();
+            let mut reason = StopReason::new(wait_status);
+            self.state = reason.reason;
 
-            let proc = Process::attach(target.pid());
-            assert_eq!(proc.is_ok(), true);
+            // Registers can only be read while the tracee is stopped.
+            if self.is_attached && self.state == ProcessState::Stopped {
+                self.registers.read_all()?;
 
-            let mut proc = proc.unwrap();
+                if self.is_loader_trap(wait_status)? {
+                    self.load_libraries();
+                    self.resume()?;
+                    continue;
+                }
 
-            assert_eq!(proc.resume().is_ok(), true);
+                reason.trap = self.trap_kind(wait_status)?;
+                reason.pc = Some(self.pc());
+
+                // Shared libraries only change on `exec`, or when the dynamic
+                // loader reports it (which is also seen when the tracee stops
+                // at its internal site some other way, e.g., a single step).
+                if reason.trap == Some(TrapKind::PtraceEvent(PTRACE_EVENT_EXEC)) {
+                    self.reload_elf();
+                } else if self
+                    .loader_site
+                    .as_ref()
+                    .is_some_and(|site| site.address() == self.pc())
+                {
+                    self.load_libraries();
+                }
 
-            // 'R' indicates the process is running and 'S' indicates the process
-            // is sleeping in an interruptible wait (waiting to be scheduled by OS).
-            assert_eq!(
-                get_process_status(proc.pid()) == 'R' || get_process_status(proc.pid()) == 'S',
-                true
-            )
+                reason.symbol = self.symbolize(self.pc());
+                reason.source = self.source_location(self.pc());
+            }
+
+            return Ok(reason);
         }
     }
 
-    #[test]
-    fn process_resume_invalid() {
-        let proc = Process::launch("target/debug/end".to_string(), true);
-        assert_eq!(proc.is_ok(), true);
+    /// Return `true` if the tracee was stopped by the internal site of the
+    /// dynamic loader, rewinding the program counter to the start of the site.
+    fn is_loader_trap(&mut self, wait_status: c_int) -> Result<bool> {
+        let address = self.pc() - 1;
+
+        // Event stops are reported as `SIGTRAP | PTRACE_EVENT_* << 8`, and
+        // `int3` as a kernel-generated signal.
+        if WSTOPSIG(wait_status) != SIGTRAP
+            || wait_status >> 16 != 0
+            || !self.loader_site_enabled_at(address)
+            || self.siginfo()?.si_code != SI_KERNEL
+        {
+            return Ok(false);
+        }
 
-        let mut proc = proc.unwrap();
+        self.set_pc(address)?;
 
-        assert_eq!(proc.resume().is_ok(), true);
-        assert_eq!(proc.wait_on_signal().is_ok(), true);
+        Ok(true)
+    }
+
+    /// Return `true` if the internal site of the dynamic loader is installed
+    /// at `address`.
+    fn loader_site_enabled_at(&self, address: VirtAddr) -> bool {
+        self.loader_site
+            .as_ref()
+            .is_some_and(|site| site.address() == address && site.is_enabled())
+    }
+
+    /// Determine the cause of a `SIGTRAP` stop from the `wait_status` of the
+    /// tracee, the `si_code` of the signal, and the `dr6` debug status register.
+    ///
+    /// Executing `int3` leaves the program counter one byte past the breakpoint
+    /// site, so it is rewound to the start of the instruction.
+    fn trap_kind(&mut self, wait_status: c_int) -> Result<Option<TrapKind>> {
+        let signal = WSTOPSIG(wait_status);
+
+        if signal == SIGTRAP | SYSCALL_TRAP {
+            let info = self.syscall_info()?;
+
+            // SAFETY: The kernel fills the member of the union selected by `op`.
+            return Ok(match info.op {
+                PTRACE_SYSCALL_INFO_ENTRY => {
+                    Some(TrapKind::SyscallEntry(unsafe { info.u.entry.nr }))
+                }
+                PTRACE_SYSCALL_INFO_EXIT => {
+                    let ret = unsafe { info.u.exit.sval } as u64;
+
+                    // The system call number is only reported on entry, but is
+                    // kept in `orig_rax` until the system call returns.
+                    Some(TrapKind::SyscallExit(self.read_u64("orig_rax"), ret))
+                }
+                _ => None,
+            });
+        }
+
+        if signal != SIGTRAP {
+            return Ok(None);
+        }
+
+        // Event stops are reported as `SIGTRAP | PTRACE_EVENT_* << 8`.
+        let event = wait_status >> 16;
+
+        if event != 0 {
+            return Ok(Some(TrapKind::PtraceEvent(event)));
+        }
+
+        let trap = match self.siginfo()?.si_code {
+            // `int3` is reported as a kernel-generated signal.
+            SI_KERNEL => {
+                let address = self.pc() - 1;
+
+                let trap = if self.breakpoint_sites.software_enabled_at(address) {
+                    self.breakpoint_sites
+                        .get_by_address(address)
+                        .map(|site| TrapKind::SoftwareBreakpoint(site.id()))
+                } else if self
+                    .temporary_site
+                    .as_ref()
+                    .is_some_and(|site| site.address() == address && site.is_enabled())
+                {
+                    Some(TrapKind::TemporaryBreakpoint)
+                } else {
+                    None
+                };
+
+                if trap.is_some() {
+                    self.set_pc(address)?;
+                }
+
+                trap
+            }
+            // Debug register traps are identified by the slot reported in `dr6`.
+            code @ (TRAP_HWBKPT | TRAP_TRACE) => match take_triggered_slot(&mut self.registers)? {
+                Some(slot) => {
+                    if let Some(site) = self.breakpoint_sites.get_by_slot(slot) {
+                        Some(TrapKind::HardwareBreakpoint(site.id()))
+                    } else if let Some(watchpoint) = self.watchpoints.get_by_slot_mut(slot) {
+                        watchpoint.update_data()?;
+
+                        let size = watchpoint.size();
+
+                        Some(TrapKind::Watchpoint {
+                            id: watchpoint.id(),
+                            old: RegisterValue::from_u64(watchpoint.previous_data(), size),
+                            new: RegisterValue::from_u64(watchpoint.data(), size),
+                        })
+                    } else {
+                        None
+                    }
+                }
+                None if code == TRAP_TRACE => Some(TrapKind::SingleStep),
+                None => None,
+            },
+            _ => None,
+        };
+
+        Ok(trap)
+    }
+
+    /// Retrieve information about the signal which stopped the tracee.
+    fn siginfo(&self) -> Result<siginfo_t> {
+        // SAFETY: `siginfo_t` is a plain C struct, for which the all-zero bit
+        // pattern is valid.
+        let mut info: siginfo_t = unsafe { mem::zeroed() };
+
+        // `addr` argument is ignored.
+        if unsafe {
+            libc::ptrace(
+                PTRACE_GETSIGINFO,
+                self.pid,
+                ptr::null_mut::<c_void>(),
+                &mut info as *mut siginfo_t as *mut c_void,
+            )
+        } < 0
+        {
+            return Err(errno!("failed to retrieve signal information for tracee"));
+        }
+
+        Ok(info)
+    }
+
+    /// Retrieve information on the system call which stopped the tracee.
+    fn syscall_info(&self) -> Result<ptrace_syscall_info> {
+        // SAFETY: `ptrace_syscall_info` is a plain C struct, for which the
+        // all-zero bit pattern is valid.
+        let mut info: ptrace_syscall_info = unsafe { mem::zeroed() };
+
+        // `addr` argument is the size of the buffer.
+        if unsafe {
+            libc::ptrace(
+                PTRACE_GET_SYSCALL_INFO,
+                self.pid,
+                mem::size_of::<ptrace_syscall_info>(),
+                &mut info as *mut ptrace_syscall_info as *mut c_void,
+            )
+        } < 0
+        {
+            return Err(errno!(
+                "failed to retrieve system call information for tracee"
+            ));
+        }
+
+        Ok(info)
+    }
+
+    /// Set the ptrace options of the tracee, so system call stops can be
+    /// distinguished from other `SIGTRAP` stops, and `exec` stops the tracee
+    /// with an event.
+    fn set_ptrace_options(&self) -> Result<()> {
+        // `addr` argument is ignored.
+        if unsafe {
+            libc::ptrace(
+                PTRACE_SETOPTIONS,
+                self.pid,
+                ptr::null_mut::<c_void>(),
+                (PTRACE_O_TRACESYSGOOD | PTRACE_O_TRACEEXEC) as *mut c_void,
+            )
+        } < 0
+        {
+            return Err(errno!("failed to set ptrace options for tracee"));
+        }
+
+        Ok(())
+    }
+
+    /// Parse the executable of the tracee, as linked by `/proc/<pid>/exe`,
+    /// along with the shared libraries it has loaded. The tracee can still be
+    /// debugged without them, so failures are only logged.
+    fn load_elf(&mut self) {
+        let path = match fs::read_link(format!("/proc/{}/exe", self.pid)) {
+            Ok(path) => path,
+            Err(err) => {
+                log_err(
+                    "rdbg",
+                    format!("failed to resolve tracee executable: {err}"),
+                );
+                return;
+            }
+        };
+
+        let mut elf = match Elf::open(&path) {
+            Ok(elf) => elf,
+            Err(err) => {
+                log_err("rdbg", err);
+                return;
+            }
+        };
+
+        match self.executable_load_bias(&elf) {
+            Ok(load_bias) => elf.set_load_bias(load_bias),
+            Err(err) => log_err("rdbg", err),
+        }
+
+        self.elf = Some(elf);
+        self.load_libraries();
+        self.set_loader_site();
+    }
+
+    /// Parse the executable of the tracee again, along with its shared
+    /// libraries, once it has executed a new program. The internal breakpoint
+    /// sites went away with the old program.
+    fn reload_elf(&mut self) {
+        self.temporary_site = None;
+        self.loader_site = None;
+        self.elf = None;
+        self.libraries.clear();
+
+        self.load_elf();
+    }
+
+    /// Install the internal breakpoint site at `_dl_debug_state`, which the
+    /// dynamic loader calls each time it changes the set of loaded shared
+    /// libraries. Statically linked executables have no dynamic loader, and so
+    /// no such site.
+    fn set_loader_site(&mut self) {
+        let Some(&(address, _, _)) = self.find_symbols("_dl_debug_state").first() else {
+            return;
+        };
+
+        let mut site = BreakpointSite::new(0, self.pid, address, false);
+
+        match site.enable(&mut self.registers) {
+            Ok(()) => self.loader_site = Some(site),
+            Err(err) => log_err("rdbg", err),
+        }
+    }
+
+    /// Determine the load bias of the executable `elf` from the auxiliary
+    /// vector of the tracee, which holds the addresses in memory of its
+    /// program headers (`AT_PHDR`) and entry point (`AT_ENTRY`).
+    fn executable_load_bias(&self, elf: &Elf) -> Result<u64> {
+        let auxv = AuxVector::read(self.pid)?;
+
+        // The program headers are only described by a segment (and so have a
+        // known address within the file) if they are loaded.
+        if let Some(phdr) = auxv.get(AT_PHDR)
+            && let Some(segment) = elf
+                .program_headers()
+                .iter()
+                .find(|segment| segment.segment_type == PT_PHDR)
+        {
+            return Ok(phdr.wrapping_sub(segment.vaddr));
+        }
+
+        let entry = auxv
+            .get(AT_ENTRY)
+            .ok_or("auxiliary vector of the tracee has no entry point")?;
+
+        Ok(entry.wrapping_sub(elf.header().entry))
+    }
+
+    /// Parse the shared libraries mapped into the tracee which have not been
+    /// already, computing each of their load biases from the start of their
+    /// first mapping. Libraries which have since been unmapped are dropped.
+    ///
+    /// Reading the mappings is not free, so this is only done when the set of
+    /// libraries may have changed (see [`Process::wait_on_signal`]).
+    fn load_libraries(&mut self) {
+        let Ok(map) = self.memory_map() else {
+            return;
+        };
+
+        let executable = self.elf.as_ref().map(|elf| elf.path().to_path_buf());
+        let mut libraries: Vec<Elf> = Vec::new();
+
+        for region in map.regions() {
+            let RegionBacking::File(path) = &region.backing else {
+                continue;
+            };
+
+            if region.offset != 0
+                || Some(path) == executable.as_ref()
+                || self.non_elf_files.contains(path)
+                || libraries.iter().any(|library| library.path() == path)
+            {
+                continue;
+            }
+
+            let library = match self
+                .libraries
+                .iter()
+                .position(|library| library.path() == path)
+            {
+                Some(idx) => Ok(self.libraries.swap_remove(idx)),
+                None => Elf::open(path),
+            };
+
+            // Files other than libraries are mapped too (e.g., locale data), so
+            // files which cannot be parsed are remembered and skipped.
+            let Ok(mut library) = library else {
+                self.non_elf_files.insert(path.clone());
+                continue;
+            };
+
+            let page_size = memory::page_size() as u64;
+
+            if let Some(load_bias) = library.load_bias_from(VirtAddr(region.start), page_size) {
+                library.set_load_bias(load_bias);
+            }
+
+            libraries.push(library);
+        }
+
+        self.libraries = libraries;
+    }
+
+    /// Read the general-purpose register with the given `name` as a `u64`.
+    fn read_u64(&self, name: &str) -> u64 {
+        RegisterInfo::register_info_by_name(name)
+            .and_then(|info| self.registers.read(info).to_u64())
+            .unwrap_or_default()
+    }
+
+    /// Return the process ID of the given [`Process`].
+    pub fn pid(&self) -> pid_t {
+        self.pid
+    }
+
+    /// Return the parsed executable of the given [`Process`], if it could be
+    /// loaded.
+    pub fn elf(&self) -> Option<&Elf> {
+        self.elf.as_ref()
+    }
+
+    /// Return the parsed shared libraries loaded by the given [`Process`], as
+    /// of the last time the dynamic loader changed them.
+    pub fn libraries(&self) -> &[Elf] {
+        &self.libraries
+    }
+
+    /// Return the parsed ELF objects of the given [`Process`]: its executable,
+    /// followed by its shared libraries.
+    pub fn objects(&self) -> impl Iterator<Item = &Elf> {
+        self.elf.iter().chain(&self.libraries)
+    }
+
+    /// Look up the ELF object with a loadable segment containing `address`,
+    /// returning it along with the corresponding address within the object.
+    pub fn object_containing(&self, address: VirtAddr) -> Option<(&Elf, FileAddr)> {
+        self.objects()
+            .find_map(|elf| Some((elf, address.to_file(elf)?)))
+    }
+
+    /// Look up the symbol containing `address`, returning it along with the
+    /// ELF object defining it and the offset of `address` from its start.
+    pub fn symbol_at(&self, address: VirtAddr) -> Option<(&Elf, &IndexedSymbol, u64)> {
+        // Addresses outside of any object (e.g., in the heap) are not
+        // described by its symbols, even if they follow one of unknown size.
+        let (elf, file_address) = self.object_containing(address)?;
+        let (symbol, offset) = elf.symbol_index().find_by_address(file_address)?;
+
+        Some((elf, symbol, offset))
+    }
+
+    /// Describe `address` by the symbol containing it (e.g., `main+0x1c`).
+    pub fn symbolize(&self, address: VirtAddr) -> Option<String> {
+        let (_, symbol, offset) = self.symbol_at(address)?;
+
+        Some(match offset {
+            0 => symbol.name.clone(),
+            offset => format!("{}+{offset:#x}", symbol.name),
+        })
+    }
+
+    /// Look up the line number information describing the instruction at
+    /// `address`, returning the row along with the ELF object it belongs to.
+    pub fn line_at(&self, address: VirtAddr) -> Option<(&Elf, &LineRow)> {
+        let (elf, file_address) = self.object_containing(address)?;
+        let row = elf.line_table().find_by_address(file_address)?;
+
+        Some((elf, row))
+    }
+
+    /// Describe `address` by its source location (e.g., `src/main.rs:42:5`),
+    /// omitting the column if it is unknown. Code which corresponds to no
+    /// source line (i.e., line 0) has no location.
+    pub fn source_location(&self, address: VirtAddr) -> Option<String> {
+        let (elf, row) = self.line_at(address).filter(|(_, row)| row.line != 0)?;
+        let file = elf.line_table().file(row)?;
+
+        Some(match row.column {
+            0 => format!("{}:{}", file.path.display(), row.line),
+            column => format!("{}:{}:{column}", file.path.display(), row.line),
+        })
+    }
+
+    /// Look up the addresses in memory at which the code of `line` of the
+    /// source files whose path ends with `path` begins (see
+    /// [`LineTable::find_by_line`](crate::dwarf::LineTable::find_by_line)).
+    pub fn find_lines(&self, path: &Path, line: u64) -> Vec<VirtAddr> {
+        self.objects()
+            .flat_map(|elf| {
+                elf.line_table()
+                    .find_by_line(path, line)
+                    .into_iter()
+                    .map(move |row| row.address.to_virt(elf))
+            })
+            .collect()
+    }
+
+    /// Look up the function whose code includes `address`, followed by the
+    /// functions inlined into it which do, from outermost to innermost (see
+    /// [`DebugInfo::scopes_at`](crate::dwarf::DebugInfo::scopes_at)), along
+    /// with the ELF object describing them.
+    pub fn scopes_at(&self, address: VirtAddr) -> Option<(&Elf, Vec<Scope<'_>>)> {
+        let (elf, file_address) = self.object_containing(address)?;
+        let scopes = elf.debug_info().scopes_at(file_address);
+
+        Some((elf, scopes))
+    }
+
+    /// Look up the source file whose path ends with `path`, returning its path
+    /// as named by the line number information.
+    pub fn find_source_file(&self, path: &Path) -> Option<&Path> {
+        self.objects()
+            .flat_map(|elf| elf.line_table().files())
+            .map(|file| file.path.as_path())
+            .find(|file| file.ends_with(path))
+    }
+
+    /// Return the compilation directory of the unit whose line number program
+    /// names the source file `path`, to which a relative path is relative.
+    pub fn comp_dir(&self, path: &Path) -> Option<&Path> {
+        self.objects().find_map(|elf| {
+            let table = elf.line_table();
+            let file = table.files().iter().position(|file| file.path == path)?;
+            let offset = table.program_offset(file)?;
+
+            elf.debug_info()
+                .units()
+                .iter()
+                .find(|unit| unit.stmt_list == Some(offset))?
+                .comp_dir
+                .as_deref()
+        })
+    }
+
+    /// Return the locations of the source files of the tracee.
+    pub fn sources(&self) -> &Sources {
+        &self.sources
+    }
+
+    /// Return a mutable reference to the locations of the source files of the
+    /// tracee.
+    pub fn sources_mut(&mut self) -> &mut Sources {
+        &mut self.sources
+    }
+
+    /// Look up the symbols with the given demangled or linkage `name`,
+    /// returning them along with their addresses in memory and the ELF objects
+    /// defining them.
+    pub fn find_symbols(&self, name: &str) -> Vec<(VirtAddr, &Elf, &IndexedSymbol)> {
+        self.objects()
+            .flat_map(|elf| {
+                elf.symbol_index()
+                    .find_by_name(name)
+                    .into_iter()
+                    .map(move |symbol| (symbol.address.to_virt(elf), elf, symbol))
+            })
+            .collect()
+    }
+
+    /// Return the current state of the given [`Process`].
+    pub fn state(&self) -> ProcessState {
+        self.state
+    }
+
+    /// Return a reference to the [`Registers`] of the given [`Process`].
+    pub fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    /// Return a mutable reference to the [`Registers`] of the given [`Process`].
+    pub fn registers_mut(&mut self) -> &mut Registers {
+        &mut self.registers
+    }
+
+    /// Return the program counter (`rip`) of the given [`Process`], as of the
+    /// last time it stopped.
+    pub fn pc(&self) -> VirtAddr {
+        VirtAddr(
+            self.registers
+                .read(Self::pc_info())
+                .to_u64()
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Set the program counter (`rip`) of the stopped [`Process`].
+    pub fn set_pc(&mut self, address: VirtAddr) -> Result<()> {
+        self.registers
+            .write(Self::pc_info(), RegisterValue::U64(address.0))
+    }
+
+    fn pc_info() -> &'static RegisterInfo {
+        RegisterInfo::register_info_by_name("rip").expect("rip should be a known register")
+    }
+
+    /// Create a new, disabled [`BreakpointSite`] at the given `address`,
+    /// optionally using a debug register rather than `int3`.
+    pub fn create_breakpoint_site(&mut self, address: VirtAddr, hardware: bool) -> Result<usize> {
+        if !self.memory_map()?.is_mapped(address.0, 1) {
+            return Err(
+                format!("cannot set breakpoint at {address:#x}: address is not mapped").into(),
+            );
+        }
+
+        let pid = self.pid;
+
+        let site = self.breakpoint_sites.push(address, |id| {
+            Ok(BreakpointSite::new(id, pid, address, hardware))
+        })?;
+
+        Ok(site.id())
+    }
+
+    /// Enable the [`BreakpointSite`] with the given `id`.
+    pub fn enable_breakpoint_site(&mut self, id: usize) -> Result<()> {
+        let site = self
+            .breakpoint_sites
+            .get_mut(id)
+            .ok_or_else(|| format!("no breakpoint with id {id}"))?;
+
+        // Only one `int3` can be patched in at an address, so the internal site
+        // of the dynamic loader gives way to the breakpoint site.
+        if !site.is_hardware()
+            && let Some(loader_site) = &mut self.loader_site
+            && loader_site.address() == site.address()
+        {
+            loader_site.disable(&mut self.registers)?;
+        }
+
+        site.enable(&mut self.registers)
+    }
+
+    /// Disable the [`BreakpointSite`] with the given `id`.
+    pub fn disable_breakpoint_site(&mut self, id: usize) -> Result<()> {
+        let site = self
+            .breakpoint_sites
+            .get_mut(id)
+            .ok_or_else(|| format!("no breakpoint with id {id}"))?;
+
+        site.disable(&mut self.registers)?;
+
+        if let Some(loader_site) = &mut self.loader_site
+            && loader_site.address() == site.address()
+        {
+            loader_site.enable(&mut self.registers)?;
+        }
+
+        Ok(())
+    }
+
+    /// Disable and remove the [`BreakpointSite`] with the given `id`.
+    pub fn remove_breakpoint_site(&mut self, id: usize) -> Result<()> {
+        self.disable_breakpoint_site(id)?;
+        self.breakpoint_sites.take(id);
+
+        Ok(())
+    }
+
+    /// Return a reference to the [`BreakpointSites`] of the given [`Process`].
+    pub fn breakpoint_sites(&self) -> &BreakpointSites {
+        &self.breakpoint_sites
+    }
+
+    /// Create a new, disabled [`Watchpoint`] on the `size` bytes at `address`.
+    /// The address must be aligned to `size`.
+    pub fn create_watchpoint(
+        &mut self,
+        address: VirtAddr,
+        mode: StoppointMode,
+        size: usize,
+    ) -> Result<usize> {
+        if !self.memory_map()?.is_mapped(address.0, size as u64) {
+            return Err(
+                format!("cannot set watchpoint at {address:#x}: address is not mapped").into(),
+            );
+        }
+
+        let pid = self.pid;
+
+        let watchpoint = self
+            .watchpoints
+            .push(address, |id| Watchpoint::new(id, pid, address, mode, size))?;
+
+        Ok(watchpoint.id())
+    }
+
+    /// Enable the [`Watchpoint`] with the given `id`.
+    pub fn enable_watchpoint(&mut self, id: usize) -> Result<()> {
+        self.watchpoints
+            .get_mut(id)
+            .ok_or_else(|| format!("no watchpoint with id {id}"))?
+            .enable(&mut self.registers)
+    }
+
+    /// Disable the [`Watchpoint`] with the given `id`.
+    pub fn disable_watchpoint(&mut self, id: usize) -> Result<()> {
+        self.watchpoints
+            .get_mut(id)
+            .ok_or_else(|| format!("no watchpoint with id {id}"))?
+            .disable(&mut self.registers)
+    }
+
+    /// Disable and remove the [`Watchpoint`] with the given `id`.
+    pub fn remove_watchpoint(&mut self, id: usize) -> Result<()> {
+        self.disable_watchpoint(id)?;
+        self.watchpoints.take(id);
+
+        Ok(())
+    }
+
+    /// Return a reference to the [`Watchpoints`] of the given [`Process`].
+    pub fn watchpoints(&self) -> &Watchpoints {
+        &self.watchpoints
+    }
+
+    /// Read the current [`MemoryMap`] of the [`Process`].
+    pub fn memory_map(&self) -> Result<MemoryMap> {
+        MemoryMap::read(self.pid)
+    }
+
+    /// Read `len` bytes from the memory of the [`Process`] at `address`.
+    ///
+    /// If only part of the range is mapped, a [`PartialRead`] holding the
+    /// readable bytes is returned.
+    pub fn read_memory(&self, address: VirtAddr, len: usize) -> Result<Vec<u8>> {
+        memory::read_memory(self.pid, address.0, len)
+    }
+
+    /// Read `len` bytes from the memory of the [`Process`] at `address`,
+    /// replacing the `int3` bytes of enabled software breakpoint sites with the
+    /// original data.
+    ///
+    /// If only part of the range is mapped, a [`PartialRead`] holding the
+    /// readable bytes is returned.
+    pub fn read_memory_without_traps(&self, address: VirtAddr, len: usize) -> Result<Vec<u8>> {
+        match self.read_memory(address, len) {
+            Ok(mut data) => {
+                self.replace_traps(address, &mut data);
+                Ok(data)
+            }
+            Err(err) => match err.downcast::<PartialRead>() {
+                Ok(mut partial) => {
+                    self.replace_traps(address, &mut partial.data);
+                    Err(partial)
+                }
+                Err(err) => Err(err),
+            },
+        }
+    }
+
+    /// Replace the `int3` bytes of enabled software breakpoint sites within
+    /// `data`, read from `address`, with the original data.
+    fn replace_traps(&self, address: VirtAddr, data: &mut [u8]) {
+        let sites = self
+            .breakpoint_sites
+            .iter()
+            .chain(&self.temporary_site)
+            .chain(&self.loader_site);
+
+        for site in sites.filter(|site| site.is_enabled() && !site.is_hardware()) {
+            if let Some(idx) = site.address().0.checked_sub(address.0)
+                && let Some(byte) = data.get_mut(idx as usize)
+            {
+                *byte = site.saved_data();
+            }
+        }
+    }
+
+    /// Write `data` into the memory of the [`Process`] at `address`, including
+    /// read-only pages.
+    ///
+    /// Enabled software breakpoint sites within the range remain installed,
+    /// with their original data replaced by the written bytes.
+    pub fn write_memory(&mut self, address: VirtAddr, data: &[u8]) -> Result<()> {
+        let end = memory::check_range(address.0, data.len(), "write")?;
+
+        if !self.memory_map()?.is_mapped(address.0, data.len() as u64) {
+            return Err(format!(
+                "cannot write memory at {address:#x}: address range is not mapped"
+            )
+            .into());
+        }
+
+        let mut overlapping: Vec<&mut BreakpointSite> = self
+            .breakpoint_sites
+            .iter_mut()
+            .chain(&mut self.loader_site)
+            .filter(|site| {
+                site.is_enabled()
+                    && !site.is_hardware()
+                    && (address.0..end).contains(&site.address().0)
+            })
+            .collect();
+
+        for site in &mut overlapping {
+            site.disable(&mut self.registers)?;
+        }
+
+        let result = memory::write_memory(self.pid, address.0, data);
+
+        for site in overlapping {
+            site.enable(&mut self.registers)?;
+        }
+
+        result
+    }
+
+    /// Decode the instruction at `address` from the memory of the [`Process`],
+    /// as it would execute without any breakpoint sites.
+    pub fn decode_instruction(&self, address: VirtAddr) -> Result<Instruction> {
+        disasm::decode(&self.read_instruction(address)?, address.0)
+    }
+
+    /// Read the bytes of the instruction at `address`, which may be shorter
+    /// than the maximum instruction length at the end of a mapping.
+    fn read_instruction(&self, address: VirtAddr) -> Result<Vec<u8>> {
+        match self.read_memory_without_traps(address, MAX_INSTRUCTION_LEN) {
+            Ok(code) => Ok(code),
+            Err(err) => match err.downcast::<PartialRead>() {
+                Ok(partial) if !partial.data.is_empty() => Ok(partial.data),
+                Ok(partial) => Err(partial),
+                Err(err) => Err(err),
+            },
+        }
+    }
+
+    /// Record the registers of the given [`Process`] at its current stop, to
+    /// be compared against those at the previously recorded stop.
+    ///
+    /// Only stops reported to the user are recorded, so that the registers
+    /// changed by a command which stops the tracee several times internally
+    /// (e.g., `stepi 3`, or stepping over a breakpoint site) are compared as
+    /// a whole. There is nothing to compare against on the first stop, or
+    /// once the tracee is no longer stopped.
+    pub fn record_stop(&mut self) {
+        if self.is_attached && self.state == ProcessState::Stopped {
+            self.previous_registers = self.stop_registers.take();
+            self.stop_registers = Some(self.registers.snapshot());
+        } else {
+            self.previous_registers = None;
+            self.stop_registers = None;
+        }
+    }
+
+    /// Compare every register between the previous recorded stop and the most
+    /// recent recorded stop of the given [`Process`] (see
+    /// [`Process::record_stop`]).
+    pub fn register_delta(&self) -> RegisterDelta {
+        match (&self.previous_registers, &self.stop_registers) {
+            (Some(old), Some(new)) => RegisterDelta::new(old, new),
+            _ => RegisterDelta::default(),
+        }
+    }
+
+    /// Return the change to the register described by `info` between the
+    /// previous recorded stop and the most recent recorded stop of the given
+    /// [`Process`], or `None` if it did not change.
+    pub fn register_change(&self, info: &'static RegisterInfo) -> Option<RegisterChange> {
+        RegisterChange::compare(
+            info,
+            self.previous_registers.as_ref()?,
+            self.stop_registers.as_ref()?,
+        )
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        if self.pid != 0 {
+            let pid = self.pid;
+            let mut status = 0;
+
+            unsafe {
+                if self.is_attached {
+                    if self.state == ProcessState::Running {
+                        // Send a signal to the tracee to stop execution.
+                        libc::kill(pid, SIGSTOP);
+                        // Wait for a state change from the tracee.
+                        libc::waitpid(pid, &mut status, 0);
+                    }
+
+                    // Remove stoppoints, so the detached tracee does not trap.
+                    for site in self.breakpoint_sites.iter_mut() {
+                        let _ = site.disable(&mut self.registers);
+                    }
+                    for watchpoint in self.watchpoints.iter_mut() {
+                        let _ = watchpoint.disable(&mut self.registers);
+                    }
+                    for site in self.temporary_site.iter_mut().chain(&mut self.loader_site) {
+                        let _ = site.disable(&mut self.registers);
+                    }
+
+                    // Detach from the tracee, then restart execution.
+                    libc::ptrace(
+                        PTRACE_DETACH,
+                        pid,
+                        ptr::null_mut::<c_void>(),
+                        ptr::null_mut::<c_void>(),
+                    );
+                    // Send a signal to tracee to ensure it continues execution.
+                    libc::kill(pid, SIGCONT);
+                }
+
+                // Terminate tracee if it was spawned due to [`Process::launch`].
+                if self.terminate {
+                    libc::kill(pid, SIGKILL);
+                    libc::waitpid(pid, &mut status, 0);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io;
+
+    use super::*;
+    use crate::core::{
+        DecodedRegister, F80, FpuTag, PrecisionControl, RegionBacking, RegisterInfo, RegisterValue,
+        RoundingMode,
+    };
+
+    fn get_process_status(pid: pid_t) -> char {
+        // The `/proc` directory in Linux is a virtual filesystem that stores
+        // information about processes and other systems activities (Linux `procfs`),
+        // organized as files.
+        //
+        // The `/proc/[pid]/stat` file gives high-level information on the
+        // current state of a given process.
+        let stats = fs::read_to_string(format!("/proc/{pid}/stat"))
+            .unwrap_or_else(|err| panic!("failed to read proc stats for /proc/{pid}/stat': {err}"));
+
+        let last_paren_idx = stats
+            .rfind(')')
+            .unwrap_or_else(|| panic!("unexpected format for /proc/{pid}/stat"));
+
+        // Return the state indicator.
+        stats
+            .chars()
+            .nth(last_paren_idx + 2) // located right after the closing parenthesis + 2
+            .unwrap_or_else(|| panic!("process state indicator missing for /proc/{pid}/stat"))
+    }
+
+    fn check_pid(pid: pid_t) -> bool {
+        // If signal is 0, then no signal is sent, but existence and permission
+        // checks are still performed on the given `pid`.
+        let ret = unsafe { libc::kill(pid, 0) };
+
+        let errno = io::Error::last_os_error().to_string();
+
+        ret != -1 && !errno.contains("No such process")
+    }
+
+    #[test]
+    fn process_exists() {
+        let proc = Process::launch("yes".to_string(), true);
+        assert!(proc.is_ok());
+
+        assert!(check_pid(proc.unwrap().pid()));
+    }
+
+    #[test]
+    fn process_not_exists() {
+        let proc = Process::launch("this_program_does_not_exist".to_string(), true);
+        assert!(proc.is_err());
+    }
+
+    #[test]
+    fn process_attach_valid() {
+        // Does not request to trace the process.
+        let target = Process::launch("target/debug/infinite_run".to_string(), false);
+        assert!(target.is_ok());
+
+        let target = target.unwrap();
+
+        let proc = Process::attach(target.pid());
+        assert!(proc.is_ok());
+
+        // 't' indicates tracing has stopped for the process
+        // (since `attach` sends SIGSTOP signal).
+        assert_eq!(get_process_status(target.pid()), 't')
+    }
+
+    #[test]
+    fn process_attach_invalid_pid() {
+        let proc = Process::attach(0);
+        assert!(proc.is_err());
+    }
+
+    #[test]
+    fn process_resume_valid() {
+        // Test: launch process and trace, then resume.
+        {
+            let proc = Process::launch("target/debug/infinite_run".to_string(), true);
+            assert!(proc.is_ok());
+
+            let mut proc = proc.unwrap();
+
+            assert!(proc.resume().is_ok());
+
+            // 'R' indicates the process is running and 'S' indicates the process
+            // is sleeping in an interruptible wait (waiting to be scheduled by OS).
+            assert!(get_process_status(proc.pid()) == 'R' || get_process_status(proc.pid()) == 'S')
+        }
+
+        // Test: launch process, attach, then resume.
+        {
+            // Does not request to trace the process.
+            let target = Process::launch("target/debug/infinite_run".to_string(), false);
+            assert!(target.is_ok());
+
+            let target = target.unwrap();
+
+            let proc = Process::attach(target.pid());
+            assert!(proc.is_ok());
+
+            let mut proc = proc.unwrap();
+
+            assert!(proc.resume().is_ok());
+
+            // 'R' indicates the process is running and 'S' indicates the process
+            // is sleeping in an interruptible wait (waiting to be scheduled by OS).
+            assert!(get_process_status(proc.pid()) == 'R' || get_process_status(proc.pid()) == 'S')
+        }
+    }
+
+    #[test]
+    fn process_resume_invalid() {
+        let proc = Process::launch("target/debug/early_end".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        assert!(proc.resume().is_err());
+    }
+
+    #[test]
+    fn process_read_registers() {
+        let proc = Process::launch("target/debug/reg_read".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        let r13 = RegisterInfo::register_info_by_name("r13").unwrap();
+        let r13b = RegisterInfo::register_info_by_name("r13b").unwrap();
+        let xmm0 = RegisterInfo::register_info_by_name("xmm0").unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+        assert_eq!(proc.registers().read(r13), RegisterValue::U64(0xcafecafe));
+
+        // The thread pointer is set up before `main` is reached.
+        let fs_base = RegisterInfo::register_info_by_dwarf(58).unwrap();
+        assert_eq!(fs_base.name, "fs_base");
+        assert_ne!(proc.registers().read(fs_base), RegisterValue::U64(0));
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+        assert_eq!(proc.registers().read(r13b), RegisterValue::U8(42));
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+        assert_eq!(
+            proc.registers().read(xmm0).to_bytes()[..8],
+            0xba5eba11u64.to_le_bytes()
+        );
+
+        let ah = RegisterInfo::register_info_by_name("ah").unwrap();
+        let al = RegisterInfo::register_info_by_name("al").unwrap();
+        let ax = RegisterInfo::register_info_by_name("ax").unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+        assert_eq!(proc.registers().read(ah), RegisterValue::U8(0x77));
+        assert_eq!(proc.registers().read(al), RegisterValue::U8(0x88));
+        assert_eq!(proc.registers().read(ax), RegisterValue::U16(0x7788));
+    }
+
+    #[test]
+    fn process_write_registers() {
+        let proc = Process::launch("target/debug/reg_write".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        let r13 = RegisterInfo::register_info_by_name("r13").unwrap();
+        let xmm0 = RegisterInfo::register_info_by_name("xmm0").unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        // Signed values are sign-extended into integer registers.
+        assert!(
+            proc.registers_mut()
+                .write(r13, RegisterValue::I8(-1))
+                .is_ok()
+        );
+        assert_eq!(proc.registers().read(r13), RegisterValue::U64(u64::MAX));
+
+        assert!(
+            proc.registers_mut()
+                .write(r13, RegisterValue::U8(0x11))
+                .is_ok()
+        );
+        // Narrower values are zero-extended into the vector register.
+        assert!(
+            proc.registers_mut()
+                .write(xmm0, RegisterValue::U64(0x22))
+                .is_ok()
+        );
+        assert_eq!(proc.registers().read(r13), RegisterValue::U64(0x11));
+
+        // Values wider than the register are rejected.
+        let r13d = RegisterInfo::register_info_by_name("r13d").unwrap();
+        assert!(
+            proc.registers_mut()
+                .write(r13d, RegisterValue::U64(0))
+                .is_err()
+        );
+
+        assert!(proc.resume().is_ok());
+
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.reason, ProcessState::Exited);
+        assert_eq!(reason.info, 0x33);
+    }
+
+    #[test]
+    fn process_write_sub_registers() {
+        let proc = Process::launch("target/debug/reg_write".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        let rax = RegisterInfo::register_info_by_name("rax").unwrap();
+        let eax = RegisterInfo::register_info_by_name("eax").unwrap();
+        let ax = RegisterInfo::register_info_by_name("ax").unwrap();
+        let ah = RegisterInfo::register_info_by_name("ah").unwrap();
+        let al = RegisterInfo::register_info_by_name("al").unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        // Re-read the registers from the tracee after each write, to verify the
+        // tracee state rather than the cached copy.
+        let mut write = |info, value: RegisterValue, expected: u64| {
+            assert!(
+                proc.registers_mut()
+                    .write(rax, RegisterValue::U64(u64::MAX))
+                    .is_ok()
+            );
+            assert!(proc.registers_mut().write(info, value).is_ok());
+            assert!(proc.registers_mut().read_all().is_ok());
+            assert_eq!(proc.registers().read(rax), RegisterValue::U64(expected));
+        };
+
+        write(ah, RegisterValue::U8(0x12), 0xffff_ffff_ffff_12ff);
+        write(al, RegisterValue::U8(0x34), 0xffff_ffff_ffff_ff34);
+        write(ax, RegisterValue::U16(0x5678), 0xffff_ffff_ffff_5678);
+        // 32-bit writes zero-extend into the parent register.
+        write(eax, RegisterValue::U32(0x9abc), 0x0000_0000_0000_9abc);
+    }
+
+    #[test]
+    fn process_extended_registers() {
+        if !std::arch::is_x86_feature_detected!("avx512f") {
+            return;
+        }
+
+        let proc = Process::launch("target/debug/reg_xstate".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        let reg = |name| RegisterInfo::register_info_by_name(name).unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        assert!(proc.registers().is_available(reg("zmm31")));
+        assert_eq!(proc.registers().read(reg("zmm1")), [0x11; 64].into());
+        assert_eq!(proc.registers().read(reg("ymm1")), [0x11; 32].into());
+        assert_eq!(proc.registers().read(reg("xmm1")), [0x11; 16].into());
+        assert_eq!(proc.registers().read(reg("k1")), RegisterValue::U64(0xf0f0));
+
+        let mut ymm2 = [0u8; 32];
+        ymm2[16] = 0x20;
+
+        assert!(proc.registers_mut().write(reg("ymm2"), ymm2.into()).is_ok());
+        assert!(
+            proc.registers_mut()
+                .write(reg("k2"), RegisterValue::U8(0x13))
+                .is_ok()
+        );
+
+        // Writes to `ymm2` are reflected in the overlapping registers.
+        assert_eq!(proc.registers().read(reg("zmm2")).to_bytes()[..32], ymm2);
+
+        assert!(proc.resume().is_ok());
+
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.reason, ProcessState::Exited);
+        assert_eq!(reason.info, 0x33);
+    }
+
+    #[test]
+    fn process_decode_registers() {
+        let proc = Process::launch("target/debug/reg_read".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        let reg = |name| RegisterInfo::register_info_by_name(name).unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        let Some(DecodedRegister::Eflags(eflags)) = proc.registers().decode(reg("eflags")) else {
+            panic!("eflags should be decoded");
+        };
+        assert!(eflags.flags().contains(&"IF"));
+        assert!(!eflags.flags().contains(&"CF"));
+
+        assert!(
+            proc.registers_mut()
+                .set_flag(reg("eflags"), "cf", true)
+                .is_ok()
+        );
+        assert!(proc.registers_mut().read_all().is_ok());
+
+        let Some(DecodedRegister::Eflags(eflags)) = proc.registers().decode(reg("eflags")) else {
+            panic!("eflags should be decoded");
+        };
+        assert!(eflags.flags().contains(&"CF"));
+
+        // Multi-bit fields cannot be set as flags.
+        assert!(
+            proc.registers_mut()
+                .set_flag(reg("fcw"), "RC", true)
+                .is_err()
+        );
+
+        let Some(DecodedRegister::FpuControl(fcw)) = proc.registers().decode(reg("fcw")) else {
+            panic!("fcw should be decoded");
+        };
+        assert_eq!(fcw.precision(), PrecisionControl::Extended);
+        assert_eq!(fcw.rounding(), RoundingMode::Nearest);
+
+        let Some(DecodedRegister::FpuTag(ftw)) = proc.registers().decode(reg("ftw")) else {
+            panic!("ftw should be decoded");
+        };
+        assert_eq!(ftw.tags, [FpuTag::Empty; 8]);
+        assert_eq!(ftw.full(), 0xffff);
+
+        assert!(proc.registers().decode(reg("rax")).is_none());
+    }
+
+    #[test]
+    fn process_x87_registers() {
+        let proc = Process::launch("target/debug/reg_x87".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        let reg = |name| RegisterInfo::register_info_by_name(name).unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        let RegisterValue::LongDouble(pi) = proc.registers().read(reg("st0")) else {
+            panic!("st0 should hold a long double");
+        };
+        assert_eq!(pi.to_string(), "3.1415926535897932385");
+        assert_eq!(pi.to_f64(), std::f64::consts::PI);
+
+        // Written without rounding through `f64`.
+        let tenth: F80 = "0.1".parse().unwrap();
+        assert!(
+            proc.registers_mut()
+                .write(reg("st1"), RegisterValue::LongDouble(tenth))
+                .is_ok()
+        );
+        assert_eq!(
+            proc.registers().read(reg("st1")),
+            RegisterValue::LongDouble(tenth)
+        );
+        assert_ne!(tenth, F80::from_f64(0.1));
+
+        // `fld` decrements `TOP`, so `ST(0)` is the last physical register pushed.
+        let Some(DecodedRegister::X87Stack(slot)) = proc.registers().decode(reg("st0")) else {
+            panic!("st0 should be decoded");
+        };
+        assert_eq!(slot.physical, 6);
+        assert_eq!(slot.tag, FpuTag::Valid);
+
+        let Some(DecodedRegister::X87Stack(slot)) = proc.registers().decode(reg("st2")) else {
+            panic!("st2 should be decoded");
+        };
+        assert_eq!(slot.physical, 0);
+        assert_eq!(slot.tag, FpuTag::Empty);
+    }
+
+    #[test]
+    fn process_register_delta() {
+        let proc = Process::launch("target/debug/reg_read".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        let reg = |name| RegisterInfo::register_info_by_name(name).unwrap();
+
+        // Nothing to compare against on the initial stop.
+        assert!(proc.register_delta().is_empty());
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+        proc.record_stop();
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+        proc.record_stop();
+
+        let delta = proc.register_delta();
+
+        let change = delta.change(reg("r13b"));
+        assert!(change.is_some());
+        assert_eq!(change.unwrap().old, RegisterValue::U8(0xfe));
+        assert_eq!(change.unwrap().new, RegisterValue::U8(42));
+
+        assert!(delta.change(reg("r13")).is_some());
+        assert!(delta.change(reg("rip")).is_some());
+        assert!(delta.change(reg("rax")).is_none());
+        assert!(delta.change(reg("rsp")).is_none());
+
+        // Stops which are not recorded do not affect the comparison.
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+        assert!(proc.register_delta().change(reg("r13b")).is_some());
+        assert!(proc.register_delta().change(reg("rax")).is_none());
+    }
+
+    #[test]
+    fn process_register_delta_steps() {
+        let proc = Process::launch("target/debug/stepping".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        let reg = |name| RegisterInfo::register_info_by_name(name).unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+        proc.record_stop();
+
+        let rsp = proc.read_u64("rsp");
+
+        // As with `stepi 3`: `call`, `push rbp`, and `mov rbp, rsp` are
+        // compared as a whole, rather than against the last step.
+        for _ in 0..3 {
+            assert!(proc.step_instruction().is_ok());
+        }
+        proc.record_stop();
+
+        let delta = proc.register_delta();
+
+        let change = delta.change(reg("rsp")).unwrap();
+        assert_eq!(change.old, RegisterValue::U64(rsp));
+        assert_eq!(change.new, RegisterValue::U64(rsp - 16));
+
+        let change = delta.change(reg("rbp")).unwrap();
+        assert_eq!(change.new, RegisterValue::U64(rsp - 16));
+        assert!(delta.change(reg("rip")).is_some());
+    }
+
+    #[test]
+    fn process_breakpoint_sites() {
+        let proc = Process::launch("target/debug/breakpoints".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        // Stopped just past the first `int3`, at the first `nop`.
+        let start = proc.pc();
+
+        let first = proc.create_breakpoint_site(start + 1, false).unwrap();
+        assert!(proc.enable_breakpoint_site(first).is_ok());
+
+        let second = proc.create_breakpoint_site(start + 2, false).unwrap();
+        assert!(proc.enable_breakpoint_site(second).is_ok());
+
+        assert!(proc.create_breakpoint_site(start + 1, true).is_err());
+
+        // The program counter is rewound to the start of the breakpoint site.
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.reason, ProcessState::Stopped);
+        assert_eq!(reason.info, SIGTRAP);
+        assert_eq!(reason.trap, Some(TrapKind::SoftwareBreakpoint(first)));
+        assert_eq!(reason.pc, Some(start + 1));
+        assert_eq!(proc.pc(), start + 1);
+
+        // The first site is stepped over, and the second site no longer traps.
+        assert!(proc.disable_breakpoint_site(second).is_ok());
+        assert!(!proc.breakpoint_sites().get(second).unwrap().is_enabled());
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+        assert_eq!(proc.pc(), start + 4);
+
+        assert!(proc.remove_breakpoint_site(first).is_ok());
+        assert!(proc.remove_breakpoint_site(first).is_err());
+        assert!(proc.breakpoint_sites().get(second).is_some());
+
+        // The tracee runs to completion.
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.reason, ProcessState::Exited);
+    }
+
+    #[test]
+    fn process_breakpoint_site_signal() {
+        let proc = Process::launch("target/debug/breakpoints".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        let start = proc.pc();
+
+        let id = proc.create_breakpoint_site(start + 1, false).unwrap();
+        assert!(proc.enable_breakpoint_site(id).is_ok());
+
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.trap, Some(TrapKind::SoftwareBreakpoint(id)));
+
+        // A signal arriving while stepping over the site is still delivered,
+        // terminating the tracee.
+        assert_eq!(unsafe { libc::kill(proc.pid(), libc::SIGUSR1) }, 0);
+
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.reason, ProcessState::Terminated);
+        assert_eq!(reason.info, libc::SIGUSR1);
+    }
+
+    #[test]
+    fn process_breakpoint_site_ignored_signal() {
+        let proc = Process::launch("target/debug/breakpoints".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        let start = proc.pc();
+
+        let id = proc.create_breakpoint_site(start + 1, false).unwrap();
+        assert!(proc.enable_breakpoint_site(id).is_ok());
+
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.trap, Some(TrapKind::SoftwareBreakpoint(id)));
+
+        // `SIGWINCH` is ignored by default. Once delivered, the stepped `nop`
+        // executes rather than trapping at the site again, and the tracee
+        // continues to the following `int3`.
+        assert_eq!(unsafe { libc::kill(proc.pid(), libc::SIGWINCH) }, 0);
+
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.info, SIGTRAP);
+        assert_ne!(reason.trap, Some(TrapKind::SoftwareBreakpoint(id)));
+        assert_eq!(proc.pc(), start + 4);
+    }
+
+    #[test]
+    fn process_hardware_breakpoint_sites() {
+        let proc = Process::launch("target/debug/breakpoints".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        let start = proc.pc();
+
+        let id = proc.create_breakpoint_site(start + 2, true).unwrap();
+        assert!(proc.enable_breakpoint_site(id).is_ok());
+        assert_eq!(
+            proc.breakpoint_sites().get(id).unwrap().hardware_slot(),
+            Some(0)
+        );
+
+        // Hardware breakpoints trap before the instruction executes.
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.info, SIGTRAP);
+        assert_eq!(proc.pc(), start + 2);
+        assert_eq!(reason.trap, Some(TrapKind::HardwareBreakpoint(id)));
+
+        // Resuming does not re-trigger the breakpoint.
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(proc.pc(), start + 4);
+        assert_eq!(reason.trap, None);
+
+        // The slot is freed once removed.
+        assert!(proc.remove_breakpoint_site(id).is_ok());
+        let dr7 = proc
+            .registers()
+            .read(RegisterInfo::register_info_by_name("dr7").unwrap());
+        assert_eq!(dr7.to_u64().unwrap() & 0xff, 0);
+    }
+
+    #[test]
+    fn process_watchpoints() {
+        let proc = Process::launch("target/debug/watchpoints".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        let address = VirtAddr(
+            proc.registers()
+                .read(RegisterInfo::register_info_by_name("rdi").unwrap())
+                .to_u64()
+                .unwrap(),
+        );
+
+        // Watched data must be aligned to its size.
+        assert!(
+            proc.create_watchpoint(address + 1, StoppointMode::Write, 2)
+                .is_err()
+        );
+        assert!(
+            proc.create_watchpoint(address, StoppointMode::Execute, 8)
+                .is_err()
+        );
+
+        let id = proc
+            .create_watchpoint(address, StoppointMode::Write, 8)
+            .unwrap();
+        assert!(proc.enable_watchpoint(id).is_ok());
+
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.info, SIGTRAP);
+        assert_eq!(
+            reason.trap,
+            Some(TrapKind::Watchpoint {
+                id,
+                old: RegisterValue::U64(0),
+                new: RegisterValue::U64(42),
+            })
+        );
+
+        let watchpoint = proc.watchpoints().get(id).unwrap();
+        assert_eq!(watchpoint.previous_data(), 0);
+        assert_eq!(watchpoint.data(), 42);
+
+        assert!(proc.remove_watchpoint(id).is_ok());
+
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.reason, ProcessState::Exited);
+    }
+
+    #[test]
+    fn process_syscall_stops() {
+        let proc = Process::launch("target/debug/breakpoints".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        let next_syscall_stop = |proc: &mut Process| {
+            assert!(proc.resume_syscall().is_ok());
+            proc.wait_on_signal().unwrap()
+        };
+
+        let reason = next_syscall_stop(&mut proc);
+        assert_eq!(reason.info, SIGTRAP);
+        let Some(TrapKind::SyscallEntry(number)) = reason.trap else {
+            panic!("expected syscall entry, got {:?}", reason.trap);
+        };
+
+        let reason = next_syscall_stop(&mut proc);
+        assert!(matches!(reason.trap, Some(TrapKind::SyscallExit(n, _)) if n == number));
+    }
+
+    #[test]
+    fn process_step_instruction() {
+        let proc = Process::launch("target/debug/stepping".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        // Stopped just past `int3`, at `call`.
+        let start = proc.pc();
+
+        let id = proc.create_breakpoint_site(start, false).unwrap();
+        assert!(proc.enable_breakpoint_site(id).is_ok());
+
+        let code = proc.read_memory_without_traps(start, 5).unwrap();
+        assert_eq!(code[0], 0xe8);
+
+        // The breakpoint site at the program counter is stepped over, but kept.
+        let reason = proc.step_instruction().unwrap();
+        assert_eq!(reason.trap, Some(TrapKind::SingleStep));
+
+        let rel = i32::from_le_bytes(code[1..5].try_into().unwrap());
+        let callee = VirtAddr((start.0 + 5).wrapping_add_signed(rel as i64));
+        assert_eq!(proc.pc(), callee);
+        assert!(proc.breakpoint_sites().get(id).unwrap().is_enabled());
+    }
+
+    #[test]
+    fn process_step_over() {
+        let proc = Process::launch("target/debug/stepping".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        let start = proc.pc();
+
+        let reason = proc.step_over().unwrap();
+        assert_eq!(reason.trap, Some(TrapKind::SingleStep));
+        assert_eq!(proc.pc(), start + 5);
+
+        let reason = proc.step_over().unwrap();
+        assert_eq!(reason.trap, Some(TrapKind::SingleStep));
+        assert_eq!(proc.pc(), start + 6);
+
+        let reason = proc.run_until(start + 7).unwrap();
+        assert_eq!(reason.trap, Some(TrapKind::TemporaryBreakpoint));
+        assert_eq!(proc.pc(), start + 7);
+    }
+
+    #[test]
+    fn process_finish_without_call_frame_info() {
+        let proc = Process::launch("target/debug/stepping".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        let start = proc.pc();
+
+        // The hand-written callee has no call frame information, so it is
+        // single-stepped until it returns.
+        assert!(proc.step_instruction().is_ok());
+        assert!(proc.frame_return_address_slot().is_none());
+
+        let reason = proc.finish().unwrap();
+        assert_eq!(reason.trap, Some(TrapKind::SingleStep));
+        assert_eq!(proc.pc(), start + 5);
+    }
+
+    #[test]
+    fn process_source_stepping() {
+        let proc = Process::launch("target/debug/source_stepping".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        let line = |proc: &Process| proc.line_at(proc.pc()).unwrap().1.line;
+        let function = |proc: &Process| proc.symbol_at(proc.pc()).unwrap().1.name.clone();
+
+        // Stopped just past `int3`.
+        assert_eq!(line(&proc), 27);
+
+        // Relative source paths are relative to the compilation directory.
+        let path = proc
+            .find_source_file(Path::new("source_stepping.rs"))
+            .unwrap();
+        assert_eq!(
+            proc.comp_dir(path),
+            Some(Path::new(env!("CARGO_MANIFEST_DIR")))
+        );
+
+        let reason = proc.step_line().unwrap();
+        assert_eq!(reason.trap, Some(TrapKind::SingleStep));
+        assert_eq!(line(&proc), 28);
+
+        // Functions are entered past their prologue.
+        assert!(proc.step_line().is_ok());
+        assert_eq!(function(&proc), "source_stepping::square");
+        assert_eq!(line(&proc), 9);
+
+        assert!(proc.next_line().is_ok());
+        assert_eq!(line(&proc), 10);
+
+        // Integers are returned in `rax`, and floating-point values in `xmm0`.
+        let reason = proc.finish().unwrap();
+        assert_eq!(reason.trap, Some(TrapKind::SingleStep));
+        assert_eq!(function(&proc), "source_stepping::main");
+        assert_eq!(line(&proc), 28);
+        assert_eq!(proc.read_u64("rax"), 36);
+
+        assert!(proc.next_line().is_ok());
+        assert_eq!(line(&proc), 29);
+
+        assert!(proc.step_line().is_ok());
+        assert_eq!(function(&proc), "source_stepping::half");
+
+        assert!(proc.finish().is_ok());
+        let xmm0 = RegisterInfo::register_info_by_name("xmm0").unwrap();
+        let xmm0 = proc.registers().read(xmm0).to_bytes();
+        assert_eq!(f64::from_le_bytes(xmm0[..8].try_into().unwrap()), 18.0);
+
+        assert!(proc.next_line().is_ok());
+        assert_eq!(line(&proc), 30);
+
+        assert!(proc.step_line().is_ok());
+        assert_eq!(function(&proc), "source_stepping::countdown");
+        assert_eq!(line(&proc), 19);
+
+        // Stepping over the recursive call, and the drop glue of the vector,
+        // stays within the same frame.
+        let rsp = proc.read_u64("rsp");
+
+        for expected in [20, 23, 24] {
+            assert!(proc.next_line().is_ok());
+            assert_eq!(line(&proc), expected);
+            assert_eq!(proc.read_u64("rsp"), rsp);
+        }
+
+        assert!(proc.next_line().is_ok());
+        assert_eq!(function(&proc), "source_stepping::main");
+        assert_eq!(line(&proc), 30);
+    }
+
+    #[test]
+    fn process_read_write_memory() {
+        let proc = Process::launch("target/debug/watchpoints".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        let address = VirtAddr(
+            proc.registers()
+                .read(RegisterInfo::register_info_by_name("rdi").unwrap())
+                .to_u64()
+                .unwrap(),
+        );
+
+        assert_eq!(proc.read_memory(address, 8).unwrap(), [0; 8]);
+
+        // Writes spanning words preserve the surrounding bytes.
+        assert!(proc.write_memory(address + 6, &[1, 2, 3]).is_ok());
+        let data = proc.read_memory(address, 16).unwrap();
+        assert_eq!(data[..10], [0, 0, 0, 0, 0, 0, 1, 2, 3, 0]);
+
+        // Text pages are read-only, but can still be patched.
+        let pc = proc.pc();
+        let id = proc.create_breakpoint_site(pc, false).unwrap();
+        assert!(proc.enable_breakpoint_site(id).is_ok());
+
+        assert_eq!(proc.read_memory(pc, 1).unwrap(), [0xcc]);
+        let original = proc.read_memory_without_traps(pc, 2).unwrap();
+        assert_ne!(original[0], 0xcc);
+
+        assert!(proc.write_memory(pc, &[0x90, 0x90]).is_ok());
+        assert_eq!(proc.read_memory(pc, 2).unwrap(), [0xcc, 0x90]);
+        assert_eq!(proc.read_memory_without_traps(pc, 2).unwrap(), [0x90, 0x90]);
+
+        assert!(proc.disable_breakpoint_site(id).is_ok());
+        assert_eq!(proc.read_memory(pc, 1).unwrap(), [0x90]);
+
+        // Ranges wrapping around the address space are rejected up front.
+        assert!(proc.write_memory(VirtAddr(u64::MAX), &[1, 2]).is_err());
+        assert!(proc.read_memory(VirtAddr(u64::MAX), 2).is_err());
+        assert!(proc.read_memory(VirtAddr(u64::MAX), 1).is_err());
+    }
+
+    #[test]
+    fn process_partial_read_memory() {
+        let proc = Process::launch("target/debug/watchpoints".to_string(), true);
+        assert!(proc.is_ok());
+
+        let proc = proc.unwrap();
+
+        let maps = fs::read_to_string(format!("/proc/{}/maps", proc.pid())).unwrap();
+        let ranges: Vec<(u64, u64)> = maps
+            .lines()
+            .map(|line| {
+                let range = line.split_whitespace().next().unwrap();
+                let (start, end) = range.split_once('-').unwrap();
+
+                (
+                    u64::from_str_radix(start, 16).unwrap(),
+                    u64::from_str_radix(end, 16).unwrap(),
+                )
+            })
+            .collect();
+
+        // The end of a mapping which is not immediately followed by another.
+        let end = ranges
+            .iter()
+            .map(|(_, end)| *end)
+            .find(|end| ranges.iter().all(|(start, _)| start != end))
+            .unwrap();
+
+        let err = proc.read_memory(VirtAddr(end - 4), 16).unwrap_err();
+        let partial = err.downcast::<PartialRead>().unwrap();
+        assert_eq!(partial.data.len(), 4);
+        assert_eq!(partial.address, end);
+        assert_eq!(partial.requested, 16);
+
+        let err = proc.read_memory(VirtAddr(0), 8).unwrap_err();
+        let partial = err.downcast::<PartialRead>().unwrap();
+        assert!(partial.data.is_empty());
+        assert_eq!(partial.address, 0);
+    }
+
+    #[test]
+    fn process_memory_map() {
+        let proc = Process::launch("target/debug/breakpoints".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        assert!(proc.resume().is_ok());
+        assert!(proc.wait_on_signal().is_ok());
+
+        let map = proc.memory_map().unwrap();
+        let region = map.region_at(proc.pc().0).unwrap();
+        assert!(region.permissions.read && region.permissions.execute);
+        assert_eq!(region.name(), "breakpoints");
+
+        // The executable is parsed from the same file which is mapped.
+        let elf = proc.elf().unwrap();
+        assert_eq!(
+            region.backing,
+            RegionBacking::File(elf.path().to_path_buf())
+        );
+        assert!(elf.build_id().is_some());
+
+        let stack = map
+            .region_at(
+                proc.registers()
+                    .read(RegisterInfo::register_info_by_name("rsp").unwrap())
+                    .to_u64()
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(stack.backing, RegionBacking::Pseudo("[stack]".to_string()));
+
+        // Unmapped addresses are refused.
+        assert!(map.region_at(0).is_none());
+        assert!(proc.create_breakpoint_site(VirtAddr(0), false).is_err());
+        assert!(proc.create_breakpoint_site(VirtAddr(0), true).is_err());
+        assert!(
+            proc.create_watchpoint(VirtAddr(0), StoppointMode::Write, 8)
+                .is_err()
+        );
+        assert!(proc.write_memory(VirtAddr(0), &[0]).is_err());
+        assert!(proc.breakpoint_sites().is_empty());
+        assert!(proc.watchpoints().is_empty());
+    }
+
+    #[test]
+    fn process_loader_site() {
+        let proc = Process::launch("target/debug/breakpoints".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
+
+        // Only the dynamic loader is mapped before it runs.
+        assert!(proc.find_symbols("malloc").is_empty());
+
+        let symbols = proc.find_symbols("_dl_debug_state");
+        assert_eq!(symbols.len(), 1);
+        let address = symbols[0].0;
+
+        // The internal site is hidden from reads without traps.
+        assert_eq!(proc.read_memory(address, 1).unwrap(), [0xcc]);
+        let original = proc.read_memory_without_traps(address, 1).unwrap();
+        assert_ne!(original, [0xcc]);
+
+        // A breakpoint site at the same address takes over from it.
+        let id = proc.create_breakpoint_site(address, false).unwrap();
+        assert!(proc.enable_breakpoint_site(id).is_ok());
+        assert_eq!(
+            proc.read_memory_without_traps(address, 1).unwrap(),
+            original
+        );
+
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.trap, Some(TrapKind::SoftwareBreakpoint(id)));
+        assert_eq!(proc.pc(), address);
+
+        assert!(proc.remove_breakpoint_site(id).is_ok());
+        assert_eq!(proc.read_memory(address, 1).unwrap(), [0xcc]);
+
+        // Later calls by the dynamic loader are not reported, but the shared
+        // libraries it loads are found by the time the tracee stops.
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+        assert_eq!(reason.info, SIGTRAP);
+        assert_eq!(reason.trap, None);
+        assert_eq!(proc.find_symbols("malloc").len(), 1);
+    }
+
+    #[test]
+    fn process_symbols() {
+        let proc = Process::launch("target/debug/breakpoints".to_string(), true);
+        assert!(proc.is_ok());
+
+        let mut proc = proc.unwrap();
 
-        assert_eq!(proc.resume().is_err(), true);
+        let symbols = proc.find_symbols("breakpoints::main");
+        assert_eq!(symbols.len(), 1);
+
+        let (main, elf, symbol) = symbols[0];
+        let size = symbol.size;
+
+        // The load bias given by the auxiliary vector agrees with where the
+        // executable is mapped.
+        let backing = RegionBacking::File(elf.path().to_path_buf());
+        let map = proc.memory_map().unwrap();
+        let first = map
+            .regions()
+            .iter()
+            .find(|region| region.backing == backing && region.offset == 0)
+            .unwrap();
+        assert_eq!(
+            elf.load_bias_from(VirtAddr(first.start), 4096),
+            Some(elf.load_bias())
+        );
+        assert_eq!(map.region_at(main.0).unwrap().backing, backing);
+        assert_eq!(main, symbol.address.to_virt(elf));
+        assert_eq!(proc.symbolize(main).unwrap(), "breakpoints::main");
+
+        // The tracee stops within `main`, after its first `int3`.
+        assert!(proc.resume().is_ok());
+        let reason = proc.wait_on_signal().unwrap();
+
+        let offset = proc.pc().0 - main.0;
+        assert!(offset > 0 && offset < size);
+        assert_eq!(
+            reason.symbol,
+            Some(format!("breakpoints::main+{offset:#x}"))
+        );
+
+        // Shared libraries have been loaded by now, with their own biases.
+        let symbols = proc.find_symbols("malloc");
+        assert_eq!(symbols.len(), 1);
+
+        let (malloc, libc, _) = symbols[0];
+        assert!(libc.path().to_string_lossy().contains("libc"));
+        assert!(proc.libraries().iter().any(|elf| elf.path() == libc.path()));
+        assert!(proc.memory_map().unwrap().is_mapped(malloc.0, 1));
+        assert_eq!(proc.symbolize(malloc + 1).unwrap(), "malloc+0x1");
+
+        // Addresses outside of any object are not symbolized.
+        assert!(proc.symbolize(VirtAddr(0)).is_none());
+        assert!(proc.find_symbols("no_such_symbol").is_empty());
     }
 }
@@ -0,0 +1,97 @@
+use std::ffi::{CStr, CString};
+use std::{fmt, mem, ptr};
+
+use crate::Result;
+
+use libc::{REG_EXTENDED, REG_NOSUB, c_char, c_int, regex_t};
+
+/// Wrapper around the POSIX extended regular expression API ([`libc::regcomp`]).
+pub(crate) struct Regex {
+    /// The compiled pattern.
+    regex: Box<regex_t>,
+}
+
+impl Regex {
+    /// Compile the extended regular expression `pattern`.
+    pub(crate) fn new(pattern: &str) -> Result<Self> {
+        let c_pattern = CString::new(pattern)
+            .map_err(|_| format!("invalid regular expression '{pattern}': contains NUL"))?;
+
+        // The compiled pattern is initialized by `regcomp`, and must not move
+        // once compiled.
+        let mut regex: Box<regex_t> = Box::new(unsafe { mem::zeroed() });
+
+        // Compile the pattern, only reporting success or failure of matches
+        // (`REG_NOSUB`).
+        let ret =
+            unsafe { libc::regcomp(&mut *regex, c_pattern.as_ptr(), REG_EXTENDED | REG_NOSUB) };
+
+        if ret != 0 {
+            let err = regerror(ret, &regex);
+
+            // A pattern which failed to compile holds no memory to free.
+            return Err(format!("invalid regular expression '{pattern}': {err}").into());
+        }
+
+        Ok(Self { regex })
+    }
+
+    /// Return `true` if `text` contains a match of the regular expression.
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        // Text containing a NUL cannot be passed to `regexec`, so match the
+        // text preceding it.
+        let text = text.split('\0').next().unwrap_or_default();
+
+        let Ok(c_text) = CString::new(text) else {
+            return false;
+        };
+
+        // Search for a match, without reporting its location.
+        unsafe { libc::regexec(&*self.regex, c_text.as_ptr(), 0, ptr::null_mut(), 0) == 0 }
+    }
+}
+
+impl Drop for Regex {
+    fn drop(&mut self) {
+        // Free the memory allocated by `regcomp`.
+        unsafe { libc::regfree(&mut *self.regex) };
+    }
+}
+
+impl fmt::Debug for Regex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Regex").finish_non_exhaustive()
+    }
+}
+
+/// Return the message describing the error code `code` returned by
+/// [`libc::regcomp`] for `regex`.
+fn regerror(code: c_int, regex: &regex_t) -> String {
+    let mut buffer = [0 as c_char; 256];
+
+    unsafe { libc::regerror(code, regex, buffer.as_mut_ptr(), buffer.len()) };
+
+    // `regerror` always NUL-terminates the message, truncating it if needed.
+    unsafe { CStr::from_ptr(buffer.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regex_matching() {
+        let regex = Regex::new("^mycrate::parser::").unwrap();
+        assert!(regex.is_match("mycrate::parser::parse"));
+        assert!(!regex.is_match("othercrate::mycrate::parser::parse"));
+
+        let regex = Regex::new("(read|write)_memory$").unwrap();
+        assert!(regex.is_match("rdbg::core::memory::read_memory"));
+        assert!(!regex.is_match("rdbg::core::memory::read_memory_without_traps"));
+
+        assert!(Regex::new("(unclosed").is_err());
+        assert!(Regex::new("a\0b").is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+
+use super::{RegisterInfo, RegisterValue, UserArea};
+
+/// Value of a single register before and after a change.
+#[derive(Debug, Copy, Clone)]
+pub struct RegisterChange {
+    /// Register which changed.
+    pub info: &'static RegisterInfo,
+    /// Value of the register in the older snapshot.
+    pub old: RegisterValue,
+    /// Value of the register in the newer snapshot.
+    pub new: RegisterValue,
+}
+
+impl RegisterChange {
+    /// Compare the register described by `info` in `old` and `new`, returning
+    /// `None` if it did not change.
+    pub(crate) fn compare(
+        info: &'static RegisterInfo,
+        old: &UserArea,
+        new: &UserArea,
+    ) -> Option<Self> {
+        let (old, new) = (old.read(info), new.read(info));
+
+        // Compare the raw bytes, so NaNs are not reported as changed.
+        (old.to_bytes() != new.to_bytes()).then_some(Self { info, old, new })
+    }
+}
+
+impl fmt::Display for RegisterChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} → {}", self.old, self.new)
+    }
+}
+
+/// Set of registers which changed between two snapshots of the register state
+/// of a tracee (e.g., taken at consecutive stops).
+#[derive(Debug, Default, Clone)]
+pub struct RegisterDelta {
+    changes: Vec<RegisterChange>,
+}
+
+impl RegisterDelta {
+    /// Compare every register in `old` and `new`, in the order of
+    /// [`RegisterInfo::all`]. Registers unavailable on this system read as
+    /// zero in both, so never change.
+    pub(crate) fn new(old: &UserArea, new: &UserArea) -> Self {
+        let changes = RegisterInfo::all()
+            .iter()
+            .filter_map(|info| RegisterChange::compare(info, old, new))
+            .collect();
+
+        Self { changes }
+    }
+
+    /// Return every register which changed.
+    pub fn changes(&self) -> &[RegisterChange] {
+        &self.changes
+    }
+
+    /// Return the change to the register described by `info`, or `None` if it
+    /// did not change.
+    pub fn change(&self, info: &RegisterInfo) -> Option<&RegisterChange> {
+        self.changes
+            .iter()
+            .find(|change| change.info.name == info.name)
+    }
+
+    /// Return `true` if no registers changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
@@ -0,0 +1,970 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Error;
+
+/// 80-bit x87 extended precision floating-point value, stored as its raw
+/// little-endian bytes (64-bit significand followed by the sign and exponent).
+///
+/// Unlike [`f32`] and [`f64`], the significand has an explicit integer bit,
+/// which permits encodings that are no longer supported by modern processors
+/// (see [`F80Category`]).
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct F80([u8; 10]);
+
+/// Classification of an [`F80`] value by its encoding.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum F80Category {
+    /// Positive or negative zero.
+    Zero,
+    /// Exponent of zero, with the integer bit clear.
+    Denormal,
+    /// Exponent of zero, with the integer bit set. Interpreted by the processor
+    /// as if the exponent were one.
+    PseudoDenormal,
+    /// Non-zero exponent, with the integer bit set.
+    Normal,
+    /// Non-zero exponent, with the integer bit clear. Unsupported.
+    Unnormal,
+    /// Maximum exponent and zero fraction, with the integer bit set.
+    Infinity,
+    /// Maximum exponent and zero fraction, with the integer bit clear.
+    /// Unsupported.
+    PseudoInfinity,
+    /// Maximum exponent and non-zero fraction, with the quiet bit set.
+    QuietNan,
+    /// Maximum exponent and non-zero fraction, with the quiet bit clear.
+    SignalingNan,
+    /// Maximum exponent and non-zero fraction, with the integer bit clear.
+    /// Unsupported.
+    PseudoNan,
+}
+
+impl F80 {
+    /// Exponent bias of the extended precision format.
+    const BIAS: i32 = 16383;
+
+    /// Maximum (biased) exponent, used by infinities and NaNs.
+    const MAX_EXP: u16 = 0x7fff;
+
+    /// Exponent of the least significant bit of the smallest denormal.
+    const MIN_EXP: i32 = 1 - Self::BIAS - 63;
+
+    /// Number of significant decimal digits needed to uniquely identify any
+    /// extended precision value.
+    const MAX_DIGITS: usize = 21;
+
+    /// Creates a new [`F80`] from its raw little-endian bytes.
+    pub fn from_bytes(bytes: [u8; 10]) -> Self {
+        Self(bytes)
+    }
+
+    /// Return the raw little-endian bytes of the given [`F80`].
+    pub fn to_bytes(self) -> [u8; 10] {
+        self.0
+    }
+
+    /// Creates a new [`F80`] from its sign, biased exponent, and significand.
+    fn from_parts(negative: bool, exponent: u16, mantissa: u64) -> Self {
+        let sign_exp = ((negative as u16) << 15) | (exponent & Self::MAX_EXP);
+
+        let mut bytes = [0u8; 10];
+        bytes[..8].copy_from_slice(&mantissa.to_le_bytes());
+        bytes[8..].copy_from_slice(&sign_exp.to_le_bytes());
+
+        Self(bytes)
+    }
+
+    /// Return the 64-bit significand, including the explicit integer bit.
+    pub fn mantissa(self) -> u64 {
+        let mut mantissa = [0u8; 8];
+        mantissa.copy_from_slice(&self.0[..8]);
+
+        u64::from_le_bytes(mantissa)
+    }
+
+    /// Return the biased 15-bit exponent.
+    pub fn exponent(self) -> u16 {
+        u16::from_le_bytes([self.0[8], self.0[9]]) & Self::MAX_EXP
+    }
+
+    /// Return `true` if the sign bit is set.
+    pub fn is_sign_negative(self) -> bool {
+        self.0[9] & 0x80 != 0
+    }
+
+    /// Classify the encoding of the given [`F80`].
+    pub fn classify(self) -> F80Category {
+        let mantissa = self.mantissa();
+        let integer = mantissa >> 63 == 1;
+        let fraction = mantissa << 1;
+
+        match self.exponent() {
+            0 if mantissa == 0 => F80Category::Zero,
+            0 if integer => F80Category::PseudoDenormal,
+            0 => F80Category::Denormal,
+            Self::MAX_EXP if !integer && fraction == 0 => F80Category::PseudoInfinity,
+            Self::MAX_EXP if !integer => F80Category::PseudoNan,
+            Self::MAX_EXP if fraction == 0 => F80Category::Infinity,
+            Self::MAX_EXP if mantissa >> 62 & 1 == 1 => F80Category::QuietNan,
+            Self::MAX_EXP => F80Category::SignalingNan,
+            _ if !integer => F80Category::Unnormal,
+            _ => F80Category::Normal,
+        }
+    }
+
+    /// Return `true` if the value is a NaN, or an unsupported encoding which the
+    /// processor treats as an invalid operand.
+    pub fn is_nan(self) -> bool {
+        matches!(
+            self.classify(),
+            F80Category::QuietNan
+                | F80Category::SignalingNan
+                | F80Category::PseudoNan
+                | F80Category::PseudoInfinity
+                | F80Category::Unnormal
+        )
+    }
+
+    /// Return the magnitude of a finite, supported value as `(m, e)`, such that
+    /// the value is `m * 2^e`.
+    fn decompose(self) -> Option<(u64, i32)> {
+        match self.classify() {
+            F80Category::Zero
+            | F80Category::Denormal
+            | F80Category::PseudoDenormal
+            | F80Category::Normal => {
+                // Denormals share the exponent of the smallest normal.
+                let exp = self.exponent().max(1) as i32;
+                Some((self.mantissa(), exp - Self::BIAS - 63))
+            }
+            _ => None,
+        }
+    }
+
+    /// Creates a new [`F80`] from an [`f64`]. The conversion is exact.
+    pub fn from_f64(value: f64) -> Self {
+        let bits = value.to_bits();
+
+        let negative = bits >> 63 == 1;
+        let exp = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = bits & ((1 << 52) - 1);
+
+        let (exp, mantissa) = match exp {
+            0 if fraction == 0 => (0, 0),
+            0 => {
+                // Subnormal doubles are normal in extended precision.
+                let shift = fraction.leading_zeros() as i32;
+                (
+                    (1 - 1023 + Self::BIAS - (shift - 11)) as u16,
+                    fraction << shift,
+                )
+            }
+            // Infinities and NaNs, preserving the NaN payload.
+            0x7ff => (Self::MAX_EXP, (1 << 63) | (fraction << 11)),
+            _ => (
+                (exp - 1023 + Self::BIAS) as u16,
+                (1 << 63) | (fraction << 11),
+            ),
+        };
+
+        Self::from_parts(negative, exp, mantissa)
+    }
+
+    /// Creates a new [`F80`] from a [`u64`]. The conversion is exact.
+    pub fn from_u64(value: u64) -> Self {
+        Self::from_integer(false, value)
+    }
+
+    /// Creates a new [`F80`] from an [`i64`]. The conversion is exact.
+    pub fn from_i64(value: i64) -> Self {
+        Self::from_integer(value < 0, value.unsigned_abs())
+    }
+
+    /// Creates a new [`F80`] from the sign and magnitude of an integer. The
+    /// 64-bit significand holds any 64-bit magnitude without rounding.
+    fn from_integer(negative: bool, magnitude: u64) -> Self {
+        if magnitude == 0 {
+            return Self::from_parts(negative, 0, 0);
+        }
+
+        let shift = magnitude.leading_zeros() as i32;
+        Self::from_parts(
+            negative,
+            (Self::BIAS + 63 - shift) as u16,
+            magnitude << shift,
+        )
+    }
+
+    /// Convert the given [`F80`] into an [`f64`], rounding to nearest (even).
+    ///
+    /// Values outside the range of [`f64`] become infinities or (subnormal)
+    /// zeros, NaN payloads are truncated, and unsupported encodings (pseudo-NaNs,
+    /// pseudo-infinities, and unnormals) become NaN.
+    pub fn to_f64(self) -> f64 {
+        let sign = (self.is_sign_negative() as u64) << 63;
+
+        match self.classify() {
+            F80Category::Infinity => f64::from_bits(sign | (0x7ff << 52)),
+            F80Category::QuietNan | F80Category::SignalingNan => {
+                // Keep the quiet bit and the upper bits of the payload, ensuring
+                // the fraction remains non-zero.
+                let fraction = ((self.mantissa() << 1) >> 12).max(1);
+                f64::from_bits(sign | (0x7ff << 52) | fraction)
+            }
+            F80Category::PseudoNan | F80Category::PseudoInfinity | F80Category::Unnormal => {
+                f64::NAN
+            }
+            _ => {
+                let (mantissa, exp) = self.decompose().unwrap_or_default();
+                f64::from_bits(sign | round_to_f64(mantissa, exp))
+            }
+        }
+    }
+
+    /// Return the digits and decimal exponent of the shortest decimal number that
+    /// converts back to the given (finite, supported) [`F80`], such that the
+    /// magnitude is `digits * 10^exp`.
+    fn shortest_decimal(self) -> Option<(Vec<u8>, i32)> {
+        let (mantissa, exp) = self.decompose()?;
+
+        if mantissa == 0 {
+            return Some((vec![0], 0));
+        }
+
+        // Exact decimal expansion of `mantissa * 2^exp`. Negative powers of two are
+        // written as `5^-exp / 10^-exp`.
+        let (digits, exp10) = if exp >= 0 {
+            let mut n = BigUint::from_u64(mantissa);
+            n.shl(exp as usize);
+            (n.to_digits(), 0)
+        } else {
+            let mut n = BigUint::from_u64(mantissa);
+            n.mul_pow(5, (-exp) as u32);
+            (n.to_digits(), exp)
+        };
+
+        // Compare magnitudes rather than encodings, as pseudo-denormals convert
+        // back to the equivalent normal.
+        (1..=Self::MAX_DIGITS)
+            .map(|precision| round_digits(&digits, exp10, precision))
+            .find(|(digits, exp10)| {
+                Self::from_decimal(false, digits, i64::from(*exp10)).decompose()
+                    == Some((mantissa, exp))
+            })
+    }
+
+    /// Creates the nearest [`F80`] (rounding half to even) to the decimal number
+    /// `digits * 10^exp10`, where each element of `digits` is a decimal digit.
+    fn from_decimal(negative: bool, digits: &[u8], exp10: i64) -> Self {
+        // Leading zeros do not contribute to the magnitude.
+        let digits = &digits[digits.iter().take_while(|&&d| d == 0).count()..];
+        let mut numerator = BigUint::from_digits(digits);
+
+        if numerator.is_zero() {
+            return Self::from_parts(negative, 0, 0);
+        }
+
+        // Magnitudes beyond these bounds are certain to overflow or underflow, and
+        // would otherwise require needlessly large integers.
+        let magnitude = exp10.saturating_add(digits.len() as i64);
+        if magnitude > 4934 {
+            return Self::from_parts(negative, Self::MAX_EXP, 1 << 63);
+        }
+        if magnitude < -4952 {
+            return Self::from_parts(negative, 0, 0);
+        }
+
+        let mut denominator = BigUint::from_u64(1);
+
+        if exp10 >= 0 {
+            numerator.mul_pow(10, exp10 as u32);
+        } else {
+            denominator.mul_pow(10, (-exp10) as u32);
+        }
+
+        // Scale the numerator so the quotient has at least 66 significant bits,
+        // leaving room for the rounding bits of a 64-bit significand.
+        let scale =
+            (66 + denominator.bit_len() as i64 - numerator.bit_len() as i64).max(0) as usize;
+        numerator.shl(scale);
+
+        // Long division is only needed for negative decimal exponents.
+        let (quotient, remainder) = if exp10 >= 0 {
+            (numerator, BigUint::from_u64(0))
+        } else {
+            numerator.div_rem(&denominator)
+        };
+
+        // The value is `quotient * 2^-scale`, plus a non-zero remainder.
+        let lead = quotient.bit_len() as i64 - 1 - scale as i64;
+
+        // Keep 64 significant bits for normal values, and all bits down to the
+        // smallest denormal otherwise.
+        let shift = if lead >= (1 - Self::BIAS) as i64 {
+            quotient.bit_len() as i64 - 64
+        } else {
+            scale as i64 + Self::MIN_EXP as i64
+        };
+
+        let mut mantissa = quotient.shr(shift.max(0) as usize).to_u128();
+
+        if shift > 0 {
+            let round = quotient.bit(shift as usize - 1);
+            let sticky = quotient.any_below(shift as usize - 1) || !remainder.is_zero();
+
+            if round && (sticky || mantissa & 1 == 1) {
+                mantissa += 1;
+            }
+        }
+
+        // Exponent of the least significant bit of the significand.
+        let mut exp = shift - scale as i64;
+
+        if mantissa >> 64 != 0 {
+            mantissa >>= 1;
+            exp += 1;
+        }
+
+        if mantissa == 0 {
+            return Self::from_parts(negative, 0, 0);
+        }
+
+        // Rounding a denormal may produce the smallest normal, which is identified
+        // by its integer bit.
+        let biased = if mantissa >> 63 == 0 {
+            0
+        } else {
+            exp + 63 + Self::BIAS as i64
+        };
+
+        if biased >= Self::MAX_EXP as i64 {
+            return Self::from_parts(negative, Self::MAX_EXP, 1 << 63);
+        }
+
+        Self::from_parts(negative, biased as u16, mantissa as u64)
+    }
+}
+
+impl From<f64> for F80 {
+    fn from(value: f64) -> Self {
+        Self::from_f64(value)
+    }
+}
+
+impl FromStr for F80 {
+    type Err = Error;
+
+    /// Parse a decimal number (e.g., `-1.5`, `6.02e23`), `inf`, or `nan` into the
+    /// nearest [`F80`]. The conversion is correctly rounded.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, body) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+
+        match body.to_ascii_lowercase().as_str() {
+            "inf" | "infinity" => return Ok(Self::from_parts(negative, Self::MAX_EXP, 1 << 63)),
+            "nan" => return Ok(Self::from_parts(negative, Self::MAX_EXP, 0b11 << 62)),
+            _ => {}
+        }
+
+        let invalid = || format!("invalid floating-point literal '{s}'");
+
+        let (number, exponent) = match body.find(['e', 'E']) {
+            Some(idx) => {
+                let exponent = parse_exponent(&body[idx + 1..]).ok_or_else(invalid)?;
+                (&body[..idx], exponent)
+            }
+            None => (body, 0),
+        };
+
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(invalid().into());
+        }
+
+        let mut digits = Vec::with_capacity(integer.len() + fraction.len());
+
+        for ch in integer.chars().chain(fraction.chars()) {
+            let digit = ch.to_digit(10).ok_or_else(invalid)?;
+            digits.push(digit as u8);
+        }
+
+        let exp10 = exponent.saturating_sub(fraction.len() as i64);
+
+        Ok(Self::from_decimal(negative, &digits, exp10))
+    }
+}
+
+/// Parse the decimal exponent of a floating-point literal, saturating values
+/// beyond the range of `i64`, which overflow or underflow any [`F80`] anyway.
+fn parse_exponent(s: &str) -> Option<i64> {
+    let (negative, digits) = match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+
+    if digits.is_empty() {
+        return None;
+    }
+
+    digits.chars().try_fold(0i64, |acc, ch| {
+        let digit = i64::from(ch.to_digit(10)?);
+        let acc = acc.saturating_mul(10);
+
+        Some(if negative {
+            acc.saturating_sub(digit)
+        } else {
+            acc.saturating_add(digit)
+        })
+    })
+}
+
+impl fmt::Display for F80 {
+    /// Formats the shortest decimal representation which converts back to the
+    /// exact same value. Unsupported encodings are displayed by name.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.is_sign_negative() { "-" } else { "" };
+
+        match self.classify() {
+            F80Category::Infinity => return write!(f, "{sign}inf"),
+            F80Category::QuietNan | F80Category::SignalingNan => return write!(f, "{sign}nan"),
+            F80Category::PseudoNan => return write!(f, "<pseudo-nan>"),
+            F80Category::PseudoInfinity => return write!(f, "<pseudo-inf>"),
+            F80Category::Unnormal => return write!(f, "<unnormal>"),
+            _ => {}
+        }
+
+        let Some((digits, exp10)) = self.shortest_decimal() else {
+            return write!(f, "{sign}nan");
+        };
+
+        let digits: String = digits.iter().map(|d| (b'0' + d) as char).collect();
+
+        // Decimal exponent of the leading digit.
+        let lead = exp10 + digits.len() as i32 - 1;
+
+        if (-5..17).contains(&lead) {
+            if exp10 >= 0 {
+                write!(f, "{sign}{digits}{}", "0".repeat(exp10 as usize))
+            } else if lead >= 0 {
+                let point = (lead + 1) as usize;
+                write!(f, "{sign}{}.{}", &digits[..point], &digits[point..])
+            } else {
+                let zeros = "0".repeat((-lead - 1) as usize);
+                write!(f, "{sign}0.{zeros}{digits}")
+            }
+        } else if digits.len() == 1 {
+            write!(f, "{sign}{digits}e{lead}")
+        } else {
+            write!(f, "{sign}{}.{}e{lead}", &digits[..1], &digits[1..])
+        }
+    }
+}
+
+/// Round the non-zero magnitude `mantissa * 2^exp` to the nearest [`f64`]
+/// (rounding half to even), returning its bits without the sign.
+fn round_to_f64(mantissa: u64, exp: i32) -> u64 {
+    if mantissa == 0 {
+        return 0;
+    }
+
+    // Normalize so the integer bit is set.
+    let zeros = mantissa.leading_zeros() as i32;
+    let mantissa = (mantissa as u128) << zeros;
+    let exp = exp - zeros;
+
+    // Exponent of the leading bit.
+    let lead = exp + 63;
+
+    if lead > 1023 {
+        return 0x7ff << 52;
+    }
+
+    // Drop the bits below the 53-bit significand for normal values, or below the
+    // smallest subnormal otherwise.
+    let shift = if lead >= -1022 { 11 } else { -1074 - exp };
+
+    if shift > 64 {
+        return 0;
+    }
+
+    let shift = shift as u32;
+    let mut bits = mantissa >> shift;
+    let remainder = mantissa & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+
+    if remainder > half || (remainder == half && bits & 1 == 1) {
+        bits += 1;
+    }
+
+    if lead < -1022 {
+        // Subnormal, where rounding up may produce the smallest normal.
+        return bits as u64;
+    }
+
+    let mut lead = lead;
+
+    if bits >> 53 != 0 {
+        bits >>= 1;
+        lead += 1;
+    }
+
+    if lead > 1023 {
+        return 0x7ff << 52;
+    }
+
+    (((lead + 1023) as u64) << 52) | (bits as u64 & ((1 << 52) - 1))
+}
+
+/// Round the decimal number `digits * 10^exp10` to `precision` significant
+/// digits (rounding half to even), with trailing zeros removed.
+fn round_digits(digits: &[u8], exp10: i32, precision: usize) -> (Vec<u8>, i32) {
+    let (mut kept, mut exp10) = if digits.len() <= precision {
+        (digits.to_vec(), exp10)
+    } else {
+        let mut kept = digits[..precision].to_vec();
+        let rest = &digits[precision..];
+
+        let round_up = match rest[0].cmp(&5) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal => {
+                rest[1..].iter().any(|&d| d != 0) || kept.last().is_some_and(|d| d % 2 == 1)
+            }
+        };
+
+        let mut exp10 = exp10 + rest.len() as i32;
+
+        if round_up {
+            // Propagate the carry, where all nines become a single leading one.
+            let mut idx = kept.len();
+
+            loop {
+                if idx == 0 {
+                    kept.insert(0, 1);
+                    kept.pop();
+                    exp10 += 1;
+                    break;
+                }
+
+                idx -= 1;
+
+                if kept[idx] == 9 {
+                    kept[idx] = 0;
+                } else {
+                    kept[idx] += 1;
+                    break;
+                }
+            }
+        }
+
+        (kept, exp10)
+    };
+
+    while kept.len() > 1 && kept.last() == Some(&0) {
+        kept.pop();
+        exp10 += 1;
+    }
+
+    (kept, exp10)
+}
+
+/// Minimal arbitrary-precision unsigned integer, used for exact conversions
+/// between binary and decimal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BigUint {
+    /// Little-endian 32-bit limbs, without trailing zero limbs.
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    fn from_u64(value: u64) -> Self {
+        let mut n = Self {
+            limbs: vec![value as u32, (value >> 32) as u32],
+        };
+        n.trim();
+        n
+    }
+
+    /// Creates a new [`BigUint`] from a sequence of decimal digits.
+    fn from_digits(digits: &[u8]) -> Self {
+        let mut n = Self { limbs: Vec::new() };
+
+        for chunk in digits.chunks(9) {
+            let value = chunk.iter().fold(0u32, |acc, &d| acc * 10 + d as u32);
+            n.mul_add(10u32.pow(chunk.len() as u32), value);
+        }
+
+        n
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn bit_len(&self) -> usize {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() * 32 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    fn bit(&self, idx: usize) -> bool {
+        self.limbs
+            .get(idx / 32)
+            .is_some_and(|limb| limb >> (idx % 32) & 1 == 1)
+    }
+
+    /// Return `true` if any bit below `idx` is set.
+    fn any_below(&self, idx: usize) -> bool {
+        let (whole, partial) = (idx / 32, idx % 32);
+
+        self.limbs.iter().take(whole).any(|&limb| limb != 0)
+            || self
+                .limbs
+                .get(whole)
+                .is_some_and(|&limb| partial > 0 && limb & ((1 << partial) - 1) != 0)
+    }
+
+    fn to_u128(&self) -> u128 {
+        self.limbs
+            .iter()
+            .take(4)
+            .enumerate()
+            .fold(0, |acc, (idx, &limb)| acc | (limb as u128) << (idx * 32))
+    }
+
+    /// Compute `self * mul + add`.
+    fn mul_add(&mut self, mul: u32, add: u32) {
+        let mut carry = add as u64;
+
+        for limb in &mut self.limbs {
+            let product = *limb as u64 * mul as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+
+        self.trim();
+    }
+
+    /// Multiply by `base^exp`, where `base` is a small integer.
+    fn mul_pow(&mut self, base: u32, mut exp: u32) {
+        // Multiply by the largest power of `base` that fits in a limb at a time.
+        let (chunk, chunk_exp) = {
+            let mut chunk = base;
+            let mut chunk_exp = 1;
+
+            while let Some(next) = chunk.checked_mul(base) {
+                chunk = next;
+                chunk_exp += 1;
+            }
+
+            (chunk, chunk_exp)
+        };
+
+        while exp >= chunk_exp {
+            self.mul_add(chunk, 0);
+            exp -= chunk_exp;
+        }
+
+        self.mul_add(base.pow(exp), 0);
+    }
+
+    fn shl(&mut self, bits: usize) {
+        if self.is_zero() {
+            return;
+        }
+
+        let (whole, partial) = (bits / 32, bits % 32);
+
+        if partial > 0 {
+            let mut carry = 0;
+
+            for limb in &mut self.limbs {
+                let shifted = (*limb as u64) << partial | carry;
+                *limb = shifted as u32;
+                carry = shifted >> 32;
+            }
+
+            if carry != 0 {
+                self.limbs.push(carry as u32);
+            }
+        }
+
+        self.limbs.splice(0..0, std::iter::repeat_n(0, whole));
+    }
+
+    fn shr(&self, bits: usize) -> Self {
+        let (whole, partial) = (bits / 32, bits % 32);
+
+        let mut limbs: Vec<u32> = self.limbs.iter().skip(whole).copied().collect();
+
+        if partial > 0 {
+            for idx in 0..limbs.len() {
+                let high = limbs.get(idx + 1).copied().unwrap_or(0);
+                limbs[idx] = limbs[idx] >> partial | high << (32 - partial);
+            }
+        }
+
+        let mut n = Self { limbs };
+        n.trim();
+        n
+    }
+
+    fn shr_one(&mut self) {
+        let mut carry = 0;
+
+        for limb in self.limbs.iter_mut().rev() {
+            let low = *limb & 1;
+            *limb = *limb >> 1 | carry << 31;
+            carry = low;
+        }
+
+        self.trim();
+    }
+
+    /// Subtract `other` from `self`, which must not be smaller.
+    fn sub_assign(&mut self, other: &Self) {
+        let mut borrow = 0i64;
+
+        for (idx, limb) in self.limbs.iter_mut().enumerate() {
+            let diff = *limb as i64 - other.limbs.get(idx).copied().unwrap_or(0) as i64 - borrow;
+
+            if diff < 0 {
+                *limb = (diff + (1 << 32)) as u32;
+                borrow = 1;
+            } else {
+                *limb = diff as u32;
+                borrow = 0;
+            }
+        }
+
+        self.trim();
+    }
+
+    /// Return the quotient and remainder of `self / divisor`, using binary long
+    /// division.
+    fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let mut quotient = Self { limbs: Vec::new() };
+        let mut remainder = self.clone();
+
+        if self.cmp(divisor) == Ordering::Less {
+            return (quotient, remainder);
+        }
+
+        let shift = self.bit_len() - divisor.bit_len();
+        quotient.limbs = vec![0; shift / 32 + 1];
+
+        let mut shifted = divisor.clone();
+        shifted.shl(shift);
+
+        for idx in (0..=shift).rev() {
+            if remainder.cmp(&shifted) != Ordering::Less {
+                remainder.sub_assign(&shifted);
+                quotient.limbs[idx / 32] |= 1 << (idx % 32);
+            }
+
+            shifted.shr_one();
+        }
+
+        quotient.trim();
+
+        (quotient, remainder)
+    }
+
+    /// Return the decimal digits of the given [`BigUint`], most significant first.
+    fn to_digits(&self) -> Vec<u8> {
+        let mut n = self.clone();
+        let mut chunks = Vec::new();
+
+        // Repeatedly divide by 10^9, collecting the remainders.
+        while !n.is_zero() {
+            let mut remainder = 0u64;
+
+            for limb in n.limbs.iter_mut().rev() {
+                let value = remainder << 32 | *limb as u64;
+                *limb = (value / 1_000_000_000) as u32;
+                remainder = value % 1_000_000_000;
+            }
+
+            n.trim();
+            chunks.push(remainder as u32);
+        }
+
+        let mut digits = Vec::with_capacity(chunks.len() * 9);
+
+        for (idx, chunk) in chunks.iter().rev().enumerate() {
+            let chunk = if idx == 0 {
+                chunk.to_string()
+            } else {
+                format!("{chunk:09}")
+            };
+
+            digits.extend(chunk.bytes().map(|b| b - b'0'));
+        }
+
+        if digits.is_empty() {
+            digits.push(0);
+        }
+
+        digits
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> F80 {
+        s.parse::<F80>().unwrap()
+    }
+
+    #[test]
+    fn f80_round_trip_f64() {
+        for value in [0.0, -0.0, 1.0, -2.5, 0.1, 1e300, f64::MIN_POSITIVE, 5e-324] {
+            assert_eq!(F80::from_f64(value).to_f64().to_bits(), value.to_bits());
+        }
+
+        assert_eq!(F80::from_f64(f64::INFINITY).to_f64(), f64::INFINITY);
+        assert!(F80::from_f64(f64::NAN).to_f64().is_nan());
+    }
+
+    #[test]
+    fn f80_from_integer() {
+        assert_eq!(F80::from_u64(0).classify(), F80Category::Zero);
+        assert_eq!(F80::from_u64(1).to_f64(), 1.0);
+        assert_eq!(F80::from_i64(-3).to_f64(), -3.0);
+        assert_eq!(F80::from_i64(i64::MIN).to_f64(), i64::MIN as f64);
+
+        // Every 64-bit integer is representable.
+        let max = F80::from_u64(u64::MAX);
+        assert_eq!(max.mantissa(), u64::MAX);
+        assert_eq!(max.to_string(), "1.8446744073709551615e19");
+    }
+
+    #[test]
+    fn f80_to_f64_rounding() {
+        // Out of range for `f64`.
+        assert_eq!(parse("1e400").to_f64(), f64::INFINITY);
+        assert_eq!(parse("-1e-400").to_f64().to_bits(), (-0.0f64).to_bits());
+
+        // Rounds to the nearest `f64`, rather than truncating.
+        assert_eq!(parse("0.1").to_f64(), 0.1);
+        assert_eq!(parse("4.9406564584124654e-324").to_f64(), 5e-324);
+    }
+
+    #[test]
+    fn f80_display_shortest() {
+        let cases = [
+            ("0", "0"),
+            ("-0", "-0"),
+            ("1", "1"),
+            ("3.5", "3.5"),
+            ("0.1", "0.1"),
+            ("-123.456", "-123.456"),
+            ("1e100", "1e100"),
+            ("1.5e-300", "1.5e-300"),
+            ("0.00001", "0.00001"),
+            ("1.18973149535723176502e4932", "1.189731495357231765e4932"),
+            ("inf", "inf"),
+            ("-inf", "-inf"),
+            ("nan", "nan"),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(parse(input).to_string(), expected);
+        }
+
+        // Values which are not exactly representable in an `f64`.
+        let third = F80::from_parts(false, 16381, 0xaaaa_aaaa_aaaa_aaab);
+        assert_eq!(third.to_string(), "0.33333333333333333334");
+        assert_eq!(parse(&third.to_string()), third);
+    }
+
+    #[test]
+    fn f80_parse_limits() {
+        // Largest finite value.
+        assert_eq!(
+            parse("1.18973149535723176502e4932"),
+            F80::from_parts(false, 0x7ffe, u64::MAX)
+        );
+        assert_eq!(parse("1.2e4932"), parse("inf"));
+
+        // Smallest denormal, and rounding half of it to even (zero).
+        let min = F80::from_parts(false, 0, 1);
+        assert_eq!(parse("3.6451995318824746025e-4951"), min);
+        assert_eq!(parse(&min.to_string()), min);
+        assert_eq!(parse("1.8e-4951"), F80::from_parts(false, 0, 0));
+
+        // Exponents beyond any representable magnitude saturate to infinity or
+        // zero, however many digits they have.
+        assert_eq!(parse("1e2147483647"), parse("inf"));
+        assert_eq!(parse("-1e99999999999999999999999"), parse("-inf"));
+        assert_eq!(parse("1e-2147483648"), F80::from_parts(false, 0, 0));
+        assert_eq!(
+            parse("-1.5e-99999999999999999999999"),
+            F80::from_parts(true, 0, 0)
+        );
+        assert_eq!(parse("000001e4931"), parse("1e4931"));
+
+        assert!("1.2.3".parse::<F80>().is_err());
+        assert!("".parse::<F80>().is_err());
+        assert!("e5".parse::<F80>().is_err());
+        assert!("1e".parse::<F80>().is_err());
+        assert!("1e+-5".parse::<F80>().is_err());
+    }
+
+    #[test]
+    fn f80_classify_unsupported() {
+        let unnormal = F80::from_parts(false, 0x4000, 0x4000_0000_0000_0000);
+        let pseudo_nan = F80::from_parts(false, 0x7fff, 0x4000_0000_0000_0000);
+        let pseudo_inf = F80::from_parts(true, 0x7fff, 0);
+        let pseudo_denormal = F80::from_parts(false, 0, 1 << 63);
+
+        assert_eq!(unnormal.classify(), F80Category::Unnormal);
+        assert_eq!(pseudo_nan.classify(), F80Category::PseudoNan);
+        assert_eq!(pseudo_inf.classify(), F80Category::PseudoInfinity);
+        assert_eq!(pseudo_denormal.classify(), F80Category::PseudoDenormal);
+
+        assert!(unnormal.to_f64().is_nan());
+        assert!(pseudo_nan.to_f64().is_nan());
+        assert!(pseudo_inf.to_f64().is_nan());
+
+        // Pseudo-denormals have the same value as the smallest normal.
+        assert_eq!(
+            pseudo_denormal.to_string(),
+            F80::from_parts(false, 1, 1 << 63).to_string()
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use std::fmt;
+
+use super::{F80, F80Category, RegisterInfo};
+
+/// Single named bit within a status or control register (e.g., `ZF` within
+/// `eflags`).
+#[derive(Debug)]
+pub struct RegisterFlag {
+    /// Mnemonic of the flag.
+    pub name: &'static str,
+    /// Bit position of the flag within the register.
+    pub bit: u32,
+    /// Short description of the flag.
+    pub description: &'static str,
+}
+
+impl RegisterFlag {
+    /// Return the named flags of the register described by `info`, or an empty
+    /// slice if the register has none.
+    pub fn flags_for(info: &RegisterInfo) -> &'static [RegisterFlag] {
+        match info.name {
+            "eflags" => EFLAGS_FLAGS,
+            "mxcsr" => MXCSR_FLAGS,
+            "fcw" => FCW_FLAGS,
+            "fsw" => FSW_FLAGS,
+            _ => &[],
+        }
+    }
+
+    /// Find the flag with the given `name` (case-insensitive) for the register
+    /// described by `info`.
+    pub fn flag_by_name(info: &RegisterInfo, name: &str) -> Option<&'static RegisterFlag> {
+        Self::flags_for(info)
+            .iter()
+            .find(|flag| flag.name.eq_ignore_ascii_case(name))
+    }
+}
+
+// Macro to define a table of single-bit flags.
+macro_rules! flags {
+    ($($name:literal = $bit:literal: $description:literal),* $(,)?) => {
+        &[$(RegisterFlag { name: $name, bit: $bit, description: $description }),*]
+    };
+}
+
+const EFLAGS_FLAGS: &[RegisterFlag] = flags![
+    "CF" = 0: "carry",
+    "PF" = 2: "parity",
+    "AF" = 4: "auxiliary carry",
+    "ZF" = 6: "zero",
+    "SF" = 7: "sign",
+    "TF" = 8: "trap",
+    "IF" = 9: "interrupt enable",
+    "DF" = 10: "direction",
+    "OF" = 11: "overflow",
+    "NT" = 14: "nested task",
+    "RF" = 16: "resume",
+    "VM" = 17: "virtual-8086 mode",
+    "AC" = 18: "alignment check",
+    "VIF" = 19: "virtual interrupt",
+    "VIP" = 20: "virtual interrupt pending",
+    "ID" = 21: "identification",
+];
+
+const MXCSR_FLAGS: &[RegisterFlag] = flags![
+    "IE" = 0: "invalid operation",
+    "DE" = 1: "denormal",
+    "ZE" = 2: "divide-by-zero",
+    "OE" = 3: "overflow",
+    "UE" = 4: "underflow",
+    "PE" = 5: "precision",
+    "DAZ" = 6: "denormals are zeros",
+    "IM" = 7: "invalid operation mask",
+    "DM" = 8: "denormal mask",
+    "ZM" = 9: "divide-by-zero mask",
+    "OM" = 10: "overflow mask",
+    "UM" = 11: "underflow mask",
+    "PM" = 12: "precision mask",
+    "FZ" = 15: "flush to zero",
+];
+
+const FCW_FLAGS: &[RegisterFlag] = flags![
+    "IM" = 0: "invalid operation mask",
+    "DM" = 1: "denormal mask",
+    "ZM" = 2: "divide-by-zero mask",
+    "OM" = 3: "overflow mask",
+    "UM" = 4: "underflow mask",
+    "PM" = 5: "precision mask",
+    "X" = 12: "infinity control",
+];
+
+const FSW_FLAGS: &[RegisterFlag] = flags![
+    "IE" = 0: "invalid operation",
+    "DE" = 1: "denormal",
+    "ZE" = 2: "divide-by-zero",
+    "OE" = 3: "overflow",
+    "UE" = 4: "underflow",
+    "PE" = 5: "precision",
+    "SF" = 6: "stack fault",
+    "ES" = 7: "exception summary",
+    "C0" = 8: "condition code 0",
+    "C1" = 9: "condition code 1",
+    "C2" = 10: "condition code 2",
+    "C3" = 14: "condition code 3",
+    "B" = 15: "busy",
+];
+
+/// Return the names of the flags in `table` that are set in `value`.
+fn set_flags(value: u64, table: &'static [RegisterFlag]) -> Vec<&'static str> {
+    table
+        .iter()
+        .filter(|flag| value & (1 << flag.bit) != 0)
+        .map(|flag| flag.name)
+        .collect()
+}
+
+/// Write the names of the set flags in the form `[ ZF PF IF ]`.
+fn fmt_flags(f: &mut fmt::Formatter<'_>, flags: &[&str]) -> fmt::Result {
+    write!(f, "[ ")?;
+
+    for flag in flags {
+        write!(f, "{flag} ")?;
+    }
+
+    write!(f, "]")
+}
+
+/// Rounding mode used by the x87 FPU and SSE units.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum RoundingMode {
+    /// Round to nearest (even).
+    Nearest,
+    /// Round down, toward negative infinity.
+    Down,
+    /// Round up, toward positive infinity.
+    Up,
+    /// Round toward zero (truncate).
+    TowardZero,
+}
+
+impl RoundingMode {
+    fn from_bits(bits: u64) -> Self {
+        match bits & 0b11 {
+            0 => Self::Nearest,
+            1 => Self::Down,
+            2 => Self::Up,
+            _ => Self::TowardZero,
+        }
+    }
+}
+
+impl fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self {
+            Self::Nearest => "nearest",
+            Self::Down => "down",
+            Self::Up => "up",
+            Self::TowardZero => "toward-zero",
+        };
+
+        write!(f, "{mode}")
+    }
+}
+
+/// Precision control of the x87 FPU.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum PrecisionControl {
+    /// 24-bit significand.
+    Single,
+    /// Reserved encoding.
+    Reserved,
+    /// 53-bit significand.
+    Double,
+    /// 64-bit significand.
+    Extended,
+}
+
+impl fmt::Display for PrecisionControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = match self {
+            Self::Single => "single",
+            Self::Reserved => "reserved",
+            Self::Double => "double",
+            Self::Extended => "extended",
+        };
+
+        write!(f, "{precision}")
+    }
+}
+
+/// Decoded view of the `eflags` register.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Eflags(pub u64);
+
+impl Eflags {
+    /// Return the names of the set flags.
+    pub fn flags(self) -> Vec<&'static str> {
+        set_flags(self.0, EFLAGS_FLAGS)
+    }
+
+    /// Return the I/O privilege level.
+    pub fn iopl(self) -> u8 {
+        ((self.0 >> 12) & 0b11) as u8
+    }
+}
+
+impl fmt::Display for Eflags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_flags(f, &self.flags())
+    }
+}
+
+/// Decoded view of the SSE control and status register (`mxcsr`).
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Mxcsr(pub u32);
+
+impl Mxcsr {
+    /// Return the names of the set flags, including exception flags and masks.
+    pub fn flags(self) -> Vec<&'static str> {
+        set_flags(self.0 as u64, MXCSR_FLAGS)
+    }
+
+    /// Return the rounding mode.
+    pub fn rounding(self) -> RoundingMode {
+        RoundingMode::from_bits((self.0 >> 13) as u64)
+    }
+}
+
+impl fmt::Display for Mxcsr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_flags(f, &self.flags())?;
+        write!(f, " RC={}", self.rounding())
+    }
+}
+
+/// Decoded view of the x87 FPU control word (`fcw`).
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct FpuControlWord(pub u16);
+
+impl FpuControlWord {
+    /// Return the names of the set flags (exception masks and infinity control).
+    pub fn flags(self) -> Vec<&'static str> {
+        set_flags(self.0 as u64, FCW_FLAGS)
+    }
+
+    /// Return the precision control.
+    pub fn precision(self) -> PrecisionControl {
+        match (self.0 >> 8) & 0b11 {
+            0 => PrecisionControl::Single,
+            1 => PrecisionControl::Reserved,
+            2 => PrecisionControl::Double,
+            _ => PrecisionControl::Extended,
+        }
+    }
+
+    /// Return the rounding mode.
+    pub fn rounding(self) -> RoundingMode {
+        RoundingMode::from_bits((self.0 >> 10) as u64)
+    }
+}
+
+impl fmt::Display for FpuControlWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_flags(f, &self.flags())?;
+        write!(f, " PC={} RC={}", self.precision(), self.rounding())
+    }
+}
+
+/// Decoded view of the x87 FPU status word (`fsw`).
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct FpuStatusWord(pub u16);
+
+impl FpuStatusWord {
+    /// Return the names of the set flags (exceptions and condition codes).
+    pub fn flags(self) -> Vec<&'static str> {
+        set_flags(self.0 as u64, FSW_FLAGS)
+    }
+
+    /// Return the index of the physical register at the top of the x87
+    /// register stack (`ST(0)`).
+    pub fn top(self) -> u8 {
+        ((self.0 >> 11) & 0b111) as u8
+    }
+}
+
+impl fmt::Display for FpuStatusWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_flags(f, &self.flags())?;
+        write!(f, " TOP={}", self.top())
+    }
+}
+
+/// State of a single physical x87 register, as encoded in the full tag word.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum FpuTag {
+    /// Holds a normal, finite value.
+    Valid,
+    /// Holds zero.
+    Zero,
+    /// Holds a NaN, infinity, denormal, or unsupported encoding.
+    Special,
+    /// Holds no value.
+    Empty,
+}
+
+impl FpuTag {
+    /// Classify the value of a non-empty register.
+    fn classify(value: F80) -> Self {
+        match value.classify() {
+            F80Category::Zero => Self::Zero,
+            F80Category::Normal => Self::Valid,
+            // Denormals, infinities, NaNs, and unsupported encodings.
+            _ => Self::Special,
+        }
+    }
+}
+
+impl fmt::Display for FpuTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag = match self {
+            Self::Valid => "valid",
+            Self::Zero => "zero",
+            Self::Special => "special",
+            Self::Empty => "empty",
+        };
+
+        write!(f, "{tag}")
+    }
+}
+
+/// Decoded view of the abridged x87 FPU tag word (`ftw`), as stored by
+/// `FXSAVE`, expanded into the full tag of each physical register `R0` to `R7`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct FpuTagWord {
+    /// Abridged tag word, with one bit set per non-empty physical register.
+    pub abridged: u8,
+    /// Full tag of each physical register.
+    pub tags: [FpuTag; 8],
+}
+
+impl FpuTagWord {
+    /// Expand the `abridged` tag word, given the status word (for `TOP`) and the
+    /// values of `ST(0)` to `ST(7)` in stack order.
+    pub fn new(abridged: u8, fsw: FpuStatusWord, st: [F80; 8]) -> Self {
+        let top = fsw.top() as usize;
+        let mut tags = [FpuTag::Empty; 8];
+
+        for (phys, tag) in tags.iter_mut().enumerate() {
+            if abridged & (1 << phys) != 0 {
+                // Physical register `Ri` is `ST((i - TOP) mod 8)`.
+                *tag = FpuTag::classify(st[(phys + 8 - top) % 8]);
+            }
+        }
+
+        Self { abridged, tags }
+    }
+
+    /// Return the full 16-bit tag word, with two bits per physical register.
+    pub fn full(&self) -> u16 {
+        self.tags.iter().enumerate().fold(0, |word, (phys, tag)| {
+            let bits = match tag {
+                FpuTag::Valid => 0b00,
+                FpuTag::Zero => 0b01,
+                FpuTag::Special => 0b10,
+                FpuTag::Empty => 0b11,
+            };
+
+            word | (bits << (phys * 2))
+        })
+    }
+}
+
+impl fmt::Display for FpuTagWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (phys, tag) in self.tags.iter().enumerate() {
+            if phys > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "R{phys}={tag}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Location of a logical x87 stack register `ST(i)` within the physical
+/// register file, given the current `TOP` of the stack.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct X87StackSlot {
+    /// Index `i` of `ST(i)`, relative to the top of the stack.
+    pub logical: u8,
+    /// Index of the physical register `R0` to `R7`.
+    pub physical: u8,
+    /// Full tag of the physical register.
+    pub tag: FpuTag,
+}
+
+impl fmt::Display for X87StackSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ST({})=R{} {}", self.logical, self.physical, self.tag)
+    }
+}
+
+/// Decoded representation of a status, control, or x87 stack register.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum DecodedRegister {
+    /// Decoded `eflags`.
+    Eflags(Eflags),
+    /// Decoded `mxcsr`.
+    Mxcsr(Mxcsr),
+    /// Decoded `fcw`.
+    FpuControl(FpuControlWord),
+    /// Decoded `fsw`.
+    FpuStatus(FpuStatusWord),
+    /// Decoded `ftw`.
+    FpuTag(FpuTagWord),
+    /// Physical location of `st0` to `st7`.
+    X87Stack(X87StackSlot),
+}
+
+impl fmt::Display for DecodedRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Eflags(v) => write!(f, "{v}"),
+            Self::Mxcsr(v) => write!(f, "{v}"),
+            Self::FpuControl(v) => write!(f, "{v}"),
+            Self::FpuStatus(v) => write!(f, "{v}"),
+            Self::FpuTag(v) => write!(f, "{v}"),
+            Self::X87Stack(v) => write!(f, "{v}"),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt;
+
+use super::RegisterValue;
+use super::value::to_array;
+
+/// Element type used to interpret the bytes of a vector register as a number
+/// of equally sized lanes.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum LaneFormat {
+    /// 8-bit unsigned integer lanes.
+    U8,
+    /// 8-bit signed integer lanes.
+    I8,
+    /// 16-bit unsigned integer lanes.
+    U16,
+    /// 16-bit signed integer lanes.
+    I16,
+    /// 32-bit unsigned integer lanes.
+    U32,
+    /// 32-bit signed integer lanes.
+    I32,
+    /// 64-bit unsigned integer lanes.
+    U64,
+    /// 64-bit signed integer lanes.
+    I64,
+    /// 32-bit IEEE 754 floating-point lanes.
+    F32,
+    /// 64-bit IEEE 754 floating-point lanes.
+    F64,
+}
+
+impl LaneFormat {
+    /// Every [`LaneFormat`], in order of increasing width.
+    pub const ALL: [Self; 10] = [
+        Self::U8,
+        Self::I8,
+        Self::U16,
+        Self::I16,
+        Self::U32,
+        Self::I32,
+        Self::U64,
+        Self::I64,
+        Self::F32,
+        Self::F64,
+    ];
+
+    /// Return the name of the lane format (e.g., `f32`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::U8 => "u8",
+            Self::I8 => "i8",
+            Self::U16 => "u16",
+            Self::I16 => "i16",
+            Self::U32 => "u32",
+            Self::I32 => "i32",
+            Self::U64 => "u64",
+            Self::I64 => "i64",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+        }
+    }
+
+    /// Look up the [`LaneFormat`] with the given `name` (e.g., `f32`).
+    pub fn format_by_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Return the size in bytes of a single lane.
+    pub fn size(self) -> usize {
+        match self {
+            Self::U8 | Self::I8 => 1,
+            Self::U16 | Self::I16 => 2,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
+        }
+    }
+
+    /// Decode the raw `bytes` of a single lane.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `bytes` does not match the size of the lane.
+    pub(crate) fn decode(self, bytes: &[u8]) -> RegisterValue {
+        match self {
+            Self::U8 => RegisterValue::U8(bytes[0]),
+            Self::I8 => RegisterValue::I8(bytes[0] as i8),
+            Self::U16 => RegisterValue::U16(u16::from_le_bytes(to_array(bytes))),
+            Self::I16 => RegisterValue::I16(i16::from_le_bytes(to_array(bytes))),
+            Self::U32 => RegisterValue::U32(u32::from_le_bytes(to_array(bytes))),
+            Self::I32 => RegisterValue::I32(i32::from_le_bytes(to_array(bytes))),
+            Self::U64 => RegisterValue::U64(u64::from_le_bytes(to_array(bytes))),
+            Self::I64 => RegisterValue::I64(i64::from_le_bytes(to_array(bytes))),
+            Self::F32 => RegisterValue::F32(f32::from_le_bytes(to_array(bytes))),
+            Self::F64 => RegisterValue::F64(f64::from_le_bytes(to_array(bytes))),
+        }
+    }
+}
+
+impl fmt::Display for LaneFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Interpretation of a vector register as lanes of a single [`LaneFormat`],
+/// ordered from the least significant lane.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LaneView {
+    /// Format of each lane.
+    pub format: LaneFormat,
+    /// Value of each lane.
+    pub lanes: Vec<RegisterValue>,
+}
+
+impl LaneView {
+    /// Return the name of the view, given by its lane count and format (e.g.,
+    /// `v4_f32` for a 128-bit register).
+    pub fn name(&self) -> String {
+        format!("v{}_{}", self.lanes.len(), self.format)
+    }
+}
+
+impl fmt::Display for LaneView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+
+        for (idx, lane) in self.lanes.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{lane}")?;
+        }
+
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lane_views() {
+        let mut bytes = [0u8; 16];
+        bytes[..4].copy_from_slice(&1.5f32.to_le_bytes());
+        bytes[12..].copy_from_slice(&(-2i32).to_le_bytes());
+
+        let value = RegisterValue::Byte128(bytes);
+
+        let view = value.lanes(LaneFormat::F32).unwrap();
+        assert_eq!(view.name(), "v4_f32");
+        assert_eq!(view.lanes[0], RegisterValue::F32(1.5));
+
+        let view = value.lanes(LaneFormat::I32).unwrap();
+        assert_eq!(view.to_string(), "{1069547520, 0, 0, -2}");
+
+        let view = value.lanes(LaneFormat::U64).unwrap();
+        assert_eq!(view.name(), "v2_u64");
+        assert_eq!(view.to_string(), "{0x000000003fc00000, 0xfffffffe00000000}");
+
+        assert!(RegisterValue::U64(0).lanes(LaneFormat::U8).is_none());
+        assert_eq!(LaneFormat::format_by_name("F64"), Some(LaneFormat::F64));
+        assert_eq!(LaneFormat::format_by_name("f16"), None);
+    }
+
+    #[test]
+    fn lane_writes() {
+        let value = RegisterValue::Byte128([0; 16]);
+
+        let value = value
+            .with_lane(LaneFormat::F32, 2, RegisterValue::F32(1.5))
+            .unwrap();
+        assert_eq!(
+            value.lanes(LaneFormat::F32).unwrap().to_string(),
+            "{0.0, 0.0, 1.5, 0.0}"
+        );
+
+        // Signed and unsigned values of the lane width are interchangeable.
+        let value = value
+            .with_lane(LaneFormat::U8, 0, RegisterValue::I8(-1))
+            .unwrap();
+        assert_eq!(value.to_bytes()[0], 0xff);
+
+        assert!(
+            value
+                .with_lane(LaneFormat::F32, 4, RegisterValue::F32(0.0))
+                .is_err()
+        );
+        assert!(
+            value
+                .with_lane(LaneFormat::F32, 0, RegisterValue::F64(0.0))
+                .is_err()
+        );
+        assert!(
+            RegisterValue::U64(0)
+                .with_lane(LaneFormat::U8, 0, RegisterValue::U8(0))
+                .is_err()
+        );
+    }
+}
@@ -1,5 +1,5 @@
 #[allow(unused_imports)]
-use super::{RegisterFormat, RegisterInfo, RegisterType};
+use super::{ExtendedRegisters, RegisterFormat, RegisterInfo, RegisterType, UserArea};
 
 // Macro to calculate offset of a general-purpose register within `libc::user`.
 macro_rules! gp_reg_offset {
@@ -68,7 +68,8 @@ macro_rules! gp_reg_8_bit_h {
             name: stringify!($name),
             dwarf_id: -1,
             size: 1,
-            offset: gp_reg_offset!($super),
+            // High byte registers address bits 8-15 of their parent register.
+            offset: gp_reg_offset!($super) + 1,
             reg_type: RegisterType::SubRegister,
             format: RegisterFormat::UInt,
         }
@@ -227,3 +228,63 @@ macro_rules! debug_reg {
 }
 
 pub(crate) use debug_reg;
+
+//=============================================================================
+
+// Macro to calculate offset of a register held in the extended (XSAVE) state,
+// which is cached directly after `libc::user`.
+macro_rules! ext_reg_offset {
+    ($reg:ident) => {
+        std::mem::offset_of!(UserArea, ext) + std::mem::offset_of!(ExtendedRegisters, $reg)
+    };
+}
+
+pub(crate) use ext_reg_offset;
+
+// Macro to define register information for a `ymm` AVX register.
+macro_rules! ext_reg_ymm {
+    ($number:expr) => {
+        RegisterInfo {
+            name: concat!("ymm", stringify!($number)),
+            dwarf_id: -1,
+            size: 32,
+            offset: ext_reg_offset!(ymm) + ($number * 32),
+            reg_type: RegisterType::Extended,
+            format: RegisterFormat::Vector,
+        }
+    };
+}
+
+pub(crate) use ext_reg_ymm;
+
+// Macro to define register information for a `zmm` AVX-512 register.
+macro_rules! ext_reg_zmm {
+    ($number:expr) => {
+        RegisterInfo {
+            name: concat!("zmm", stringify!($number)),
+            dwarf_id: -1,
+            size: 64,
+            offset: ext_reg_offset!(zmm) + ($number * 64),
+            reg_type: RegisterType::Extended,
+            format: RegisterFormat::Vector,
+        }
+    };
+}
+
+pub(crate) use ext_reg_zmm;
+
+// Macro to define register information for a `k` AVX-512 opmask register.
+macro_rules! ext_reg_k {
+    ($number:expr) => {
+        RegisterInfo {
+            name: concat!("k", stringify!($number)),
+            dwarf_id: 118 + $number,
+            size: 8,
+            offset: ext_reg_offset!(k) + ($number * 8),
+            reg_type: RegisterType::Extended,
+            format: RegisterFormat::UInt,
+        }
+    };
+}
+
+pub(crate) use ext_reg_k;
@@ -2,10 +2,35 @@
 
 mod macros;
 pub(crate) use macros::{
-    debug_reg, debug_reg_offset, fp_reg, fp_reg_mm, fp_reg_offset, fp_reg_size, fp_reg_st,
-    fp_reg_xmm, gp_reg_8_bit_h, gp_reg_8_bit_l, gp_reg_16_bit, gp_reg_32_bit, gp_reg_64_bit,
-    gp_reg_offset,
+    debug_reg, debug_reg_offset, ext_reg_k, ext_reg_offset, ext_reg_ymm, ext_reg_zmm, fp_reg,
+    fp_reg_mm, fp_reg_offset, fp_reg_size, fp_reg_st, fp_reg_xmm, gp_reg_8_bit_h, gp_reg_8_bit_l,
+    gp_reg_16_bit, gp_reg_32_bit, gp_reg_64_bit, gp_reg_offset,
 };
 
 mod register_info;
-pub(crate) use register_info::{RegisterFormat, RegisterInfo, RegisterType};
+pub use register_info::{RegisterFormat, RegisterInfo, RegisterType};
+
+mod registers;
+pub use registers::Registers;
+pub(crate) use registers::UserArea;
+
+mod xstate;
+pub(crate) use xstate::ExtendedRegisters;
+
+mod value;
+pub use value::RegisterValue;
+
+mod lanes;
+pub use lanes::{LaneFormat, LaneView};
+
+mod delta;
+pub use delta::{RegisterChange, RegisterDelta};
+
+mod f80;
+pub use f80::{F80, F80Category};
+
+mod flags;
+pub use flags::{
+    DecodedRegister, Eflags, FpuControlWord, FpuStatusWord, FpuTag, FpuTagWord, Mxcsr,
+    PrecisionControl, RegisterFlag, RoundingMode, X87StackSlot,
+};
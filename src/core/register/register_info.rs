@@ -1,39 +1,50 @@
 #![allow(dead_code)]
 
 use super::{
-    debug_reg, debug_reg_offset, fp_reg, fp_reg_mm, fp_reg_offset, fp_reg_size, fp_reg_st,
-    fp_reg_xmm, gp_reg_8_bit_h, gp_reg_8_bit_l, gp_reg_16_bit, gp_reg_32_bit, gp_reg_64_bit,
-    gp_reg_offset,
+    ExtendedRegisters, UserArea, debug_reg, debug_reg_offset, ext_reg_k, ext_reg_offset,
+    ext_reg_ymm, ext_reg_zmm, fp_reg, fp_reg_mm, fp_reg_offset, fp_reg_size, fp_reg_st, fp_reg_xmm,
+    gp_reg_8_bit_h, gp_reg_8_bit_l, gp_reg_16_bit, gp_reg_32_bit, gp_reg_64_bit, gp_reg_offset,
 };
 
 /// Specifies the type of a given [`RegisterInfo`].
-#[derive(Debug)]
-pub(crate) enum RegisterType {
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum RegisterType {
+    /// Full-width (64-bit) general-purpose register.
     GeneralPurpose,
+    /// Lower portion of a general-purpose register (e.g., `eax`, `ax`, `al`).
     SubRegister,
+    /// x87, MMX, or SSE register.
     FloatingPoint,
+    /// Hardware debug register (`dr0` to `dr7`).
     Debug,
+    /// AVX or AVX-512 register held in the extended (XSAVE) state.
+    Extended,
 }
 
 /// Different ways a given [`RegisterInfo`] can be interpreted.
-#[derive(Debug)]
-pub(crate) enum RegisterFormat {
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum RegisterFormat {
+    /// Unsigned integer.
     UInt,
+    /// 64-bit IEEE 754 floating-point value.
     DoubleFloat,
+    /// 80-bit x87 extended precision floating-point value.
     LongDouble,
+    /// Packed vector of bytes.
     Vector,
 }
 
 /// Collection of information needed for a single [`RegisterInfo`].
 #[derive(Debug)]
-pub(crate) struct RegisterInfo {
+pub struct RegisterInfo {
     /// Name of the register
     pub name: &'static str,
     /// DWARF register number assigned in SYSV ABI.
     pub dwarf_id: i32,
     /// Size of register in bytes.
     pub size: usize,
-    /// Byte offset into [`libc::user`].
+    /// Byte offset into the cached register state, consisting of [`libc::user`]
+    /// followed by the registers held in the extended (XSAVE) state.
     pub offset: usize,
     /// Type of register (e.g., general-purpose, sub-register, floating-point, debug).
     pub reg_type: RegisterType,
@@ -42,21 +53,27 @@ pub(crate) struct RegisterInfo {
 }
 
 impl RegisterInfo {
+    /// Return the [`RegisterInfo`] of every register known to the debugger.
+    pub fn all() -> &'static [RegisterInfo] {
+        REGISTER_INFO
+    }
+
     /// Find a register by its name and return a reference to the [`RegisterInfo`].
-    pub(crate) fn register_info_by_name(name: &str) -> Option<&'static RegisterInfo> {
+    pub fn register_info_by_name(name: &str) -> Option<&'static RegisterInfo> {
         REGISTER_INFO.iter().find(|&reg| reg.name == name)
     }
 
     /// Find a register by its DWARF register number and return a reference to
     /// the [`RegisterInfo`].
-    pub(crate) fn register_info_by_dwarf(dwarf_id: i32) -> Option<&'static RegisterInfo> {
+    pub fn register_info_by_dwarf(dwarf_id: i32) -> Option<&'static RegisterInfo> {
         REGISTER_INFO.iter().find(|&reg| reg.dwarf_id == dwarf_id)
     }
 }
 
-// `RegisterInfo` definitions for 124 registers, including general-purpose registers
+// `RegisterInfo` definitions for 182 registers, including general-purpose registers
 // (in 64-bit, 32-bit, 16-bit, and 8-bit sizes), FPU, MMX, SSE (xmm0 to xmm15),
-// debug registers, and the `orig_rax` register.
+// debug registers, the `orig_rax`, `fs_base`, and `gs_base` registers, and the
+// AVX (ymm0 to ymm15) and AVX-512 (zmm0 to zmm31, k0 to k7) registers.
 const REGISTER_INFO: &[RegisterInfo] = &[
     gp_reg_64_bit!(rax, 0),
     gp_reg_64_bit!(rdx, 1),
@@ -82,6 +99,9 @@ const REGISTER_INFO: &[RegisterInfo] = &[
     gp_reg_64_bit!(ss, 52),
     gp_reg_64_bit!(ds, 53),
     gp_reg_64_bit!(es, 50),
+    // Base addresses of the `fs` and `gs` segments, used for thread-local storage.
+    gp_reg_64_bit!(fs_base, 58),
+    gp_reg_64_bit!(gs_base, 59),
     // Provided by [`libc::ptrace`] to get the ID of a syscall.
     gp_reg_64_bit!(orig_rax, -1),
     //=========================================================================
@@ -193,4 +213,63 @@ const REGISTER_INFO: &[RegisterInfo] = &[
     debug_reg!(5),
     debug_reg!(6),
     debug_reg!(7),
+    //=========================================================================
+    ext_reg_ymm!(0),
+    ext_reg_ymm!(1),
+    ext_reg_ymm!(2),
+    ext_reg_ymm!(3),
+    ext_reg_ymm!(4),
+    ext_reg_ymm!(5),
+    ext_reg_ymm!(6),
+    ext_reg_ymm!(7),
+    ext_reg_ymm!(8),
+    ext_reg_ymm!(9),
+    ext_reg_ymm!(10),
+    ext_reg_ymm!(11),
+    ext_reg_ymm!(12),
+    ext_reg_ymm!(13),
+    ext_reg_ymm!(14),
+    ext_reg_ymm!(15),
+    //=========================================================================
+    ext_reg_zmm!(0),
+    ext_reg_zmm!(1),
+    ext_reg_zmm!(2),
+    ext_reg_zmm!(3),
+    ext_reg_zmm!(4),
+    ext_reg_zmm!(5),
+    ext_reg_zmm!(6),
+    ext_reg_zmm!(7),
+    ext_reg_zmm!(8),
+    ext_reg_zmm!(9),
+    ext_reg_zmm!(10),
+    ext_reg_zmm!(11),
+    ext_reg_zmm!(12),
+    ext_reg_zmm!(13),
+    ext_reg_zmm!(14),
+    ext_reg_zmm!(15),
+    ext_reg_zmm!(16),
+    ext_reg_zmm!(17),
+    ext_reg_zmm!(18),
+    ext_reg_zmm!(19),
+    ext_reg_zmm!(20),
+    ext_reg_zmm!(21),
+    ext_reg_zmm!(22),
+    ext_reg_zmm!(23),
+    ext_reg_zmm!(24),
+    ext_reg_zmm!(25),
+    ext_reg_zmm!(26),
+    ext_reg_zmm!(27),
+    ext_reg_zmm!(28),
+    ext_reg_zmm!(29),
+    ext_reg_zmm!(30),
+    ext_reg_zmm!(31),
+    //=========================================================================
+    ext_reg_k!(0),
+    ext_reg_k!(1),
+    ext_reg_k!(2),
+    ext_reg_k!(3),
+    ext_reg_k!(4),
+    ext_reg_k!(5),
+    ext_reg_k!(6),
+    ext_reg_k!(7),
 ];
@@ -0,0 +1,364 @@
+use std::{fmt, mem, ptr, slice};
+
+use super::xstate::{ExtendedRegister, ExtendedRegisters, XSaveArea};
+use super::{
+    DecodedRegister, Eflags, F80, FpuControlWord, FpuStatusWord, FpuTagWord, Mxcsr, RegisterFlag,
+    RegisterInfo, RegisterType, RegisterValue, X87StackSlot, debug_reg_offset,
+};
+use crate::Result;
+use crate::utils::errno;
+
+use libc::{PTRACE_GETFPREGS, PTRACE_GETREGS, PTRACE_PEEKUSER, PTRACE_POKEUSER, PTRACE_SETFPREGS};
+use libc::{c_void, pid_t};
+
+/// Cached register state of a tracee: the user area ([`libc::user`]), followed
+/// by the registers held in the extended (XSAVE) state.
+#[repr(C)]
+#[derive(Clone)]
+pub(crate) struct UserArea {
+    /// Copy of the tracee user area.
+    pub(crate) user: libc::user,
+    /// Registers assembled from the XSAVE area of the tracee.
+    pub(crate) ext: ExtendedRegisters,
+}
+
+impl UserArea {
+    /// Return the [`RegisterValue`] of the register described by `info`.
+    pub(crate) fn read(&self, info: &RegisterInfo) -> RegisterValue {
+        RegisterValue::from_bytes(info, &self.as_bytes()[info.offset..info.offset + info.size])
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        // SAFETY: `UserArea` is a plain C struct, so viewing it as bytes is valid
+        // for its entire size.
+        unsafe { slice::from_raw_parts(self as *const Self as *const u8, mem::size_of::<Self>()) }
+    }
+}
+
+/// Snapshot of the user area ([`libc::user`]) and extended state of a stopped
+/// tracee, used to read and write the registers described by [`RegisterInfo`].
+pub struct Registers {
+    /// Process ID of the tracee the registers belong to.
+    pid: pid_t,
+    /// Cached copy of the tracee register state, refreshed on every stop.
+    data: UserArea,
+    /// Cached copy of the tracee XSAVE area, if supported by the system.
+    xsave: Option<XSaveArea>,
+}
+
+impl Registers {
+    /// Creates a new, zeroed [`Registers`] instance for the given `pid`.
+    pub(crate) fn new(pid: pid_t) -> Self {
+        Self {
+            pid,
+            // SAFETY: `UserArea` is a plain C struct, for which the all-zero bit
+            // pattern is valid.
+            data: unsafe { mem::zeroed() },
+            xsave: None,
+        }
+    }
+
+    /// Refresh the cached user area with the general-purpose, floating-point,
+    /// and debug registers of the stopped tracee.
+    pub(crate) fn read_all(&mut self) -> Result<()> {
+        // Copy the tracee's general-purpose registers into `data.user.regs`.
+        // `addr` argument is ignored.
+        if unsafe {
+            libc::ptrace(
+                PTRACE_GETREGS,
+                self.pid,
+                ptr::null_mut::<c_void>(),
+                &mut self.data.user.regs as *mut libc::user_regs_struct as *mut c_void,
+            )
+        } < 0
+        {
+            return Err(errno!("failed to read general-purpose registers"));
+        }
+
+        self.read_fprs()?;
+
+        // Debug registers can only be read one word at a time from the user area.
+        for (idx, reg) in self.data.user.u_debugreg.iter_mut().enumerate() {
+            *reg = peek_user(self.pid, debug_reg_offset!(idx))
+                .map_err(|err| format!("failed to read debug register dr{idx}: {err}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Refresh the cached floating-point registers and extended state of the
+    /// stopped tracee.
+    fn read_fprs(&mut self) -> Result<()> {
+        // Copy the tracee's floating-point registers into `data.user.i387`.
+        // `addr` argument is ignored.
+        if unsafe {
+            libc::ptrace(
+                PTRACE_GETFPREGS,
+                self.pid,
+                ptr::null_mut::<c_void>(),
+                &mut self.data.user.i387 as *mut libc::user_fpregs_struct as *mut c_void,
+            )
+        } < 0
+        {
+            return Err(errno!("failed to read floating-point registers"));
+        }
+
+        self.xsave = XSaveArea::read(self.pid)?;
+
+        if let Some(xsave) = &self.xsave {
+            xsave.unpack(&mut self.data.ext);
+        }
+
+        Ok(())
+    }
+
+    /// Return `true` if the register described by `info` is supported by the
+    /// system. Registers held in disabled XSAVE state components (e.g., `zmm0`
+    /// on a CPU without AVX-512) are unavailable, and always read as zero.
+    pub fn is_available(&self, info: &RegisterInfo) -> bool {
+        if info.reg_type != RegisterType::Extended {
+            return true;
+        }
+
+        let reg = Self::extended_register(info);
+
+        self.xsave.as_ref().is_some_and(|xsave| {
+            reg.components()
+                .iter()
+                .all(|&component| xsave.is_enabled(component))
+        })
+    }
+
+    /// Locate the register described by `info` within [`ExtendedRegisters`].
+    fn extended_register(info: &RegisterInfo) -> ExtendedRegister {
+        ExtendedRegister::from_offset(info.offset - mem::offset_of!(UserArea, ext))
+    }
+
+    /// Return the [`RegisterValue`] of the register described by `info`, as of
+    /// the last time the tracee stopped.
+    pub fn read(&self, info: &RegisterInfo) -> RegisterValue {
+        self.data.read(info)
+    }
+
+    /// Return a copy of the cached register state, which unlike [`Registers`]
+    /// cannot be written back into the tracee.
+    pub(crate) fn snapshot(&self) -> Box<UserArea> {
+        Box::new(self.data.clone())
+    }
+
+    /// Write `value` into the register described by `info`, both in the cached
+    /// user area and in the tracee.
+    ///
+    /// Values narrower than the register are widened according to
+    /// [`RegisterValue::widen`]. Sub-registers follow the semantics of the
+    /// hardware: writing a 32-bit sub-register (e.g., `eax`) clears the upper
+    /// half of its parent, whereas 16-bit and 8-bit sub-registers (e.g., `ax`,
+    /// `ah`, `al`) leave the remaining bits of their parent untouched.
+    pub fn write(&mut self, info: &RegisterInfo, value: RegisterValue) -> Result<()> {
+        let bytes = value.widen(info)?;
+
+        self.as_bytes_mut()[info.offset..info.offset + info.size].copy_from_slice(&bytes);
+
+        // Writes to 32-bit sub-registers zero-extend into the parent 64-bit
+        // register, while 16-bit and 8-bit writes preserve the upper bits.
+        if info.reg_type == RegisterType::SubRegister && info.size == 4 {
+            self.as_bytes_mut()[info.offset + 4..info.offset + 8].fill(0);
+        }
+
+        match info.reg_type {
+            RegisterType::FloatingPoint => {
+                self.write_fprs()?;
+
+                // Registers in the extended state overlap with the `xmm`
+                // registers, so must be re-read.
+                self.read_fprs()
+            }
+            RegisterType::Extended => {
+                if !self.is_available(info) {
+                    return Err(format!(
+                        "register '{}' is not supported on this system",
+                        info.name
+                    )
+                    .into());
+                }
+
+                let reg = Self::extended_register(info);
+
+                if let Some(xsave) = &mut self.xsave {
+                    xsave.pack(&self.data.ext, reg)?;
+                    xsave.write(self.pid)?;
+                }
+
+                // Registers in the extended state overlap with each other, and
+                // with the `xmm` registers, so must be re-read.
+                self.read_fprs()
+            }
+            _ => {
+                // `PTRACE_POKEUSER` requires a word-aligned offset, so the entire
+                // word containing the (sub-)register is written back.
+                let aligned = info.offset & !0b111;
+
+                let mut word = [0u8; 8];
+                word.copy_from_slice(&self.as_bytes()[aligned..aligned + 8]);
+
+                self.write_user_area(aligned, u64::from_le_bytes(word))
+            }
+        }
+    }
+
+    /// Return the decoded representation of the register described by `info`,
+    /// or `None` if the register is not a status or control register (i.e.,
+    /// `eflags`, `mxcsr`, `fcw`, `fsw`, or `ftw`) or an x87 stack register.
+    pub fn decode(&self, info: &RegisterInfo) -> Option<DecodedRegister> {
+        if let Some(logical) = ST_NAMES.iter().position(|name| *name == info.name) {
+            let top = FpuStatusWord(self.data.user.i387.swd).top() as usize;
+            let physical = (logical + top) % 8;
+
+            return Some(DecodedRegister::X87Stack(X87StackSlot {
+                logical: logical as u8,
+                physical: physical as u8,
+                tag: self.tag_word()?.tags[physical],
+            }));
+        }
+
+        let raw = self.read(info).to_u64()?;
+
+        let decoded = match info.name {
+            "eflags" => DecodedRegister::Eflags(Eflags(raw)),
+            "mxcsr" => DecodedRegister::Mxcsr(Mxcsr(raw as u32)),
+            "fcw" => DecodedRegister::FpuControl(FpuControlWord(raw as u16)),
+            "fsw" => DecodedRegister::FpuStatus(FpuStatusWord(raw as u16)),
+            "ftw" => DecodedRegister::FpuTag(self.tag_word()?),
+            _ => return None,
+        };
+
+        Some(decoded)
+    }
+
+    /// Expand the abridged x87 tag word into the full tag of each physical
+    /// register, classifying the values of `st0` to `st7`.
+    fn tag_word(&self) -> Option<FpuTagWord> {
+        let i387 = &self.data.user.i387;
+        let mut st = [F80::default(); 8];
+
+        for (idx, value) in st.iter_mut().enumerate() {
+            let info = RegisterInfo::register_info_by_name(ST_NAMES[idx])?;
+
+            if let RegisterValue::LongDouble(v) = self.read(info) {
+                *value = v;
+            }
+        }
+
+        Some(FpuTagWord::new(i387.ftw as u8, FpuStatusWord(i387.swd), st))
+    }
+
+    /// Set or clear the flag with the given `name` (e.g., `ZF`) within the
+    /// register described by `info`, writing the result into the tracee.
+    pub fn set_flag(&mut self, info: &RegisterInfo, name: &str, set: bool) -> Result<()> {
+        let flag = RegisterFlag::flag_by_name(info, name)
+            .ok_or_else(|| format!("register '{}' has no flag '{name}'", info.name))?;
+
+        let raw = self
+            .read(info)
+            .to_u64()
+            .ok_or_else(|| format!("register '{}' is not an integer register", info.name))?;
+
+        let raw = if set {
+            raw | (1 << flag.bit)
+        } else {
+            raw & !(1 << flag.bit)
+        };
+
+        self.write(info, RegisterValue::from_u64(raw, info.size))
+    }
+
+    /// Write a word into the user area of the tracee at the given `offset`.
+    fn write_user_area(&self, offset: usize, word: u64) -> Result<()> {
+        if unsafe {
+            libc::ptrace(
+                PTRACE_POKEUSER,
+                self.pid,
+                offset as *mut c_void,
+                word as *mut c_void,
+            )
+        } < 0
+        {
+            return Err(errno!("failed to write to user area at offset {offset:#x}"));
+        }
+
+        Ok(())
+    }
+
+    /// Write the cached floating-point registers back into the tracee.
+    fn write_fprs(&mut self) -> Result<()> {
+        if unsafe {
+            libc::ptrace(
+                PTRACE_SETFPREGS,
+                self.pid,
+                ptr::null_mut::<c_void>(),
+                &mut self.data.user.i387 as *mut libc::user_fpregs_struct as *mut c_void,
+            )
+        } < 0
+        {
+            return Err(errno!("failed to write floating-point registers"));
+        }
+
+        Ok(())
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        self.data.as_bytes()
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        // SAFETY: `UserArea` is a plain C struct, for which any bit pattern
+        // written into its register fields is valid.
+        unsafe {
+            slice::from_raw_parts_mut(
+                &mut self.data as *mut UserArea as *mut u8,
+                mem::size_of::<UserArea>(),
+            )
+        }
+    }
+}
+
+impl fmt::Debug for UserArea {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserArea").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registers")
+            .field("pid", &self.pid)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Names of the x87 registers `ST(0)` to `ST(7)`, in stack order.
+const ST_NAMES: [&str; 8] = ["st0", "st1", "st2", "st3", "st4", "st5", "st6", "st7"];
+
+/// Read a word from the user area of the tracee at the given `offset`.
+fn peek_user(pid: pid_t, offset: usize) -> Result<u64> {
+    // `PTRACE_PEEKUSER` can legitimately return -1, so `errno` must be cleared
+    // beforehand to distinguish errors.
+    unsafe { *libc::__errno_location() = 0 };
+
+    let word = unsafe {
+        libc::ptrace(
+            PTRACE_PEEKUSER,
+            pid,
+            offset as *mut c_void,
+            ptr::null_mut::<c_void>(),
+        )
+    };
+
+    if word == -1 && unsafe { *libc::__errno_location() } != 0 {
+        return Err(errno!(
+            "failed to read from user area at offset {offset:#x}"
+        ));
+    }
+
+    Ok(word as u64)
+}
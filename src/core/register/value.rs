@@ -0,0 +1,387 @@
+use std::fmt;
+
+use super::{F80, LaneFormat, LaneView, RegisterFormat, RegisterInfo};
+use crate::Result;
+
+/// Typed value of a register, decoded according to the size and
+/// [`RegisterFormat`] of its [`RegisterInfo`].
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum RegisterValue {
+    /// 8-bit unsigned integer.
+    U8(u8),
+    /// 16-bit unsigned integer.
+    U16(u16),
+    /// 32-bit unsigned integer.
+    U32(u32),
+    /// 64-bit unsigned integer.
+    U64(u64),
+    /// 8-bit signed integer.
+    I8(i8),
+    /// 16-bit signed integer.
+    I16(i16),
+    /// 32-bit signed integer.
+    I32(i32),
+    /// 64-bit signed integer.
+    I64(i64),
+    /// 32-bit IEEE 754 floating-point value.
+    F32(f32),
+    /// 64-bit IEEE 754 floating-point value.
+    F64(f64),
+    /// 80-bit x87 extended precision floating-point value.
+    LongDouble(F80),
+    /// 64-bit vector of bytes (e.g., `mm0`).
+    Byte64([u8; 8]),
+    /// 128-bit vector of bytes (e.g., `xmm0`).
+    Byte128([u8; 16]),
+    /// 256-bit vector of bytes (e.g., `ymm0`).
+    Byte256([u8; 32]),
+    /// 512-bit vector of bytes (e.g., `zmm0`).
+    Byte512([u8; 64]),
+}
+
+impl RegisterValue {
+    /// Decode the raw `bytes` of the register described by `info`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `bytes` does not match the size of `info`.
+    pub(crate) fn from_bytes(info: &RegisterInfo, bytes: &[u8]) -> Self {
+        match (info.format, bytes.len()) {
+            (RegisterFormat::UInt, 1) => Self::U8(bytes[0]),
+            (RegisterFormat::UInt, 2) => Self::U16(u16::from_le_bytes(to_array(bytes))),
+            (RegisterFormat::UInt, 4) => Self::U32(u32::from_le_bytes(to_array(bytes))),
+            (RegisterFormat::UInt, 8) => Self::U64(u64::from_le_bytes(to_array(bytes))),
+            (RegisterFormat::DoubleFloat, 8) => Self::F64(f64::from_le_bytes(to_array(bytes))),
+            // The 80-bit value is stored within a 16 byte slot.
+            (RegisterFormat::LongDouble, 16) => {
+                Self::LongDouble(F80::from_bytes(to_array(&bytes[..10])))
+            }
+            (RegisterFormat::Vector, 8) => Self::Byte64(to_array(bytes)),
+            (RegisterFormat::Vector, 16) => Self::Byte128(to_array(bytes)),
+            (RegisterFormat::Vector, 32) => Self::Byte256(to_array(bytes)),
+            (RegisterFormat::Vector, 64) => Self::Byte512(to_array(bytes)),
+            (format, size) => {
+                panic!(
+                    "invalid size {size} for {format:?} register '{}'",
+                    info.name
+                )
+            }
+        }
+    }
+
+    /// Creates an unsigned integer [`RegisterValue`] of `size` bytes from the low
+    /// bytes of `value`.
+    pub(crate) fn from_u64(value: u64, size: usize) -> Self {
+        match size {
+            1 => Self::U8(value as u8),
+            2 => Self::U16(value as u16),
+            4 => Self::U32(value as u32),
+            _ => Self::U64(value),
+        }
+    }
+
+    /// Return the size in bytes of the given [`RegisterValue`].
+    pub fn size(&self) -> usize {
+        match self {
+            Self::U8(_) | Self::I8(_) => 1,
+            Self::U16(_) | Self::I16(_) => 2,
+            Self::U32(_) | Self::I32(_) | Self::F32(_) => 4,
+            Self::U64(_) | Self::I64(_) | Self::F64(_) | Self::Byte64(_) => 8,
+            Self::LongDouble(_) => 10,
+            Self::Byte128(_) => 16,
+            Self::Byte256(_) => 32,
+            Self::Byte512(_) => 64,
+        }
+    }
+
+    /// Return the raw little-endian bytes of the given [`RegisterValue`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match *self {
+            Self::U8(v) => v.to_le_bytes().to_vec(),
+            Self::U16(v) => v.to_le_bytes().to_vec(),
+            Self::U32(v) => v.to_le_bytes().to_vec(),
+            Self::U64(v) => v.to_le_bytes().to_vec(),
+            Self::I8(v) => v.to_le_bytes().to_vec(),
+            Self::I16(v) => v.to_le_bytes().to_vec(),
+            Self::I32(v) => v.to_le_bytes().to_vec(),
+            Self::I64(v) => v.to_le_bytes().to_vec(),
+            Self::F32(v) => v.to_le_bytes().to_vec(),
+            Self::F64(v) => v.to_le_bytes().to_vec(),
+            Self::LongDouble(v) => v.to_bytes().to_vec(),
+            Self::Byte64(v) => v.to_vec(),
+            Self::Byte128(v) => v.to_vec(),
+            Self::Byte256(v) => v.to_vec(),
+            Self::Byte512(v) => v.to_vec(),
+        }
+    }
+
+    /// Return the value of an integer [`RegisterValue`] as a [`u64`], with
+    /// signed values being sign-extended. Returns `None` for non-integer values.
+    pub fn to_u64(&self) -> Option<u64> {
+        match *self {
+            Self::U8(v) => Some(v as u64),
+            Self::U16(v) => Some(v as u64),
+            Self::U32(v) => Some(v as u64),
+            Self::U64(v) => Some(v),
+            Self::I8(v) => Some(v as u64),
+            Self::I16(v) => Some(v as u64),
+            Self::I32(v) => Some(v as u64),
+            Self::I64(v) => Some(v as u64),
+            _ => None,
+        }
+    }
+
+    /// Return the bytes of a vector [`RegisterValue`], or `None` for non-vector
+    /// values.
+    fn vector_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Byte64(v) => Some(v),
+            Self::Byte128(v) => Some(v),
+            Self::Byte256(v) => Some(v),
+            Self::Byte512(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Interpret a vector [`RegisterValue`] as lanes of the given `format`.
+    /// Returns `None` for non-vector values.
+    pub fn lanes(&self, format: LaneFormat) -> Option<LaneView> {
+        let lanes = self
+            .vector_bytes()?
+            .chunks_exact(format.size())
+            .map(|bytes| format.decode(bytes))
+            .collect();
+
+        Some(LaneView { format, lanes })
+    }
+
+    /// Return a copy of a vector [`RegisterValue`] with the lane at `index`,
+    /// when interpreted as lanes of the given `format`, replaced by `value`.
+    ///
+    /// The size of `value` must match the size of the lane.
+    pub fn with_lane(
+        &self,
+        format: LaneFormat,
+        index: usize,
+        value: RegisterValue,
+    ) -> Result<Self> {
+        let mut vector = *self;
+
+        let bytes: &mut [u8] = match &mut vector {
+            Self::Byte64(v) => v,
+            Self::Byte128(v) => v,
+            Self::Byte256(v) => v,
+            Self::Byte512(v) => v,
+            _ => return Err("lanes can only be written within vector values".into()),
+        };
+
+        let count = bytes.len() / format.size();
+
+        if index >= count {
+            return Err(format!(
+                "lane index {index} is out of range for v{count}_{format} (0 to {})",
+                count - 1
+            )
+            .into());
+        }
+
+        if value.size() != format.size() {
+            return Err(format!(
+                "value of {} bytes does not fit in {} byte {format} lane",
+                value.size(),
+                format.size()
+            )
+            .into());
+        }
+
+        let start = index * format.size();
+        bytes[start..start + format.size()].copy_from_slice(&value.to_bytes());
+
+        Ok(vector)
+    }
+
+    /// Convert the given [`RegisterValue`] into the raw bytes of the register
+    /// described by `info`, widening it to the size of the register.
+    ///
+    /// - Floating-point values are converted to the floating-point format of the
+    ///   register.
+    /// - Integers are converted to the value they represent in extended
+    ///   precision registers.
+    /// - Signed integers are sign-extended into integer registers.
+    /// - All other values are zero-extended.
+    ///
+    /// Values wider than the register, and floating-point values written to
+    /// integer registers, result in an error.
+    pub fn widen(&self, info: &RegisterInfo) -> Result<Vec<u8>> {
+        if self.size() > info.size {
+            return Err(format!(
+                "value of {} bytes does not fit in {} byte register '{}'",
+                self.size(),
+                info.size,
+                info.name
+            )
+            .into());
+        }
+
+        let bytes = match (*self, info.format) {
+            (Self::F32(v), RegisterFormat::DoubleFloat) => (v as f64).to_le_bytes().to_vec(),
+            (Self::F32(v), RegisterFormat::LongDouble) => {
+                F80::from_f64(v as f64).to_bytes().to_vec()
+            }
+            (Self::F64(v), RegisterFormat::LongDouble) => F80::from_f64(v).to_bytes().to_vec(),
+            (Self::F32(_) | Self::F64(_) | Self::LongDouble(_), RegisterFormat::UInt) => {
+                return Err(format!(
+                    "cannot write floating-point value to integer register '{}'",
+                    info.name
+                )
+                .into());
+            }
+            (
+                Self::U8(_) | Self::U16(_) | Self::U32(_) | Self::U64(_),
+                RegisterFormat::LongDouble,
+            ) => F80::from_u64(self.to_u64().unwrap_or_default())
+                .to_bytes()
+                .to_vec(),
+            (
+                Self::I8(_) | Self::I16(_) | Self::I32(_) | Self::I64(_),
+                RegisterFormat::LongDouble,
+            ) => {
+                // `to_u64` sign-extends, so the value round-trips through `i64`.
+                F80::from_i64(self.to_u64().unwrap_or_default() as i64)
+                    .to_bytes()
+                    .to_vec()
+            }
+            (Self::I8(_) | Self::I16(_) | Self::I32(_) | Self::I64(_), RegisterFormat::UInt) => {
+                // `to_u64` sign-extends, so truncating keeps the upper bytes set.
+                let v = self.to_u64().unwrap_or_default();
+                v.to_le_bytes()[..info.size].to_vec()
+            }
+            _ => self.to_bytes(),
+        };
+
+        let mut widened = vec![0u8; info.size];
+        widened[..bytes.len()].copy_from_slice(&bytes);
+
+        Ok(widened)
+    }
+}
+
+impl fmt::Display for RegisterValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Unsigned integers are displayed in hexadecimal, padded to their width.
+        let width = self.size() * 2 + 2;
+
+        match self {
+            Self::U8(v) => write!(f, "{v:#0width$x}"),
+            Self::U16(v) => write!(f, "{v:#0width$x}"),
+            Self::U32(v) => write!(f, "{v:#0width$x}"),
+            Self::U64(v) => write!(f, "{v:#0width$x}"),
+            Self::I8(v) => write!(f, "{v}"),
+            Self::I16(v) => write!(f, "{v}"),
+            Self::I32(v) => write!(f, "{v}"),
+            Self::I64(v) => write!(f, "{v}"),
+            // Very large and small magnitudes use scientific notation.
+            Self::F32(v) => write!(f, "{v:?}"),
+            Self::F64(v) => write!(f, "{v:?}"),
+            Self::LongDouble(v) => write!(f, "{v}"),
+            Self::Byte64(v) => fmt_bytes(f, v),
+            Self::Byte128(v) => fmt_bytes(f, v),
+            Self::Byte256(v) => fmt_bytes(f, v),
+            Self::Byte512(v) => fmt_bytes(f, v),
+        }
+    }
+}
+
+// Macro to implement `From` for each primitive held by a `RegisterValue`.
+macro_rules! impl_from {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$ty> for RegisterValue {
+                fn from(value: $ty) -> Self {
+                    Self::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_from! {
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    i8 => I8,
+    i16 => I16,
+    i32 => I32,
+    i64 => I64,
+    f32 => F32,
+    f64 => F64,
+    F80 => LongDouble,
+    [u8; 8] => Byte64,
+    [u8; 16] => Byte128,
+    [u8; 32] => Byte256,
+    [u8; 64] => Byte512,
+}
+
+fn fmt_bytes(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    write!(f, "[")?;
+
+    for (idx, byte) in bytes.iter().enumerate() {
+        if idx > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{byte:#04x}")?;
+    }
+
+    write!(f, "]")
+}
+
+pub(super) fn to_array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut array = [0u8; N];
+    array.copy_from_slice(bytes);
+    array
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reg(name: &str) -> &'static RegisterInfo {
+        RegisterInfo::register_info_by_name(name).unwrap()
+    }
+
+    fn long_double(bytes: &[u8]) -> F80 {
+        F80::from_bytes(to_array(&bytes[..10]))
+    }
+
+    #[test]
+    fn widen_integer_to_long_double() {
+        let bytes = RegisterValue::U8(42).widen(reg("st0")).unwrap();
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(long_double(&bytes).to_f64(), 42.0);
+        assert!(bytes[10..].iter().all(|&b| b == 0));
+
+        let bytes = RegisterValue::I32(-7).widen(reg("st0")).unwrap();
+        assert_eq!(long_double(&bytes).to_f64(), -7.0);
+
+        // Exact, unlike a conversion through `f64`.
+        let bytes = RegisterValue::U64(u64::MAX).widen(reg("st0")).unwrap();
+        assert_eq!(long_double(&bytes), F80::from_u64(u64::MAX));
+        assert_eq!(long_double(&bytes).mantissa(), u64::MAX);
+    }
+
+    #[test]
+    fn widen_float_to_integer() {
+        assert!(RegisterValue::F64(1.5).widen(reg("rax")).is_err());
+        assert!(RegisterValue::F32(1.5).widen(reg("eax")).is_err());
+        assert!(
+            RegisterValue::LongDouble(F80::from_f64(1.0))
+                .widen(reg("rax"))
+                .is_err()
+        );
+
+        // Floating-point values still fill vector registers bitwise.
+        let bytes = RegisterValue::F64(1.5).widen(reg("xmm0")).unwrap();
+        assert_eq!(bytes[..8], 1.5f64.to_le_bytes());
+    }
+}
@@ -0,0 +1,301 @@
+use std::arch::x86_64::__cpuid_count;
+use std::mem;
+
+use crate::Result;
+use crate::utils::errno;
+
+use libc::{PTRACE_GETREGSET, PTRACE_SETREGSET, c_void, iovec, pid_t};
+
+/// Note type used with `PTRACE_GETREGSET` to retrieve the XSAVE area (not
+/// exported by `libc`).
+const NT_X86_XSTATE: usize = 0x202;
+
+/// Offset of the XCR0 bitmap, which Linux stores within the software reserved
+/// bytes of the legacy FXSAVE region.
+const XCR0_OFFSET: usize = 464;
+
+/// Offset of the `XSTATE_BV` bitmap within the XSAVE header.
+const XSTATE_BV_OFFSET: usize = 512;
+
+/// Offset of `xmm0` within the legacy FXSAVE region.
+const XMM_OFFSET: usize = 160;
+
+/// Registers held in the extended (XSAVE) state of the tracee. Each register
+/// is stored contiguously, having been assembled from the XSAVE components
+/// that hold its parts.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub(crate) struct ExtendedRegisters {
+    /// `ymm0` to `ymm15`.
+    pub(crate) ymm: [[u8; 32]; 16],
+    /// `zmm0` to `zmm31`.
+    pub(crate) zmm: [[u8; 64]; 32],
+    /// `k0` to `k7` opmask registers.
+    pub(crate) k: [[u8; 8]; 8],
+}
+
+/// State components of the XSAVE area used by [`ExtendedRegisters`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub(crate) enum XStateComponent {
+    /// Legacy SSE state (`xmm0` to `xmm15`).
+    Sse = 1,
+    /// Upper 128 bits of `ymm0` to `ymm15`.
+    Avx = 2,
+    /// Opmask registers `k0` to `k7`.
+    Opmask = 5,
+    /// Upper 256 bits of `zmm0` to `zmm15`.
+    ZmmHi256 = 6,
+    /// Full 512 bits of `zmm16` to `zmm31`.
+    Hi16Zmm = 7,
+}
+
+/// A single register held in [`ExtendedRegisters`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub(crate) enum ExtendedRegister {
+    /// `ymm` register with the given index.
+    Ymm(usize),
+    /// `zmm` register with the given index.
+    Zmm(usize),
+    /// `k` opmask register with the given index.
+    K(usize),
+}
+
+impl ExtendedRegister {
+    /// Locate the register at the given byte `offset` into [`ExtendedRegisters`].
+    pub(crate) fn from_offset(offset: usize) -> Self {
+        let zmm = mem::offset_of!(ExtendedRegisters, zmm);
+        let k = mem::offset_of!(ExtendedRegisters, k);
+
+        if offset < zmm {
+            Self::Ymm(offset / 32)
+        } else if offset < k {
+            Self::Zmm((offset - zmm) / 64)
+        } else {
+            Self::K((offset - k) / 8)
+        }
+    }
+
+    /// Return the XSAVE state components required to hold the register.
+    pub(crate) fn components(self) -> &'static [XStateComponent] {
+        match self {
+            Self::Ymm(_) => &[XStateComponent::Sse, XStateComponent::Avx],
+            Self::Zmm(0..16) => &[
+                XStateComponent::Sse,
+                XStateComponent::Avx,
+                XStateComponent::ZmmHi256,
+            ],
+            Self::Zmm(_) => &[XStateComponent::Hi16Zmm],
+            Self::K(_) => &[XStateComponent::Opmask],
+        }
+    }
+}
+
+/// Copy of the XSAVE area of a tracee, in the standard (non-compacted) format.
+#[derive(Debug)]
+pub(crate) struct XSaveArea {
+    buffer: Vec<u8>,
+}
+
+impl XSaveArea {
+    /// Read the XSAVE area of the stopped tracee with the given `pid`.
+    ///
+    /// Returns `None` if the CPU or kernel does not support XSAVE.
+    pub(crate) fn read(pid: pid_t) -> Result<Option<Self>> {
+        // The maximum size of the XSAVE area for all features supported by the
+        // processor. The kernel trims the length to the size it actually writes.
+        let size = __cpuid_count(0xd, 0).ecx as usize;
+
+        if size == 0 {
+            return Ok(None);
+        }
+
+        let mut buffer = vec![0u8; size];
+        let mut iov = iovec {
+            iov_base: buffer.as_mut_ptr() as *mut c_void,
+            iov_len: buffer.len(),
+        };
+
+        if unsafe {
+            libc::ptrace(
+                PTRACE_GETREGSET,
+                pid,
+                NT_X86_XSTATE as *mut c_void,
+                &mut iov as *mut iovec as *mut c_void,
+            )
+        } < 0
+        {
+            let err = std::io::Error::last_os_error();
+
+            // The regset is not available on systems without XSAVE support.
+            if matches!(err.raw_os_error(), Some(libc::EINVAL | libc::ENODEV)) {
+                return Ok(None);
+            }
+
+            return Err(format!("failed to read extended state: {err}").into());
+        }
+
+        buffer.truncate(iov.iov_len);
+
+        Ok(Some(Self { buffer }))
+    }
+
+    /// Write the XSAVE area back into the stopped tracee with the given `pid`.
+    pub(crate) fn write(&mut self, pid: pid_t) -> Result<()> {
+        let mut iov = iovec {
+            iov_base: self.buffer.as_mut_ptr() as *mut c_void,
+            iov_len: self.buffer.len(),
+        };
+
+        if unsafe {
+            libc::ptrace(
+                PTRACE_SETREGSET,
+                pid,
+                NT_X86_XSTATE as *mut c_void,
+                &mut iov as *mut iovec as *mut c_void,
+            )
+        } < 0
+        {
+            return Err(errno!("failed to write extended state"));
+        }
+
+        Ok(())
+    }
+
+    /// Return `true` if the state `component` is enabled in XCR0, meaning it is
+    /// present within the XSAVE area.
+    pub(crate) fn is_enabled(&self, component: XStateComponent) -> bool {
+        self.read_u64(XCR0_OFFSET) & (1 << component as u64) != 0
+    }
+
+    /// Populate `regs` from the contents of the XSAVE area. Components that are
+    /// disabled, or in their initial configuration, read as zero.
+    pub(crate) fn unpack(&self, regs: &mut ExtendedRegisters) {
+        // The all-zero bit pattern is valid for the plain byte arrays.
+        *regs = unsafe { mem::zeroed() };
+
+        for n in 0..16 {
+            let xmm = self.get(XStateComponent::Sse, XMM_OFFSET + n * 16, 16);
+            let ymm_hi = self.component(XStateComponent::Avx, n * 16, 16);
+            let zmm_hi = self.component(XStateComponent::ZmmHi256, n * 32, 32);
+
+            if let Some(xmm) = xmm {
+                regs.ymm[n][..16].copy_from_slice(xmm);
+                regs.zmm[n][..16].copy_from_slice(xmm);
+            }
+            if let Some(ymm_hi) = ymm_hi {
+                regs.ymm[n][16..].copy_from_slice(ymm_hi);
+                regs.zmm[n][16..32].copy_from_slice(ymm_hi);
+            }
+            if let Some(zmm_hi) = zmm_hi {
+                regs.zmm[n][32..].copy_from_slice(zmm_hi);
+            }
+        }
+
+        for n in 16..32 {
+            if let Some(zmm) = self.component(XStateComponent::Hi16Zmm, (n - 16) * 64, 64) {
+                regs.zmm[n].copy_from_slice(zmm);
+            }
+        }
+
+        for n in 0..8 {
+            if let Some(k) = self.component(XStateComponent::Opmask, n * 8, 8) {
+                regs.k[n].copy_from_slice(k);
+            }
+        }
+    }
+
+    /// Scatter the value of `reg` from `regs` into the XSAVE components that
+    /// hold it, marking those components as in use.
+    pub(crate) fn pack(&mut self, regs: &ExtendedRegisters, reg: ExtendedRegister) -> Result<()> {
+        let parts: Vec<(XStateComponent, usize, &[u8])> = match reg {
+            ExtendedRegister::Ymm(n) => vec![
+                (
+                    XStateComponent::Sse,
+                    XMM_OFFSET + n * 16,
+                    &regs.ymm[n][..16],
+                ),
+                (XStateComponent::Avx, n * 16, &regs.ymm[n][16..]),
+            ],
+            ExtendedRegister::Zmm(n @ 0..16) => vec![
+                (
+                    XStateComponent::Sse,
+                    XMM_OFFSET + n * 16,
+                    &regs.zmm[n][..16],
+                ),
+                (XStateComponent::Avx, n * 16, &regs.zmm[n][16..32]),
+                (XStateComponent::ZmmHi256, n * 32, &regs.zmm[n][32..]),
+            ],
+            ExtendedRegister::Zmm(n) => {
+                vec![(XStateComponent::Hi16Zmm, (n - 16) * 64, &regs.zmm[n][..])]
+            }
+            ExtendedRegister::K(n) => vec![(XStateComponent::Opmask, n * 8, &regs.k[n][..])],
+        };
+
+        for (component, offset, bytes) in parts {
+            let start = match component {
+                // The legacy region is addressed directly.
+                XStateComponent::Sse => offset,
+                _ => {
+                    self.component_offset(component).ok_or_else(|| {
+                        format!("extended state component {component:?} is not enabled")
+                    })? + offset
+                }
+            };
+
+            self.buffer
+                .get_mut(start..start + bytes.len())
+                .ok_or("extended state component lies outside of the XSAVE area")?
+                .copy_from_slice(bytes);
+
+            // Components not marked in `XSTATE_BV` are restored to their initial
+            // configuration, discarding the written value.
+            let bv = self.read_u64(XSTATE_BV_OFFSET) | (1 << component as u64);
+            self.buffer[XSTATE_BV_OFFSET..XSTATE_BV_OFFSET + 8].copy_from_slice(&bv.to_le_bytes());
+        }
+
+        Ok(())
+    }
+
+    /// Return `len` bytes at `offset` within the given state `component`, or
+    /// `None` if the component is unavailable.
+    fn component(&self, component: XStateComponent, offset: usize, len: usize) -> Option<&[u8]> {
+        let start = self.component_offset(component)? + offset;
+        self.get(component, start, len)
+    }
+
+    /// Return `len` bytes at the absolute `offset` within the XSAVE area, or
+    /// `None` if `component` is disabled or in its initial configuration.
+    fn get(&self, component: XStateComponent, offset: usize, len: usize) -> Option<&[u8]> {
+        let bit = 1 << component as u64;
+
+        if !self.is_enabled(component) || self.read_u64(XSTATE_BV_OFFSET) & bit == 0 {
+            return None;
+        }
+
+        self.buffer.get(offset..offset + len)
+    }
+
+    /// Return the offset of `component` within the standard format XSAVE area.
+    fn component_offset(&self, component: XStateComponent) -> Option<usize> {
+        if !self.is_enabled(component) {
+            return None;
+        }
+
+        // CPUID leaf 0xD, sub-leaf `n` reports the size (EAX) and offset (EBX)
+        // of state component `n`.
+        let offset = __cpuid_count(0xd, component as u32).ebx as usize;
+
+        (offset != 0).then_some(offset)
+    }
+
+    fn read_u64(&self, offset: usize) -> u64 {
+        self.buffer
+            .get(offset..offset + 8)
+            .map(|bytes| {
+                let mut word = [0u8; 8];
+                word.copy_from_slice(bytes);
+                u64::from_le_bytes(word)
+            })
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,290 @@
+use std::cell::OnceCell;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use crate::Result;
+
+/// Locates the source files named by the line number information of a tracee
+/// on the local filesystem, and tracks the lines last displayed from them.
+#[derive(Debug)]
+pub struct Sources {
+    /// Directories searched for source files, most recently added first.
+    directories: Vec<PathBuf>,
+    /// Rules replacing a prefix of source paths (e.g., the build directory of
+    /// a CI container) with another (e.g., a local checkout), tried in order.
+    substitutions: Vec<(PathBuf, PathBuf)>,
+    /// Directory holding the sources of the Rust standard library within the
+    /// sysroot of `rustc`, located on first use.
+    rust_src: OnceCell<Option<PathBuf>>,
+    /// Lines most recently displayed, continued by the next listing.
+    listing: Option<Listing>,
+}
+
+/// Range of lines of a source file displayed by a listing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listing {
+    /// Path of the source file, as named by the line number information.
+    pub path: PathBuf,
+    /// Line numbers displayed, starting from 1.
+    pub lines: RangeInclusive<u64>,
+}
+
+impl Sources {
+    /// Creates a new [`Sources`] without any directories or substitutions.
+    pub(crate) fn new() -> Self {
+        Self {
+            directories: Vec::new(),
+            substitutions: Vec::new(),
+            rust_src: OnceCell::new(),
+            listing: None,
+        }
+    }
+
+    /// Return the directories searched for source files, in order.
+    pub fn directories(&self) -> &[PathBuf] {
+        &self.directories
+    }
+
+    /// Search `directory` for source files before any other directory.
+    pub fn add_directory(&mut self, directory: PathBuf) {
+        self.directories.retain(|dir| *dir != directory);
+        self.directories.insert(0, directory);
+    }
+
+    /// Return the rules replacing a prefix of source paths with another.
+    pub fn substitutions(&self) -> &[(PathBuf, PathBuf)] {
+        &self.substitutions
+    }
+
+    /// Replace the prefix `from` of source paths with `to`, overriding any
+    /// previous rule for `from`.
+    pub fn set_substitution(&mut self, from: PathBuf, to: PathBuf) {
+        match self
+            .substitutions
+            .iter_mut()
+            .find(|(prefix, _)| *prefix == from)
+        {
+            Some(rule) => rule.1 = to,
+            None => self.substitutions.push((from, to)),
+        }
+    }
+
+    /// Return the lines most recently displayed.
+    pub fn listing(&self) -> Option<&Listing> {
+        self.listing.as_ref()
+    }
+
+    /// Record the lines most recently displayed, or forget them (e.g., once
+    /// the tracee stops elsewhere).
+    pub fn set_listing(&mut self, listing: Option<Listing>) {
+        self.listing = listing;
+    }
+
+    /// Find the source file named `path` by line number information on the
+    /// local filesystem. A relative path is first looked up within `comp_dir`,
+    /// the compilation directory of its unit. The first matching substitution
+    /// rule is then applied, paths within the Rust standard library are mapped
+    /// to the sysroot, and the directories are searched for the path, and for
+    /// its file name.
+    pub fn resolve(&self, path: &Path, comp_dir: Option<&Path>) -> Option<PathBuf> {
+        let mut candidates = Vec::new();
+
+        // The compilation directory may itself have moved (e.g., built within
+        // a CI container), so substitution rules apply to it.
+        if path.is_relative()
+            && let Some(comp_dir) = comp_dir
+        {
+            candidates.push(self.substitute(&comp_dir.join(path)));
+        }
+
+        let path = self.substitute(path);
+        candidates.push(path.clone());
+        candidates.extend(self.rust_library_path(&path));
+
+        // Absolute paths are also looked up relative to each directory.
+        let relative: PathBuf = path
+            .components()
+            .filter(|component| matches!(component, Component::Normal(_)))
+            .collect();
+
+        for directory in &self.directories {
+            candidates.push(directory.join(&relative));
+            candidates.extend(path.file_name().map(|name| directory.join(name)));
+        }
+
+        candidates.into_iter().find(|candidate| candidate.is_file())
+    }
+
+    /// Read the lines of the source file named `path` by line number
+    /// information, compiled within `comp_dir` (see [`Sources::resolve`]).
+    pub fn read_lines(&self, path: &Path, comp_dir: Option<&Path>) -> Result<Vec<String>> {
+        let resolved = self.resolve(path, comp_dir).ok_or_else(|| {
+            format!(
+                "source file '{}' not found (see `directory` and `set substitute-path`)",
+                path.display()
+            )
+        })?;
+
+        let data = fs::read(&resolved)
+            .map_err(|err| format!("failed to read '{}': {err}", resolved.display()))?;
+
+        Ok(String::from_utf8_lossy(&data)
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Apply the first substitution rule whose prefix matches `path`.
+    fn substitute(&self, path: &Path) -> PathBuf {
+        self.substitutions
+            .iter()
+            .find_map(|(from, to)| Some(to.join(path.strip_prefix(from).ok()?)))
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+
+    /// Map a path within the Rust standard library, as recorded by `rustc`
+    /// (e.g., `/rustc/<commit>/library/core/src/ptr/mod.rs`), to its copy in
+    /// the sysroot, installed by the `rust-src` component.
+    fn rust_library_path(&self, path: &Path) -> Option<PathBuf> {
+        let mut components = path.strip_prefix("/rustc").ok()?.components();
+        components.next()?; // Commit hash of the compiler.
+
+        Some(self.rust_src()?.join(components.as_path()))
+    }
+
+    /// Return the directory holding the sources of the Rust standard library,
+    /// if a `rustc` is available.
+    fn rust_src(&self) -> Option<&Path> {
+        self.rust_src
+            .get_or_init(|| {
+                let output = Command::new("rustc")
+                    .args(["--print", "sysroot"])
+                    .output()
+                    .ok()?;
+
+                if !output.status.success() {
+                    return None;
+                }
+
+                let sysroot = String::from_utf8(output.stdout).ok()?;
+                Some(Path::new(sysroot.trim()).join("lib/rustlib/src/rust"))
+            })
+            .as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+
+    /// Create a directory holding an empty file at each of the `files` paths,
+    /// unique to the calling test.
+    fn create_tree(name: &str, files: &[&str]) -> PathBuf {
+        let root = env::temp_dir().join(format!("rdbg-sources-{name}-{}", std::process::id()));
+
+        for file in files {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        root
+    }
+
+    #[test]
+    fn sources_resolve() {
+        let root = create_tree("resolve", &["checkout/src/app.rs", "extra/lib.rs"]);
+        let mut sources = Sources::new();
+
+        // Missing files are not found, and existing ones are found as is.
+        assert_eq!(sources.resolve(Path::new("/build/src/app.rs"), None), None);
+        let app = root.join("checkout/src/app.rs");
+        assert_eq!(sources.resolve(&app, None), Some(app.clone()));
+
+        // Substitutions only apply to whole components of the prefix.
+        sources.set_substitution("/build".into(), root.join("wrong"));
+        sources.set_substitution("/build".into(), root.join("checkout"));
+        sources.set_substitution("/bui".into(), root.join("checkout"));
+        assert_eq!(sources.substitutions().len(), 2);
+        assert_eq!(
+            sources.resolve(Path::new("/build/src/app.rs"), None),
+            Some(app.clone())
+        );
+        assert_eq!(
+            sources.resolve(Path::new("/builder/src/app.rs"), None),
+            None
+        );
+
+        // Relative paths are looked up within the compilation directory, to
+        // which substitutions also apply.
+        let checkout = root.join("checkout");
+        assert_eq!(
+            sources.resolve(Path::new("src/app.rs"), Some(&checkout)),
+            Some(app.clone())
+        );
+        assert_eq!(
+            sources.resolve(Path::new("src/app.rs"), Some(Path::new("/build"))),
+            Some(app.clone())
+        );
+
+        // Directories are searched for relative paths, and file names.
+        assert_eq!(sources.resolve(Path::new("src/app.rs"), None), None);
+        sources.add_directory(root.join("checkout"));
+        sources.add_directory(root.join("extra"));
+        sources.add_directory(root.join("checkout"));
+        assert_eq!(
+            sources.directories(),
+            [root.join("checkout"), root.join("extra")]
+        );
+        assert_eq!(sources.resolve(Path::new("src/app.rs"), None), Some(app));
+        assert_eq!(
+            sources.resolve(Path::new("/other/lib.rs"), None),
+            Some(root.join("extra/lib.rs"))
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn sources_resolve_rust_library() {
+        let root = create_tree("rust-library", &["library/core/src/ptr/mod.rs"]);
+        let sources = Sources {
+            rust_src: OnceCell::from(Some(root.clone())),
+            ..Sources::new()
+        };
+
+        assert_eq!(
+            sources.resolve(
+                Path::new("/rustc/0123abcd/library/core/src/ptr/mod.rs"),
+                None
+            ),
+            Some(root.join("library/core/src/ptr/mod.rs"))
+        );
+        assert_eq!(
+            sources.resolve(Path::new("/rustc/0123abcd/library/core/src/lib.rs"), None),
+            None
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn sources_read_lines() {
+        let sources = Sources::new();
+
+        let lines = sources
+            .read_lines(Path::new("src/bin/source_stepping.rs"), None)
+            .unwrap();
+        assert_eq!(lines[8], "    let result = x * x;");
+
+        assert!(
+            sources
+                .read_lines(Path::new("/missing/main.rs"), None)
+                .is_err()
+        );
+    }
+}
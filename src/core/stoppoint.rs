@@ -0,0 +1,103 @@
+use crate::Result;
+use crate::elf::VirtAddr;
+
+/// Location within the tracee at which execution halts, such as a
+/// [`BreakpointSite`](super::BreakpointSite) or [`Watchpoint`](super::Watchpoint).
+pub trait Stoppoint {
+    /// Name of the kind of stoppoint (e.g., `breakpoint`), used in messages.
+    const KIND: &'static str;
+
+    /// Return the identifier of the stoppoint, unique within its
+    /// [`Stoppoints`] collection.
+    fn id(&self) -> usize;
+
+    /// Return the virtual address of the stoppoint.
+    fn address(&self) -> VirtAddr;
+
+    /// Return `true` if the stoppoint is enabled.
+    fn is_enabled(&self) -> bool;
+}
+
+/// Collection of the stoppoints of a tracee, each located at a distinct
+/// address.
+#[derive(Debug)]
+pub struct Stoppoints<T> {
+    /// Stoppoints, in order of creation.
+    items: Vec<T>,
+    /// Identifier assigned to the next stoppoint.
+    next_id: usize,
+}
+
+impl<T: Stoppoint> Stoppoints<T> {
+    /// Creates a new, empty [`Stoppoints`] collection.
+    pub(crate) fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Add the stoppoint created by `create`, given the next identifier.
+    ///
+    /// Returns an error if a stoppoint already exists at the same address.
+    pub(crate) fn push<F>(&mut self, address: VirtAddr, create: F) -> Result<&mut T>
+    where
+        F: FnOnce(usize) -> Result<T>,
+    {
+        if let Some(item) = self.get_by_address(address) {
+            return Err(format!(
+                "{} {} already exists at address {address:#x}",
+                T::KIND,
+                item.id()
+            )
+            .into());
+        }
+
+        let item = create(self.next_id)?;
+        self.next_id += 1;
+        self.items.push(item);
+
+        Ok(self.items.last_mut().expect("stoppoint was just pushed"))
+    }
+
+    /// Return the stoppoint with the given `id`.
+    pub fn get(&self, id: usize) -> Option<&T> {
+        self.items.iter().find(|item| item.id() == id)
+    }
+
+    /// Return a mutable reference to the stoppoint with the given `id`.
+    pub(crate) fn get_mut(&mut self, id: usize) -> Option<&mut T> {
+        self.items.iter_mut().find(|item| item.id() == id)
+    }
+
+    /// Return the stoppoint at the given `address`.
+    pub fn get_by_address(&self, address: VirtAddr) -> Option<&T> {
+        self.items.iter().find(|item| item.address() == address)
+    }
+
+    /// Return a mutable reference to the stoppoint at the given `address`.
+    pub(crate) fn get_by_address_mut(&mut self, address: VirtAddr) -> Option<&mut T> {
+        self.items.iter_mut().find(|item| item.address() == address)
+    }
+
+    /// Remove the stoppoint with the given `id`, without disabling it.
+    pub(crate) fn take(&mut self, id: usize) -> Option<T> {
+        let idx = self.items.iter().position(|item| item.id() == id)?;
+        Some(self.items.remove(idx))
+    }
+
+    /// Return an iterator over every stoppoint, in order of creation.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+
+    /// Return a mutable iterator over every stoppoint.
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.items.iter_mut()
+    }
+
+    /// Return `true` if the collection is empty.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
@@ -0,0 +1,175 @@
+use super::hardware::{clear_hardware_stoppoint, set_hardware_stoppoint};
+use super::memory::peek_data;
+use super::{Registers, Stoppoint, StoppointMode, Stoppoints};
+use crate::Result;
+use crate::elf::VirtAddr;
+
+use libc::pid_t;
+
+/// Hardware stoppoint which halts the tracee when the `size` bytes at
+/// `address` are accessed according to its [`StoppointMode`].
+#[derive(Debug)]
+pub struct Watchpoint {
+    /// Identifier of the watchpoint, unique within a [`Watchpoints`].
+    id: usize,
+    /// Process ID of the tracee the watchpoint belongs to.
+    pid: pid_t,
+    /// Virtual address of the watched data.
+    address: VirtAddr,
+    /// Kind of access which triggers the watchpoint.
+    mode: StoppointMode,
+    /// Number of bytes watched.
+    size: usize,
+    /// Indicates whether the watchpoint is currently installed.
+    is_enabled: bool,
+    /// Debug register slot of an enabled watchpoint.
+    hardware_slot: Option<usize>,
+    /// Value of the watched data as of the last time the watchpoint triggered
+    /// (or was enabled).
+    data: u64,
+    /// Value of the watched data before the last time the watchpoint triggered.
+    previous_data: u64,
+}
+
+/// Collection of the [`Watchpoint`]s of a tracee.
+pub type Watchpoints = Stoppoints<Watchpoint>;
+
+impl Watchpoint {
+    /// Creates a new, disabled [`Watchpoint`], validating the `size` and
+    /// alignment of `address`.
+    pub(crate) fn new(
+        id: usize,
+        pid: pid_t,
+        address: VirtAddr,
+        mode: StoppointMode,
+        size: usize,
+    ) -> Result<Self> {
+        if !matches!(size, 1 | 2 | 4 | 8) {
+            return Err(format!("invalid watchpoint size {size}: expected 1, 2, 4, or 8").into());
+        }
+
+        if mode == StoppointMode::Execute && size != 1 {
+            return Err("execute watchpoints must have a size of 1".into());
+        }
+
+        if !address.0.is_multiple_of(size as u64) {
+            return Err(
+                format!("watchpoint address {address:#x} must be aligned to {size} bytes").into(),
+            );
+        }
+
+        Ok(Self {
+            id,
+            pid,
+            address,
+            mode,
+            size,
+            is_enabled: false,
+            hardware_slot: None,
+            data: 0,
+            previous_data: 0,
+        })
+    }
+
+    /// Return the [`StoppointMode`] of the given [`Watchpoint`].
+    pub fn mode(&self) -> StoppointMode {
+        self.mode
+    }
+
+    /// Return the number of bytes watched by the given [`Watchpoint`].
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Return the debug register slot of an enabled [`Watchpoint`].
+    pub fn hardware_slot(&self) -> Option<usize> {
+        self.hardware_slot
+    }
+
+    /// Return the value of the watched data as of the last time the watchpoint
+    /// triggered (or was enabled).
+    pub fn data(&self) -> u64 {
+        self.data
+    }
+
+    /// Return the value of the watched data before the last time the watchpoint
+    /// triggered.
+    pub fn previous_data(&self) -> u64 {
+        self.previous_data
+    }
+
+    /// Install the watchpoint into a debug register slot, recording the current
+    /// value of the watched data.
+    pub(crate) fn enable(&mut self, regs: &mut Registers) -> Result<()> {
+        if self.is_enabled {
+            return Ok(());
+        }
+
+        self.hardware_slot = Some(set_hardware_stoppoint(
+            regs,
+            self.address.0,
+            self.mode,
+            self.size,
+        )?);
+        self.is_enabled = true;
+
+        self.update_data()?;
+        self.previous_data = self.data;
+
+        Ok(())
+    }
+
+    /// Uninstall the watchpoint, freeing its debug register slot.
+    pub(crate) fn disable(&mut self, regs: &mut Registers) -> Result<()> {
+        if let Some(slot) = self.hardware_slot.take() {
+            clear_hardware_stoppoint(regs, slot)?;
+        }
+
+        self.is_enabled = false;
+
+        Ok(())
+    }
+
+    /// Read the current value of the watched data, retaining the old value.
+    pub(crate) fn update_data(&mut self) -> Result<()> {
+        // The data is aligned to its size, so it never straddles a word.
+        let aligned = self.address.0 & !0b111;
+        let word = peek_data(self.pid, aligned)?;
+
+        let shift = (self.address.0 - aligned) * 8;
+        let mask = if self.size == 8 {
+            u64::MAX
+        } else {
+            (1 << (self.size * 8)) - 1
+        };
+
+        self.previous_data = self.data;
+        self.data = (word >> shift) & mask;
+
+        Ok(())
+    }
+}
+
+impl Stoppoint for Watchpoint {
+    const KIND: &'static str = "watchpoint";
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn address(&self) -> VirtAddr {
+        self.address
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.is_enabled
+    }
+}
+
+impl Watchpoints {
+    /// Return the enabled [`Watchpoint`] using the given debug register `slot`.
+    pub(crate) fn get_by_slot_mut(&mut self, slot: usize) -> Option<&mut Watchpoint> {
+        self.iter_mut()
+            .find(|watchpoint| watchpoint.hardware_slot == Some(slot))
+    }
+}
@@ -0,0 +1,1215 @@
+//! Decoding of the prefixes, opcode, and operands of an instruction.
+
+use super::operand::{Memory, Operand, Register, RegisterClass};
+use super::tables::{
+    D64, Entry, F64, MASK, ONE_BYTE, STR, THREE_BYTE_3A, THREE_BYTE_38, TWO_BYTE, VEX, X87_MEMORY,
+};
+use super::{Instruction, MAX_INSTRUCTION_LEN};
+
+/// Reason an instruction could not be decoded.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Fault {
+    /// The bytes end before the end of the instruction.
+    Truncated,
+    /// The bytes do not encode a valid instruction.
+    Invalid,
+}
+
+/// Result of decoding (part of) an instruction.
+type Decoded<T> = std::result::Result<T, Fault>;
+
+/// Fields of a VEX or EVEX prefix.
+#[derive(Debug, Default)]
+struct Vex {
+    /// Opcode map: 1 (`0F`), 2 (`0F 38`), or 3 (`0F 3A`).
+    map: u8,
+    /// Implied mandatory prefix: none, `66`, `F3`, or `F2`.
+    pp: u8,
+    /// Vector length: 0 (128 bits), 1 (256 bits), or 2 (512 bits).
+    length: u8,
+    /// Additional register operand, with its high bit from EVEX.V'.
+    vvvv: u8,
+    /// Fields specific to EVEX prefixes.
+    evex: Option<Evex>,
+}
+
+/// Fields of an EVEX prefix.
+#[derive(Debug, Default)]
+struct Evex {
+    /// Opmask register applied to the destination, or zero if none.
+    mask: u8,
+    /// Masked elements are zeroed, rather than left unchanged.
+    zeroing: bool,
+    /// The memory operand is a single element broadcast to the whole vector.
+    broadcast: bool,
+    /// High bit of the vector register in ModRM.reg (EVEX.R').
+    reg_high: u8,
+    /// High bit of the vector register in ModRM.r/m (EVEX.X).
+    rm_high: u8,
+}
+
+/// Decoder of a single instruction.
+#[derive(Debug)]
+struct Decoder<'a> {
+    /// Bytes of the instruction, up to the maximum instruction length.
+    bytes: &'a [u8],
+    /// Address of the instruction.
+    address: u64,
+    /// Offset of the next byte to decode.
+    pos: usize,
+    /// An operand-size (`66`) prefix is present.
+    operand_size_prefix: bool,
+    /// An address-size (`67`) prefix is present.
+    address_size_prefix: bool,
+    /// A `LOCK` prefix is present.
+    lock: bool,
+    /// The last of any `F2` or `F3` prefixes.
+    rep: Option<u8>,
+    /// The last segment override prefix.
+    segment: Option<u8>,
+    /// The REX prefix, or the equivalent bits of a VEX prefix.
+    rex: u8,
+    /// The VEX or EVEX prefix.
+    vex: Option<Vex>,
+    /// Opcode map of the instruction: 0 (one-byte) to 3.
+    map: u8,
+    /// The (last) opcode byte.
+    opcode: u8,
+    /// The ModRM byte, once read.
+    modrm: Option<u8>,
+    /// Address of the memory operand, once decoded.
+    memory: Option<Memory>,
+    /// The displacement of the memory operand is a compressed EVEX `disp8`.
+    disp8: bool,
+    /// The `66`, `F2`, or `F3` prefix selected the instruction, rather than
+    /// modifying it.
+    mandatory: bool,
+    /// A register operand determines the operation size.
+    sized_by_register: bool,
+    /// A general-purpose memory operand of the given size is present.
+    general_memory: u8,
+}
+
+/// Decode the instruction at the start of `bytes`, located at `address`.
+pub(super) fn decode(bytes: &[u8], address: u64) -> Decoded<Instruction> {
+    let bytes = &bytes[..bytes.len().min(MAX_INSTRUCTION_LEN)];
+
+    Decoder {
+        bytes,
+        address,
+        pos: 0,
+        operand_size_prefix: false,
+        address_size_prefix: false,
+        lock: false,
+        rep: None,
+        segment: None,
+        rex: 0,
+        vex: None,
+        map: 0,
+        opcode: 0,
+        modrm: None,
+        memory: None,
+        disp8: false,
+        mandatory: false,
+        sized_by_register: false,
+        general_memory: 0,
+    }
+    .decode()
+}
+
+impl Decoder<'_> {
+    /// Decode the instruction.
+    fn decode(mut self) -> Decoded<Instruction> {
+        self.prefixes()?;
+
+        let (mut mnemonic, operands, flags) = match self.opcode()? {
+            Some(opcode) => opcode,
+            None => return self.x87(),
+        };
+
+        // Legacy instructions extended to VEX take a `v` prefix, while
+        // VEX-only instructions are named in full.
+        if let Some(vex) = &self.vex {
+            if flags & VEX == 0 {
+                let vector = operands.contains(['V', 'W', 'U']) && !operands.contains(['P', 'Q']);
+                if !vector && !matches!(mnemonic.as_str(), "ldmxcsr" | "stmxcsr") {
+                    self.skip_unknown()?;
+                    return Ok(self.finish("(bad)".to_string(), Vec::new()));
+                }
+
+                mnemonic = match vex.evex.is_some() {
+                    true => evex_mnemonic(&mnemonic, self.wide()),
+                    false => format!("v{mnemonic}"),
+                };
+            }
+        } else if flags & VEX != 0 {
+            return Err(Fault::Invalid);
+        }
+
+        let size = self.operand_size(flags);
+
+        if flags & STR != 0 {
+            mnemonic.push(suffix(size));
+        }
+
+        if flags & MASK != 0 {
+            mnemonic.push(self.mask_suffix());
+        }
+
+        // The ModRM byte (and any SIB byte and displacement following it)
+        // precedes any immediate operands.
+        if operands.contains([
+            'C', 'D', 'E', 'G', 'K', 'M', 'N', 'P', 'Q', 'R', 'S', 'U', 'V', 'W',
+        ]) {
+            self.decode_modrm()?;
+        }
+
+        let mut decoded = Vec::new();
+
+        for token in operands.split(',').filter(|token| !token.is_empty()) {
+            if let Some(operand) = self.operand(token, size)? {
+                // Shift counts and port numbers do not determine the
+                // operation size.
+                if matches!(operand, Operand::Register(_)) && !matches!(token, "CL" | "DX") {
+                    self.sized_by_register = true;
+                }
+
+                decoded.push(operand);
+            }
+        }
+
+        self.special_cases(&mut mnemonic, &mut decoded, size);
+
+        Ok(self.finish(mnemonic, decoded))
+    }
+
+    /// Decode the legacy and REX prefixes of the instruction.
+    fn prefixes(&mut self) -> Decoded<()> {
+        loop {
+            let byte = self.peek()?;
+
+            match byte {
+                0x66 => self.operand_size_prefix = true,
+                0x67 => self.address_size_prefix = true,
+                0xf0 => self.lock = true,
+                0xf2 | 0xf3 => self.rep = Some(byte),
+                0x26 | 0x2e | 0x36 | 0x3e | 0x64 | 0x65 => self.segment = Some(byte),
+                0x40..=0x4f => {
+                    self.pos += 1;
+                    self.rex = byte;
+                    continue;
+                }
+                _ => return Ok(()),
+            }
+
+            self.pos += 1;
+
+            // A REX prefix only applies if it immediately precedes the opcode.
+            self.rex = 0;
+        }
+    }
+
+    /// Decode the opcode of the instruction, including any VEX or EVEX
+    /// prefix, returning its mnemonic, operands, and flags, or `None` for an
+    /// x87 instruction.
+    fn opcode(&mut self) -> Decoded<Option<(String, &'static str, u8)>> {
+        let mut opcode = self.byte()?;
+
+        match opcode {
+            0x0f => {
+                opcode = self.byte()?;
+                self.map = 1;
+
+                if opcode == 0x38 || opcode == 0x3a {
+                    self.map = if opcode == 0x38 { 2 } else { 3 };
+                    opcode = self.byte()?;
+                }
+            }
+            0xc4 | 0xc5 | 0x62 => {
+                self.vex_prefix(opcode)?;
+                opcode = self.byte()?;
+            }
+            0xd8..=0xdf => {
+                self.opcode = opcode;
+                return Ok(None);
+            }
+            _ => {}
+        }
+
+        self.opcode = opcode;
+
+        if let Some(opcode) = self.irregular()? {
+            return Ok(Some(opcode));
+        }
+
+        let entry = match self.map {
+            0 => &ONE_BYTE[usize::from(opcode)],
+            1 => &TWO_BYTE[usize::from(opcode)],
+            2 => &THREE_BYTE_38[usize::from(opcode)],
+            _ => &THREE_BYTE_3A[usize::from(opcode)],
+        };
+
+        match self.resolve(entry) {
+            Err(Fault::Invalid) if self.vex.is_some() || self.map >= 2 => {
+                // Undefined instructions of the newer maps still have a known
+                // length, which matters when stepping over them.
+                self.skip_unknown()?;
+                Ok(Some(("(bad)".to_string(), "", VEX)))
+            }
+            resolved => resolved.map(Some),
+        }
+    }
+
+    /// Decode a VEX (`C4` or `C5`) or EVEX (`62`) prefix.
+    fn vex_prefix(&mut self, prefix: u8) -> Decoded<()> {
+        // VEX prefixes cannot follow legacy SIMD or REX prefixes.
+        if self.rex != 0 || self.operand_size_prefix || self.rep.is_some() || self.lock {
+            return Err(Fault::Invalid);
+        }
+
+        // Register extension bits are stored inverted, and the two-byte VEX
+        // prefix only has REX.R.
+        let first = self.byte()?;
+        let (r, x, b) = match prefix {
+            0xc5 => ((!first >> 7) & 1, 0, 0),
+            _ => ((!first >> 7) & 1, (!first >> 6) & 1, (!first >> 5) & 1),
+        };
+
+        let (vex, w) = match prefix {
+            0xc5 => (
+                Vex {
+                    map: 1,
+                    pp: first & 3,
+                    length: (first >> 2) & 1,
+                    vvvv: (!first >> 3) & 15,
+                    evex: None,
+                },
+                0,
+            ),
+            0xc4 => {
+                let second = self.byte()?;
+                let vex = Vex {
+                    map: first & 31,
+                    pp: second & 3,
+                    length: (second >> 2) & 1,
+                    vvvv: (!second >> 3) & 15,
+                    evex: None,
+                };
+                (vex, second >> 7)
+            }
+            _ => {
+                let second = self.byte()?;
+                let third = self.byte()?;
+
+                if first & 0x08 != 0 || second & 0x04 == 0 {
+                    return Err(Fault::Invalid);
+                }
+
+                let vex = Vex {
+                    map: first & 7,
+                    pp: second & 3,
+                    length: (third >> 5) & 3,
+                    vvvv: ((!second >> 3) & 15) | (((!third >> 3) & 1) << 4),
+                    evex: Some(Evex {
+                        mask: third & 7,
+                        zeroing: third & 0x80 != 0,
+                        broadcast: third & 0x10 != 0,
+                        reg_high: ((!first >> 4) & 1) << 4,
+                        rm_high: x << 4,
+                    }),
+                };
+                (vex, second >> 7)
+            }
+        };
+
+        if !(1..=3).contains(&vex.map) {
+            return Err(Fault::Invalid);
+        }
+
+        self.map = vex.map;
+        self.rex = 0x40 | (w << 3) | (r << 2) | (x << 1) | b;
+        self.vex = Some(vex);
+
+        Ok(())
+    }
+
+    /// Decode instructions whose mnemonic or operands cannot be given by the
+    /// opcode maps.
+    fn irregular(&mut self) -> Decoded<Option<(String, &'static str, u8)>> {
+        let pp = self.vex.as_ref().map(|vex| vex.pp);
+        let evex = self.vex.as_ref().is_some_and(|vex| vex.evex.is_some());
+        let wide = self.wide();
+
+        let opcode = match (self.map, self.opcode, pp) {
+            // With a REX.B prefix, `90` exchanges `r8` and `rax`.
+            (0, 0x90, None) if self.rex & 1 != 0 => ("xchg".to_string(), "Zv,rAX", 0),
+            (0, 0xe3, None) if self.address_size_prefix => ("jecxz".to_string(), "Jb", F64),
+            (1, 0x77, Some(0)) if !evex => {
+                let length = self.vex.as_ref().map_or(0, |vex| vex.length);
+                let name = if length == 0 {
+                    "vzeroupper"
+                } else {
+                    "vzeroall"
+                };
+                (name.to_string(), "", VEX)
+            }
+            // Fused multiply-add instructions, named by their operation, the
+            // order of their operands, and their element type.
+            (2, 0x96..=0xbf, Some(1)) if self.opcode & 15 >= 6 => {
+                let operation = [
+                    "fmaddsub", "fmsubadd", "fmadd", "fmadd", "fmsub", "fmsub", "fnmadd", "fnmadd",
+                    "fnmsub", "fnmsub",
+                ][usize::from(self.opcode & 15) - 6];
+                let order = ["132", "213", "231"][usize::from(self.opcode >> 4) - 9];
+                let scalar = self.opcode & 15 >= 9 && self.opcode & 1 == 1;
+
+                let (kind, operands) = match (scalar, wide) {
+                    (false, false) => ("ps", "Vx,Hx,Wx"),
+                    (false, true) => ("pd", "Vx,Hx,Wx"),
+                    (true, false) => ("ss", "Vss,Hss,Wss"),
+                    (true, true) => ("sd", "Vsd,Hsd,Wsd"),
+                };
+
+                (format!("v{operation}{order}{kind}"), operands, VEX)
+            }
+            (2, 0x26 | 0x27, Some(pp @ (1 | 2))) if evex => {
+                let test = if pp == 1 { "vptestm" } else { "vptestnm" };
+                let kind = match (self.opcode, wide) {
+                    (0x26, false) => 'b',
+                    (0x26, true) => 'w',
+                    (_, false) => 'd',
+                    (_, true) => 'q',
+                };
+                (format!("{test}{kind}"), "Kr,Hx,Wx", VEX)
+            }
+            (2, 0x7a..=0x7c, Some(1)) if evex => {
+                let name = match (self.opcode, wide) {
+                    (0x7a, _) => "vpbroadcastb",
+                    (0x7b, _) => "vpbroadcastw",
+                    (_, false) => "vpbroadcastd",
+                    (_, true) => "vpbroadcastq",
+                };
+                (name.to_string(), "Vx,Ry", VEX)
+            }
+            (3, 0x1e | 0x1f | 0x3e | 0x3f, Some(1)) if evex => {
+                let unsigned = if self.opcode & 1 == 0 { "u" } else { "" };
+                let kind = match (self.opcode >= 0x3e, wide) {
+                    (true, false) => 'b',
+                    (true, true) => 'w',
+                    (false, false) => 'd',
+                    (false, true) => 'q',
+                };
+                (format!("vpcmp{unsigned}{kind}"), "Kr,Hx,Wx,Ib", VEX)
+            }
+            (1, 0x4b, Some(pp @ (0 | 1))) => {
+                let kind = match (wide, pp) {
+                    (false, 0) => "wd",
+                    (false, _) => "bw",
+                    (true, _) => "dq",
+                };
+                (format!("kunpck{kind}"), "Kr,Kv,Km", VEX)
+            }
+            (1, 0x6f | 0x7f, Some(3)) if evex => {
+                let name = if wide { "vmovdqu16" } else { "vmovdqu8" };
+                let operands = if self.opcode == 0x6f {
+                    "Vx,Wx"
+                } else {
+                    "Wx,Vx"
+                };
+                (name.to_string(), operands, VEX)
+            }
+            (3, 0x25, Some(1)) if evex => {
+                let kind = if wide { 'q' } else { 'd' };
+                (format!("vpternlog{kind}"), "Vx,Hx,Wx,Ib", VEX)
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(opcode))
+    }
+
+    /// Resolve `entry` of an opcode map to the mnemonic, operands, and flags
+    /// of an instruction.
+    fn resolve(&mut self, mut entry: &'static Entry) -> Decoded<(String, &'static str, u8)> {
+        loop {
+            entry = match entry {
+                Entry::Invalid => return Err(Fault::Invalid),
+                Entry::Op(name, operands, flags) => {
+                    return Ok((name.to_string(), operands, *flags));
+                }
+                Entry::Group(entries) => &entries[self.reg()?],
+                Entry::Names(names, operands, flags) => {
+                    let name = names[self.reg()?];
+
+                    if name.is_empty() {
+                        return Err(Fault::Invalid);
+                    }
+
+                    return Ok((name.to_string(), operands, *flags));
+                }
+                Entry::Rm(entries) => &entries[usize::from(self.modrm()? & 7)],
+                Entry::Prefixed(entries) => {
+                    self.mandatory = true;
+                    &entries[self.mandatory_prefix()]
+                }
+                Entry::Rep(plain, rep) => {
+                    if self.rep == Some(0xf3) && self.vex.is_none() {
+                        self.mandatory = true;
+                        rep
+                    } else {
+                        plain
+                    }
+                }
+                Entry::Memory(memory, register) => {
+                    if self.modrm()? >> 6 == 3 {
+                        register
+                    } else {
+                        memory
+                    }
+                }
+                Entry::Size(entries) => match self.operand_size(0) {
+                    2 => &entries[0],
+                    4 => &entries[1],
+                    _ => &entries[2],
+                },
+                Entry::Wide(narrow, wide) => {
+                    if self.wide() {
+                        wide
+                    } else {
+                        narrow
+                    }
+                }
+                Entry::Vex(legacy, vex) => {
+                    if self.vex.is_some() {
+                        vex
+                    } else {
+                        legacy
+                    }
+                }
+            };
+        }
+    }
+
+    /// Skip the operands of an instruction of a VEX or three-byte map unknown
+    /// to the opcode maps, whose length only depends on the map and opcode.
+    fn skip_unknown(&mut self) -> Decoded<()> {
+        self.decode_modrm()?;
+
+        let immediate = self.map == 3
+            || (self.map == 1 && matches!(self.opcode, 0x70..=0x73 | 0xc2 | 0xc4..=0xc6));
+
+        if immediate {
+            self.byte()?;
+        }
+
+        Ok(())
+    }
+
+    /// Decode an x87 floating-point instruction (opcodes `D8` to `DF`).
+    fn x87(mut self) -> Decoded<Instruction> {
+        let row = usize::from(self.opcode & 7);
+        let reg = self.reg()?;
+        let modrm = self.modrm()?;
+
+        if modrm >> 6 != 3 {
+            let (name, size) = X87_MEMORY[row][reg];
+
+            if name.is_empty() {
+                return Err(Fault::Invalid);
+            }
+
+            self.decode_modrm()?;
+            let operand = self.memory_operand(size)?;
+
+            // Integer operands are suffixed by their size in AT&T syntax,
+            // and floating-point ones by their precision.
+            let suffix = match (name.starts_with("fi"), size) {
+                (true, 2) => "s",
+                (true, 4) => "l",
+                (true, 8) => "ll",
+                (false, 4) if !name.starts_with("fb") => "s",
+                (false, 8) => "l",
+                (false, 10) if !name.starts_with("fb") => "t",
+                _ => "",
+            };
+
+            let mut instruction = self.finish(name.to_string(), vec![operand]);
+            instruction.suffix = suffix;
+
+            return Ok(instruction);
+        }
+
+        let i = modrm & 7;
+        let st = |number| Operand::Register(Register::new(RegisterClass::X87, number));
+
+        let (name, operands) = match (row, reg) {
+            (0, 2 | 3) => (["fcom", "fcomp"][reg - 2], vec![st(i)]),
+            (0, _) => (
+                ["fadd", "fmul", "", "", "fsub", "fsubr", "fdiv", "fdivr"][reg],
+                vec![st(0), st(i)],
+            ),
+            (1, 0) => ("fld", vec![st(i)]),
+            (1, 1) => ("fxch", vec![st(i)]),
+            (1, 2) if i == 0 => ("fnop", vec![]),
+            (1, 4) => (
+                ["fchs", "fabs", "", "", "ftst", "fxam", "", ""][usize::from(i)],
+                vec![],
+            ),
+            (1, 5) => (
+                [
+                    "fld1", "fldl2t", "fldl2e", "fldpi", "fldlg2", "fldln2", "fldz", "",
+                ][usize::from(i)],
+                vec![],
+            ),
+            (1, 6) => (
+                [
+                    "f2xm1", "fyl2x", "fptan", "fpatan", "fxtract", "fprem1", "fdecstp", "fincstp",
+                ][usize::from(i)],
+                vec![],
+            ),
+            (1, 7) => (
+                [
+                    "fprem", "fyl2xp1", "fsqrt", "fsincos", "frndint", "fscale", "fsin", "fcos",
+                ][usize::from(i)],
+                vec![],
+            ),
+            (2, 0..=3) => (
+                ["fcmovb", "fcmove", "fcmovbe", "fcmovu"][reg],
+                vec![st(0), st(i)],
+            ),
+            (2, 5) if i == 1 => ("fucompp", vec![]),
+            (3, 0..=3) => (
+                ["fcmovnb", "fcmovne", "fcmovnbe", "fcmovnu"][reg],
+                vec![st(0), st(i)],
+            ),
+            (3, 4) if i == 2 => ("fnclex", vec![]),
+            (3, 4) if i == 3 => ("fninit", vec![]),
+            (3, 5) => ("fucomi", vec![st(0), st(i)]),
+            (3, 6) => ("fcomi", vec![st(0), st(i)]),
+            (4, 0 | 1 | 4..=7) => (
+                ["fadd", "fmul", "", "", "fsubr", "fsub", "fdivr", "fdiv"][reg],
+                vec![st(i), st(0)],
+            ),
+            (5, 0) => ("ffree", vec![st(i)]),
+            (5, 2..=5) => (["fst", "fstp", "fucom", "fucomp"][reg - 2], vec![st(i)]),
+            (6, 3) if i == 1 => ("fcompp", vec![]),
+            (6, 0 | 1 | 4..=7) => (
+                [
+                    "faddp", "fmulp", "", "", "fsubrp", "fsubp", "fdivrp", "fdivp",
+                ][reg],
+                vec![st(i), st(0)],
+            ),
+            (7, 0) => ("ffreep", vec![st(i)]),
+            (7, 4) if i == 0 => (
+                "fnstsw",
+                vec![Operand::Register(Register::new(
+                    RegisterClass::General(2),
+                    0,
+                ))],
+            ),
+            (7, 5) => ("fucomip", vec![st(0), st(i)]),
+            (7, 6) => ("fcomip", vec![st(0), st(i)]),
+            _ => ("", vec![]),
+        };
+
+        if name.is_empty() {
+            return Err(Fault::Invalid);
+        }
+
+        Ok(self.finish(name.to_string(), operands))
+    }
+
+    /// Decode the operand given by `token`, in the notation of the opcode
+    /// maps, for an operation of `size` bytes. Return `None` for a VEX
+    /// operand of a legacy encoding.
+    fn operand(&mut self, token: &str, size: u8) -> Decoded<Option<Operand>> {
+        let operand = match token {
+            "AL" => self.general(0, 1),
+            "rAX" => self.general(0, size),
+            "eAX" => self.general(0, size.min(4)),
+            "CL" => Operand::Register(Register::new(RegisterClass::General(1), 1)),
+            "DX" => Operand::Register(Register::new(RegisterClass::General(2), 2)),
+            "1" => Operand::Immediate { value: 1, size: 1 },
+            "FS" => Operand::Register(Register::new(RegisterClass::Segment, 4)),
+            "GS" => Operand::Register(Register::new(RegisterClass::Segment, 5)),
+            _ => {
+                let (method, kind) = token.split_at(1);
+                let operand_size = self.size(kind, size);
+
+                match method {
+                    "B" => {
+                        let number = self.vvvv() & 15;
+                        self.general(number, operand_size)
+                    }
+                    "C" => register(RegisterClass::Control, self.reg_number()?),
+                    "D" => register(RegisterClass::Debug, self.reg_number()?),
+                    "E" | "M" | "O" | "Q" | "W" | "K" if self.is_memory(method, kind)? => {
+                        self.general_memory(method, operand_size)?
+                    }
+                    "E" | "R" => {
+                        let number = self.rm_number()?;
+                        self.general(number, operand_size)
+                    }
+                    "M" => return Err(Fault::Invalid),
+                    "G" => {
+                        let number = self.reg_number()?;
+                        self.general(number, operand_size)
+                    }
+                    "H" => match &self.vex {
+                        Some(_) => self.vector(self.vvvv(), kind),
+                        None => return Ok(None),
+                    },
+                    "I" => self.immediate(kind, size)?,
+                    "J" => {
+                        let offset = match kind {
+                            "b" => i64::from(self.byte()? as i8),
+                            _ => i64::from(self.dword()? as i32),
+                        };
+                        let next = self.address.wrapping_add(self.pos as u64);
+                        Operand::Target(next.wrapping_add_signed(offset))
+                    }
+                    "K" => match kind {
+                        "r" => register(RegisterClass::Mask, self.reg_number()?),
+                        "v" => register(RegisterClass::Mask, self.vvvv()),
+                        _ => register(RegisterClass::Mask, self.rm_number()?),
+                    },
+                    "L" => {
+                        let number = self.byte()? >> 4;
+                        self.vector(number, kind)
+                    }
+                    "N" | "Q" => register(RegisterClass::Mmx, self.modrm()? & 7),
+                    "P" => register(RegisterClass::Mmx, self.reg()? as u8),
+                    "S" => register(RegisterClass::Segment, self.reg()? as u8),
+                    "U" | "W" => {
+                        let number = self.rm_number()? | self.rm_high();
+                        self.vector(number, kind)
+                    }
+                    "V" => {
+                        let number = self.reg_number()? | self.reg_high();
+                        self.vector(number, kind)
+                    }
+                    "Z" => {
+                        let number = (self.opcode & 7) | ((self.rex & 1) << 3);
+                        self.general(number, operand_size)
+                    }
+                    _ => return Err(Fault::Invalid),
+                }
+            }
+        };
+
+        Ok(Some(operand))
+    }
+
+    /// Return `true` if the operand of addressing `method` and type `kind`
+    /// refers to memory.
+    fn is_memory(&mut self, method: &str, kind: &str) -> Decoded<bool> {
+        Ok(match (method, kind) {
+            ("O", _) => true,
+            ("K", "r" | "v") => false,
+            _ => self.modrm()? >> 6 != 3,
+        })
+    }
+
+    /// Decode a memory operand of addressing `method` and `size` bytes.
+    fn general_memory(&mut self, method: &str, size: u8) -> Decoded<Operand> {
+        if method == "O" {
+            // The offset takes the size of an address.
+            let offset = match self.address_size_prefix {
+                true => u64::from(self.dword()?),
+                false => self.qword()?,
+            };
+
+            self.memory = Some(Memory {
+                segment: self.segment_register(),
+                base: None,
+                index: None,
+                displacement: offset as i64,
+                size,
+            });
+        }
+
+        if matches!(method, "E" | "O") {
+            self.general_memory = size;
+        }
+
+        self.memory_operand(size)
+    }
+
+    /// Return the memory operand decoded from the ModRM byte, accessing
+    /// `size` bytes.
+    fn memory_operand(&self, size: u8) -> Decoded<Operand> {
+        let mut memory = self.memory.ok_or(Fault::Invalid)?;
+        memory.size = size;
+
+        // Compressed EVEX displacements are scaled by the size of the memory
+        // operand, or of its element when broadcast.
+        if self.disp8 {
+            let scale = match self.broadcast() {
+                true if self.wide() => 8,
+                true => 4,
+                false => size.max(1),
+            };
+            memory.displacement *= i64::from(scale);
+        }
+
+        Ok(Operand::Memory(memory))
+    }
+
+    /// Decode an immediate of type `kind`, for an operation of `size` bytes.
+    fn immediate(&mut self, kind: &str, size: u8) -> Decoded<Operand> {
+        let (value, size) = match kind {
+            "b" => (i64::from(self.byte()?), 1),
+            "s" => (i64::from(self.byte()? as i8), size),
+            "w" => (i64::from(self.word()?), 2),
+            "z" if size == 2 => (i64::from(self.word()? as i16), size),
+            "z" => (i64::from(self.dword()? as i32), size),
+            _ => match size {
+                2 => (i64::from(self.word()? as i16), size),
+                4 => (i64::from(self.dword()? as i32), size),
+                _ => (self.qword()? as i64, size),
+            },
+        };
+
+        Ok(Operand::Immediate { value, size })
+    }
+
+    /// Return the size in bytes of an operand of type `kind`, for an
+    /// operation of `size` bytes.
+    fn size(&self, kind: &str, size: u8) -> u8 {
+        match kind {
+            "b" => 1,
+            "w" => 2,
+            "d" | "ss" => 4,
+            "q" | "sd" => 8,
+            "t" => 10,
+            "dq" => 16,
+            "qq" => 32,
+            "v" => size,
+            "z" => size.min(4),
+            "y" => {
+                if self.wide() {
+                    8
+                } else {
+                    4
+                }
+            }
+            "p" => size.max(4) + 2,
+            "x" | "ps" | "pd" => self.vector_size(),
+            "h" => self.vector_size() / 2,
+            "r" | "m" => self.mask_size(),
+            _ => 0,
+        }
+    }
+
+    /// Return the size in bytes of the vector registers operated on.
+    fn vector_size(&self) -> u8 {
+        16 << self.vex.as_ref().map_or(0, |vex| vex.length.min(2))
+    }
+
+    /// Return a general-purpose register operand of `size` bytes.
+    fn general(&mut self, number: u8, size: u8) -> Operand {
+        // Without a REX prefix, byte registers 4 to 7 are the legacy high
+        // byte registers.
+        let class = match size {
+            1 if self.rex == 0 && (4..8).contains(&number) => {
+                return register(RegisterClass::HighByte, number - 4);
+            }
+            0 => RegisterClass::General(8),
+            size => RegisterClass::General(size),
+        };
+
+        register(class, number)
+    }
+
+    /// Return a vector register operand of type `kind`.
+    fn vector(&self, number: u8, kind: &str) -> Operand {
+        let size = match kind {
+            "x" | "ps" | "pd" => self.vector_size(),
+            "h" => (self.vector_size() / 2).max(16),
+            "qq" => 32,
+            _ => 16,
+        };
+
+        let class = match size {
+            64 => RegisterClass::Zmm,
+            32 => RegisterClass::Ymm,
+            _ => RegisterClass::Xmm,
+        };
+
+        register(class, number)
+    }
+
+    /// Read the ModRM byte, and decode the address of any memory operand it
+    /// describes.
+    fn decode_modrm(&mut self) -> Decoded<()> {
+        let modrm = self.modrm()?;
+        let (mode, rm) = (modrm >> 6, modrm & 7);
+
+        if mode == 3 || self.memory.is_some() {
+            return Ok(());
+        }
+
+        let address_size = if self.address_size_prefix { 4 } else { 8 };
+        let gpr = |number| Some(Register::new(RegisterClass::General(address_size), number));
+
+        let mut memory = Memory {
+            segment: self.segment_register(),
+            base: None,
+            index: None,
+            displacement: 0,
+            size: 0,
+        };
+
+        let mut displacement_size = match mode {
+            1 => 1,
+            2 => 4,
+            _ => 0,
+        };
+
+        if rm == 4 {
+            let sib = self.byte()?;
+            let index = ((sib >> 3) & 7) | ((self.rex & 2) << 2);
+            let base = (sib & 7) | ((self.rex & 1) << 3);
+
+            if index != 4 {
+                memory.index = gpr(index).map(|register| (register, 1 << (sib >> 6)));
+            }
+
+            if sib & 7 == 5 && mode == 0 {
+                displacement_size = 4;
+            } else {
+                memory.base = gpr(base);
+            }
+        } else if rm == 5 && mode == 0 {
+            memory.base = Some(Register::new(RegisterClass::Rip, 0));
+            displacement_size = 4;
+        } else {
+            memory.base = gpr(rm | ((self.rex & 1) << 3));
+        }
+
+        memory.displacement = match displacement_size {
+            1 => i64::from(self.byte()? as i8),
+            4 => i64::from(self.dword()? as i32),
+            _ => 0,
+        };
+
+        self.disp8 =
+            displacement_size == 1 && self.vex.as_ref().is_some_and(|vex| vex.evex.is_some());
+        self.memory = Some(memory);
+
+        Ok(())
+    }
+
+    /// Apply the corrections to the mnemonic and operands which depend on
+    /// the operands decoded.
+    fn special_cases(&mut self, mnemonic: &mut String, operands: &mut Vec<Operand>, size: u8) {
+        let evex = self.vex.as_ref().is_some_and(|vex| vex.evex.is_some());
+
+        // EVEX comparisons write their result to an opmask register.
+        let compare = mnemonic.starts_with("vpcmpeq")
+            || mnemonic.starts_with("vpcmpgt")
+            || (mnemonic.starts_with("vcmp") && mnemonic.len() == 6);
+
+        if evex
+            && compare
+            && let Some(Operand::Register(register)) = operands.first_mut()
+        {
+            *register = Register::new(RegisterClass::Mask, register.number & 7);
+        }
+
+        // Comparisons are named by their predicate (e.g., `cmpltsd` or
+        // `vpcmpnequb`), rather than given it as an immediate.
+        let (prefix, predicates) = match mnemonic.as_str() {
+            "cmpps" | "cmppd" | "cmpss" | "cmpsd" => ("cmp", FP_PREDICATES),
+            "vcmpps" | "vcmppd" | "vcmpss" | "vcmpsd" => ("vcmp", FP_PREDICATES),
+            name if evex && name.starts_with("vpcmp") => ("vpcmp", INT_PREDICATES),
+            _ => ("", FP_PREDICATES),
+        };
+
+        if !prefix.is_empty()
+            && let [.., Operand::Immediate { value, .. }] = operands.as_slice()
+            && let Some(predicate) = predicates
+                .get(*value as usize)
+                .filter(|predicate| !predicate.is_empty())
+        {
+            *mnemonic = format!("{prefix}{predicate}{}", &mnemonic[prefix.len()..]);
+            operands.pop();
+        }
+
+        // Immediate moves of 64-bit values are spelled `movabs`.
+        if self.map == 0 && (0xb8..=0xbf).contains(&self.opcode) && size == 8 {
+            *mnemonic = "movabs".to_string();
+        }
+
+        // Only memory operands take a `LOCK` prefix.
+        if self.lock && self.memory.is_none() {
+            self.lock = false;
+        }
+    }
+
+    /// Build the decoded instruction.
+    fn finish(self, mnemonic: String, operands: Vec<Operand>) -> Instruction {
+        let prefix = self.prefix(&mnemonic, &operands);
+
+        let (mask, broadcast) = match self.vex.as_ref().and_then(|vex| vex.evex.as_ref()) {
+            Some(evex) => {
+                let mask = (evex.mask != 0).then_some((evex.mask, evex.zeroing));
+                let element = if self.wide() { 8 } else { 4 };
+                let broadcast =
+                    (evex.broadcast && self.memory.is_some()).then(|| self.vector_size() / element);
+                (mask, broadcast)
+            }
+            None => (None, None),
+        };
+
+        // In AT&T syntax, the operation size is given by a suffix when no
+        // register operand determines it.
+        let sized = self.general_memory != 0 && !self.sized_by_register;
+        let implied = matches!(
+            mnemonic.as_str(),
+            "call" | "jmp" | "lcall" | "ljmp" | "push" | "pop"
+        ) || mnemonic.starts_with("set");
+        let suffix = match self.general_memory {
+            _ if !sized || implied => "",
+            1 => "b",
+            2 => "w",
+            4 => "l",
+            8 => "q",
+            _ => "",
+        };
+
+        Instruction {
+            address: self.address,
+            bytes: self.bytes[..self.pos].to_vec(),
+            prefix,
+            mnemonic,
+            operands,
+            mask,
+            broadcast,
+            suffix,
+        }
+    }
+
+    /// Return the prefix modifying the instruction, as written before its
+    /// mnemonic.
+    fn prefix(&self, mnemonic: &str, operands: &[Operand]) -> Option<&'static str> {
+        if self.lock {
+            return Some("lock");
+        }
+
+        let string = operands.is_empty()
+            && ["movs", "stos", "lods", "ins", "outs", "cmps", "scas"]
+                .iter()
+                .any(|name| mnemonic.starts_with(name));
+        let compare = mnemonic.starts_with("cmps") || mnemonic.starts_with("scas");
+        let branch = matches!(mnemonic, "call" | "jmp" | "ret") || mnemonic.starts_with('j');
+
+        match self.rep {
+            _ if self.mandatory || self.vex.is_some() => None,
+            Some(0xf3) if string && compare => Some("repe"),
+            Some(0xf3) if string => Some("rep"),
+            Some(0xf3) if mnemonic == "ret" => Some("repz"),
+            Some(0xf2) if string && compare => Some("repne"),
+            Some(0xf2) if branch => Some("bnd"),
+            _ => {
+                let indirect = matches!(operands, [Operand::Register(_) | Operand::Memory(_)]);
+                let notrack =
+                    self.segment == Some(0x3e) && indirect && matches!(mnemonic, "call" | "jmp");
+
+                notrack.then_some("notrack")
+            }
+        }
+    }
+
+    /// Return the size in bytes of the operation, as given by the prefixes
+    /// and `flags` of the instruction.
+    fn operand_size(&self, flags: u8) -> u8 {
+        if flags & F64 != 0 || self.rex & 8 != 0 {
+            8
+        } else if self.operand_size_prefix && !self.mandatory {
+            2
+        } else if flags & D64 != 0 {
+            8
+        } else {
+            4
+        }
+    }
+
+    /// Return the index into [`Entry::Prefixed`] entries of the mandatory
+    /// prefix of the instruction.
+    fn mandatory_prefix(&self) -> usize {
+        if let Some(vex) = &self.vex {
+            return usize::from(vex.pp);
+        }
+
+        match self.rep {
+            Some(0xf3) => 2,
+            Some(0xf2) => 3,
+            _ if self.operand_size_prefix => 1,
+            _ => 0,
+        }
+    }
+
+    /// Return the suffix of an opmask instruction, given by VEX.W and
+    /// VEX.pp.
+    fn mask_suffix(&self) -> char {
+        let pp = self.vex.as_ref().map_or(0, |vex| vex.pp);
+
+        match (self.wide(), pp) {
+            (false, 0) => 'w',
+            (false, 1) => 'b',
+            (true, 0) | (true, 3) => 'q',
+            _ => 'd',
+        }
+    }
+
+    /// Return the size in bytes of the opmask operated on.
+    fn mask_size(&self) -> u8 {
+        match self.mask_suffix() {
+            'b' => 1,
+            'w' => 2,
+            'd' => 4,
+            _ => 8,
+        }
+    }
+
+    /// Return `true` if REX.W (or VEX.W) is set.
+    fn wide(&self) -> bool {
+        self.rex & 8 != 0
+    }
+
+    /// Return `true` if the EVEX memory operand is a broadcast element.
+    fn broadcast(&self) -> bool {
+        self.vex
+            .as_ref()
+            .and_then(|vex| vex.evex.as_ref())
+            .is_some_and(|evex| evex.broadcast)
+    }
+
+    /// Return the register in the VEX.vvvv field.
+    fn vvvv(&self) -> u8 {
+        self.vex.as_ref().map_or(0, |vex| vex.vvvv)
+    }
+
+    /// Return the high bit of a vector register in ModRM.reg (EVEX.R').
+    fn reg_high(&self) -> u8 {
+        let evex = self.vex.as_ref().and_then(|vex| vex.evex.as_ref());
+        evex.map_or(0, |evex| evex.reg_high)
+    }
+
+    /// Return the high bit of a vector register in ModRM.r/m (EVEX.X).
+    fn rm_high(&self) -> u8 {
+        let evex = self.vex.as_ref().and_then(|vex| vex.evex.as_ref());
+        evex.map_or(0, |evex| evex.rm_high)
+    }
+
+    /// Return the segment register overriding the default of memory
+    /// operands. Only `fs` and `gs` overrides have an effect in 64-bit mode.
+    fn segment_register(&self) -> Option<Register> {
+        match self.segment {
+            Some(0x64) => Some(Register::new(RegisterClass::Segment, 4)),
+            Some(0x65) => Some(Register::new(RegisterClass::Segment, 5)),
+            _ => None,
+        }
+    }
+
+    /// Return the ModRM byte, reading it if needed.
+    fn modrm(&mut self) -> Decoded<u8> {
+        if let Some(modrm) = self.modrm {
+            return Ok(modrm);
+        }
+
+        let modrm = self.byte()?;
+        self.modrm = Some(modrm);
+
+        Ok(modrm)
+    }
+
+    /// Return the ModRM.reg field, without its REX extension.
+    fn reg(&mut self) -> Decoded<usize> {
+        Ok(usize::from((self.modrm()? >> 3) & 7))
+    }
+
+    /// Return the register in ModRM.reg, extended by REX.R.
+    fn reg_number(&mut self) -> Decoded<u8> {
+        Ok(self.reg()? as u8 | ((self.rex & 4) << 1))
+    }
+
+    /// Return the register in ModRM.r/m, extended by REX.B.
+    fn rm_number(&mut self) -> Decoded<u8> {
+        Ok((self.modrm()? & 7) | ((self.rex & 1) << 3))
+    }
+
+    /// Return the next byte, without consuming it.
+    fn peek(&self) -> Decoded<u8> {
+        self.bytes.get(self.pos).copied().ok_or(Fault::Truncated)
+    }
+
+    /// Consume the next byte.
+    fn byte(&mut self) -> Decoded<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+
+        Ok(byte)
+    }
+
+    /// Consume the next `N` bytes.
+    fn array<const N: usize>(&mut self) -> Decoded<[u8; N]> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + N)
+            .ok_or(Fault::Truncated)?;
+        self.pos += N;
+
+        Ok(bytes.try_into().expect("slice has the requested length"))
+    }
+
+    /// Consume the next little-endian `u16`.
+    fn word(&mut self) -> Decoded<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    /// Consume the next little-endian `u32`.
+    fn dword(&mut self) -> Decoded<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    /// Consume the next little-endian `u64`.
+    fn qword(&mut self) -> Decoded<u64> {
+        self.array().map(u64::from_le_bytes)
+    }
+}
+
+/// Predicates of floating-point comparisons, by immediate.
+const FP_PREDICATES: &[&str] = &["eq", "lt", "le", "unord", "neq", "nlt", "nle", "ord"];
+
+/// Predicates of EVEX integer comparisons, by immediate.
+const INT_PREDICATES: &[&str] = &["eq", "lt", "le", "", "neq", "nlt", "nle", ""];
+
+/// Return a register operand.
+fn register(class: RegisterClass, number: u8) -> Operand {
+    Operand::Register(Register::new(class, number))
+}
+
+/// Return the suffix of a string instruction operating on `size` bytes.
+fn suffix(size: u8) -> char {
+    match size {
+        1 => 'b',
+        2 => 'w',
+        4 => 'd',
+        _ => 'q',
+    }
+}
+
+/// Return the EVEX mnemonic of the legacy instruction `name`, whose element
+/// size may be given by EVEX.W.
+fn evex_mnemonic(name: &str, wide: bool) -> String {
+    let element = if wide { 'q' } else { 'd' };
+
+    match name {
+        "movdqa" => format!("vmovdqa{}", if wide { 64 } else { 32 }),
+        "movdqu" => format!("vmovdqu{}", if wide { 64 } else { 32 }),
+        "pand" | "pandn" | "por" | "pxor" => format!("v{name}{element}"),
+        _ => format!("v{name}"),
+    }
+}
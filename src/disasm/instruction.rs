@@ -0,0 +1,386 @@
+//! Decoded instructions, and their formatting in Intel or AT&T syntax.
+
+use std::fmt;
+
+use super::operand::{Memory, Operand, Register, RegisterClass};
+
+/// Assembly syntax in which instructions are written.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum Syntax {
+    /// Intel syntax, with the destination operand first (e.g.,
+    /// `mov rax, qword ptr [rbp - 0x8]`).
+    #[default]
+    Intel,
+    /// AT&T syntax, with the destination operand last (e.g.,
+    /// `mov -0x8(%rbp),%rax`).
+    Att,
+}
+
+/// A decoded instruction.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Instruction {
+    /// Address of the instruction.
+    pub address: u64,
+    /// Encoding of the instruction.
+    pub bytes: Vec<u8>,
+    /// Prefix modifying the instruction, written before its mnemonic (e.g.,
+    /// `lock` or `rep`).
+    pub prefix: Option<&'static str>,
+    /// Mnemonic of the instruction in Intel syntax (e.g., `movzx`), or
+    /// `(bad)` for an undefined instruction.
+    pub mnemonic: String,
+    /// Operands of the instruction in Intel order, with the destination
+    /// first.
+    pub operands: Vec<Operand>,
+    /// EVEX opmask register applied to the destination, and whether masked
+    /// elements are zeroed rather than merged.
+    pub(super) mask: Option<(u8, bool)>,
+    /// Number of elements an EVEX memory operand is broadcast to.
+    pub(super) broadcast: Option<u8>,
+    /// Suffix of the mnemonic in AT&T syntax, giving the operation size when
+    /// no register operand determines it.
+    pub(super) suffix: &'static str,
+}
+
+impl Instruction {
+    /// Creates a new [`Instruction`] for the undefined instruction starting
+    /// with `byte`, taken to be a single byte long.
+    pub(super) fn invalid(address: u64, byte: u8) -> Self {
+        Self {
+            address,
+            bytes: vec![byte],
+            prefix: None,
+            mnemonic: "(bad)".to_string(),
+            operands: Vec::new(),
+            mask: None,
+            broadcast: None,
+            suffix: "",
+        }
+    }
+
+    /// Return the length of the instruction in bytes.
+    pub fn length(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Return the address of the instruction following this one.
+    pub fn next_address(&self) -> u64 {
+        self.address.wrapping_add(self.bytes.len() as u64)
+    }
+
+    /// Return `true` if the instruction is undefined.
+    pub fn is_invalid(&self) -> bool {
+        self.mnemonic == "(bad)"
+    }
+
+    /// Return `true` if the instruction calls a subroutine.
+    pub fn is_call(&self) -> bool {
+        matches!(self.mnemonic.as_str(), "call" | "lcall")
+    }
+
+    /// Return `true` if the instruction returns from a subroutine (or an
+    /// interrupt handler).
+    pub fn is_return(&self) -> bool {
+        matches!(
+            self.mnemonic.as_str(),
+            "ret" | "retf" | "iretw" | "iretd" | "iretq"
+        )
+    }
+
+    /// Return `true` if the instruction is a jump, either unconditional or
+    /// conditional.
+    pub fn is_jump(&self) -> bool {
+        let mnemonic = self.mnemonic.as_str();
+        (mnemonic.starts_with('j') || mnemonic.starts_with("loop") || mnemonic == "ljmp")
+            && mnemonic != "jmpe"
+    }
+
+    /// Return the target of a relative call or jump.
+    pub fn target(&self) -> Option<u64> {
+        self.operands.iter().find_map(|operand| match operand {
+            Operand::Target(target) => Some(*target),
+            _ => None,
+        })
+    }
+
+    /// Return the address of a RIP-relative memory operand, which is relative
+    /// to the address of the following instruction.
+    pub fn rip_relative_address(&self) -> Option<u64> {
+        self.operands.iter().find_map(|operand| match operand {
+            Operand::Memory(Memory {
+                base:
+                    Some(Register {
+                        class: RegisterClass::Rip,
+                        ..
+                    }),
+                displacement,
+                ..
+            }) => Some(self.next_address().wrapping_add_signed(*displacement)),
+            _ => None,
+        })
+    }
+
+    /// Return the instruction written in `syntax`, with the targets of
+    /// branches and RIP-relative operands described by `symbolize` (e.g., as
+    /// `0x401136 <main+6>`).
+    pub fn format(&self, syntax: Syntax, symbolize: &dyn Fn(u64) -> Option<String>) -> String {
+        let describe = |address: u64| match symbolize(address) {
+            Some(symbol) => format!("{address:#x} <{symbol}>"),
+            None => format!("{address:#x}"),
+        };
+
+        let mut operands: Vec<String> = self
+            .operands
+            .iter()
+            .map(|operand| match operand {
+                Operand::Target(target) => describe(*target),
+                operand => self.format_operand(operand, syntax),
+            })
+            .collect();
+
+        if let Some((mask, zeroing)) = self.mask
+            && let Some(destination) = operands.first_mut()
+        {
+            let register = Register::new(RegisterClass::Mask, mask);
+            destination.push_str(&format!("{{{}}}", format_register(&register, syntax)));
+
+            if zeroing {
+                destination.push_str("{z}");
+            }
+        }
+
+        let mnemonic = match syntax {
+            Syntax::Intel => self.mnemonic.clone(),
+            Syntax::Att => {
+                // Indirect branches mark their operand with `*`.
+                let indirect = matches!(
+                    self.operands.as_slice(),
+                    [Operand::Register(_) | Operand::Memory(_)]
+                );
+                if indirect && (self.is_call() || self.is_jump()) {
+                    operands[0].insert(0, '*');
+                }
+
+                // Operands are written source first, except for `enter`.
+                if self.mnemonic != "enter" {
+                    operands.reverse();
+                }
+
+                self.att_mnemonic()
+            }
+        };
+
+        let separator = match syntax {
+            Syntax::Intel => ", ",
+            Syntax::Att => ",",
+        };
+
+        let mnemonic = match self.prefix {
+            Some(prefix) => format!("{prefix} {mnemonic}"),
+            None => mnemonic,
+        };
+
+        let mut text = match operands.is_empty() {
+            true => mnemonic,
+            false => format!("{mnemonic:<6} {}", operands.join(separator)),
+        };
+
+        // Describe the address of RIP-relative operands as a comment, as the
+        // operand itself only gives its offset.
+        if let Some(address) = self.rip_relative_address() {
+            text.push_str(&format!("        # {}", describe(address)));
+        }
+
+        text
+    }
+
+    /// Return `operand` written in `syntax`.
+    fn format_operand(&self, operand: &Operand, syntax: Syntax) -> String {
+        match (operand, syntax) {
+            (Operand::Register(register), _) => format_register(register, syntax),
+            (Operand::Immediate { value, size }, _) => {
+                let value = match size {
+                    1 => *value as u8 as u64,
+                    2 => *value as u16 as u64,
+                    4 => *value as u32 as u64,
+                    _ => *value as u64,
+                };
+
+                match syntax {
+                    Syntax::Intel => format!("{value:#x}"),
+                    Syntax::Att => format!("${value:#x}"),
+                }
+            }
+            (Operand::Memory(memory), _) => {
+                let mut text = match syntax {
+                    Syntax::Intel => format_intel_memory(memory),
+                    Syntax::Att => format_att_memory(memory),
+                };
+
+                if let Some(count) = self.broadcast {
+                    text.push_str(&format!("{{1to{count}}}"));
+                }
+
+                text
+            }
+            (Operand::Target(target), _) => format!("{target:#x}"),
+        }
+    }
+
+    /// Return the mnemonic of the instruction in AT&T syntax.
+    fn att_mnemonic(&self) -> String {
+        let mnemonic = self.mnemonic.as_str();
+
+        let renamed = match mnemonic {
+            "cbw" => "cbtw",
+            "cwde" => "cwtl",
+            "cdqe" => "cltq",
+            "cwd" => "cwtd",
+            "cdq" => "cltd",
+            "cqo" => "cqto",
+            "retf" => "lret",
+            // Subtractions and divisions into `st(i)` are named for the
+            // reverse operation in AT&T syntax, following the assembler.
+            "fsub" | "fsubr" | "fdiv" | "fdivr" | "fsubp" | "fsubrp" | "fdivp" | "fdivrp"
+                if matches!(
+                    self.operands.first(),
+                    Some(Operand::Register(Register {
+                        class: RegisterClass::X87,
+                        number: 1..,
+                    }))
+                ) =>
+            {
+                return match mnemonic.find('r') {
+                    Some(idx) => format!("{}{}", &mnemonic[..idx], &mnemonic[idx + 1..]),
+                    None => format!("{}r{}", &mnemonic[..4], &mnemonic[4..]),
+                };
+            }
+            "movsxd" => "movslq",
+            "movsd" | "cmpsd" | "stosd" | "lodsd" | "scasd" | "insd" | "outsd"
+                if self.operands.is_empty() =>
+            {
+                return format!("{}l", &mnemonic[..mnemonic.len() - 1]);
+            }
+            "movzx" | "movsx" => {
+                // Extensions are suffixed by their source and destination
+                // sizes (e.g., `movzbl`).
+                let size = |operand: Option<&Operand>| match operand {
+                    Some(Operand::Register(register)) => register.size(),
+                    Some(Operand::Memory(memory)) => memory.size,
+                    _ => 0,
+                };
+                let suffix = |size| match size {
+                    1 => "b",
+                    2 => "w",
+                    4 => "l",
+                    _ => "q",
+                };
+
+                return format!(
+                    "{}{}{}",
+                    &mnemonic[..4],
+                    suffix(size(self.operands.get(1))),
+                    suffix(size(self.operands.first()))
+                );
+            }
+            _ => mnemonic,
+        };
+
+        format!("{renamed}{}", self.suffix)
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(Syntax::Intel, &|_| None))
+    }
+}
+
+/// Return the name of `register` in `syntax`.
+fn format_register(register: &Register, syntax: Syntax) -> String {
+    match syntax {
+        Syntax::Intel => register.name(),
+        Syntax::Att => format!("%{}", register.name()),
+    }
+}
+
+/// Return `memory` written in Intel syntax (e.g., `qword ptr [rbp - 0x8]`).
+fn format_intel_memory(memory: &Memory) -> String {
+    let size = match memory.size {
+        1 => "byte ptr ",
+        2 => "word ptr ",
+        4 => "dword ptr ",
+        6 => "fword ptr ",
+        8 => "qword ptr ",
+        10 => "tbyte ptr ",
+        16 => "xmmword ptr ",
+        32 => "ymmword ptr ",
+        64 => "zmmword ptr ",
+        _ => "",
+    };
+
+    let segment = match memory.segment {
+        Some(segment) => format!("{}:", segment.name()),
+        None => String::new(),
+    };
+
+    let mut address = String::new();
+
+    if let Some(base) = memory.base {
+        address.push_str(&base.name());
+    }
+
+    if let Some((index, scale)) = memory.index {
+        if !address.is_empty() {
+            address.push_str(" + ");
+        }
+
+        address.push_str(&format!("{}*{scale}", index.name()));
+    }
+
+    let displacement = memory.displacement;
+
+    if address.is_empty() {
+        address = format!("{:#x}", displacement as u64);
+    } else if displacement < 0 {
+        address.push_str(&format!(" - {:#x}", displacement.unsigned_abs()));
+    } else if displacement > 0 {
+        address.push_str(&format!(" + {displacement:#x}"));
+    }
+
+    format!("{size}{segment}[{address}]")
+}
+
+/// Return `memory` written in AT&T syntax (e.g., `-0x8(%rbp)`).
+fn format_att_memory(memory: &Memory) -> String {
+    let mut text = match memory.segment {
+        Some(segment) => format!("%{}:", segment.name()),
+        None => String::new(),
+    };
+
+    let displacement = memory.displacement;
+
+    if memory.base.is_none() && memory.index.is_none() {
+        text.push_str(&format!("{:#x}", displacement as u64));
+        return text;
+    }
+
+    if displacement < 0 {
+        text.push_str(&format!("-{:#x}", displacement.unsigned_abs()));
+    } else if displacement > 0 {
+        text.push_str(&format!("{displacement:#x}"));
+    }
+
+    text.push('(');
+
+    if let Some(base) = memory.base {
+        text.push_str(&format!("%{}", base.name()));
+    }
+
+    if let Some((index, scale)) = memory.index {
+        text.push_str(&format!(",%{},{scale}", index.name()));
+    }
+
+    text.push(')');
+    text
+}
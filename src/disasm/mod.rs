@@ -0,0 +1,197 @@
+//! Decoder of x86-64 instructions, such as those executed by a tracee.
+//!
+//! Instructions are decoded from their prefixes (legacy, REX, VEX, and EVEX),
+//! opcode, ModRM and SIB bytes, displacement, and immediates, following the
+//! opcode maps of the Intel SDM. Decoded instructions can be written in
+//! Intel or AT&T syntax, and their lengths are exact even for instructions
+//! the decoder cannot name, so code can be walked instruction by instruction.
+
+use crate::Result;
+
+mod decoder;
+use decoder::Fault;
+
+mod instruction;
+pub use instruction::{Instruction, Syntax};
+
+mod operand;
+pub use operand::{Memory, Operand, Register, RegisterClass};
+
+mod tables;
+
+/// Maximum length of an x86-64 instruction in bytes.
+pub const MAX_INSTRUCTION_LEN: usize = 15;
+
+/// Decode the instruction at the start of `bytes`, located at `address`.
+/// Bytes which do not encode a valid instruction decode to a single-byte
+/// `(bad)` instruction, so only running out of bytes is an error.
+pub fn decode(bytes: &[u8], address: u64) -> Result<Instruction> {
+    match decoder::decode(bytes, address) {
+        Ok(instruction) => Ok(instruction),
+        Err(Fault::Truncated) if bytes.len() < MAX_INSTRUCTION_LEN => {
+            Err(format!("truncated instruction at {address:#x}").into())
+        }
+        Err(_) => Ok(Instruction::invalid(address, bytes[0])),
+    }
+}
+
+/// Decode the instructions in `bytes`, located at `address`, stopping at the
+/// first one truncated by the end of `bytes`.
+pub fn decode_all(bytes: &[u8], address: u64) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+
+    while let Ok(instruction) = decode(&bytes[offset..], address + offset as u64) {
+        offset += instruction.length();
+        instructions.push(instruction);
+    }
+
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode `bytes` at a fixed address, checking the whole input is used.
+    fn decode_exact(bytes: &[u8]) -> Instruction {
+        let instruction = decode(bytes, 0x401000).unwrap();
+        assert_eq!(instruction.length(), bytes.len(), "length of {bytes:02x?}");
+        instruction
+    }
+
+    fn intel(bytes: &[u8]) -> String {
+        decode_exact(bytes).format(Syntax::Intel, &|_| None)
+    }
+
+    fn att(bytes: &[u8]) -> String {
+        decode_exact(bytes).format(Syntax::Att, &|_| None)
+    }
+
+    #[test]
+    fn general_instructions() {
+        assert_eq!(intel(&[0x55]), "push   rbp");
+        assert_eq!(intel(&[0x48, 0x89, 0xe5]), "mov    rbp, rsp");
+        assert_eq!(att(&[0x48, 0x89, 0xe5]), "mov    %rsp,%rbp");
+        assert_eq!(
+            intel(&[0x48, 0x8b, 0x45, 0xf8]),
+            "mov    rax, qword ptr [rbp - 0x8]"
+        );
+        assert_eq!(att(&[0x48, 0x8b, 0x45, 0xf8]), "mov    -0x8(%rbp),%rax");
+        assert_eq!(
+            intel(&[0xc7, 0x44, 0x24, 0x08, 0x2a, 0x00, 0x00, 0x00]),
+            "mov    dword ptr [rsp + 0x8], 0x2a"
+        );
+        assert_eq!(
+            att(&[0xc7, 0x44, 0x24, 0x08, 0x2a, 0x00, 0x00, 0x00]),
+            "movl   $0x2a,0x8(%rsp)"
+        );
+        assert_eq!(
+            intel(&[0x42, 0x0f, 0xb6, 0x04, 0x8b]),
+            "movzx  eax, byte ptr [rbx + r9*4]"
+        );
+        assert_eq!(
+            att(&[0x42, 0x0f, 0xb6, 0x04, 0x8b]),
+            "movzbl (%rbx,%r9,4),%eax"
+        );
+        assert_eq!(
+            intel(&[0x64, 0x48, 0x8b, 0x04, 0x25, 0x28, 0x00, 0x00, 0x00]),
+            "mov    rax, qword ptr fs:[0x28]"
+        );
+        assert_eq!(intel(&[0xf3, 0x0f, 0x1e, 0xfa]), "endbr64");
+        assert_eq!(intel(&[0xf3, 0x48, 0xab]), "rep stosq");
+        assert_eq!(intel(&[0xc3]), "ret");
+    }
+
+    #[test]
+    fn branches() {
+        let symbolize = |address| (address == 0x401136).then(|| "main".to_string());
+
+        let call = decode_exact(&[0xe8, 0x31, 0x01, 0x00, 0x00]);
+        assert!(call.is_call());
+        assert_eq!(call.target(), Some(0x401136));
+        assert_eq!(
+            call.format(Syntax::Intel, &symbolize),
+            "call   0x401136 <main>"
+        );
+
+        let jump = decode_exact(&[0x75, 0xfe]);
+        assert!(jump.is_jump());
+        assert_eq!(jump.target(), Some(0x401000));
+        assert_eq!(jump.to_string(), "jne    0x401000");
+
+        assert_eq!(att(&[0xff, 0xd0]), "call   *%rax");
+        assert_eq!(att(&[0xff, 0x64, 0xc5, 0x00]), "jmp    *(%rbp,%rax,8)");
+        assert!(decode_exact(&[0xc3]).is_return());
+    }
+
+    #[test]
+    fn call_lengths() {
+        // call r8
+        assert!(decode_exact(&[0x41, 0xff, 0xd0]).is_call());
+        // call qword ptr [rip + disp32]
+        assert!(decode_exact(&[0xff, 0x15, 0, 0, 0, 0]).is_call());
+        // call qword ptr [rax + rbx * 8 + disp8]
+        assert!(decode_exact(&[0xff, 0x54, 0xd8, 0x10]).is_call());
+        // notrack call qword ptr [rbp - 8]
+        assert!(decode_exact(&[0x3e, 0xff, 0x55, 0xf8]).is_call());
+        // jmp rax
+        assert!(!decode_exact(&[0xff, 0xe0]).is_call());
+    }
+
+    #[test]
+    fn rip_relative_operands() {
+        let lea = decode_exact(&[0x48, 0x8d, 0x05, 0xf9, 0x0f, 0x00, 0x00]);
+        assert_eq!(lea.rip_relative_address(), Some(0x402000));
+
+        let symbolize = |address| (address == 0x402000).then(|| "message".to_string());
+        assert_eq!(
+            lea.format(Syntax::Intel, &symbolize),
+            "lea    rax, [rip + 0xff9]        # 0x402000 <message>"
+        );
+        assert_eq!(
+            lea.format(Syntax::Att, &symbolize),
+            "lea    0xff9(%rip),%rax        # 0x402000 <message>"
+        );
+    }
+
+    #[test]
+    fn vector_instructions() {
+        assert_eq!(intel(&[0x66, 0x0f, 0xef, 0xc0]), "pxor   xmm0, xmm0");
+        assert_eq!(intel(&[0xc5, 0xf1, 0xef, 0xc2]), "vpxor  xmm0, xmm1, xmm2");
+        assert_eq!(att(&[0xc5, 0xfd, 0x6f, 0x0e]), "vmovdqa (%rsi),%ymm1");
+        assert_eq!(
+            intel(&[0x62, 0xf1, 0xfe, 0x49, 0x6f, 0x4e, 0x01]),
+            "vmovdqu64 zmm1{k1}, zmmword ptr [rsi + 0x40]"
+        );
+        assert_eq!(
+            att(&[0x62, 0xf1, 0xfe, 0x49, 0x6f, 0x4e, 0x01]),
+            "vmovdqu64 0x40(%rsi),%zmm1{%k1}"
+        );
+        assert_eq!(intel(&[0xf2, 0x0f, 0xc2, 0xc1, 0x01]), "cmpltsd xmm0, xmm1");
+    }
+
+    #[test]
+    fn x87_instructions() {
+        assert_eq!(intel(&[0xd9, 0xe8]), "fld1");
+        assert_eq!(intel(&[0xde, 0xc1]), "faddp  st(1), st(0)");
+        assert_eq!(intel(&[0xdd, 0x45, 0xf8]), "fld    qword ptr [rbp - 0x8]");
+        assert_eq!(att(&[0xdd, 0x45, 0xf8]), "fldl   -0x8(%rbp)");
+        assert_eq!(att(&[0xdb, 0x2c, 0x24]), "fldt   (%rsp)");
+    }
+
+    #[test]
+    fn undefined_and_truncated_instructions() {
+        let bad = decode(&[0x06, 0x90], 0x401000).unwrap();
+        assert!(bad.is_invalid());
+        assert_eq!(bad.length(), 1);
+        assert_eq!(bad.to_string(), "(bad)");
+
+        assert!(decode(&[0xe8, 0x00, 0x00], 0x401000).is_err());
+        assert!(decode(&[], 0x401000).is_err());
+
+        let instructions = decode_all(&[0x55, 0x48, 0x89, 0xe5, 0x48, 0x8b], 0x401000);
+        let addresses: Vec<u64> = instructions.iter().map(|i| i.address).collect();
+        assert_eq!(addresses, [0x401000, 0x401001]);
+    }
+}
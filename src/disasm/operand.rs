@@ -0,0 +1,149 @@
+//! Registers and operands of decoded x86-64 instructions.
+
+/// Class of a register operand, which determines its name and size.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum RegisterClass {
+    /// General-purpose register of the given size in bytes (e.g., `eax` is 4).
+    General(u8),
+    /// Legacy high byte register (`ah`, `ch`, `dh`, or `bh`), only addressable
+    /// without a REX prefix.
+    HighByte,
+    /// Segment register (e.g., `fs`).
+    Segment,
+    /// Control register (e.g., `cr3`).
+    Control,
+    /// Debug register (e.g., `dr7`).
+    Debug,
+    /// x87 stack register (e.g., `st(1)`).
+    X87,
+    /// MMX register (e.g., `mm0`).
+    Mmx,
+    /// SSE register (e.g., `xmm0`).
+    Xmm,
+    /// AVX register (e.g., `ymm0`).
+    Ymm,
+    /// AVX-512 register (e.g., `zmm0`).
+    Zmm,
+    /// AVX-512 opmask register (e.g., `k1`).
+    Mask,
+    /// The instruction pointer, used as the base of RIP-relative operands.
+    Rip,
+}
+
+/// A register operand of an instruction.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Register {
+    /// Class of the register.
+    pub class: RegisterClass,
+    /// Number of the register within its class (e.g., 8 for `r8`).
+    pub number: u8,
+}
+
+/// Names of the 64-bit general-purpose registers.
+const GENERAL_64: [&str; 16] = [
+    "rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi", "r8", "r9", "r10", "r11", "r12", "r13",
+    "r14", "r15",
+];
+
+/// Names of the 32-bit general-purpose registers.
+const GENERAL_32: [&str; 16] = [
+    "eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "r8d", "r9d", "r10d", "r11d", "r12d",
+    "r13d", "r14d", "r15d",
+];
+
+/// Names of the 16-bit general-purpose registers.
+const GENERAL_16: [&str; 16] = [
+    "ax", "cx", "dx", "bx", "sp", "bp", "si", "di", "r8w", "r9w", "r10w", "r11w", "r12w", "r13w",
+    "r14w", "r15w",
+];
+
+/// Names of the 8-bit general-purpose registers, as addressed with a REX
+/// prefix.
+const GENERAL_8: [&str; 16] = [
+    "al", "cl", "dl", "bl", "spl", "bpl", "sil", "dil", "r8b", "r9b", "r10b", "r11b", "r12b",
+    "r13b", "r14b", "r15b",
+];
+
+/// Names of the segment registers.
+const SEGMENT: [&str; 8] = ["es", "cs", "ss", "ds", "fs", "gs", "?", "?"];
+
+impl Register {
+    /// Creates a new [`Register`] of the given `class` and `number`.
+    pub fn new(class: RegisterClass, number: u8) -> Self {
+        Self { class, number }
+    }
+
+    /// Return the name of the register (e.g., `r8d` or `xmm15`).
+    pub fn name(&self) -> String {
+        let number = usize::from(self.number);
+
+        match self.class {
+            RegisterClass::General(8) => GENERAL_64[number % 16].to_string(),
+            RegisterClass::General(4) => GENERAL_32[number % 16].to_string(),
+            RegisterClass::General(2) => GENERAL_16[number % 16].to_string(),
+            RegisterClass::General(_) => GENERAL_8[number % 16].to_string(),
+            RegisterClass::HighByte => ["ah", "ch", "dh", "bh"][number % 4].to_string(),
+            RegisterClass::Segment => SEGMENT[number % 8].to_string(),
+            RegisterClass::Control => format!("cr{number}"),
+            RegisterClass::Debug => format!("dr{number}"),
+            RegisterClass::X87 => format!("st({number})"),
+            RegisterClass::Mmx => format!("mm{}", number % 8),
+            RegisterClass::Xmm => format!("xmm{number}"),
+            RegisterClass::Ymm => format!("ymm{number}"),
+            RegisterClass::Zmm => format!("zmm{number}"),
+            RegisterClass::Mask => format!("k{}", number % 8),
+            RegisterClass::Rip => "rip".to_string(),
+        }
+    }
+
+    /// Return the size of the register in bytes, or zero for registers whose
+    /// size does not determine the size of an operation (e.g., segment
+    /// registers).
+    pub fn size(&self) -> u8 {
+        match self.class {
+            RegisterClass::General(size) => size,
+            RegisterClass::HighByte => 1,
+            RegisterClass::X87 => 10,
+            RegisterClass::Mmx | RegisterClass::Mask | RegisterClass::Rip => 8,
+            RegisterClass::Xmm => 16,
+            RegisterClass::Ymm => 32,
+            RegisterClass::Zmm => 64,
+            RegisterClass::Segment | RegisterClass::Control | RegisterClass::Debug => 0,
+        }
+    }
+}
+
+/// A memory operand, addressing `segment:[base + index * scale + displacement]`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Memory {
+    /// Segment override (e.g., `fs` for thread-local data).
+    pub segment: Option<Register>,
+    /// Base register, which is `rip` for RIP-relative operands.
+    pub base: Option<Register>,
+    /// Index register and its scale (1, 2, 4, or 8).
+    pub index: Option<(Register, u8)>,
+    /// Signed displacement.
+    pub displacement: i64,
+    /// Size of the data accessed in bytes, or zero if no data is accessed
+    /// (e.g., by `lea`) or the size is implied.
+    pub size: u8,
+}
+
+/// An operand of an instruction.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Operand {
+    /// A register.
+    Register(Register),
+    /// An immediate value, sign-extended from its encoding, along with the
+    /// size in bytes of the operation it applies to.
+    Immediate {
+        /// Value of the immediate.
+        value: i64,
+        /// Size in bytes of the operation.
+        size: u8,
+    },
+    /// A memory location.
+    Memory(Memory),
+    /// Target of a relative branch (e.g., a `call` or `jmp`).
+    Target(u64),
+}
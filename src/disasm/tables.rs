@@ -0,0 +1,1649 @@
+//! Opcode maps of the x86-64 instruction set.
+//!
+//! Operands are written in the notation of the opcode maps of the Intel SDM
+//! (Volume 2, Appendix A): an addressing method (e.g., `E` for a ModRM r/m
+//! operand) followed by an operand type (e.g., `v` for the operand size), so
+//! the tables determine both the length of an encoding and its operands.
+//! VEX-encodable instructions are listed under their legacy mnemonic, with
+//! their additional VEX source operand (`H`) omitted for legacy encodings.
+//!
+//! Addressing methods:
+//!
+//! - `B`: general-purpose register in VEX.vvvv.
+//! - `C`, `D`: control or debug register in ModRM.reg.
+//! - `E`: general-purpose register or memory in ModRM.r/m.
+//! - `G`: general-purpose register in ModRM.reg.
+//! - `H`: vector register in VEX.vvvv.
+//! - `I`: immediate (`Is` being a sign-extended byte).
+//! - `J`: relative branch offset.
+//! - `K`: opmask register in ModRM.reg (`Kr`), ModRM.r/m (`Km`), or VEX.vvvv
+//!   (`Kv`).
+//! - `L`: vector register in bits 7:4 of an immediate byte.
+//! - `M`: memory in ModRM.r/m.
+//! - `N`, `P`, `Q`: MMX register in ModRM.r/m, in ModRM.reg, or MMX register
+//!   or memory in ModRM.r/m.
+//! - `O`: memory at an absolute offset.
+//! - `R`: general-purpose register in ModRM.r/m.
+//! - `S`: segment register in ModRM.reg.
+//! - `U`, `V`, `W`: vector register in ModRM.r/m, in ModRM.reg, or vector
+//!   register or memory in ModRM.r/m.
+//! - `Z`: general-purpose register in the low 3 bits of the opcode.
+
+/// The operand size defaults to 64 bits, but can be overridden to 16 bits.
+pub(super) const D64: u8 = 1 << 0;
+
+/// The operand size is always 64 bits.
+pub(super) const F64: u8 = 1 << 1;
+
+/// The mnemonic takes a suffix given by the operand size (e.g., `movsq`).
+pub(super) const STR: u8 = 1 << 2;
+
+/// The instruction only has a VEX encoding, and its mnemonic is given in
+/// full.
+pub(super) const VEX: u8 = 1 << 3;
+
+/// The mnemonic of an opmask instruction takes a suffix given by VEX.W and
+/// VEX.pp (e.g., `kmovw`).
+pub(super) const MASK: u8 = 1 << 4;
+
+/// Entry of an opcode map.
+#[derive(Debug, Copy, Clone)]
+pub(super) enum Entry {
+    /// Undefined opcode.
+    Invalid,
+    /// Instruction with the given mnemonic, operands, and flags.
+    Op(&'static str, &'static str, u8),
+    /// Instructions selected by ModRM.reg.
+    Group(&'static [Entry; 8]),
+    /// Instructions selected by ModRM.reg, sharing their operands and flags.
+    /// An empty mnemonic marks an undefined instruction.
+    Names(&'static [&'static str; 8], &'static str, u8),
+    /// Instructions selected by ModRM.r/m.
+    Rm(&'static [Entry; 8]),
+    /// Instructions selected by the mandatory prefix: none, `66`, `F3`, or
+    /// `F2`.
+    Prefixed(&'static [Entry; 4]),
+    /// Instructions selected by the presence of an `F3` prefix, leaving the
+    /// meaning of any `66` prefix unchanged.
+    Rep(&'static Entry, &'static Entry),
+    /// Instructions with a memory operand, and with a register operand.
+    Memory(&'static Entry, &'static Entry),
+    /// Instructions selected by the operand size: 16, 32, or 64 bits.
+    Size(&'static [Entry; 3]),
+    /// Instructions selected by REX.W (or VEX.W).
+    Wide(&'static Entry, &'static Entry),
+    /// Instructions selected by the encoding: legacy, or VEX (and EVEX).
+    Vex(&'static Entry, &'static Entry),
+}
+
+use Entry::*;
+
+/// Instruction taking MMX operands without a prefix, and SSE operands with a
+/// `66` prefix (e.g., `paddb`).
+macro_rules! mmx {
+    ($name:expr) => {
+        Prefixed(&[
+            Op($name, "Pq,Qq", 0),
+            Op($name, "Vx,Hx,Wx", 0),
+            Invalid,
+            Invalid,
+        ])
+    };
+    ($name:expr, $mmx:expr, $sse:expr) => {
+        Prefixed(&[Op($name, $mmx, 0), Op($name, $sse, 0), Invalid, Invalid])
+    };
+}
+
+/// Instruction requiring a `66` prefix.
+macro_rules! p66 {
+    ($name:expr, $operands:expr) => {
+        Prefixed(&[Invalid, Op($name, $operands, 0), Invalid, Invalid])
+    };
+    ($name:expr, $operands:expr, $flags:expr) => {
+        Prefixed(&[Invalid, Op($name, $operands, $flags), Invalid, Invalid])
+    };
+}
+
+/// Packed and scalar floating-point instruction (e.g., `addps`, `addpd`,
+/// `addss`, and `addsd`).
+macro_rules! fp {
+    ($ps:expr, $pd:expr, $ss:expr, $sd:expr) => {
+        Prefixed(&[
+            Op($ps, "Vps,Hps,Wps", 0),
+            Op($pd, "Vpd,Hpd,Wpd", 0),
+            Op($ss, "Vss,Hss,Wss", 0),
+            Op($sd, "Vsd,Hsd,Wsd", 0),
+        ])
+    };
+}
+
+/// Packed floating-point instruction (e.g., `andps` and `andpd`).
+macro_rules! packed {
+    ($ps:expr, $pd:expr) => {
+        Prefixed(&[
+            Op($ps, "Vps,Hps,Wps", 0),
+            Op($pd, "Vpd,Hpd,Wpd", 0),
+            Invalid,
+            Invalid,
+        ])
+    };
+}
+
+/// Arithmetic instruction of the first rows of the one-byte map (e.g., `add`).
+macro_rules! alu {
+    ($name:expr) => {
+        [
+            Op($name, "Eb,Gb", 0),
+            Op($name, "Ev,Gv", 0),
+            Op($name, "Gb,Eb", 0),
+            Op($name, "Gv,Ev", 0),
+            Op($name, "AL,Ib", 0),
+            Op($name, "rAX,Iz", 0),
+        ]
+    };
+}
+
+/// Instructions of the first rows of the one-byte map, which are arithmetic
+/// operations followed by two undefined (or prefix) opcodes.
+macro_rules! alu_row {
+    ($a:expr, $b:expr) => {{
+        let [a0, a1, a2, a3, a4, a5] = alu!($a);
+        let [b0, b1, b2, b3, b4, b5] = alu!($b);
+        [
+            a0, a1, a2, a3, a4, a5, Invalid, Invalid, b0, b1, b2, b3, b4, b5, Invalid, Invalid,
+        ]
+    }};
+}
+
+/// Instructions of a row of 16 opcodes, each encoding a condition code.
+macro_rules! conditional {
+    ($names:expr, $operands:expr, $flags:expr) => {{
+        let names: [&str; 16] = $names;
+        [
+            Op(names[0], $operands, $flags),
+            Op(names[1], $operands, $flags),
+            Op(names[2], $operands, $flags),
+            Op(names[3], $operands, $flags),
+            Op(names[4], $operands, $flags),
+            Op(names[5], $operands, $flags),
+            Op(names[6], $operands, $flags),
+            Op(names[7], $operands, $flags),
+            Op(names[8], $operands, $flags),
+            Op(names[9], $operands, $flags),
+            Op(names[10], $operands, $flags),
+            Op(names[11], $operands, $flags),
+            Op(names[12], $operands, $flags),
+            Op(names[13], $operands, $flags),
+            Op(names[14], $operands, $flags),
+            Op(names[15], $operands, $flags),
+        ]
+    }};
+}
+
+/// Instructions of a row of 8 opcodes, each encoding a register in its low
+/// 3 bits.
+macro_rules! registers {
+    ($name:expr, $operands:expr, $flags:expr) => {
+        [
+            Op($name, $operands, $flags),
+            Op($name, $operands, $flags),
+            Op($name, $operands, $flags),
+            Op($name, $operands, $flags),
+            Op($name, $operands, $flags),
+            Op($name, $operands, $flags),
+            Op($name, $operands, $flags),
+            Op($name, $operands, $flags),
+        ]
+    };
+}
+
+/// Concatenate arrays of entries into a single opcode map.
+macro_rules! map {
+    ($($row:expr),* $(,)?) => {{
+        let mut map = [const { Invalid }; 256];
+        let mut idx = 0;
+        $(
+            let row = $row;
+            let mut col = 0;
+            while col < row.len() {
+                map[idx] = row[col];
+                col += 1;
+                idx += 1;
+            }
+        )*
+        assert!(idx == 256);
+        map
+    }};
+}
+
+/// Names of the shift and rotate instructions of group 2.
+const SHIFTS: [&str; 8] = ["rol", "ror", "rcl", "rcr", "shl", "shr", "sal", "sar"];
+
+/// Names of the arithmetic instructions of group 1.
+const ARITHMETIC: [&str; 8] = ["add", "or", "adc", "sbb", "and", "sub", "xor", "cmp"];
+
+/// The one-byte opcode map. Prefixes, and the escapes to the other maps, are
+/// handled by the decoder.
+pub(super) static ONE_BYTE: [Entry; 256] = map![
+    alu_row!("add", "or"),
+    alu_row!("adc", "sbb"),
+    alu_row!("and", "sub"),
+    alu_row!("xor", "cmp"),
+    // 0x40: REX prefixes.
+    [const { Invalid }; 16],
+    registers!("push", "Zv", D64),
+    registers!("pop", "Zv", D64),
+    [
+        Invalid,
+        Invalid,
+        Invalid,
+        Op("movsxd", "Gv,Ed", 0),
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Op("push", "Iz", D64),
+        Op("imul", "Gv,Ev,Iz", 0),
+        Op("push", "Is", D64),
+        Op("imul", "Gv,Ev,Is", 0),
+        Op("insb", "", 0),
+        Op("ins", "", STR),
+        Op("outsb", "", 0),
+        Op("outs", "", STR),
+    ],
+    conditional!(
+        [
+            "jo", "jno", "jb", "jae", "je", "jne", "jbe", "ja", "js", "jns", "jp", "jnp", "jl",
+            "jge", "jle", "jg"
+        ],
+        "Jb",
+        F64
+    ),
+    [
+        Names(&ARITHMETIC, "Eb,Ib", 0),
+        Names(&ARITHMETIC, "Ev,Iz", 0),
+        Invalid,
+        Names(&ARITHMETIC, "Ev,Is", 0),
+        Op("test", "Eb,Gb", 0),
+        Op("test", "Ev,Gv", 0),
+        Op("xchg", "Eb,Gb", 0),
+        Op("xchg", "Ev,Gv", 0),
+        Op("mov", "Eb,Gb", 0),
+        Op("mov", "Ev,Gv", 0),
+        Op("mov", "Gb,Eb", 0),
+        Op("mov", "Gv,Ev", 0),
+        Op("mov", "Ew,Sw", 0),
+        Op("lea", "Gv,M", 0),
+        Op("mov", "Sw,Ew", 0),
+        Names(&["pop", "", "", "", "", "", "", ""], "Ev", D64),
+    ],
+    [
+        Rep(&Op("nop", "", 0), &Op("pause", "", 0)),
+        Op("xchg", "Zv,rAX", 0),
+        Op("xchg", "Zv,rAX", 0),
+        Op("xchg", "Zv,rAX", 0),
+        Op("xchg", "Zv,rAX", 0),
+        Op("xchg", "Zv,rAX", 0),
+        Op("xchg", "Zv,rAX", 0),
+        Op("xchg", "Zv,rAX", 0),
+        Size(&[Op("cbw", "", 0), Op("cwde", "", 0), Op("cdqe", "", 0)]),
+        Size(&[Op("cwd", "", 0), Op("cdq", "", 0), Op("cqo", "", 0)]),
+        Invalid,
+        Op("fwait", "", 0),
+        Op("pushf", "", D64 | STR),
+        Op("popf", "", D64 | STR),
+        Op("sahf", "", 0),
+        Op("lahf", "", 0),
+    ],
+    [
+        Op("movabs", "AL,Ob", 0),
+        Op("movabs", "rAX,Ov", 0),
+        Op("movabs", "Ob,AL", 0),
+        Op("movabs", "Ov,rAX", 0),
+        Op("movsb", "", 0),
+        Op("movs", "", STR),
+        Op("cmpsb", "", 0),
+        Op("cmps", "", STR),
+        Op("test", "AL,Ib", 0),
+        Op("test", "rAX,Iz", 0),
+        Op("stosb", "", 0),
+        Op("stos", "", STR),
+        Op("lodsb", "", 0),
+        Op("lods", "", STR),
+        Op("scasb", "", 0),
+        Op("scas", "", STR),
+    ],
+    registers!("mov", "Zb,Ib", 0),
+    registers!("mov", "Zv,Iv", 0),
+    [
+        Names(&SHIFTS, "Eb,Ib", 0),
+        Names(&SHIFTS, "Ev,Ib", 0),
+        Op("ret", "Iw", F64),
+        Op("ret", "", F64),
+        // 0xc4 and 0xc5: VEX prefixes.
+        Invalid,
+        Invalid,
+        Group(&[
+            Op("mov", "Eb,Ib", 0),
+            Invalid,
+            Invalid,
+            Invalid,
+            Invalid,
+            Invalid,
+            Invalid,
+            Memory(&Invalid, &Op("xabort", "Ib", 0)),
+        ]),
+        Group(&[
+            Op("mov", "Ev,Iz", 0),
+            Invalid,
+            Invalid,
+            Invalid,
+            Invalid,
+            Invalid,
+            Invalid,
+            Memory(&Invalid, &Op("xbegin", "Jz", F64)),
+        ]),
+        Op("enter", "Iw,Ib", 0),
+        Op("leave", "", D64),
+        Op("retf", "Iw", 0),
+        Op("retf", "", 0),
+        Op("int3", "", 0),
+        Op("int", "Ib", 0),
+        Invalid,
+        Size(&[Op("iretw", "", 0), Op("iretd", "", 0), Op("iretq", "", 0)]),
+    ],
+    [
+        Names(&SHIFTS, "Eb,1", 0),
+        Names(&SHIFTS, "Ev,1", 0),
+        Names(&SHIFTS, "Eb,CL", 0),
+        Names(&SHIFTS, "Ev,CL", 0),
+        Invalid,
+        Invalid,
+        Invalid,
+        Op("xlatb", "", 0),
+        // 0xd8 to 0xdf: x87 instructions, decoded separately.
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+    [
+        Op("loopne", "Jb", F64),
+        Op("loope", "Jb", F64),
+        Op("loop", "Jb", F64),
+        Op("jrcxz", "Jb", F64),
+        Op("in", "AL,Ib", 0),
+        Op("in", "eAX,Ib", 0),
+        Op("out", "Ib,AL", 0),
+        Op("out", "Ib,eAX", 0),
+        Op("call", "Jz", F64),
+        Op("jmp", "Jz", F64),
+        Invalid,
+        Op("jmp", "Jb", F64),
+        Op("in", "AL,DX", 0),
+        Op("in", "eAX,DX", 0),
+        Op("out", "DX,AL", 0),
+        Op("out", "DX,eAX", 0),
+    ],
+    [
+        // 0xf0: LOCK prefix.
+        Invalid,
+        Op("int1", "", 0),
+        // 0xf2 and 0xf3: REPNE and REP prefixes.
+        Invalid,
+        Invalid,
+        Op("hlt", "", 0),
+        Op("cmc", "", 0),
+        Group(&[
+            Op("test", "Eb,Ib", 0),
+            Op("test", "Eb,Ib", 0),
+            Op("not", "Eb", 0),
+            Op("neg", "Eb", 0),
+            Op("mul", "Eb", 0),
+            Op("imul", "Eb", 0),
+            Op("div", "Eb", 0),
+            Op("idiv", "Eb", 0),
+        ]),
+        Group(&[
+            Op("test", "Ev,Iz", 0),
+            Op("test", "Ev,Iz", 0),
+            Op("not", "Ev", 0),
+            Op("neg", "Ev", 0),
+            Op("mul", "Ev", 0),
+            Op("imul", "Ev", 0),
+            Op("div", "Ev", 0),
+            Op("idiv", "Ev", 0),
+        ]),
+        Op("clc", "", 0),
+        Op("stc", "", 0),
+        Op("cli", "", 0),
+        Op("sti", "", 0),
+        Op("cld", "", 0),
+        Op("std", "", 0),
+        Names(&["inc", "dec", "", "", "", "", "", ""], "Eb", 0),
+        Group(&[
+            Op("inc", "Ev", 0),
+            Op("dec", "Ev", 0),
+            Op("call", "Ev", F64),
+            Op("lcall", "Mp", 0),
+            Op("jmp", "Ev", F64),
+            Op("ljmp", "Mp", 0),
+            Op("push", "Ev", D64),
+            Invalid,
+        ]),
+    ],
+];
+
+/// The two-byte opcode map, following a `0F` escape.
+pub(super) static TWO_BYTE: [Entry; 256] = map![
+    [
+        Names(
+            &["sldt", "str", "lldt", "ltr", "verr", "verw", "", ""],
+            "Ew",
+            0
+        ),
+        Memory(
+            &Names(
+                &["sgdt", "sidt", "lgdt", "lidt", "smsw", "", "lmsw", "invlpg"],
+                "M",
+                0,
+            ),
+            &Group(&[
+                Rm(&[
+                    Invalid,
+                    Op("vmcall", "", 0),
+                    Op("vmlaunch", "", 0),
+                    Op("vmresume", "", 0),
+                    Op("vmxoff", "", 0),
+                    Invalid,
+                    Invalid,
+                    Invalid,
+                ]),
+                Rm(&[
+                    Op("monitor", "", 0),
+                    Op("mwait", "", 0),
+                    Op("clac", "", 0),
+                    Op("stac", "", 0),
+                    Invalid,
+                    Invalid,
+                    Invalid,
+                    Op("encls", "", 0),
+                ]),
+                Rm(&[
+                    Op("xgetbv", "", 0),
+                    Op("xsetbv", "", 0),
+                    Invalid,
+                    Invalid,
+                    Op("vmfunc", "", 0),
+                    Op("xend", "", 0),
+                    Op("xtest", "", 0),
+                    Op("enclu", "", 0),
+                ]),
+                Invalid,
+                Op("smsw", "Rv", 0),
+                Rm(&[
+                    Op("serialize", "", 0),
+                    Invalid,
+                    Invalid,
+                    Invalid,
+                    Invalid,
+                    Invalid,
+                    Op("rdpkru", "", 0),
+                    Op("wrpkru", "", 0),
+                ]),
+                Op("lmsw", "Rw", 0),
+                Rm(&[
+                    Op("swapgs", "", 0),
+                    Op("rdtscp", "", 0),
+                    Op("monitorx", "", 0),
+                    Op("mwaitx", "", 0),
+                    Op("clzero", "", 0),
+                    Op("rdpru", "", 0),
+                    Invalid,
+                    Invalid,
+                ]),
+            ]),
+        ),
+        Op("lar", "Gv,Ew", 0),
+        Op("lsl", "Gv,Ew", 0),
+        Invalid,
+        Op("syscall", "", 0),
+        Op("clts", "", 0),
+        Op("sysret", "", 0),
+        Op("invd", "", 0),
+        Op("wbinvd", "", 0),
+        Invalid,
+        Op("ud2", "", 0),
+        Invalid,
+        Names(
+            &[
+                "prefetch",
+                "prefetchw",
+                "prefetchwt1",
+                "prefetch",
+                "prefetch",
+                "prefetch",
+                "prefetch",
+                "prefetch",
+            ],
+            "Mb",
+            0,
+        ),
+        Op("femms", "", 0),
+        // 3DNow! instructions, selected by a trailing immediate byte.
+        Op("3dnow", "Pq,Qq,Ib", 0),
+    ],
+    [
+        Prefixed(&[
+            Op("movups", "Vps,Wps", 0),
+            Op("movupd", "Vpd,Wpd", 0),
+            Op("movss", "Vx,Hx,Wss", 0),
+            Op("movsd", "Vx,Hx,Wsd", 0),
+        ]),
+        Prefixed(&[
+            Op("movups", "Wps,Vps", 0),
+            Op("movupd", "Wpd,Vpd", 0),
+            Op("movss", "Wss,Hx,Vss", 0),
+            Op("movsd", "Wsd,Hx,Vsd", 0),
+        ]),
+        Prefixed(&[
+            Memory(&Op("movlps", "Vq,Hq,Mq", 0), &Op("movhlps", "Vq,Hq,Uq", 0)),
+            Op("movlpd", "Vq,Hq,Mq", 0),
+            Op("movsldup", "Vx,Wx", 0),
+            Op("movddup", "Vx,Wx", 0),
+        ]),
+        Prefixed(&[
+            Op("movlps", "Mq,Vq", 0),
+            Op("movlpd", "Mq,Vq", 0),
+            Invalid,
+            Invalid,
+        ]),
+        packed!("unpcklps", "unpcklpd"),
+        packed!("unpckhps", "unpckhpd"),
+        Prefixed(&[
+            Memory(
+                &Op("movhps", "Vdq,Hq,Mq", 0),
+                &Op("movlhps", "Vdq,Hq,Uq", 0)
+            ),
+            Op("movhpd", "Vdq,Hq,Mq", 0),
+            Op("movshdup", "Vx,Wx", 0),
+            Invalid,
+        ]),
+        Prefixed(&[
+            Op("movhps", "Mq,Vq", 0),
+            Op("movhpd", "Mq,Vq", 0),
+            Invalid,
+            Invalid,
+        ]),
+        Memory(
+            &Names(
+                &[
+                    "prefetchnta",
+                    "prefetcht0",
+                    "prefetcht1",
+                    "prefetcht2",
+                    "nop",
+                    "nop",
+                    "nop",
+                    "nop",
+                ],
+                "Mb",
+                0,
+            ),
+            &Op("nop", "Ev", 0),
+        ),
+        Op("nop", "Ev", 0),
+        Op("nop", "Ev", 0),
+        Op("nop", "Ev", 0),
+        Op("nop", "Ev", 0),
+        Op("nop", "Ev", 0),
+        Rep(
+            &Op("nop", "Ev", 0),
+            &Memory(
+                &Op("nop", "Ev", 0),
+                &Group(&[
+                    Op("nop", "Ev", 0),
+                    Wide(&Op("rdsspd", "Rd", 0), &Op("rdsspq", "Rq", 0)),
+                    Op("nop", "Ev", 0),
+                    Op("nop", "Ev", 0),
+                    Op("nop", "Ev", 0),
+                    Op("nop", "Ev", 0),
+                    Op("nop", "Ev", 0),
+                    Rm(&[
+                        Op("nop", "Ev", 0),
+                        Op("nop", "Ev", 0),
+                        Op("endbr64", "", 0),
+                        Op("endbr32", "", 0),
+                        Op("nop", "Ev", 0),
+                        Op("nop", "Ev", 0),
+                        Op("nop", "Ev", 0),
+                        Op("nop", "Ev", 0),
+                    ]),
+                ]),
+            ),
+        ),
+        Op("nop", "Ev", 0),
+    ],
+    [
+        Op("mov", "Rq,Cd", 0),
+        Op("mov", "Rq,Dd", 0),
+        Op("mov", "Cd,Rq", 0),
+        Op("mov", "Dd,Rq", 0),
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Prefixed(&[
+            Op("movaps", "Vps,Wps", 0),
+            Op("movapd", "Vpd,Wpd", 0),
+            Invalid,
+            Invalid,
+        ]),
+        Prefixed(&[
+            Op("movaps", "Wps,Vps", 0),
+            Op("movapd", "Wpd,Vpd", 0),
+            Invalid,
+            Invalid,
+        ]),
+        Prefixed(&[
+            Op("cvtpi2ps", "Vps,Qq", 0),
+            Op("cvtpi2pd", "Vpd,Qq", 0),
+            Op("cvtsi2ss", "Vss,Hss,Ey", 0),
+            Op("cvtsi2sd", "Vsd,Hsd,Ey", 0),
+        ]),
+        Prefixed(&[
+            Op("movntps", "Mps,Vps", 0),
+            Op("movntpd", "Mpd,Vpd", 0),
+            Invalid,
+            Invalid,
+        ]),
+        Prefixed(&[
+            Op("cvttps2pi", "Pq,Wq", 0),
+            Op("cvttpd2pi", "Pq,Wpd", 0),
+            Op("cvttss2si", "Gy,Wss", 0),
+            Op("cvttsd2si", "Gy,Wsd", 0),
+        ]),
+        Prefixed(&[
+            Op("cvtps2pi", "Pq,Wq", 0),
+            Op("cvtpd2pi", "Pq,Wpd", 0),
+            Op("cvtss2si", "Gy,Wss", 0),
+            Op("cvtsd2si", "Gy,Wsd", 0),
+        ]),
+        Prefixed(&[
+            Op("ucomiss", "Vss,Wss", 0),
+            Op("ucomisd", "Vsd,Wsd", 0),
+            Invalid,
+            Invalid,
+        ]),
+        Prefixed(&[
+            Op("comiss", "Vss,Wss", 0),
+            Op("comisd", "Vsd,Wsd", 0),
+            Invalid,
+            Invalid,
+        ]),
+    ],
+    [
+        Op("wrmsr", "", 0),
+        Op("rdtsc", "", 0),
+        Op("rdmsr", "", 0),
+        Op("rdpmc", "", 0),
+        Op("sysenter", "", 0),
+        Op("sysexit", "", 0),
+        Invalid,
+        Op("getsec", "", 0),
+        // 0x38 and 0x3a: escapes to the three-byte maps.
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+    {
+        // Opmask instructions share their opcodes with `cmovcc` under VEX.
+        let mut row = conditional!(
+            [
+                "cmovo", "cmovno", "cmovb", "cmovae", "cmove", "cmovne", "cmovbe", "cmova",
+                "cmovs", "cmovns", "cmovp", "cmovnp", "cmovl", "cmovge", "cmovle", "cmovg"
+            ],
+            "Gv,Ev",
+            0
+        );
+        row[1] = Vex(
+            &Op("cmovno", "Gv,Ev", 0),
+            &Op("kand", "Kr,Kv,Km", VEX | MASK),
+        );
+        row[2] = Vex(
+            &Op("cmovb", "Gv,Ev", 0),
+            &Op("kandn", "Kr,Kv,Km", VEX | MASK),
+        );
+        row[4] = Vex(&Op("cmove", "Gv,Ev", 0), &Op("knot", "Kr,Km", VEX | MASK));
+        row[5] = Vex(
+            &Op("cmovne", "Gv,Ev", 0),
+            &Op("kor", "Kr,Kv,Km", VEX | MASK),
+        );
+        row[6] = Vex(
+            &Op("cmovbe", "Gv,Ev", 0),
+            &Op("kxnor", "Kr,Kv,Km", VEX | MASK),
+        );
+        row[7] = Vex(
+            &Op("cmova", "Gv,Ev", 0),
+            &Op("kxor", "Kr,Kv,Km", VEX | MASK),
+        );
+        row[10] = Vex(
+            &Op("cmovp", "Gv,Ev", 0),
+            &Op("kadd", "Kr,Kv,Km", VEX | MASK),
+        );
+        row
+    },
+    [
+        Prefixed(&[
+            Op("movmskps", "Gd,Ups", 0),
+            Op("movmskpd", "Gd,Upd", 0),
+            Invalid,
+            Invalid,
+        ]),
+        Prefixed(&[
+            Op("sqrtps", "Vps,Wps", 0),
+            Op("sqrtpd", "Vpd,Wpd", 0),
+            Op("sqrtss", "Vss,Hss,Wss", 0),
+            Op("sqrtsd", "Vsd,Hsd,Wsd", 0),
+        ]),
+        Prefixed(&[
+            Op("rsqrtps", "Vps,Wps", 0),
+            Invalid,
+            Op("rsqrtss", "Vss,Hss,Wss", 0),
+            Invalid,
+        ]),
+        Prefixed(&[
+            Op("rcpps", "Vps,Wps", 0),
+            Invalid,
+            Op("rcpss", "Vss,Hss,Wss", 0),
+            Invalid,
+        ]),
+        packed!("andps", "andpd"),
+        packed!("andnps", "andnpd"),
+        packed!("orps", "orpd"),
+        packed!("xorps", "xorpd"),
+        fp!("addps", "addpd", "addss", "addsd"),
+        fp!("mulps", "mulpd", "mulss", "mulsd"),
+        Prefixed(&[
+            Op("cvtps2pd", "Vpd,Wh", 0),
+            Op("cvtpd2ps", "Vh,Wpd", 0),
+            Op("cvtss2sd", "Vsd,Hx,Wss", 0),
+            Op("cvtsd2ss", "Vss,Hx,Wsd", 0),
+        ]),
+        Prefixed(&[
+            Op("cvtdq2ps", "Vps,Wx", 0),
+            Op("cvtps2dq", "Vx,Wps", 0),
+            Op("cvttps2dq", "Vx,Wps", 0),
+            Invalid,
+        ]),
+        fp!("subps", "subpd", "subss", "subsd"),
+        fp!("minps", "minpd", "minss", "minsd"),
+        fp!("divps", "divpd", "divss", "divsd"),
+        fp!("maxps", "maxpd", "maxss", "maxsd"),
+    ],
+    [
+        mmx!("punpcklbw", "Pq,Qd", "Vx,Hx,Wx"),
+        mmx!("punpcklwd", "Pq,Qd", "Vx,Hx,Wx"),
+        mmx!("punpckldq", "Pq,Qd", "Vx,Hx,Wx"),
+        mmx!("packsswb"),
+        mmx!("pcmpgtb"),
+        mmx!("pcmpgtw"),
+        mmx!("pcmpgtd"),
+        mmx!("packuswb"),
+        mmx!("punpckhbw"),
+        mmx!("punpckhwd"),
+        mmx!("punpckhdq"),
+        mmx!("packssdw"),
+        p66!("punpcklqdq", "Vx,Hx,Wx"),
+        p66!("punpckhqdq", "Vx,Hx,Wx"),
+        Prefixed(&[
+            Wide(&Op("movd", "Pq,Ed", 0), &Op("movq", "Pq,Eq", 0)),
+            Wide(&Op("movd", "Vdq,Ed", 0), &Op("movq", "Vdq,Eq", 0)),
+            Invalid,
+            Invalid,
+        ]),
+        Prefixed(&[
+            Op("movq", "Pq,Qq", 0),
+            Op("movdqa", "Vx,Wx", 0),
+            Op("movdqu", "Vx,Wx", 0),
+            Invalid,
+        ]),
+    ],
+    [
+        Prefixed(&[
+            Op("pshufw", "Pq,Qq,Ib", 0),
+            Op("pshufd", "Vx,Wx,Ib", 0),
+            Op("pshufhw", "Vx,Wx,Ib", 0),
+            Op("pshuflw", "Vx,Wx,Ib", 0),
+        ]),
+        Group(&[
+            Invalid,
+            Invalid,
+            mmx!("psrlw", "Nq,Ib", "Hx,Ux,Ib"),
+            Invalid,
+            mmx!("psraw", "Nq,Ib", "Hx,Ux,Ib"),
+            Invalid,
+            mmx!("psllw", "Nq,Ib", "Hx,Ux,Ib"),
+            Invalid,
+        ]),
+        Group(&[
+            Invalid,
+            Invalid,
+            mmx!("psrld", "Nq,Ib", "Hx,Ux,Ib"),
+            Invalid,
+            mmx!("psrad", "Nq,Ib", "Hx,Ux,Ib"),
+            Invalid,
+            mmx!("pslld", "Nq,Ib", "Hx,Ux,Ib"),
+            Invalid,
+        ]),
+        Group(&[
+            Invalid,
+            Invalid,
+            mmx!("psrlq", "Nq,Ib", "Hx,Ux,Ib"),
+            p66!("psrldq", "Hx,Ux,Ib"),
+            Invalid,
+            Invalid,
+            mmx!("psllq", "Nq,Ib", "Hx,Ux,Ib"),
+            p66!("pslldq", "Hx,Ux,Ib"),
+        ]),
+        mmx!("pcmpeqb"),
+        mmx!("pcmpeqw"),
+        mmx!("pcmpeqd"),
+        // 0x77: `emms`, or `vzeroupper` and `vzeroall` under VEX.
+        Op("emms", "", 0),
+        Op("vmread", "Eq,Gq", 0),
+        Op("vmwrite", "Gq,Eq", 0),
+        Invalid,
+        Invalid,
+        Prefixed(&[
+            Invalid,
+            Op("haddpd", "Vpd,Hpd,Wpd", 0),
+            Invalid,
+            Op("haddps", "Vps,Hps,Wps", 0),
+        ]),
+        Prefixed(&[
+            Invalid,
+            Op("hsubpd", "Vpd,Hpd,Wpd", 0),
+            Invalid,
+            Op("hsubps", "Vps,Hps,Wps", 0),
+        ]),
+        Prefixed(&[
+            Wide(&Op("movd", "Ed,Pq", 0), &Op("movq", "Eq,Pq", 0)),
+            Wide(&Op("movd", "Ed,Vdq", 0), &Op("movq", "Eq,Vdq", 0)),
+            Op("movq", "Vq,Wq", 0),
+            Invalid,
+        ]),
+        Prefixed(&[
+            Op("movq", "Qq,Pq", 0),
+            Op("movdqa", "Wx,Vx", 0),
+            Op("movdqu", "Wx,Vx", 0),
+            Invalid,
+        ]),
+    ],
+    conditional!(
+        [
+            "jo", "jno", "jb", "jae", "je", "jne", "jbe", "ja", "js", "jns", "jp", "jnp", "jl",
+            "jge", "jle", "jg"
+        ],
+        "Jz",
+        F64
+    ),
+    {
+        // Opmask moves and tests share their opcodes with `setcc` under VEX.
+        let mut row = conditional!(
+            [
+                "seto", "setno", "setb", "setae", "sete", "setne", "setbe", "seta", "sets",
+                "setns", "setp", "setnp", "setl", "setge", "setle", "setg"
+            ],
+            "Eb",
+            0
+        );
+        row[0] = Vex(&Op("seto", "Eb", 0), &Op("kmov", "Kr,Km", VEX | MASK));
+        row[1] = Vex(&Op("setno", "Eb", 0), &Op("kmov", "M,Kr", VEX | MASK));
+        row[2] = Vex(&Op("setb", "Eb", 0), &Op("kmov", "Kr,Ry", VEX | MASK));
+        row[3] = Vex(&Op("setae", "Eb", 0), &Op("kmov", "Gy,Km", VEX | MASK));
+        row[8] = Vex(&Op("sets", "Eb", 0), &Op("kortest", "Kr,Km", VEX | MASK));
+        row[9] = Vex(&Op("setns", "Eb", 0), &Op("ktest", "Kr,Km", VEX | MASK));
+        row
+    },
+    [
+        Op("push", "FS", D64),
+        Op("pop", "FS", D64),
+        Op("cpuid", "", 0),
+        Op("bt", "Ev,Gv", 0),
+        Op("shld", "Ev,Gv,Ib", 0),
+        Op("shld", "Ev,Gv,CL", 0),
+        Invalid,
+        Invalid,
+        Op("push", "GS", D64),
+        Op("pop", "GS", D64),
+        Op("rsm", "", 0),
+        Op("bts", "Ev,Gv", 0),
+        Op("shrd", "Ev,Gv,Ib", 0),
+        Op("shrd", "Ev,Gv,CL", 0),
+        Memory(
+            &Group(&[
+                Op("fxsave", "M", 0),
+                Op("fxrstor", "M", 0),
+                Op("ldmxcsr", "Md", 0),
+                Op("stmxcsr", "Md", 0),
+                Op("xsave", "M", 0),
+                Op("xrstor", "M", 0),
+                Prefixed(&[
+                    Op("xsaveopt", "M", 0),
+                    Op("clwb", "Mb", 0),
+                    Invalid,
+                    Invalid,
+                ]),
+                Prefixed(&[
+                    Op("clflush", "Mb", 0),
+                    Op("clflushopt", "Mb", 0),
+                    Invalid,
+                    Invalid,
+                ]),
+            ]),
+            &Group(&[
+                Prefixed(&[Invalid, Invalid, Op("rdfsbase", "Ry", 0), Invalid]),
+                Prefixed(&[Invalid, Invalid, Op("rdgsbase", "Ry", 0), Invalid]),
+                Prefixed(&[Invalid, Invalid, Op("wrfsbase", "Ry", 0), Invalid]),
+                Prefixed(&[Invalid, Invalid, Op("wrgsbase", "Ry", 0), Invalid]),
+                Invalid,
+                Op("lfence", "", 0),
+                Op("mfence", "", 0),
+                Op("sfence", "", 0),
+            ]),
+        ),
+        Op("imul", "Gv,Ev", 0),
+    ],
+    [
+        Op("cmpxchg", "Eb,Gb", 0),
+        Op("cmpxchg", "Ev,Gv", 0),
+        Op("lss", "Gv,Mp", 0),
+        Op("btr", "Ev,Gv", 0),
+        Op("lfs", "Gv,Mp", 0),
+        Op("lgs", "Gv,Mp", 0),
+        Op("movzx", "Gv,Eb", 0),
+        Op("movzx", "Gv,Ew", 0),
+        Rep(&Op("jmpe", "", 0), &Op("popcnt", "Gv,Ev", 0)),
+        Op("ud1", "Gv,Ev", 0),
+        Names(&["", "", "", "", "bt", "bts", "btr", "btc"], "Ev,Ib", 0),
+        Op("btc", "Ev,Gv", 0),
+        Rep(&Op("bsf", "Gv,Ev", 0), &Op("tzcnt", "Gv,Ev", 0)),
+        Rep(&Op("bsr", "Gv,Ev", 0), &Op("lzcnt", "Gv,Ev", 0)),
+        Op("movsx", "Gv,Eb", 0),
+        Op("movsx", "Gv,Ew", 0),
+    ],
+    [
+        Op("xadd", "Eb,Gb", 0),
+        Op("xadd", "Ev,Gv", 0),
+        Prefixed(&[
+            Op("cmpps", "Vps,Hps,Wps,Ib", 0),
+            Op("cmppd", "Vpd,Hpd,Wpd,Ib", 0),
+            Op("cmpss", "Vss,Hss,Wss,Ib", 0),
+            Op("cmpsd", "Vsd,Hsd,Wsd,Ib", 0),
+        ]),
+        Op("movnti", "My,Gy", 0),
+        mmx!("pinsrw", "Pq,Ed,Ib", "Vdq,Hdq,Ed,Ib"),
+        mmx!("pextrw", "Gd,Nq,Ib", "Gd,Udq,Ib"),
+        Prefixed(&[
+            Op("shufps", "Vps,Hps,Wps,Ib", 0),
+            Op("shufpd", "Vpd,Hpd,Wpd,Ib", 0),
+            Invalid,
+            Invalid,
+        ]),
+        Memory(
+            &Group(&[
+                Invalid,
+                Wide(&Op("cmpxchg8b", "Mq", 0), &Op("cmpxchg16b", "Mdq", 0)),
+                Invalid,
+                Op("xrstors", "M", 0),
+                Op("xsavec", "M", 0),
+                Op("xsaves", "M", 0),
+                Prefixed(&[
+                    Op("vmptrld", "Mq", 0),
+                    Op("vmclear", "Mq", 0),
+                    Op("vmxon", "Mq", 0),
+                    Invalid,
+                ]),
+                Op("vmptrst", "Mq", 0),
+            ]),
+            &Group(&[
+                Invalid,
+                Invalid,
+                Invalid,
+                Invalid,
+                Invalid,
+                Invalid,
+                Op("rdrand", "Rv", 0),
+                Rep(&Op("rdseed", "Rv", 0), &Op("rdpid", "Rq", 0)),
+            ]),
+        ),
+        Op("bswap", "Zy", 0),
+        Op("bswap", "Zy", 0),
+        Op("bswap", "Zy", 0),
+        Op("bswap", "Zy", 0),
+        Op("bswap", "Zy", 0),
+        Op("bswap", "Zy", 0),
+        Op("bswap", "Zy", 0),
+        Op("bswap", "Zy", 0),
+    ],
+    [
+        Prefixed(&[
+            Invalid,
+            Op("addsubpd", "Vpd,Hpd,Wpd", 0),
+            Invalid,
+            Op("addsubps", "Vps,Hps,Wps", 0),
+        ]),
+        mmx!("psrlw"),
+        mmx!("psrld"),
+        mmx!("psrlq"),
+        mmx!("paddq"),
+        mmx!("pmullw"),
+        Prefixed(&[
+            Invalid,
+            Op("movq", "Wq,Vq", 0),
+            Op("movq2dq", "Vdq,Nq", 0),
+            Op("movdq2q", "Pq,Uq", 0),
+        ]),
+        mmx!("pmovmskb", "Gd,Nq", "Gd,Ux"),
+        mmx!("psubusb"),
+        mmx!("psubusw"),
+        mmx!("pminub"),
+        mmx!("pand"),
+        mmx!("paddusb"),
+        mmx!("paddusw"),
+        mmx!("pmaxub"),
+        mmx!("pandn"),
+    ],
+    [
+        mmx!("pavgb"),
+        mmx!("psraw"),
+        mmx!("psrad"),
+        mmx!("pavgw"),
+        mmx!("pmulhuw"),
+        mmx!("pmulhw"),
+        Prefixed(&[
+            Invalid,
+            Op("cvttpd2dq", "Vh,Wpd", 0),
+            Op("cvtdq2pd", "Vx,Wh", 0),
+            Op("cvtpd2dq", "Vh,Wpd", 0),
+        ]),
+        Prefixed(&[
+            Op("movntq", "Mq,Pq", 0),
+            Op("movntdq", "Mx,Vx", 0),
+            Invalid,
+            Invalid,
+        ]),
+        mmx!("psubsb"),
+        mmx!("psubsw"),
+        mmx!("pminsw"),
+        mmx!("por"),
+        mmx!("paddsb"),
+        mmx!("paddsw"),
+        mmx!("pmaxsw"),
+        mmx!("pxor"),
+    ],
+    [
+        Prefixed(&[Invalid, Invalid, Invalid, Op("lddqu", "Vx,Mx", 0)]),
+        mmx!("psllw"),
+        mmx!("pslld"),
+        mmx!("psllq"),
+        mmx!("pmuludq"),
+        mmx!("pmaddwd"),
+        mmx!("psadbw"),
+        mmx!("maskmovq", "Pq,Nq", "Vdq,Udq"),
+        mmx!("psubb"),
+        mmx!("psubw"),
+        mmx!("psubd"),
+        mmx!("psubq"),
+        mmx!("paddb"),
+        mmx!("paddw"),
+        mmx!("paddd"),
+        Op("ud0", "Gd,Ed", 0),
+    ],
+];
+
+/// Move with sign or zero extension of the three-byte map (e.g., `pmovsxbw`),
+/// whose source is the given fraction of the destination.
+macro_rules! extend {
+    ($name:expr, $source:expr) => {
+        p66!($name, concat!("Vx,W", $source))
+    };
+}
+
+/// VEX-only instruction with a `66` prefix, named by VEX.W (e.g., `vpsrlvd`
+/// and `vpsrlvq`).
+macro_rules! wide66 {
+    ($d:expr, $q:expr, $operands:expr) => {
+        Prefixed(&[
+            Invalid,
+            Wide(&Op($d, $operands, VEX), &Op($q, $operands, VEX)),
+            Invalid,
+            Invalid,
+        ])
+    };
+}
+
+/// The three-byte opcode map following a `0F 38` escape. Fused multiply-add
+/// instructions are named by the decoder.
+pub(super) static THREE_BYTE_38: [Entry; 256] = map![
+    [
+        mmx!("pshufb"),
+        mmx!("phaddw"),
+        mmx!("phaddd"),
+        mmx!("phaddsw"),
+        mmx!("pmaddubsw"),
+        mmx!("phsubw"),
+        mmx!("phsubd"),
+        mmx!("phsubsw"),
+        mmx!("psignb"),
+        mmx!("psignw"),
+        mmx!("psignd"),
+        mmx!("pmulhrsw"),
+        p66!("vpermilps", "Vx,Hx,Wx", VEX),
+        p66!("vpermilpd", "Vx,Hx,Wx", VEX),
+        p66!("vtestps", "Vx,Wx", VEX),
+        p66!("vtestpd", "Vx,Wx", VEX),
+    ],
+    [
+        p66!("pblendvb", "Vdq,Wdq"),
+        Invalid,
+        Invalid,
+        p66!("vcvtph2ps", "Vx,Wh", VEX),
+        p66!("blendvps", "Vdq,Wdq"),
+        p66!("blendvpd", "Vdq,Wdq"),
+        p66!("vpermps", "Vqq,Hqq,Wqq", VEX),
+        p66!("ptest", "Vx,Wx"),
+        p66!("vbroadcastss", "Vx,Wd", VEX),
+        p66!("vbroadcastsd", "Vqq,Wq", VEX),
+        p66!("vbroadcastf128", "Vqq,Mdq", VEX),
+        Invalid,
+        mmx!("pabsb", "Pq,Qq", "Vx,Wx"),
+        mmx!("pabsw", "Pq,Qq", "Vx,Wx"),
+        mmx!("pabsd", "Pq,Qq", "Vx,Wx"),
+        Invalid,
+    ],
+    [
+        extend!("pmovsxbw", "h"),
+        extend!("pmovsxbd", "d"),
+        extend!("pmovsxbq", "w"),
+        extend!("pmovsxwd", "h"),
+        extend!("pmovsxwq", "d"),
+        extend!("pmovsxdq", "h"),
+        Invalid,
+        Invalid,
+        p66!("pmuldq", "Vx,Hx,Wx"),
+        p66!("pcmpeqq", "Vx,Hx,Wx"),
+        p66!("movntdqa", "Vx,Mx"),
+        p66!("packusdw", "Vx,Hx,Wx"),
+        p66!("vmaskmovps", "Vx,Hx,Mx", VEX),
+        p66!("vmaskmovpd", "Vx,Hx,Mx", VEX),
+        p66!("vmaskmovps", "Mx,Hx,Vx", VEX),
+        p66!("vmaskmovpd", "Mx,Hx,Vx", VEX),
+    ],
+    [
+        extend!("pmovzxbw", "h"),
+        extend!("pmovzxbd", "d"),
+        extend!("pmovzxbq", "w"),
+        extend!("pmovzxwd", "h"),
+        extend!("pmovzxwq", "d"),
+        extend!("pmovzxdq", "h"),
+        p66!("vpermd", "Vqq,Hqq,Wqq", VEX),
+        p66!("pcmpgtq", "Vx,Hx,Wx"),
+        p66!("pminsb", "Vx,Hx,Wx"),
+        p66!("pminsd", "Vx,Hx,Wx"),
+        p66!("pminuw", "Vx,Hx,Wx"),
+        p66!("pminud", "Vx,Hx,Wx"),
+        p66!("pmaxsb", "Vx,Hx,Wx"),
+        p66!("pmaxsd", "Vx,Hx,Wx"),
+        p66!("pmaxuw", "Vx,Hx,Wx"),
+        p66!("pmaxud", "Vx,Hx,Wx"),
+    ],
+    [
+        p66!("pmulld", "Vx,Hx,Wx"),
+        p66!("phminposuw", "Vdq,Wdq"),
+        Invalid,
+        Invalid,
+        Invalid,
+        wide66!("vpsrlvd", "vpsrlvq", "Vx,Hx,Wx"),
+        p66!("vpsravd", "Vx,Hx,Wx", VEX),
+        wide66!("vpsllvd", "vpsllvq", "Vx,Hx,Wx"),
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+    [
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        p66!("vpbroadcastd", "Vx,Wd", VEX),
+        p66!("vpbroadcastq", "Vx,Wq", VEX),
+        p66!("vbroadcasti128", "Vqq,Mdq", VEX),
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+    [const { Invalid }; 16],
+    [
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        p66!("vpbroadcastb", "Vx,Wb", VEX),
+        p66!("vpbroadcastw", "Vx,Ww", VEX),
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+    [
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        wide66!("vpmaskmovd", "vpmaskmovq", "Vx,Hx,Mx"),
+        Invalid,
+        wide66!("vpmaskmovd", "vpmaskmovq", "Mx,Vx,Hx"),
+        Invalid,
+    ],
+    // 0x90 to 0xbf: gathers and fused multiply-add instructions.
+    [const { Invalid }; 16],
+    [const { Invalid }; 16],
+    [const { Invalid }; 16],
+    [
+        Prefixed(&[Op("sha1nexte", "Vdq,Wdq", 0), Invalid, Invalid, Invalid]),
+        Prefixed(&[Op("sha1msg1", "Vdq,Wdq", 0), Invalid, Invalid, Invalid]),
+        Prefixed(&[Op("sha1msg2", "Vdq,Wdq", 0), Invalid, Invalid, Invalid]),
+        Prefixed(&[Op("sha256rnds2", "Vdq,Wdq", 0), Invalid, Invalid, Invalid]),
+        Prefixed(&[Op("sha256msg1", "Vdq,Wdq", 0), Invalid, Invalid, Invalid]),
+        Prefixed(&[Op("sha256msg2", "Vdq,Wdq", 0), Invalid, Invalid, Invalid]),
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+    [
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        p66!("aesimc", "Vdq,Wdq"),
+        p66!("aesenc", "Vx,Hx,Wx"),
+        p66!("aesenclast", "Vx,Hx,Wx"),
+        p66!("aesdec", "Vx,Hx,Wx"),
+        p66!("aesdeclast", "Vx,Hx,Wx"),
+    ],
+    [const { Invalid }; 16],
+    [
+        Prefixed(&[
+            Op("movbe", "Gy,My", 0),
+            Op("movbe", "Gw,Mw", 0),
+            Invalid,
+            Op("crc32", "Gd,Eb", 0),
+        ]),
+        Prefixed(&[
+            Op("movbe", "My,Gy", 0),
+            Op("movbe", "Mw,Gw", 0),
+            Invalid,
+            Op("crc32", "Gd,Ev", 0),
+        ]),
+        Prefixed(&[Op("andn", "Gy,By,Ey", VEX), Invalid, Invalid, Invalid]),
+        Prefixed(&[
+            Names(
+                &["", "blsr", "blsmsk", "blsi", "", "", "", ""],
+                "By,Ey",
+                VEX
+            ),
+            Invalid,
+            Invalid,
+            Invalid,
+        ]),
+        Invalid,
+        Prefixed(&[
+            Op("bzhi", "Gy,Ey,By", VEX),
+            Invalid,
+            Op("pext", "Gy,By,Ey", VEX),
+            Op("pdep", "Gy,By,Ey", VEX),
+        ]),
+        Prefixed(&[
+            Invalid,
+            Op("adcx", "Gy,Ey", 0),
+            Op("adox", "Gy,Ey", 0),
+            Op("mulx", "Gy,By,Ey", VEX),
+        ]),
+        Prefixed(&[
+            Op("bextr", "Gy,Ey,By", VEX),
+            Op("shlx", "Gy,Ey,By", VEX),
+            Op("sarx", "Gy,Ey,By", VEX),
+            Op("shrx", "Gy,Ey,By", VEX),
+        ]),
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+];
+
+/// The three-byte opcode map following a `0F 3A` escape, whose instructions
+/// all take an immediate byte.
+pub(super) static THREE_BYTE_3A: [Entry; 256] = map![
+    [
+        p66!("vpermq", "Vqq,Wqq,Ib", VEX),
+        p66!("vpermpd", "Vqq,Wqq,Ib", VEX),
+        p66!("vpblendd", "Vx,Hx,Wx,Ib", VEX),
+        Invalid,
+        p66!("vpermilps", "Vx,Wx,Ib", VEX),
+        p66!("vpermilpd", "Vx,Wx,Ib", VEX),
+        p66!("vperm2f128", "Vqq,Hqq,Wqq,Ib", VEX),
+        Invalid,
+        p66!("roundps", "Vx,Wx,Ib"),
+        p66!("roundpd", "Vx,Wx,Ib"),
+        p66!("roundss", "Vss,Hss,Wss,Ib"),
+        p66!("roundsd", "Vsd,Hsd,Wsd,Ib"),
+        p66!("blendps", "Vx,Hx,Wx,Ib"),
+        p66!("blendpd", "Vx,Hx,Wx,Ib"),
+        p66!("pblendw", "Vx,Hx,Wx,Ib"),
+        mmx!("palignr", "Pq,Qq,Ib", "Vx,Hx,Wx,Ib"),
+    ],
+    [
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        p66!("pextrb", "Ed,Vdq,Ib"),
+        p66!("pextrw", "Ed,Vdq,Ib"),
+        Prefixed(&[
+            Invalid,
+            Wide(&Op("pextrd", "Ed,Vdq,Ib", 0), &Op("pextrq", "Eq,Vdq,Ib", 0)),
+            Invalid,
+            Invalid,
+        ]),
+        p66!("extractps", "Ed,Vdq,Ib"),
+        p66!("vinsertf128", "Vqq,Hqq,Wdq,Ib", VEX),
+        p66!("vextractf128", "Wdq,Vqq,Ib", VEX),
+        Invalid,
+        Invalid,
+        Invalid,
+        p66!("vcvtps2ph", "Wh,Vx,Ib", VEX),
+        Invalid,
+        Invalid,
+    ],
+    [
+        p66!("pinsrb", "Vdq,Hdq,Ed,Ib"),
+        p66!("insertps", "Vdq,Hdq,Wd,Ib"),
+        Prefixed(&[
+            Invalid,
+            Wide(
+                &Op("pinsrd", "Vdq,Hdq,Ed,Ib", 0),
+                &Op("pinsrq", "Vdq,Hdq,Eq,Ib", 0),
+            ),
+            Invalid,
+            Invalid,
+        ]),
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+    [
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        p66!("vinserti128", "Vqq,Hqq,Wdq,Ib", VEX),
+        p66!("vextracti128", "Wdq,Vqq,Ib", VEX),
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+    [
+        p66!("dpps", "Vx,Hx,Wx,Ib"),
+        p66!("dppd", "Vdq,Hdq,Wdq,Ib"),
+        p66!("mpsadbw", "Vx,Hx,Wx,Ib"),
+        Invalid,
+        p66!("pclmulqdq", "Vdq,Hdq,Wdq,Ib"),
+        Invalid,
+        p66!("vperm2i128", "Vqq,Hqq,Wqq,Ib", VEX),
+        Invalid,
+        Invalid,
+        Invalid,
+        p66!("vblendvps", "Vx,Hx,Wx,Lx", VEX),
+        p66!("vblendvpd", "Vx,Hx,Wx,Lx", VEX),
+        p66!("vpblendvb", "Vx,Hx,Wx,Lx", VEX),
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+    [const { Invalid }; 16],
+    [
+        p66!("pcmpestrm", "Vdq,Wdq,Ib"),
+        p66!("pcmpestri", "Vdq,Wdq,Ib"),
+        p66!("pcmpistrm", "Vdq,Wdq,Ib"),
+        p66!("pcmpistri", "Vdq,Wdq,Ib"),
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+    [const { Invalid }; 16],
+    [const { Invalid }; 16],
+    [const { Invalid }; 16],
+    [const { Invalid }; 16],
+    [const { Invalid }; 16],
+    [
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Prefixed(&[Op("sha1rnds4", "Vdq,Wdq,Ib", 0), Invalid, Invalid, Invalid]),
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+    [
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        p66!("aeskeygenassist", "Vdq,Wdq,Ib"),
+    ],
+    [const { Invalid }; 16],
+    [
+        Prefixed(&[Invalid, Invalid, Invalid, Op("rorx", "Gy,Ey,Ib", VEX)]),
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+        Invalid,
+    ],
+];
+
+/// Memory operands of the x87 instructions, indexed by the low 3 bits of the
+/// opcode and by ModRM.reg, as a mnemonic and the size of the operand.
+pub(super) const X87_MEMORY: [[(&str, u8); 8]; 8] = [
+    [
+        ("fadd", 4),
+        ("fmul", 4),
+        ("fcom", 4),
+        ("fcomp", 4),
+        ("fsub", 4),
+        ("fsubr", 4),
+        ("fdiv", 4),
+        ("fdivr", 4),
+    ],
+    [
+        ("fld", 4),
+        ("", 0),
+        ("fst", 4),
+        ("fstp", 4),
+        ("fldenv", 0),
+        ("fldcw", 2),
+        ("fnstenv", 0),
+        ("fnstcw", 2),
+    ],
+    [
+        ("fiadd", 4),
+        ("fimul", 4),
+        ("ficom", 4),
+        ("ficomp", 4),
+        ("fisub", 4),
+        ("fisubr", 4),
+        ("fidiv", 4),
+        ("fidivr", 4),
+    ],
+    [
+        ("fild", 4),
+        ("fisttp", 4),
+        ("fist", 4),
+        ("fistp", 4),
+        ("", 0),
+        ("fld", 10),
+        ("", 0),
+        ("fstp", 10),
+    ],
+    [
+        ("fadd", 8),
+        ("fmul", 8),
+        ("fcom", 8),
+        ("fcomp", 8),
+        ("fsub", 8),
+        ("fsubr", 8),
+        ("fdiv", 8),
+        ("fdivr", 8),
+    ],
+    [
+        ("fld", 8),
+        ("fisttp", 8),
+        ("fst", 8),
+        ("fstp", 8),
+        ("frstor", 0),
+        ("", 0),
+        ("fnsave", 0),
+        ("fnstsw", 2),
+    ],
+    [
+        ("fiadd", 2),
+        ("fimul", 2),
+        ("ficom", 2),
+        ("ficomp", 2),
+        ("fisub", 2),
+        ("fisubr", 2),
+        ("fidiv", 2),
+        ("fidivr", 2),
+    ],
+    [
+        ("fild", 2),
+        ("fisttp", 2),
+        ("fist", 2),
+        ("fistp", 2),
+        ("fbld", 10),
+        ("fild", 8),
+        ("fbstp", 10),
+        ("fistp", 8),
+    ],
+];
@@ -0,0 +1,176 @@
+//! Abbreviation tables of the `.debug_abbrev` section, declaring the tag and
+//! the attributes (along with their forms) of debugging information entries.
+//!
+//! Each entry starts with the code of its abbreviation, within the table
+//! referenced by the header of its unit. Several units may share a table.
+
+use super::constants::*;
+use crate::elf::reader::Reader;
+
+/// Declaration of an attribute of the entries of an [`Abbreviation`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct AttributeSpec {
+    /// Name of the attribute (e.g., [`DW_AT_name`]).
+    pub name: u64,
+    /// Form encoding the value of the attribute (e.g., [`DW_FORM_strp`]).
+    pub form: u64,
+    /// Value shared by every entry, if the form is [`DW_FORM_implicit_const`].
+    pub implicit_const: i64,
+}
+
+/// Declaration of the tag and attributes of the entries referencing its code.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Abbreviation {
+    /// Code of the abbreviation, unique within its table.
+    pub code: u64,
+    /// Tag of the entries (e.g., [`DW_TAG_subprogram`]).
+    pub tag: u64,
+    /// Whether the entries are followed by their children.
+    pub has_children: bool,
+    /// Attributes of the entries, in the order their values are encoded.
+    pub attributes: Vec<AttributeSpec>,
+}
+
+/// An abbreviation table of the `.debug_abbrev` section.
+#[derive(Debug, Default)]
+pub struct Abbreviations {
+    /// Abbreviations of the table, sorted by code.
+    abbreviations: Vec<Abbreviation>,
+}
+
+impl Abbreviations {
+    /// Parse the abbreviation table at `offset` of the `debug_abbrev` section,
+    /// returning `None` if it is malformed. A table truncated by the end of
+    /// the section is accepted.
+    pub fn parse(debug_abbrev: &[u8], offset: u64) -> Option<Self> {
+        let mut reader = Reader::new(debug_abbrev, usize::try_from(offset).ok()?);
+        let mut abbreviations = Vec::new();
+
+        while !reader.is_empty() {
+            let code = reader.uleb128()?;
+
+            if code == 0 {
+                break;
+            }
+
+            let tag = reader.uleb128()?;
+            let has_children = reader.u8()? == DW_CHILDREN_yes;
+            let mut attributes = Vec::new();
+
+            loop {
+                let name = reader.uleb128()?;
+                let form = reader.uleb128()?;
+
+                if (name, form) == (0, 0) {
+                    break;
+                }
+
+                let implicit_const = match form {
+                    DW_FORM_implicit_const => reader.sleb128()?,
+                    _ => 0,
+                };
+
+                attributes.push(AttributeSpec {
+                    name,
+                    form,
+                    implicit_const,
+                });
+            }
+
+            abbreviations.push(Abbreviation {
+                code,
+                tag,
+                has_children,
+                attributes,
+            });
+        }
+
+        abbreviations.sort_by_key(|abbreviation| abbreviation.code);
+        Some(Self { abbreviations })
+    }
+
+    /// Look up the abbreviation with the given `code`.
+    pub fn get(&self, code: u64) -> Option<&Abbreviation> {
+        // Codes are usually assigned consecutively from 1.
+        let direct = usize::try_from(code.wrapping_sub(1))
+            .ok()
+            .and_then(|idx| self.abbreviations.get(idx))
+            .filter(|abbreviation| abbreviation.code == code);
+
+        direct.or_else(|| {
+            let idx = self
+                .abbreviations
+                .binary_search_by_key(&code, |abbreviation| abbreviation.code)
+                .ok()?;
+
+            self.abbreviations.get(idx)
+        })
+    }
+
+    /// Return the abbreviations of the table, sorted by code.
+    pub fn iter(&self) -> impl Iterator<Item = &Abbreviation> {
+        self.abbreviations.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abbreviations_parse() {
+        #[rustfmt::skip]
+        let debug_abbrev = [
+            0xff, // Padding before the table.
+            // Abbreviation 3, declared first.
+            0x03, DW_TAG_variable as u8, 0x00,
+            DW_AT_name as u8, DW_FORM_strp as u8,
+            DW_AT_decl_line as u8, DW_FORM_implicit_const as u8, 0x7f, // -1
+            0x00, 0x00,
+            // Abbreviation 1.
+            0x01, DW_TAG_compile_unit as u8, DW_CHILDREN_yes,
+            DW_AT_name as u8, DW_FORM_string as u8,
+            0x00, 0x00,
+            // Abbreviation 200, beyond a single byte of ULEB128.
+            0xc8, 0x01, DW_TAG_subprogram as u8, 0x00,
+            0x00, 0x00,
+            // End of the table.
+            0x00,
+            // Abbreviation of another table.
+            0x02, DW_TAG_base_type as u8, 0x00, 0x00, 0x00,
+        ];
+
+        let table = Abbreviations::parse(&debug_abbrev, 1).unwrap();
+        let codes: Vec<u64> = table.iter().map(|abbreviation| abbreviation.code).collect();
+        assert_eq!(codes, [1, 3, 200]);
+
+        let unit = table.get(1).unwrap();
+        assert_eq!(unit.tag, DW_TAG_compile_unit);
+        assert!(unit.has_children);
+
+        let variable = table.get(3).unwrap();
+        assert!(!variable.has_children);
+        assert_eq!(
+            variable.attributes,
+            [
+                AttributeSpec {
+                    name: DW_AT_name,
+                    form: DW_FORM_strp,
+                    implicit_const: 0,
+                },
+                AttributeSpec {
+                    name: DW_AT_decl_line,
+                    form: DW_FORM_implicit_const,
+                    implicit_const: -1,
+                },
+            ]
+        );
+
+        assert_eq!(table.get(200).unwrap().tag, DW_TAG_subprogram);
+        assert_eq!(table.get(0), None);
+        assert_eq!(table.get(2), None);
+
+        // Tables truncated within an abbreviation are rejected.
+        assert!(Abbreviations::parse(&debug_abbrev[..6], 1).is_none());
+    }
+}
@@ -0,0 +1,374 @@
+//! Constants of the DWARF debugging format (versions 2 to 5), and of the
+//! `.eh_frame` call frame information based on it.
+//!
+//! Only the constants used by the debugger, and those commonly needed to
+//! inspect debugging information entries, are defined.
+
+/// Initial length marking a unit in the 64-bit DWARF format, whose length
+/// follows as a `u64`.
+pub const DW_LENGTH_64: u32 = 0xffff_ffff;
+/// Start of the initial length values reserved by DWARF.
+pub const DW_LENGTH_RESERVED: u32 = 0xffff_fff0;
+
+/// Address, of the size given by the unit header.
+pub const DW_FORM_addr: u64 = 0x01;
+/// Block of data, preceded by its length as a 2-byte integer.
+pub const DW_FORM_block2: u64 = 0x03;
+/// Block of data, preceded by its length as a 4-byte integer.
+pub const DW_FORM_block4: u64 = 0x04;
+/// 2-byte constant.
+pub const DW_FORM_data2: u64 = 0x05;
+/// 4-byte constant.
+pub const DW_FORM_data4: u64 = 0x06;
+/// 8-byte constant.
+pub const DW_FORM_data8: u64 = 0x07;
+/// Inline NUL-terminated string.
+pub const DW_FORM_string: u64 = 0x08;
+/// Block of data, preceded by its length as an unsigned LEB128 integer.
+pub const DW_FORM_block: u64 = 0x09;
+/// Block of data, preceded by its length as a 1-byte integer.
+pub const DW_FORM_block1: u64 = 0x0a;
+/// 1-byte constant.
+pub const DW_FORM_data1: u64 = 0x0b;
+/// 1-byte flag.
+pub const DW_FORM_flag: u64 = 0x0c;
+/// Signed LEB128 constant.
+pub const DW_FORM_sdata: u64 = 0x0d;
+/// Offset of a string in `.debug_str`.
+pub const DW_FORM_strp: u64 = 0x0e;
+/// Unsigned LEB128 constant.
+pub const DW_FORM_udata: u64 = 0x0f;
+/// Offset of an entry within `.debug_info`.
+pub const DW_FORM_ref_addr: u64 = 0x10;
+/// Offset of an entry within its unit, as a 1-byte integer.
+pub const DW_FORM_ref1: u64 = 0x11;
+/// Offset of an entry within its unit, as a 2-byte integer.
+pub const DW_FORM_ref2: u64 = 0x12;
+/// Offset of an entry within its unit, as a 4-byte integer.
+pub const DW_FORM_ref4: u64 = 0x13;
+/// Offset of an entry within its unit, as an 8-byte integer.
+pub const DW_FORM_ref8: u64 = 0x14;
+/// Offset of an entry within its unit, as an unsigned LEB128 integer.
+pub const DW_FORM_ref_udata: u64 = 0x15;
+/// Form given by an unsigned LEB128 integer preceding the value.
+pub const DW_FORM_indirect: u64 = 0x16;
+/// Offset within another section (e.g., `.debug_line`).
+pub const DW_FORM_sec_offset: u64 = 0x17;
+/// DWARF expression, preceded by its length as an unsigned LEB128 integer.
+pub const DW_FORM_exprloc: u64 = 0x18;
+/// Flag which is set by its presence, occupying no space.
+pub const DW_FORM_flag_present: u64 = 0x19;
+/// Index of a string in `.debug_str_offsets`.
+pub const DW_FORM_strx: u64 = 0x1a;
+/// Index of an address in `.debug_addr`.
+pub const DW_FORM_addrx: u64 = 0x1b;
+/// Offset of an entry within the `.debug_info` of a supplementary object
+/// file, as a 4-byte integer.
+pub const DW_FORM_ref_sup4: u64 = 0x1c;
+/// Offset of a string in the `.debug_str` of a supplementary object file.
+pub const DW_FORM_strp_sup: u64 = 0x1d;
+/// 16-byte constant.
+pub const DW_FORM_data16: u64 = 0x1e;
+/// Offset of a string in `.debug_line_str`.
+pub const DW_FORM_line_strp: u64 = 0x1f;
+/// 8-byte signature of a type unit.
+pub const DW_FORM_ref_sig8: u64 = 0x20;
+/// Constant stored in the abbreviation, occupying no space in the entry.
+pub const DW_FORM_implicit_const: u64 = 0x21;
+/// Index of a location list in `.debug_loclists`.
+pub const DW_FORM_loclistx: u64 = 0x22;
+/// Index of a range list in `.debug_rnglists`.
+pub const DW_FORM_rnglistx: u64 = 0x23;
+/// Offset of an entry within the `.debug_info` of a supplementary object
+/// file, as an 8-byte integer.
+pub const DW_FORM_ref_sup8: u64 = 0x24;
+/// Index of a string in `.debug_str_offsets`, as a 1-byte integer.
+pub const DW_FORM_strx1: u64 = 0x25;
+/// Index of a string in `.debug_str_offsets`, as a 2-byte integer.
+pub const DW_FORM_strx2: u64 = 0x26;
+/// Index of a string in `.debug_str_offsets`, as a 3-byte integer.
+pub const DW_FORM_strx3: u64 = 0x27;
+/// Index of a string in `.debug_str_offsets`, as a 4-byte integer.
+pub const DW_FORM_strx4: u64 = 0x28;
+/// Index of an address in `.debug_addr`, as a 1-byte integer.
+pub const DW_FORM_addrx1: u64 = 0x29;
+/// Index of an address in `.debug_addr`, as a 2-byte integer.
+pub const DW_FORM_addrx2: u64 = 0x2a;
+/// Index of an address in `.debug_addr`, as a 3-byte integer.
+pub const DW_FORM_addrx3: u64 = 0x2b;
+/// Index of an address in `.debug_addr`, as a 4-byte integer.
+pub const DW_FORM_addrx4: u64 = 0x2c;
+/// GNU extension equivalent to [`DW_FORM_addrx`], used by split DWARF 4.
+pub const DW_FORM_GNU_addr_index: u64 = 0x1f01;
+/// GNU extension equivalent to [`DW_FORM_strx`], used by split DWARF 4.
+pub const DW_FORM_GNU_str_index: u64 = 0x1f02;
+/// GNU extension equivalent to [`DW_FORM_ref_sup4`], of the offset size.
+pub const DW_FORM_GNU_ref_alt: u64 = 0x1f20;
+/// GNU extension equivalent to [`DW_FORM_strp_sup`].
+pub const DW_FORM_GNU_strp_alt: u64 = 0x1f21;
+
+/// Full compilation unit.
+pub const DW_UT_compile: u8 = 0x01;
+/// Type unit.
+pub const DW_UT_type: u8 = 0x02;
+/// Partial unit, imported by other units.
+pub const DW_UT_partial: u8 = 0x03;
+/// Skeleton of a compilation unit split into a separate object.
+pub const DW_UT_skeleton: u8 = 0x04;
+/// Compilation unit within a separate object.
+pub const DW_UT_split_compile: u8 = 0x05;
+/// Type unit within a separate object.
+pub const DW_UT_split_type: u8 = 0x06;
+
+/// Entries of the abbreviation have children.
+pub const DW_CHILDREN_yes: u8 = 0x01;
+
+/// Array type.
+pub const DW_TAG_array_type: u64 = 0x01;
+/// Class type.
+pub const DW_TAG_class_type: u64 = 0x02;
+/// Enumeration type.
+pub const DW_TAG_enumeration_type: u64 = 0x04;
+/// Parameter of a function.
+pub const DW_TAG_formal_parameter: u64 = 0x05;
+/// Lexical block (e.g., a Rust block declaring variables).
+pub const DW_TAG_lexical_block: u64 = 0x0b;
+/// Member of a structure, class, or union.
+pub const DW_TAG_member: u64 = 0x0d;
+/// Pointer type.
+pub const DW_TAG_pointer_type: u64 = 0x0f;
+/// Compilation unit.
+pub const DW_TAG_compile_unit: u64 = 0x11;
+/// Structure type.
+pub const DW_TAG_structure_type: u64 = 0x13;
+/// Type of a function.
+pub const DW_TAG_subroutine_type: u64 = 0x15;
+/// Type alias.
+pub const DW_TAG_typedef: u64 = 0x16;
+/// Union type.
+pub const DW_TAG_union_type: u64 = 0x17;
+/// Instance of a function inlined into another.
+pub const DW_TAG_inlined_subroutine: u64 = 0x1d;
+/// Base type (e.g., `u64`).
+pub const DW_TAG_base_type: u64 = 0x24;
+/// Function.
+pub const DW_TAG_subprogram: u64 = 0x2e;
+/// Type parameter of a generic function or type.
+pub const DW_TAG_template_type_parameter: u64 = 0x2f;
+/// Variable.
+pub const DW_TAG_variable: u64 = 0x34;
+/// Namespace (e.g., a Rust module).
+pub const DW_TAG_namespace: u64 = 0x39;
+/// Partial unit, imported by other units.
+pub const DW_TAG_partial_unit: u64 = 0x3c;
+/// Type unit.
+pub const DW_TAG_type_unit: u64 = 0x41;
+/// Skeleton of a compilation unit split into a separate object.
+pub const DW_TAG_skeleton_unit: u64 = 0x4a;
+
+/// Sibling of the entry.
+pub const DW_AT_sibling: u64 = 0x01;
+/// Location of a variable or parameter.
+pub const DW_AT_location: u64 = 0x02;
+/// Name of the entity, as written in the source.
+pub const DW_AT_name: u64 = 0x03;
+/// Size in bytes of a type.
+pub const DW_AT_byte_size: u64 = 0x0b;
+/// Offset of the line number program of a unit within `.debug_line`.
+pub const DW_AT_stmt_list: u64 = 0x10;
+/// Lowest address of the code of the entity.
+pub const DW_AT_low_pc: u64 = 0x11;
+/// Address past the code of the entity, or its size.
+pub const DW_AT_high_pc: u64 = 0x12;
+/// Source language of a unit.
+pub const DW_AT_language: u64 = 0x13;
+/// Compilation directory of a unit.
+pub const DW_AT_comp_dir: u64 = 0x1b;
+/// Whether, and how, a function was declared or inlined.
+pub const DW_AT_inline: u64 = 0x20;
+/// Producer of a unit (e.g., a compiler and its version).
+pub const DW_AT_producer: u64 = 0x25;
+/// Abstract instance of an inlined or out-of-line instance of a function.
+pub const DW_AT_abstract_origin: u64 = 0x31;
+/// File declaring the entity, as an index into the line number program.
+pub const DW_AT_decl_file: u64 = 0x3a;
+/// Line declaring the entity.
+pub const DW_AT_decl_line: u64 = 0x3b;
+/// Whether the entry is a declaration, defined elsewhere.
+pub const DW_AT_declaration: u64 = 0x3c;
+/// Whether the entity is visible outside of its unit.
+pub const DW_AT_external: u64 = 0x3f;
+/// Location of the frame base of a function.
+pub const DW_AT_frame_base: u64 = 0x40;
+/// Declaration completed by the entry.
+pub const DW_AT_specification: u64 = 0x47;
+/// Type of the entity.
+pub const DW_AT_type: u64 = 0x49;
+/// Entry point of the code of the entity.
+pub const DW_AT_entry_pc: u64 = 0x52;
+/// Offset of the address ranges of the code of the entity, within
+/// `.debug_ranges` or `.debug_rnglists`.
+pub const DW_AT_ranges: u64 = 0x55;
+/// Column of the call of an inlined function.
+pub const DW_AT_call_column: u64 = 0x57;
+/// File of the call of an inlined function, as an index into the line
+/// number program.
+pub const DW_AT_call_file: u64 = 0x58;
+/// Line of the call of an inlined function.
+pub const DW_AT_call_line: u64 = 0x59;
+/// Name of the entity as seen by the linker (i.e., its mangled name).
+pub const DW_AT_linkage_name: u64 = 0x6e;
+/// Offset of the contribution of a unit to `.debug_str_offsets`.
+pub const DW_AT_str_offsets_base: u64 = 0x72;
+/// Offset of the contribution of a unit to `.debug_addr`.
+pub const DW_AT_addr_base: u64 = 0x73;
+/// Offset of the contribution of a unit to `.debug_rnglists`.
+pub const DW_AT_rnglists_base: u64 = 0x74;
+/// Offset of the contribution of a unit to `.debug_loclists`.
+pub const DW_AT_loclists_base: u64 = 0x8c;
+/// Name of the entity as seen by the linker, emitted before DWARF 4.
+pub const DW_AT_MIPS_linkage_name: u64 = 0x2007;
+
+/// End of a range list.
+pub const DW_RLE_end_of_list: u8 = 0x00;
+/// Base address of the following entries, as an index into `.debug_addr`.
+pub const DW_RLE_base_addressx: u8 = 0x01;
+/// Range given by indices of its start and end into `.debug_addr`.
+pub const DW_RLE_startx_endx: u8 = 0x02;
+/// Range given by an index of its start into `.debug_addr`, and its length.
+pub const DW_RLE_startx_length: u8 = 0x03;
+/// Range given by offsets of its start and end from the base address.
+pub const DW_RLE_offset_pair: u8 = 0x04;
+/// Base address of the following entries.
+pub const DW_RLE_base_address: u8 = 0x05;
+/// Range given by the addresses of its start and end.
+pub const DW_RLE_start_end: u8 = 0x06;
+/// Range given by the address of its start, and its length.
+pub const DW_RLE_start_length: u8 = 0x07;
+
+/// Append a row to the line number matrix.
+pub const DW_LNS_copy: u8 = 0x01;
+/// Advance the address by an unsigned LEB128 number of instructions.
+pub const DW_LNS_advance_pc: u8 = 0x02;
+/// Advance the line by a signed LEB128 amount.
+pub const DW_LNS_advance_line: u8 = 0x03;
+/// Set the file register.
+pub const DW_LNS_set_file: u8 = 0x04;
+/// Set the column register.
+pub const DW_LNS_set_column: u8 = 0x05;
+/// Toggle the `is_stmt` register.
+pub const DW_LNS_negate_stmt: u8 = 0x06;
+/// Mark the start of a basic block.
+pub const DW_LNS_set_basic_block: u8 = 0x07;
+/// Advance the address by the amount of special opcode 255.
+pub const DW_LNS_const_add_pc: u8 = 0x08;
+/// Advance the address by an unscaled 2-byte amount.
+pub const DW_LNS_fixed_advance_pc: u8 = 0x09;
+/// Mark the end of a function prologue.
+pub const DW_LNS_set_prologue_end: u8 = 0x0a;
+/// Mark the start of a function epilogue.
+pub const DW_LNS_set_epilogue_begin: u8 = 0x0b;
+/// Set the instruction set architecture register.
+pub const DW_LNS_set_isa: u8 = 0x0c;
+
+/// Append a row ending the current sequence of addresses.
+pub const DW_LNE_end_sequence: u8 = 0x01;
+/// Set the address register to a relocatable address.
+pub const DW_LNE_set_address: u8 = 0x02;
+/// Define an additional source file (DWARF 4 and earlier).
+pub const DW_LNE_define_file: u8 = 0x03;
+/// Set the discriminator register.
+pub const DW_LNE_set_discriminator: u8 = 0x04;
+
+/// Path of a directory or file entry.
+pub const DW_LNCT_path: u64 = 0x1;
+/// Index of the directory of a file entry.
+pub const DW_LNCT_directory_index: u64 = 0x2;
+/// Modification time of a file entry.
+pub const DW_LNCT_timestamp: u64 = 0x3;
+/// Size in bytes of a file entry.
+pub const DW_LNCT_size: u64 = 0x4;
+/// MD5 digest of the contents of a file entry.
+pub const DW_LNCT_MD5: u64 = 0x5;
+
+/// Advance the location by the delta in the low 6 bits, scaled by the code
+/// alignment factor.
+pub const DW_CFA_advance_loc: u8 = 0x40;
+/// Save the register in the low 6 bits at an unsigned LEB128 offset from the
+/// CFA, scaled by the data alignment factor.
+pub const DW_CFA_offset: u8 = 0x80;
+/// Restore the register in the low 6 bits to its initial rule.
+pub const DW_CFA_restore: u8 = 0xc0;
+/// Padding.
+pub const DW_CFA_nop: u8 = 0x00;
+/// Set the location to an address, encoded as the addresses of the FDE.
+pub const DW_CFA_set_loc: u8 = 0x01;
+/// Advance the location by a 1-byte delta.
+pub const DW_CFA_advance_loc1: u8 = 0x02;
+/// Advance the location by a 2-byte delta.
+pub const DW_CFA_advance_loc2: u8 = 0x03;
+/// Advance the location by a 4-byte delta.
+pub const DW_CFA_advance_loc4: u8 = 0x04;
+/// Save a register at an unsigned offset from the CFA.
+pub const DW_CFA_offset_extended: u8 = 0x05;
+/// Restore a register to its initial rule.
+pub const DW_CFA_restore_extended: u8 = 0x06;
+/// Mark a register as unrecoverable.
+pub const DW_CFA_undefined: u8 = 0x07;
+/// Mark a register as unchanged from the caller.
+pub const DW_CFA_same_value: u8 = 0x08;
+/// Save a register in another register.
+pub const DW_CFA_register: u8 = 0x09;
+/// Push the current rules onto a stack.
+pub const DW_CFA_remember_state: u8 = 0x0a;
+/// Pop the rules pushed by the last `DW_CFA_remember_state`.
+pub const DW_CFA_restore_state: u8 = 0x0b;
+/// Define the CFA as a register plus an unsigned offset.
+pub const DW_CFA_def_cfa: u8 = 0x0c;
+/// Change the register of the CFA rule.
+pub const DW_CFA_def_cfa_register: u8 = 0x0d;
+/// Change the unsigned offset of the CFA rule.
+pub const DW_CFA_def_cfa_offset: u8 = 0x0e;
+/// Define the CFA by a DWARF expression.
+pub const DW_CFA_def_cfa_expression: u8 = 0x0f;
+/// Save a register at the address given by a DWARF expression.
+pub const DW_CFA_expression: u8 = 0x10;
+/// Save a register at a signed, scaled offset from the CFA.
+pub const DW_CFA_offset_extended_sf: u8 = 0x11;
+/// Define the CFA as a register plus a signed, scaled offset.
+pub const DW_CFA_def_cfa_sf: u8 = 0x12;
+/// Change the offset of the CFA rule to a signed, scaled offset.
+pub const DW_CFA_def_cfa_offset_sf: u8 = 0x13;
+/// Set a register to the CFA plus an unsigned, scaled offset.
+pub const DW_CFA_val_offset: u8 = 0x14;
+/// Set a register to the CFA plus a signed, scaled offset.
+pub const DW_CFA_val_offset_sf: u8 = 0x15;
+/// Set a register to the value of a DWARF expression.
+pub const DW_CFA_val_expression: u8 = 0x16;
+/// Record the size of the arguments pushed onto the stack (GNU extension).
+pub const DW_CFA_GNU_args_size: u8 = 0x2e;
+/// Save a register at a negated offset from the CFA (GNU extension).
+pub const DW_CFA_GNU_negative_offset_extended: u8 = 0x2f;
+
+/// Pointer of the size of an address, or an absolute pointer.
+pub const DW_EH_PE_absptr: u8 = 0x00;
+/// Unsigned LEB128 pointer.
+pub const DW_EH_PE_uleb128: u8 = 0x01;
+/// 2-byte unsigned pointer.
+pub const DW_EH_PE_udata2: u8 = 0x02;
+/// 4-byte unsigned pointer.
+pub const DW_EH_PE_udata4: u8 = 0x03;
+/// 8-byte unsigned pointer.
+pub const DW_EH_PE_udata8: u8 = 0x04;
+/// Signed LEB128 pointer.
+pub const DW_EH_PE_sleb128: u8 = 0x09;
+/// 2-byte signed pointer.
+pub const DW_EH_PE_sdata2: u8 = 0x0a;
+/// 4-byte signed pointer.
+pub const DW_EH_PE_sdata4: u8 = 0x0b;
+/// 8-byte signed pointer.
+pub const DW_EH_PE_sdata8: u8 = 0x0c;
+/// Pointer relative to its own address.
+pub const DW_EH_PE_pcrel: u8 = 0x10;
+/// Absent pointer.
+pub const DW_EH_PE_omit: u8 = 0xff;
@@ -0,0 +1,553 @@
+//! Debugging information entries (DIEs) of the `.debug_info` section, and the
+//! values of their attributes.
+//!
+//! Values are decoded according to their form. Those given as indices into
+//! the tables of other sections (e.g., [`DW_FORM_strx`] or [`DW_FORM_addrx`])
+//! are resolved using the base offsets of their unit, which are attributes of
+//! its root entry.
+
+use std::ops::Range;
+
+use super::abbrev::{Abbreviation, Abbreviations};
+use super::constants::*;
+use super::info::DwarfSections;
+use crate::elf::FileAddr;
+use crate::elf::reader::{Reader, c_str};
+
+/// Value of an attribute of a [`Die`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AttributeValue {
+    /// Address within the object ([`DW_FORM_addr`] and [`DW_FORM_addrx`]).
+    Address(FileAddr),
+    /// Block of uninterpreted bytes (e.g., [`DW_FORM_block`] and
+    /// [`DW_FORM_data16`]).
+    Block(Vec<u8>),
+    /// DWARF expression ([`DW_FORM_exprloc`]).
+    Expression(Vec<u8>),
+    /// Unsigned constant (e.g., [`DW_FORM_data4`] and [`DW_FORM_udata`]),
+    /// whose meaning depends on the attribute.
+    Unsigned(u64),
+    /// Signed constant ([`DW_FORM_sdata`] and [`DW_FORM_implicit_const`]).
+    Signed(i64),
+    /// Flag ([`DW_FORM_flag`] and [`DW_FORM_flag_present`]).
+    Flag(bool),
+    /// String, either inline or resolved from a string section.
+    String(String),
+    /// Offset of another entry within `.debug_info`.
+    Reference(u64),
+    /// Signature of a type unit ([`DW_FORM_ref_sig8`]).
+    TypeSignature(u64),
+    /// Offset within another section (e.g., within `.debug_line` for
+    /// [`DW_AT_stmt_list`]). Indices of range and location lists are resolved
+    /// to offsets within `.debug_rnglists` and `.debug_loclists`.
+    SectionOffset(u64),
+    /// Value which cannot be resolved from the object alone (e.g., a string
+    /// of a supplementary object file), or whose index is out of bounds.
+    Unresolved,
+}
+
+impl AttributeValue {
+    /// Return the address held by the value.
+    pub fn address(&self) -> Option<FileAddr> {
+        match *self {
+            Self::Address(address) => Some(address),
+            _ => None,
+        }
+    }
+
+    /// Return the non-negative constant held by the value.
+    pub fn unsigned(&self) -> Option<u64> {
+        match *self {
+            Self::Unsigned(value) => Some(value),
+            Self::Signed(value) => u64::try_from(value).ok(),
+            _ => None,
+        }
+    }
+
+    /// Return the string held by the value.
+    pub fn string(&self) -> Option<&str> {
+        match self {
+            Self::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Return the offset of the entry referenced by the value.
+    pub fn reference(&self) -> Option<u64> {
+        match *self {
+            Self::Reference(offset) => Some(offset),
+            _ => None,
+        }
+    }
+
+    /// Return the section offset held by the value. DWARF 3 and earlier
+    /// encode section offsets as 4- or 8-byte constants.
+    pub fn section_offset(&self) -> Option<u64> {
+        match *self {
+            Self::SectionOffset(offset) | Self::Unsigned(offset) => Some(offset),
+            _ => None,
+        }
+    }
+
+    /// Return the flag held by the value.
+    pub fn flag(&self) -> Option<bool> {
+        match *self {
+            Self::Flag(flag) => Some(flag),
+            _ => None,
+        }
+    }
+}
+
+/// An attribute of a [`Die`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Attribute {
+    /// Name of the attribute (e.g., [`DW_AT_name`]).
+    pub name: u64,
+    /// Form the value was encoded in (e.g., [`DW_FORM_strp`]).
+    pub form: u64,
+    /// Decoded value of the attribute.
+    pub value: AttributeValue,
+}
+
+/// A debugging information entry, describing an entity of the program (e.g.,
+/// a function, variable, or type).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Die {
+    /// Offset of the entry within `.debug_info`.
+    pub offset: u64,
+    /// Tag of the entry (e.g., [`DW_TAG_subprogram`]).
+    pub tag: u64,
+    /// Attributes of the entry, in the order they were encoded.
+    pub attributes: Vec<Attribute>,
+    /// Index of the parent of the entry within its [`DieTree`], or `None` for
+    /// the root entry of the unit.
+    pub parent: Option<usize>,
+    /// Index following the last descendant of the entry within its tree.
+    end: usize,
+}
+
+impl Die {
+    /// Return the value of the attribute of the entry with the given `name`.
+    pub fn attribute(&self, name: u64) -> Option<&AttributeValue> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| &attribute.value)
+    }
+
+    /// Return the name of the entry (i.e., its [`DW_AT_name`]).
+    pub fn name(&self) -> Option<&str> {
+        self.attribute(DW_AT_name)?.string()
+    }
+}
+
+/// The entries of a unit, in the order they are encoded, which is a
+/// depth-first traversal of their tree.
+#[derive(Debug, Default)]
+pub struct DieTree {
+    /// Entries of the unit, sorted by offset.
+    entries: Vec<Die>,
+}
+
+impl DieTree {
+    /// Return `true` if the tree has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return the entries of the tree, in depth-first order.
+    pub fn entries(&self) -> &[Die] {
+        &self.entries
+    }
+
+    /// Return the root entry of the unit (e.g., a [`DW_TAG_compile_unit`]).
+    pub fn root(&self) -> Option<&Die> {
+        self.entries.first()
+    }
+
+    /// Return the entry at `index`.
+    pub fn get(&self, index: usize) -> Option<&Die> {
+        self.entries.get(index)
+    }
+
+    /// Return the indices of the children of the entry at `index`.
+    pub fn children(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let end = self.entries.get(index).map_or(0, |die| die.end);
+        let mut child = index + 1;
+
+        std::iter::from_fn(move || {
+            if child >= end {
+                return None;
+            }
+
+            let current = child;
+            child = self.entries.get(current)?.end;
+
+            Some(current)
+        })
+    }
+
+    /// Return the indices of the descendants of the entry at `index`.
+    pub fn descendants(&self, index: usize) -> Range<usize> {
+        match self.entries.get(index) {
+            Some(die) => index + 1..die.end,
+            None => 0..0,
+        }
+    }
+
+    /// Look up the index of the entry at `offset` within `.debug_info`.
+    pub fn find_by_offset(&self, offset: u64) -> Option<usize> {
+        self.entries
+            .binary_search_by_key(&offset, |die| die.offset)
+            .ok()
+    }
+}
+
+/// Encoding of the entries of a unit, given by its header.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Encoding {
+    /// Version of DWARF (2 to 5).
+    pub(super) version: u16,
+    /// Size in bytes of addresses.
+    pub(super) address_size: u8,
+    /// Size in bytes of section offsets: 4 or 8.
+    pub(super) offset_size: usize,
+    /// Offset of the unit within `.debug_info`, to which most references are
+    /// relative.
+    pub(super) unit_offset: u64,
+}
+
+/// Offsets of the contributions of a unit to the sections whose tables are
+/// indexed by attribute values, given by attributes of its root entry.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub(super) struct Bases {
+    /// [`DW_AT_str_offsets_base`], within `.debug_str_offsets`.
+    pub(super) str_offsets: Option<u64>,
+    /// [`DW_AT_addr_base`], within `.debug_addr`.
+    pub(super) addr: Option<u64>,
+    /// [`DW_AT_rnglists_base`], within `.debug_rnglists`.
+    pub(super) rnglists: Option<u64>,
+    /// [`DW_AT_loclists_base`], within `.debug_loclists`.
+    pub(super) loclists: Option<u64>,
+}
+
+impl Bases {
+    /// Collect the base offsets given by the `attributes` of a root entry.
+    fn from_attributes(attributes: &[(u64, u64, RawValue)]) -> Self {
+        let mut bases = Self::default();
+
+        for (name, _, value) in attributes {
+            let RawValue::Resolved(value) = value else {
+                continue;
+            };
+
+            let base = match *name {
+                DW_AT_str_offsets_base => &mut bases.str_offsets,
+                DW_AT_addr_base => &mut bases.addr,
+                DW_AT_rnglists_base => &mut bases.rnglists,
+                DW_AT_loclists_base => &mut bases.loclists,
+                _ => continue,
+            };
+
+            *base = value.section_offset();
+        }
+
+        bases
+    }
+}
+
+/// Value of an attribute as encoded, before any index into the table of
+/// another section is resolved.
+#[derive(Debug)]
+enum RawValue {
+    Resolved(AttributeValue),
+    StringIndex(u64),
+    AddressIndex(u64),
+    RangeListIndex(u64),
+    LocationListIndex(u64),
+}
+
+/// Parse the entries of a unit, starting at `offset` of `debug_info` and
+/// ending at `end`, or only its root entry if `root_only` is set. The base
+/// offsets of the unit, given by its root entry, are returned along with the
+/// entries.
+///
+/// Parsing stops at the first malformed entry (e.g., with an unknown
+/// abbreviation code or form), keeping the entries parsed so far.
+pub(super) fn parse_entries(
+    sections: &DwarfSections<'_>,
+    encoding: Encoding,
+    abbreviations: &Abbreviations,
+    offset: usize,
+    end: usize,
+    root_only: bool,
+) -> (DieTree, Bases) {
+    let mut entries: Vec<Die> = Vec::new();
+    let mut bases = Bases::default();
+
+    let Some(data) = sections.debug_info.get(..end) else {
+        return (DieTree { entries }, bases);
+    };
+
+    let mut reader = Reader::new(data, offset);
+    // Indices of the entries whose children are being parsed.
+    let mut parents: Vec<usize> = Vec::new();
+
+    while !reader.is_empty() {
+        let offset = reader.offset() as u64;
+
+        let Some(code) = reader.uleb128() else {
+            break;
+        };
+
+        // A null entry ends a list of siblings.
+        if code == 0 {
+            if let Some(parent) = parents.pop() {
+                entries[parent].end = entries.len();
+            }
+            continue;
+        }
+
+        let Some(abbreviation) = abbreviations.get(code) else {
+            break;
+        };
+
+        let Some(raw) = read_attributes(&mut reader, abbreviation, encoding, sections) else {
+            break;
+        };
+
+        if entries.is_empty() {
+            bases = Bases::from_attributes(&raw);
+        }
+
+        let attributes = raw
+            .into_iter()
+            .map(|(name, form, value)| Attribute {
+                name,
+                form,
+                value: resolve(value, encoding, bases, sections),
+            })
+            .collect();
+
+        let index = entries.len();
+
+        entries.push(Die {
+            offset,
+            tag: abbreviation.tag,
+            attributes,
+            parent: parents.last().copied(),
+            end: index + 1,
+        });
+
+        if root_only {
+            break;
+        }
+
+        if abbreviation.has_children {
+            parents.push(index);
+        }
+    }
+
+    // Entries whose list of children was not terminated extend to the end.
+    for parent in parents {
+        entries[parent].end = entries.len();
+    }
+
+    (DieTree { entries }, bases)
+}
+
+/// Read the values of the attributes of an entry of the given `abbreviation`.
+fn read_attributes(
+    reader: &mut Reader<'_>,
+    abbreviation: &Abbreviation,
+    encoding: Encoding,
+    sections: &DwarfSections<'_>,
+) -> Option<Vec<(u64, u64, RawValue)>> {
+    abbreviation
+        .attributes
+        .iter()
+        .map(|spec| {
+            let value = read_value(reader, spec.form, spec.implicit_const, encoding, sections)?;
+            Some((spec.name, spec.form, value))
+        })
+        .collect()
+}
+
+/// Read an attribute value of the given `form`, or return `None` if the form
+/// is unknown (so the size of the value, and the rest of the unit, cannot be
+/// determined) or the value is truncated.
+fn read_value(
+    reader: &mut Reader<'_>,
+    form: u64,
+    implicit_const: i64,
+    encoding: Encoding,
+    sections: &DwarfSections<'_>,
+) -> Option<RawValue> {
+    let offset_size = encoding.offset_size;
+
+    let string = |offset: u64, section: &[u8]| match c_str(section, offset) {
+        Some(string) => AttributeValue::String(String::from_utf8_lossy(string).into_owned()),
+        None => AttributeValue::Unresolved,
+    };
+
+    let value = match form {
+        DW_FORM_addr => {
+            AttributeValue::Address(FileAddr(reader.uint(encoding.address_size.into())?))
+        }
+        DW_FORM_addrx | DW_FORM_GNU_addr_index => {
+            return Some(RawValue::AddressIndex(reader.uleb128()?));
+        }
+        DW_FORM_addrx1 | DW_FORM_addrx2 | DW_FORM_addrx3 | DW_FORM_addrx4 => {
+            let size = usize::try_from(form - DW_FORM_addrx1 + 1).ok()?;
+            return Some(RawValue::AddressIndex(reader.uint(size)?));
+        }
+        DW_FORM_block | DW_FORM_block1 | DW_FORM_block2 | DW_FORM_block4 => {
+            let len = match form {
+                DW_FORM_block1 => reader.u8()?.into(),
+                DW_FORM_block2 => reader.u16()?.into(),
+                DW_FORM_block4 => reader.u32()?.into(),
+                _ => reader.uleb128()?,
+            };
+
+            AttributeValue::Block(reader.bytes(usize::try_from(len).ok()?)?.to_vec())
+        }
+        DW_FORM_data16 => AttributeValue::Block(reader.bytes(16)?.to_vec()),
+        DW_FORM_exprloc => {
+            let len = usize::try_from(reader.uleb128()?).ok()?;
+            AttributeValue::Expression(reader.bytes(len)?.to_vec())
+        }
+        DW_FORM_data1 => AttributeValue::Unsigned(reader.u8()?.into()),
+        DW_FORM_data2 => AttributeValue::Unsigned(reader.u16()?.into()),
+        DW_FORM_data4 => AttributeValue::Unsigned(reader.u32()?.into()),
+        DW_FORM_data8 => AttributeValue::Unsigned(reader.u64()?),
+        DW_FORM_udata => AttributeValue::Unsigned(reader.uleb128()?),
+        DW_FORM_sdata => AttributeValue::Signed(reader.sleb128()?),
+        DW_FORM_implicit_const => AttributeValue::Signed(implicit_const),
+        DW_FORM_flag => AttributeValue::Flag(reader.u8()? != 0),
+        DW_FORM_flag_present => AttributeValue::Flag(true),
+        DW_FORM_string => {
+            AttributeValue::String(String::from_utf8_lossy(reader.c_str()?).into_owned())
+        }
+        DW_FORM_strp => string(reader.uint(offset_size)?, sections.debug_str),
+        DW_FORM_line_strp => string(reader.uint(offset_size)?, sections.debug_line_str),
+        DW_FORM_strx | DW_FORM_GNU_str_index => {
+            return Some(RawValue::StringIndex(reader.uleb128()?));
+        }
+        DW_FORM_strx1 | DW_FORM_strx2 | DW_FORM_strx3 | DW_FORM_strx4 => {
+            let size = usize::try_from(form - DW_FORM_strx1 + 1).ok()?;
+            return Some(RawValue::StringIndex(reader.uint(size)?));
+        }
+        DW_FORM_ref1 | DW_FORM_ref2 | DW_FORM_ref4 | DW_FORM_ref8 | DW_FORM_ref_udata => {
+            let offset = match form {
+                DW_FORM_ref1 => reader.u8()?.into(),
+                DW_FORM_ref2 => reader.u16()?.into(),
+                DW_FORM_ref4 => reader.u32()?.into(),
+                DW_FORM_ref8 => reader.u64()?,
+                _ => reader.uleb128()?,
+            };
+
+            AttributeValue::Reference(encoding.unit_offset.wrapping_add(offset))
+        }
+        DW_FORM_ref_addr => {
+            // DWARF 2 encodes these references with the size of an address.
+            let size = match encoding.version {
+                2 => encoding.address_size.into(),
+                _ => offset_size,
+            };
+
+            AttributeValue::Reference(reader.uint(size)?)
+        }
+        DW_FORM_ref_sig8 => AttributeValue::TypeSignature(reader.u64()?),
+        DW_FORM_sec_offset => AttributeValue::SectionOffset(reader.uint(offset_size)?),
+        DW_FORM_rnglistx => return Some(RawValue::RangeListIndex(reader.uleb128()?)),
+        DW_FORM_loclistx => return Some(RawValue::LocationListIndex(reader.uleb128()?)),
+        // Supplementary object files are not loaded.
+        DW_FORM_ref_sup4 => {
+            reader.u32()?;
+            AttributeValue::Unresolved
+        }
+        DW_FORM_ref_sup8 => {
+            reader.u64()?;
+            AttributeValue::Unresolved
+        }
+        DW_FORM_strp_sup | DW_FORM_GNU_strp_alt | DW_FORM_GNU_ref_alt => {
+            reader.uint(offset_size)?;
+            AttributeValue::Unresolved
+        }
+        DW_FORM_indirect => {
+            let form = reader.uleb128()?;
+
+            // An indirect form cannot refer to a constant in the abbreviation.
+            if form == DW_FORM_implicit_const {
+                return None;
+            }
+
+            return read_value(reader, form, 0, encoding, sections);
+        }
+        _ => return None,
+    };
+
+    Some(RawValue::Resolved(value))
+}
+
+/// Resolve any index held by the `value` of an attribute into the tables of
+/// other sections, using the base offsets of its unit.
+fn resolve(
+    value: RawValue,
+    encoding: Encoding,
+    bases: Bases,
+    sections: &DwarfSections<'_>,
+) -> AttributeValue {
+    let offset_size = encoding.offset_size;
+
+    let resolved = match value {
+        RawValue::Resolved(value) => return value,
+        RawValue::StringIndex(index) => {
+            let offset = read_indexed(
+                sections.debug_str_offsets,
+                bases.str_offsets,
+                index,
+                offset_size,
+            );
+            offset
+                .and_then(|offset| c_str(sections.debug_str, offset))
+                .map(|string| AttributeValue::String(String::from_utf8_lossy(string).into_owned()))
+        }
+        RawValue::AddressIndex(index) => {
+            read_address(sections, encoding, bases, index).map(AttributeValue::Address)
+        }
+        RawValue::RangeListIndex(index) => {
+            // Offsets of the list table are relative to its base.
+            read_indexed(sections.debug_rnglists, bases.rnglists, index, offset_size)
+                .zip(bases.rnglists)
+                .map(|(offset, base)| AttributeValue::SectionOffset(base.wrapping_add(offset)))
+        }
+        RawValue::LocationListIndex(index) => {
+            read_indexed(sections.debug_loclists, bases.loclists, index, offset_size)
+                .zip(bases.loclists)
+                .map(|(offset, base)| AttributeValue::SectionOffset(base.wrapping_add(offset)))
+        }
+    };
+
+    resolved.unwrap_or(AttributeValue::Unresolved)
+}
+
+/// Read the address at `index` of the contribution of a unit to `.debug_addr`.
+pub(super) fn read_address(
+    sections: &DwarfSections<'_>,
+    encoding: Encoding,
+    bases: Bases,
+    index: u64,
+) -> Option<FileAddr> {
+    let size = encoding.address_size.into();
+    read_indexed(sections.debug_addr, bases.addr, index, size).map(FileAddr)
+}
+
+/// Read entry `index` of a table of `size`-byte integers at offset `base` of
+/// `section`.
+fn read_indexed(section: &[u8], base: Option<u64>, index: u64, size: usize) -> Option<u64> {
+    let offset = index.checked_mul(size as u64)?.checked_add(base?)?;
+    let mut reader = Reader::new(section, usize::try_from(offset).ok()?);
+
+    reader.uint(size)
+}
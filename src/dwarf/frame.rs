@@ -0,0 +1,466 @@
+//! Call frame information of the `.eh_frame` section, describing how to find
+//! the frame of the caller from any instruction of a function.
+//!
+//! The section holds Common Information Entries (CIEs), each shared by the
+//! Frame Description Entries (FDEs) which cover a range of addresses (e.g., a
+//! single function). The instructions of both are run to build the rules in
+//! effect at an address, of which only the rule giving the canonical frame
+//! address (CFA) is kept: the value of the stack pointer in the caller just
+//! before its `call`.
+
+use super::{Unit, constants::*, unit_at};
+use crate::elf::FileAddr;
+use crate::elf::reader::Reader;
+
+/// Rule giving the canonical frame address (CFA) as the value of a register
+/// plus an offset.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct CfaRule {
+    /// DWARF number of the register (e.g., 7 for `rsp`).
+    pub register: u64,
+    /// Offset added to the value of the register.
+    pub offset: i64,
+}
+
+/// Reader of the call frame information of an `.eh_frame` section.
+#[derive(Debug, Copy, Clone)]
+pub struct CallFrameInfo<'a> {
+    /// Contents of the section.
+    data: &'a [u8],
+    /// Address of the section, to which pc-relative pointers are relative.
+    address: u64,
+}
+
+/// A Common Information Entry, holding what the FDEs referencing it share.
+#[derive(Debug)]
+struct Cie<'a> {
+    /// Factor by which location advances are scaled.
+    code_alignment: u64,
+    /// Factor by which signed offsets are scaled.
+    data_alignment: i64,
+    /// Encoding of the addresses of the FDEs (a `DW_EH_PE_*` value).
+    pointer_encoding: u8,
+    /// Indicates whether FDEs have augmentation data, preceded by its length.
+    has_augmentation_data: bool,
+    /// Instructions giving the initial rules of every FDE.
+    instructions: &'a [u8],
+}
+
+/// A Frame Description Entry, covering the addresses `start..end`.
+#[derive(Debug)]
+struct Fde<'a> {
+    /// The CIE referenced by the FDE.
+    cie: Cie<'a>,
+    /// First address covered by the FDE.
+    start: u64,
+    /// Address just past the last one covered by the FDE.
+    end: u64,
+    /// Instructions giving the rules from `start` onwards.
+    instructions: &'a [u8],
+}
+
+/// Rules in effect at a location, as built by running instructions.
+#[derive(Debug)]
+struct Rules {
+    /// Address the rules apply from.
+    location: u64,
+    /// Rule giving the CFA, or `None` if it is given by a DWARF expression.
+    cfa: Option<CfaRule>,
+    /// Rules saved by `DW_CFA_remember_state`.
+    saved: Vec<Option<CfaRule>>,
+}
+
+impl<'a> CallFrameInfo<'a> {
+    /// Creates a new [`CallFrameInfo`] reader of the `.eh_frame` section
+    /// `data`, located at `address`.
+    pub fn new(data: &'a [u8], address: u64) -> Self {
+        Self { data, address }
+    }
+
+    /// Return the rule giving the CFA at `address`, or `None` if no FDE covers
+    /// it, or the CFA is given by a DWARF expression (e.g., within the PLT).
+    pub fn cfa_rule(&self, address: FileAddr) -> Option<CfaRule> {
+        let mut offset = 0;
+
+        while offset < self.data.len() {
+            let entry = unit_at(self.data, offset)?;
+
+            // A zero length terminates the section.
+            if entry.data.is_empty() {
+                return None;
+            }
+
+            offset = entry.end;
+
+            let Some(fde) = self.parse_fde(&entry) else {
+                continue;
+            };
+
+            if (fde.start..fde.end).contains(&address.0) {
+                return run(&fde, address.0);
+            }
+        }
+
+        None
+    }
+
+    /// Parse `entry` as an FDE, or return `None` if it is a CIE or malformed.
+    fn parse_fde(&self, entry: &Unit<'a>) -> Option<Fde<'a>> {
+        let start = entry.end - entry.data.len();
+        let data = self.data.get(..entry.end)?;
+        let mut reader = Reader::new(data, start);
+
+        // The CIE pointer is relative to its own offset, and is zero in CIEs.
+        let cie = match reader.uint(entry.offset_size)? {
+            0 => return None,
+            pointer => self.parse_cie(start.checked_sub(usize::try_from(pointer).ok()?)?)?,
+        };
+
+        let start = self.read_pointer(&mut reader, cie.pointer_encoding)?;
+        let len = read_value(&mut reader, cie.pointer_encoding)?;
+
+        if cie.has_augmentation_data {
+            let len = reader.uleb128()?;
+            reader.bytes(usize::try_from(len).ok()?)?;
+        }
+
+        Some(Fde {
+            cie,
+            start,
+            end: start.wrapping_add(len),
+            instructions: data.get(reader.offset()..)?,
+        })
+    }
+
+    /// Parse the CIE at `offset` of the section.
+    fn parse_cie(&self, offset: usize) -> Option<Cie<'a>> {
+        let entry = unit_at(self.data, offset)?;
+        let mut reader = Reader::new(self.data.get(..entry.end)?, entry.end - entry.data.len());
+
+        if reader.uint(entry.offset_size)? != 0 {
+            return None;
+        }
+
+        let version = reader.u8()?;
+        let augmentation = reader.c_str()?;
+
+        // Old producers record the address of exception handling data.
+        if augmentation.starts_with(b"eh") {
+            reader.u64()?;
+        }
+
+        let code_alignment = reader.uleb128()?;
+        let data_alignment = reader.sleb128()?;
+
+        // The return address register is a single byte in version 1.
+        if version == 1 {
+            reader.u8()?;
+        } else {
+            reader.uleb128()?;
+        }
+
+        let mut pointer_encoding = DW_EH_PE_absptr;
+        let has_augmentation_data = augmentation.first() == Some(&b'z');
+
+        if has_augmentation_data {
+            let len = reader.uleb128()?;
+            let mut data = Reader::new(reader.bytes(usize::try_from(len).ok()?)?, 0);
+
+            // Only the encoding of the addresses of the FDEs is needed, but the
+            // data preceding it must be skipped.
+            for &letter in &augmentation[1..] {
+                match letter {
+                    b'R' => pointer_encoding = data.u8()?,
+                    b'L' => {
+                        data.u8()?;
+                    }
+                    b'P' => match data.u8()? {
+                        DW_EH_PE_omit => {}
+                        encoding => {
+                            read_value(&mut data, encoding)?;
+                        }
+                    },
+                    b'S' | b'B' => {}
+                    _ => break,
+                }
+            }
+        }
+
+        Some(Cie {
+            code_alignment,
+            data_alignment,
+            pointer_encoding,
+            has_augmentation_data,
+            instructions: self.data.get(reader.offset()..entry.end)?,
+        })
+    }
+
+    /// Read an address encoded as given by `encoding`, applying it relative to
+    /// the address of the pointer itself if pc-relative. Other relative
+    /// encodings are not supported.
+    fn read_pointer(&self, reader: &mut Reader<'_>, encoding: u8) -> Option<u64> {
+        let address = self.address.wrapping_add(reader.offset() as u64);
+        let value = read_value(reader, encoding)?;
+
+        match encoding & 0x70 {
+            DW_EH_PE_absptr => Some(value),
+            DW_EH_PE_pcrel => Some(value.wrapping_add(address)),
+            _ => None,
+        }
+    }
+}
+
+/// Read a value in the format given by the low 4 bits of `encoding`, without
+/// applying it to any base address.
+fn read_value(reader: &mut Reader<'_>, encoding: u8) -> Option<u64> {
+    let value = match encoding & 0x0f {
+        DW_EH_PE_absptr | DW_EH_PE_udata8 | DW_EH_PE_sdata8 => reader.u64()?,
+        DW_EH_PE_uleb128 => reader.uleb128()?,
+        DW_EH_PE_udata2 => reader.u16()?.into(),
+        DW_EH_PE_udata4 => reader.u32()?.into(),
+        DW_EH_PE_sleb128 => reader.sleb128()? as u64,
+        DW_EH_PE_sdata2 => reader.u16()? as i16 as u64,
+        DW_EH_PE_sdata4 => reader.u32()? as i32 as u64,
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/// Run the instructions of the CIE of `fde`, then its own, returning the rule
+/// giving the CFA at `address`.
+fn run(fde: &Fde<'_>, address: u64) -> Option<CfaRule> {
+    let mut rules = Rules {
+        location: fde.start,
+        cfa: None,
+        saved: Vec::new(),
+    };
+
+    rules.run(&fde.cie, fde.cie.instructions, address)?;
+    rules.run(&fde.cie, fde.instructions, address)?;
+
+    rules.cfa
+}
+
+impl Rules {
+    /// Run `instructions` until the location advances past `address`.
+    fn run(&mut self, cie: &Cie<'_>, instructions: &[u8], address: u64) -> Option<()> {
+        let mut reader = Reader::new(instructions, 0);
+
+        while !reader.is_empty() {
+            let opcode = reader.u8()?;
+
+            let advance = match (opcode & 0xc0, opcode & 0x3f) {
+                (DW_CFA_advance_loc, delta) => Some(u64::from(delta)),
+                (DW_CFA_offset, _) => {
+                    reader.uleb128()?;
+                    None
+                }
+                (DW_CFA_restore, _) => None,
+                _ => self.run_extended(cie, opcode, &mut reader)?,
+            };
+
+            if let Some(delta) = advance {
+                let location = self
+                    .location
+                    .wrapping_add(delta.wrapping_mul(cie.code_alignment));
+
+                if location > address {
+                    break;
+                }
+
+                self.location = location;
+            }
+        }
+
+        Some(())
+    }
+
+    /// Run an instruction whose `opcode` is not in its high 2 bits, returning
+    /// the delta by which it advances the location, if any.
+    fn run_extended(
+        &mut self,
+        cie: &Cie<'_>,
+        opcode: u8,
+        reader: &mut Reader<'_>,
+    ) -> Option<Option<u64>> {
+        let data_offset = |offset: i64| offset.wrapping_mul(cie.data_alignment);
+
+        match opcode {
+            DW_CFA_nop => {}
+            DW_CFA_advance_loc1 => return Some(Some(reader.u8()?.into())),
+            DW_CFA_advance_loc2 => return Some(Some(reader.u16()?.into())),
+            DW_CFA_advance_loc4 => return Some(Some(reader.u32()?.into())),
+            // The new location is absolute, so it is given as a delta from
+            // the current location.
+            DW_CFA_set_loc => {
+                let location = read_value(reader, cie.pointer_encoding)?;
+                return Some(Some(location.wrapping_sub(self.location)));
+            }
+            DW_CFA_offset_extended
+            | DW_CFA_register
+            | DW_CFA_val_offset
+            | DW_CFA_GNU_negative_offset_extended => {
+                reader.uleb128()?;
+                reader.uleb128()?;
+            }
+            DW_CFA_offset_extended_sf | DW_CFA_val_offset_sf => {
+                reader.uleb128()?;
+                reader.sleb128()?;
+            }
+            DW_CFA_restore_extended
+            | DW_CFA_undefined
+            | DW_CFA_same_value
+            | DW_CFA_GNU_args_size => {
+                reader.uleb128()?;
+            }
+            DW_CFA_remember_state => self.saved.push(self.cfa),
+            DW_CFA_restore_state => self.cfa = self.saved.pop()?,
+            DW_CFA_def_cfa => {
+                let register = reader.uleb128()?;
+                let offset = reader.uleb128()? as i64;
+                self.cfa = Some(CfaRule { register, offset });
+            }
+            DW_CFA_def_cfa_sf => {
+                let register = reader.uleb128()?;
+                let offset = data_offset(reader.sleb128()?);
+                self.cfa = Some(CfaRule { register, offset });
+            }
+            DW_CFA_def_cfa_register => {
+                let register = reader.uleb128()?;
+                self.cfa = self.cfa.map(|rule| CfaRule { register, ..rule });
+            }
+            DW_CFA_def_cfa_offset => {
+                let offset = reader.uleb128()? as i64;
+                self.cfa = self.cfa.map(|rule| CfaRule { offset, ..rule });
+            }
+            DW_CFA_def_cfa_offset_sf => {
+                let offset = data_offset(reader.sleb128()?);
+                self.cfa = self.cfa.map(|rule| CfaRule { offset, ..rule });
+            }
+            DW_CFA_def_cfa_expression => {
+                let len = reader.uleb128()?;
+                reader.bytes(usize::try_from(len).ok()?)?;
+                self.cfa = None;
+            }
+            DW_CFA_expression | DW_CFA_val_expression => {
+                reader.uleb128()?;
+                let len = reader.uleb128()?;
+                reader.bytes(usize::try_from(len).ok()?)?;
+            }
+            // The operands of unknown instructions cannot be skipped.
+            _ => return None,
+        }
+
+        Some(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elf::Elf;
+
+    /// Return an `.eh_frame` section at address 0x2000 with a CIE, and an FDE
+    /// covering 0x1000..0x1020 with a frame pointer prologue.
+    fn eh_frame() -> Vec<u8> {
+        let mut cie = vec![0, 0, 0, 0, 1, b'z', b'R', 0, 1, 0x78, 16, 1];
+        cie.push(DW_EH_PE_pcrel | DW_EH_PE_sdata4);
+        // CFA = rsp + 8, return address at CFA - 8.
+        cie.extend([DW_CFA_def_cfa, 7, 8, DW_CFA_offset | 16, 1]);
+        cie.resize(20, DW_CFA_nop);
+
+        let mut section = (cie.len() as u32).to_le_bytes().to_vec();
+        section.extend(cie);
+
+        // The FDE follows the CIE at offset 0x18.
+        let mut fde = (0x1cu32).to_le_bytes().to_vec();
+        // The start is relative to the address of the field, 0x2020.
+        fde.extend((0x1000i32 - 0x2020).to_le_bytes());
+        fde.extend(0x20u32.to_le_bytes());
+        fde.push(0);
+        fde.extend([
+            DW_CFA_advance_loc | 1, // 0x1001: after `push rbp`
+            DW_CFA_def_cfa_offset,
+            16,
+            DW_CFA_offset | 6,
+            2,
+            DW_CFA_advance_loc | 3, // 0x1004: after `mov rbp, rsp`
+            DW_CFA_def_cfa_register,
+            6,
+            DW_CFA_remember_state,
+            DW_CFA_advance_loc1,
+            0x10, // 0x1014: after `pop rbp`
+            DW_CFA_def_cfa,
+            7,
+            8,
+            DW_CFA_advance_loc | 1, // 0x1015: past `ret`
+            DW_CFA_restore_state,
+            DW_CFA_nop,
+        ]);
+
+        section.extend((fde.len() as u32).to_le_bytes());
+        section.extend(fde);
+        section.extend(0u32.to_le_bytes());
+
+        section
+    }
+
+    #[test]
+    fn cfa_rules() {
+        let data = eh_frame();
+        let info = CallFrameInfo::new(&data, 0x2000);
+
+        let rule = |address| info.cfa_rule(FileAddr(address));
+        let rsp = |offset| {
+            Some(CfaRule {
+                register: 7,
+                offset,
+            })
+        };
+        let rbp = |offset| {
+            Some(CfaRule {
+                register: 6,
+                offset,
+            })
+        };
+
+        assert_eq!(rule(0x1000), rsp(8));
+        assert_eq!(rule(0x1001), rsp(16));
+        assert_eq!(rule(0x1003), rsp(16));
+        assert_eq!(rule(0x1004), rbp(16));
+        assert_eq!(rule(0x1013), rbp(16));
+        assert_eq!(rule(0x1014), rsp(8));
+        assert_eq!(rule(0x1015), rbp(16));
+        assert_eq!(rule(0x101f), rbp(16));
+
+        assert_eq!(rule(0x0fff), None);
+        assert_eq!(rule(0x1020), None);
+
+        // Truncated sections yield no rules, rather than causing a panic.
+        for len in 0..data.len() {
+            let info = CallFrameInfo::new(&data[..len], 0x2000);
+            let _ = info.cfa_rule(FileAddr(0x1010));
+        }
+    }
+
+    #[test]
+    fn cfa_rules_executable() {
+        let elf = Elf::open("target/debug/stepping").unwrap();
+        let index = elf.symbol_index();
+
+        // `main` is compiled by rustc, with call frame information.
+        let main = index.find_by_name("main")[0].address;
+        assert_eq!(
+            elf.call_frame_info().cfa_rule(main),
+            Some(CfaRule {
+                register: 7,
+                offset: 8
+            })
+        );
+
+        // The hand-written callee has none.
+        let callee = index.find_by_name("stepping_callee")[0].address;
+        assert_eq!(elf.call_frame_info().cfa_rule(callee), None);
+    }
+}
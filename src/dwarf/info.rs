@@ -0,0 +1,1007 @@
+//! Debugging information of the `.debug_info` section, describing the
+//! entities of a program (e.g., functions, variables, and types) as a tree of
+//! entries per unit.
+//!
+//! Units are scanned on first use, parsing only their header and root entry,
+//! which gives the address ranges of their code. The rest of the entries of a
+//! unit are parsed when first accessed, and abbreviation tables are parsed
+//! once, however many units share them.
+
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use super::abbrev::Abbreviations;
+use super::constants::*;
+use super::die::{AttributeValue, Bases, Die, DieTree, Encoding, parse_entries, read_address};
+use super::unit_at;
+use crate::elf::reader::Reader;
+use crate::elf::{FileAddr, demangle};
+
+/// Maximum number of abstract origins and specifications followed to name an
+/// entry, guarding against cycles.
+const MAX_ORIGIN_DEPTH: usize = 8;
+
+/// Contents of the DWARF sections of an object, which are empty if absent.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DwarfSections<'a> {
+    /// Contents of `.debug_info`.
+    pub debug_info: &'a [u8],
+    /// Contents of `.debug_abbrev`.
+    pub debug_abbrev: &'a [u8],
+    /// Contents of `.debug_str`.
+    pub debug_str: &'a [u8],
+    /// Contents of `.debug_str_offsets`.
+    pub debug_str_offsets: &'a [u8],
+    /// Contents of `.debug_line`.
+    pub debug_line: &'a [u8],
+    /// Contents of `.debug_line_str`.
+    pub debug_line_str: &'a [u8],
+    /// Contents of `.debug_addr`.
+    pub debug_addr: &'a [u8],
+    /// Contents of `.debug_ranges` (DWARF 4 and earlier).
+    pub debug_ranges: &'a [u8],
+    /// Contents of `.debug_rnglists` (DWARF 5).
+    pub debug_rnglists: &'a [u8],
+    /// Contents of `.debug_loclists` (DWARF 5).
+    pub debug_loclists: &'a [u8],
+}
+
+/// A unit of the `.debug_info` section, usually describing a compilation
+/// unit (i.e., a Rust crate, or a C source file).
+#[derive(Debug)]
+pub struct CompileUnit {
+    /// Offset of the unit within `.debug_info`.
+    pub offset: u64,
+    /// Version of DWARF (2 to 5).
+    pub version: u16,
+    /// Type of the unit (e.g., [`DW_UT_compile`]). Units of DWARF 4 and
+    /// earlier are all compilation units.
+    pub unit_type: u8,
+    /// Tag of the root entry (e.g., [`DW_TAG_compile_unit`]).
+    pub tag: u64,
+    /// Name of the primary source file of the unit.
+    pub name: Option<String>,
+    /// Directory the unit was compiled in, to which relative paths are
+    /// relative.
+    pub comp_dir: Option<PathBuf>,
+    /// Producer of the unit (e.g., a compiler and its version).
+    pub producer: Option<String>,
+    /// Offset of the line number program of the unit within `.debug_line`.
+    pub stmt_list: Option<u64>,
+    /// Base address of the unit, to which range lists are relative.
+    pub base_address: FileAddr,
+    /// Address ranges of the code of the unit.
+    pub ranges: Vec<Range<FileAddr>>,
+    /// Encoding of the entries of the unit.
+    encoding: Encoding,
+    /// Offsets of the contributions of the unit to other sections.
+    bases: Bases,
+    /// Offset of the root entry within `.debug_info`.
+    entries_offset: usize,
+    /// Offset of the following unit within `.debug_info`.
+    end: usize,
+    /// Abbreviation table of the unit.
+    abbreviations: Rc<Abbreviations>,
+    /// Entries of the unit, parsed on first use.
+    tree: OnceCell<DieTree>,
+}
+
+impl CompileUnit {
+    /// Return `true` if the code of the unit includes `address`.
+    pub fn contains(&self, address: FileAddr) -> bool {
+        self.ranges.iter().any(|range| range.contains(&address))
+    }
+
+    /// Return the range of offsets of the unit within `.debug_info`.
+    pub fn offsets(&self) -> Range<u64> {
+        self.offset..self.end as u64
+    }
+}
+
+/// Cache of the units and abbreviation tables of a [`DebugInfo`], filled as
+/// they are first used.
+#[derive(Debug, Default)]
+pub struct DebugInfoCache {
+    /// Units of `.debug_info`, sorted by offset.
+    units: OnceCell<Vec<CompileUnit>>,
+    /// Address ranges of the code of the units, along with the index of their
+    /// unit, sorted by start address.
+    unit_ranges: OnceCell<Vec<(Range<FileAddr>, usize)>>,
+    /// Abbreviation tables, by offset within `.debug_abbrev`.
+    abbreviations: RefCell<HashMap<u64, Rc<Abbreviations>>>,
+}
+
+impl DebugInfoCache {
+    /// Creates a new, empty [`DebugInfoCache`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Reader of the debugging information entries of an object, from its
+/// [`DwarfSections`], storing what it parses in a [`DebugInfoCache`].
+#[derive(Debug, Clone, Copy)]
+pub struct DebugInfo<'a> {
+    /// Sections of the object.
+    sections: DwarfSections<'a>,
+    /// Units and abbreviation tables parsed so far.
+    cache: &'a DebugInfoCache,
+}
+
+/// An entry describing code which contains an address: a function (i.e., a
+/// [`DW_TAG_subprogram`]), or a function inlined into another (i.e., a
+/// [`DW_TAG_inlined_subroutine`]).
+#[derive(Debug, Clone)]
+pub struct Scope<'a> {
+    /// Unit of the entry.
+    pub unit: &'a CompileUnit,
+    /// The entry itself.
+    pub die: &'a Die,
+    /// Name of the function (see [`DebugInfo::name`]).
+    pub name: Option<String>,
+    /// Address ranges of the code of the entry.
+    pub ranges: Vec<Range<FileAddr>>,
+}
+
+/// Location of the call of an inlined function.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct CallSite {
+    /// Index of the source file within the line number program of the unit
+    /// (see [`LineTable::program_file`](super::LineTable::program_file)).
+    pub file: u64,
+    /// Line number, starting from 1, or zero if unknown.
+    pub line: u64,
+    /// Column number, starting from 1, or zero if unknown.
+    pub column: u64,
+}
+
+impl Scope<'_> {
+    /// Return `true` if the scope is a function inlined into another.
+    pub fn is_inlined(&self) -> bool {
+        self.die.tag == DW_TAG_inlined_subroutine
+    }
+
+    /// Return the location of the call of an inlined function.
+    pub fn call_site(&self) -> Option<CallSite> {
+        let value = |name| self.die.attribute(name).and_then(AttributeValue::unsigned);
+
+        Some(CallSite {
+            file: value(DW_AT_call_file)?,
+            line: value(DW_AT_call_line).unwrap_or(0),
+            column: value(DW_AT_call_column).unwrap_or(0),
+        })
+    }
+}
+
+impl<'a> DebugInfo<'a> {
+    /// Creates a new [`DebugInfo`] over the given `sections`, storing what it
+    /// parses in `cache`, which must only ever be used with these sections.
+    pub fn new(sections: DwarfSections<'a>, cache: &'a DebugInfoCache) -> Self {
+        Self { sections, cache }
+    }
+
+    /// Return the sections of the object.
+    pub fn sections(&self) -> &DwarfSections<'a> {
+        &self.sections
+    }
+
+    /// Return the units of `.debug_info`, sorted by offset, scanning them on
+    /// first use. Units with a malformed header or root entry are skipped.
+    pub fn units(&self) -> &'a [CompileUnit] {
+        self.cache.units.get_or_init(|| {
+            let debug_info = self.sections.debug_info;
+            let mut units = Vec::new();
+            let mut offset = 0;
+
+            while offset < debug_info.len() {
+                let Some(unit) = unit_at(debug_info, offset) else {
+                    break;
+                };
+
+                units.extend(self.parse_unit(offset, unit.data, unit.offset_size, unit.end));
+                offset = unit.end;
+            }
+
+            units
+        })
+    }
+
+    /// Parse the header and root entry of the unit at `offset`, whose `data`
+    /// follows its initial length.
+    fn parse_unit(
+        &self,
+        offset: usize,
+        data: &[u8],
+        offset_size: usize,
+        end: usize,
+    ) -> Option<CompileUnit> {
+        let mut reader = Reader::new(data, 0);
+        let version = reader.u16()?;
+
+        let (unit_type, address_size, abbrev_offset) = match version {
+            2..=4 => {
+                let abbrev_offset = reader.uint(offset_size)?;
+                (DW_UT_compile, reader.u8()?, abbrev_offset)
+            }
+            5 => {
+                let unit_type = reader.u8()?;
+                let address_size = reader.u8()?;
+                let abbrev_offset = reader.uint(offset_size)?;
+
+                match unit_type {
+                    // Identifier of the split unit.
+                    DW_UT_skeleton | DW_UT_split_compile => {
+                        reader.u64()?;
+                    }
+                    // Signature of the type, and offset of its entry.
+                    DW_UT_type | DW_UT_split_type => {
+                        reader.u64()?;
+                        reader.uint(offset_size)?;
+                    }
+                    _ => {}
+                }
+
+                (unit_type, address_size, abbrev_offset)
+            }
+            _ => return None,
+        };
+
+        if !matches!(address_size, 1..=8) {
+            return None;
+        }
+
+        let encoding = Encoding {
+            version,
+            address_size,
+            offset_size,
+            unit_offset: offset as u64,
+        };
+
+        let abbreviations = self.abbreviations(abbrev_offset)?;
+        let entries_offset = end - data.len() + reader.offset();
+
+        let (root, bases) = parse_entries(
+            &self.sections,
+            encoding,
+            &abbreviations,
+            entries_offset,
+            end,
+            true,
+        );
+        let root = root.root()?;
+
+        let string = |name| root.attribute(name)?.string().map(str::to_string);
+
+        let mut unit = CompileUnit {
+            offset: offset as u64,
+            version,
+            unit_type,
+            tag: root.tag,
+            name: string(DW_AT_name),
+            comp_dir: string(DW_AT_comp_dir).map(PathBuf::from),
+            producer: string(DW_AT_producer),
+            stmt_list: root
+                .attribute(DW_AT_stmt_list)
+                .and_then(AttributeValue::section_offset),
+            base_address: root
+                .attribute(DW_AT_low_pc)
+                .and_then(AttributeValue::address)
+                .unwrap_or_default(),
+            ranges: Vec::new(),
+            encoding,
+            bases,
+            entries_offset,
+            end,
+            abbreviations,
+            tree: OnceCell::new(),
+        };
+
+        unit.ranges = self.ranges(&unit, root);
+        Some(unit)
+    }
+
+    /// Return the abbreviation table at `offset` of `.debug_abbrev`, parsing
+    /// it unless another unit already did.
+    fn abbreviations(&self, offset: u64) -> Option<Rc<Abbreviations>> {
+        let mut cache = self.cache.abbreviations.borrow_mut();
+
+        if let Some(abbreviations) = cache.get(&offset) {
+            return Some(Rc::clone(abbreviations));
+        }
+
+        let abbreviations = Rc::new(Abbreviations::parse(self.sections.debug_abbrev, offset)?);
+        cache.insert(offset, Rc::clone(&abbreviations));
+
+        Some(abbreviations)
+    }
+
+    /// Return the entries of `unit`, parsing them on first use.
+    pub fn entries(&self, unit: &'a CompileUnit) -> &'a DieTree {
+        unit.tree.get_or_init(|| {
+            parse_entries(
+                &self.sections,
+                unit.encoding,
+                &unit.abbreviations,
+                unit.entries_offset,
+                unit.end,
+                false,
+            )
+            .0
+        })
+    }
+
+    /// Look up the unit whose code includes `address`.
+    ///
+    /// Units whose root entry has no address ranges may still describe code,
+    /// so their functions are searched if no unit has a range including the
+    /// address.
+    pub fn unit_containing(&self, address: FileAddr) -> Option<&'a CompileUnit> {
+        let units = self.units();
+        let ranges = self.unit_ranges();
+        let idx = ranges.partition_point(|(range, _)| range.start <= address);
+
+        if let Some((range, unit)) = idx.checked_sub(1).and_then(|idx| ranges.get(idx))
+            && range.contains(&address)
+        {
+            return Some(&units[*unit]);
+        }
+
+        units
+            .iter()
+            .filter(|unit| unit.ranges.is_empty())
+            .find(|unit| !self.unit_scopes_at(unit, address).is_empty())
+    }
+
+    /// Return the address ranges of the code of every unit, along with the
+    /// index of their unit, sorted by start address on first use.
+    fn unit_ranges(&self) -> &'a [(Range<FileAddr>, usize)] {
+        self.cache.unit_ranges.get_or_init(|| {
+            let mut ranges: Vec<_> = self
+                .units()
+                .iter()
+                .enumerate()
+                .flat_map(|(idx, unit)| unit.ranges.iter().map(move |range| (range.clone(), idx)))
+                .collect();
+
+            ranges.sort_by_key(|(range, _)| range.start);
+            ranges
+        })
+    }
+
+    /// Look up the unit holding the entry at `offset` within `.debug_info`.
+    pub fn unit_at_offset(&self, offset: u64) -> Option<&'a CompileUnit> {
+        let units = self.units();
+        let idx = units.partition_point(|unit| unit.offset <= offset);
+
+        units
+            .get(idx.checked_sub(1)?)
+            .filter(|unit| unit.offsets().contains(&offset))
+    }
+
+    /// Look up the entry at `offset` within `.debug_info` (e.g., the target
+    /// of a [`AttributeValue::Reference`]), along with its unit.
+    pub fn entry_at_offset(&self, offset: u64) -> Option<(&'a CompileUnit, &'a Die)> {
+        let unit = self.unit_at_offset(offset)?;
+        let tree = self.entries(unit);
+
+        Some((unit, tree.get(tree.find_by_offset(offset)?)?))
+    }
+
+    /// Return the address ranges of the code described by `die`, given by its
+    /// [`DW_AT_low_pc`] and [`DW_AT_high_pc`], or its [`DW_AT_ranges`].
+    ///
+    /// Ranges starting at address zero describe code discarded by the linker,
+    /// and are dropped, as are empty ranges.
+    pub fn ranges(&self, unit: &CompileUnit, die: &Die) -> Vec<Range<FileAddr>> {
+        let mut ranges = Vec::new();
+
+        if let Some(offset) = die
+            .attribute(DW_AT_ranges)
+            .and_then(AttributeValue::section_offset)
+        {
+            match unit.version {
+                5 => self.read_range_list(unit, offset, &mut ranges),
+                _ => self.read_ranges(unit, offset, &mut ranges),
+            };
+        } else if let Some(low) = die
+            .attribute(DW_AT_low_pc)
+            .and_then(AttributeValue::address)
+        {
+            // The high address is either given as is, or as an offset from the
+            // low address.
+            let high = match die.attribute(DW_AT_high_pc) {
+                Some(AttributeValue::Address(high)) => Some(*high),
+                Some(value) => value
+                    .unsigned()
+                    .map(|size| FileAddr(low.0.wrapping_add(size))),
+                None => None,
+            };
+
+            ranges.extend(high.map(|high| low..high));
+        }
+
+        ranges.retain(|range| range.start.0 != 0 && range.start < range.end);
+        ranges
+    }
+
+    /// Read the range list at `offset` of `.debug_ranges` (DWARF 4 and
+    /// earlier) into `ranges`, stopping early if it is malformed.
+    fn read_ranges(
+        &self,
+        unit: &CompileUnit,
+        offset: u64,
+        ranges: &mut Vec<Range<FileAddr>>,
+    ) -> Option<()> {
+        let mut reader = Reader::new(self.sections.debug_ranges, usize::try_from(offset).ok()?);
+
+        let size = usize::from(unit.encoding.address_size);
+        let max = u64::MAX >> (64 - 8 * size);
+        let mut base = unit.base_address.0;
+
+        loop {
+            let start = reader.uint(size)?;
+            let end = reader.uint(size)?;
+
+            match (start, end) {
+                (0, 0) => return Some(()),
+                // Selection of the base address of the following entries.
+                (start, end) if start == max => base = end,
+                (start, end) => ranges
+                    .push(FileAddr(base.wrapping_add(start))..FileAddr(base.wrapping_add(end))),
+            }
+        }
+    }
+
+    /// Read the range list at `offset` of `.debug_rnglists` (DWARF 5) into
+    /// `ranges`, stopping early if it is malformed.
+    fn read_range_list(
+        &self,
+        unit: &CompileUnit,
+        offset: u64,
+        ranges: &mut Vec<Range<FileAddr>>,
+    ) -> Option<()> {
+        let mut reader = Reader::new(self.sections.debug_rnglists, usize::try_from(offset).ok()?);
+
+        let size = usize::from(unit.encoding.address_size);
+        let address = |index| read_address(&self.sections, unit.encoding, unit.bases, index);
+        let mut base = unit.base_address;
+
+        loop {
+            let (start, end) = match reader.u8()? {
+                DW_RLE_end_of_list => return Some(()),
+                DW_RLE_base_addressx => {
+                    base = address(reader.uleb128()?)?;
+                    continue;
+                }
+                DW_RLE_base_address => {
+                    base = FileAddr(reader.uint(size)?);
+                    continue;
+                }
+                DW_RLE_startx_endx => (address(reader.uleb128()?)?, address(reader.uleb128()?)?),
+                DW_RLE_startx_length => {
+                    let start = address(reader.uleb128()?)?;
+                    (start, FileAddr(start.0.wrapping_add(reader.uleb128()?)))
+                }
+                DW_RLE_offset_pair => (
+                    FileAddr(base.0.wrapping_add(reader.uleb128()?)),
+                    FileAddr(base.0.wrapping_add(reader.uleb128()?)),
+                ),
+                DW_RLE_start_end => (FileAddr(reader.uint(size)?), FileAddr(reader.uint(size)?)),
+                DW_RLE_start_length => {
+                    let start = reader.uint(size)?;
+                    (
+                        FileAddr(start),
+                        FileAddr(start.wrapping_add(reader.uleb128()?)),
+                    )
+                }
+                _ => return None,
+            };
+
+            ranges.push(start..end);
+        }
+    }
+
+    /// Return the name of the entity described by `die`: its demangled linkage
+    /// name if it has one, or its [`DW_AT_name`]. The names of inlined and
+    /// out-of-line instances of functions are given by their abstract origin,
+    /// and those of definitions possibly by their specification.
+    pub fn name(&self, die: &'a Die) -> Option<String> {
+        let mut die = die;
+
+        for _ in 0..MAX_ORIGIN_DEPTH {
+            let linkage_name = die
+                .attribute(DW_AT_linkage_name)
+                .or_else(|| die.attribute(DW_AT_MIPS_linkage_name))
+                .and_then(AttributeValue::string);
+
+            if let Some(name) = linkage_name {
+                return Some(demangle(name).unwrap_or_else(|| name.to_string()));
+            }
+
+            if let Some(name) = die.name() {
+                return Some(name.to_string());
+            }
+
+            let origin = die
+                .attribute(DW_AT_abstract_origin)
+                .or_else(|| die.attribute(DW_AT_specification))
+                .and_then(AttributeValue::reference)?;
+
+            die = self.entry_at_offset(origin)?.1;
+        }
+
+        None
+    }
+
+    /// Look up the function whose code includes `address`, followed by the
+    /// functions inlined into it which do, from outermost to innermost.
+    pub fn scopes_at(&self, address: FileAddr) -> Vec<Scope<'a>> {
+        match self.unit_containing(address) {
+            Some(unit) => self.unit_scopes_at(unit, address),
+            None => Vec::new(),
+        }
+    }
+
+    /// Look up the scopes including `address` within `unit` (see
+    /// [`DebugInfo::scopes_at`]).
+    fn unit_scopes_at(&self, unit: &'a CompileUnit, address: FileAddr) -> Vec<Scope<'a>> {
+        let mut scopes = Vec::new();
+
+        let tree = self.entries(unit);
+        let mut pending: Vec<usize> = tree.children(0).collect();
+
+        // Entries which cannot have code (e.g., namespaces) are searched, while
+        // those whose code does not include the address are skipped.
+        while let Some(index) = pending.pop() {
+            let Some(die) = tree.get(index) else {
+                continue;
+            };
+
+            if !matches!(
+                die.tag,
+                DW_TAG_subprogram | DW_TAG_inlined_subroutine | DW_TAG_lexical_block
+            ) {
+                pending.extend(tree.children(index));
+                continue;
+            }
+
+            let ranges = self.ranges(unit, die);
+
+            // Declarations and abstract instances of functions have no code,
+            // but their descendants may (e.g., a nested function).
+            if ranges.is_empty() {
+                pending.extend(tree.children(index));
+                continue;
+            }
+
+            if !ranges.iter().any(|range| range.contains(&address)) {
+                continue;
+            }
+
+            if matches!(die.tag, DW_TAG_subprogram | DW_TAG_inlined_subroutine) {
+                scopes.push(Scope {
+                    unit,
+                    die,
+                    name: self.name(die),
+                    ranges,
+                });
+            }
+
+            // Only the descendants of the entry can describe the address more
+            // precisely (e.g., lexical blocks and inlined functions).
+            pending.clear();
+            pending.extend(tree.children(index));
+        }
+
+        scopes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::elf::Elf;
+
+    /// Prefix `data` with its length, as a 32-bit DWARF initial length.
+    fn with_length(data: &[u8]) -> Vec<u8> {
+        [&(data.len() as u32).to_le_bytes(), data].concat()
+    }
+
+    #[test]
+    fn debug_info_v5() {
+        let debug_str = b"\0rdbg test\0main.rs\0caller\0";
+        let debug_line_str = b"\0local\0";
+
+        // Offsets of the strings at indices 0 and 1.
+        #[rustfmt::skip]
+        let debug_str_offsets = with_length(&[
+            0x05, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00,
+            0x0b, 0x00, 0x00, 0x00,
+        ]);
+
+        // Addresses 0x1000 and 0x1040 at indices 0 and 1.
+        #[rustfmt::skip]
+        let debug_addr = with_length(&[
+            0x05, 0x00, 0x08, 0x00,
+            0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x40, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ]);
+
+        #[rustfmt::skip]
+        let debug_rnglists = with_length(&[
+            0x05, 0x00, 0x08, 0x00,
+            0x01, 0x00, 0x00, 0x00, // offset_entry_count
+            0x04, 0x00, 0x00, 0x00, // Offset of list 0, from the base (12).
+            // List 0, at offset 16, of the unit.
+            DW_RLE_startx_length, 0x00, 0x80, 0x02,
+            DW_RLE_start_end,
+            0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x10, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            DW_RLE_end_of_list,
+            // List at offset 38, of the inlined function.
+            DW_RLE_base_addressx, 0x01,
+            DW_RLE_offset_pair, 0x00, 0x20,
+            DW_RLE_offset_pair, 0x30, 0x40,
+            DW_RLE_end_of_list,
+        ]);
+
+        #[rustfmt::skip]
+        let debug_loclists = with_length(&[
+            0x05, 0x00, 0x08, 0x00,
+            0x01, 0x00, 0x00, 0x00, // offset_entry_count
+            0x04, 0x00, 0x00, 0x00, // Offset of list 0, from the base (12).
+            0x00, // DW_LLE_end_of_list
+        ]);
+
+        #[rustfmt::skip]
+        let debug_abbrev = [
+            0x01, DW_TAG_compile_unit as u8, DW_CHILDREN_yes,
+            // The producer is given by index before the base of the indices.
+            DW_AT_producer as u8, DW_FORM_strx1 as u8,
+            DW_AT_str_offsets_base as u8, DW_FORM_sec_offset as u8,
+            DW_AT_addr_base as u8, DW_FORM_sec_offset as u8,
+            DW_AT_rnglists_base as u8, DW_FORM_sec_offset as u8,
+            0x8c, 0x01, DW_FORM_sec_offset as u8, // DW_AT_loclists_base
+            DW_AT_name as u8, DW_FORM_strx as u8,
+            DW_AT_stmt_list as u8, DW_FORM_sec_offset as u8,
+            DW_AT_low_pc as u8, DW_FORM_addr as u8,
+            DW_AT_ranges as u8, DW_FORM_rnglistx as u8,
+            0x00, 0x00,
+            0x02, DW_TAG_subprogram as u8, DW_CHILDREN_yes,
+            DW_AT_name as u8, DW_FORM_strp as u8,
+            DW_AT_low_pc as u8, DW_FORM_addrx as u8,
+            DW_AT_high_pc as u8, DW_FORM_data4 as u8,
+            DW_AT_decl_line as u8, DW_FORM_implicit_const as u8, 0x07,
+            DW_AT_external as u8, DW_FORM_flag_present as u8,
+            0x00, 0x00,
+            0x03, DW_TAG_inlined_subroutine as u8, 0x00,
+            DW_AT_abstract_origin as u8, DW_FORM_ref4 as u8,
+            DW_AT_ranges as u8, DW_FORM_sec_offset as u8,
+            DW_AT_call_file as u8, DW_FORM_data1 as u8,
+            DW_AT_call_line as u8, DW_FORM_udata as u8,
+            DW_AT_call_column as u8, DW_FORM_indirect as u8,
+            0x00, 0x00,
+            0x04, DW_TAG_subprogram as u8, 0x00,
+            DW_AT_name as u8, DW_FORM_string as u8,
+            DW_AT_inline as u8, DW_FORM_data1 as u8,
+            0x00, 0x00,
+            0x05, DW_TAG_variable as u8, 0x00,
+            DW_AT_name as u8, DW_FORM_line_strp as u8,
+            DW_AT_location as u8, DW_FORM_loclistx as u8,
+            DW_AT_byte_size as u8, DW_FORM_sdata as u8,
+            0x1c, DW_FORM_data16 as u8, // DW_AT_const_value
+            0x00, 0x00,
+            0x00,
+        ];
+
+        #[rustfmt::skip]
+        let debug_info = with_length(&[
+            &[
+                0x05, 0x00, // version
+                DW_UT_compile, 0x08,
+                0x00, 0x00, 0x00, 0x00, // debug_abbrev_offset
+                // Compilation unit, at offset 12.
+                0x01,
+                0x00,
+                0x08, 0x00, 0x00, 0x00,
+                0x08, 0x00, 0x00, 0x00,
+                0x0c, 0x00, 0x00, 0x00,
+                0x0c, 0x00, 0x00, 0x00,
+                0x01,
+                0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00,
+                // Abstract instance of `helper`, at offset 44.
+                0x04, b'h', b'e', b'l', b'p', b'e', b'r', 0x00, 0x03,
+                // Function `caller`, at offset 53.
+                0x02,
+                0x13, 0x00, 0x00, 0x00,
+                0x00,
+                0x00, 0x01, 0x00, 0x00,
+                // Inlined `helper`, at offset 63.
+                0x03,
+                0x2c, 0x00, 0x00, 0x00,
+                0x26, 0x00, 0x00, 0x00,
+                0x01,
+                0x0c,
+                DW_FORM_udata as u8, 0x05,
+                // Variable `local`, at offset 76.
+                0x05,
+                0x01, 0x00, 0x00, 0x00,
+                0x00,
+                0x78, // -8
+            ],
+            &[0xab; 16][..],
+            &[
+                0x00, // End of the children of `caller`.
+                0x00, // End of the children of the unit.
+            ],
+        ]
+        .concat());
+
+        let sections = DwarfSections {
+            debug_info: &debug_info,
+            debug_abbrev: &debug_abbrev,
+            debug_str,
+            debug_str_offsets: &debug_str_offsets,
+            debug_line_str,
+            debug_addr: &debug_addr,
+            debug_rnglists: &debug_rnglists,
+            debug_loclists: &debug_loclists,
+            ..DwarfSections::default()
+        };
+        let cache = DebugInfoCache::new();
+        let info = DebugInfo::new(sections, &cache);
+
+        let [unit] = info.units() else {
+            panic!("expected a single unit");
+        };
+        assert_eq!(unit.version, 5);
+        assert_eq!(unit.unit_type, DW_UT_compile);
+        assert_eq!(unit.tag, DW_TAG_compile_unit);
+        assert_eq!(unit.name.as_deref(), Some("main.rs"));
+        assert_eq!(unit.producer.as_deref(), Some("rdbg test"));
+        assert_eq!(unit.comp_dir, None);
+        assert_eq!(unit.stmt_list, Some(0));
+        assert_eq!(unit.offsets(), 0..101);
+        assert_eq!(
+            unit.ranges,
+            [
+                FileAddr(0x1000)..FileAddr(0x1100),
+                FileAddr(0x2000)..FileAddr(0x2010),
+            ]
+        );
+
+        let tree = info.entries(unit);
+        let offsets: Vec<u64> = tree.entries().iter().map(|die| die.offset).collect();
+        assert_eq!(offsets, [12, 44, 53, 63, 76]);
+        assert_eq!(tree.children(0).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(tree.children(2).collect::<Vec<_>>(), [3, 4]);
+        assert_eq!(tree.children(3).count(), 0);
+        assert_eq!(tree.get(4).unwrap().parent, Some(2));
+        assert_eq!(tree.descendants(2), 3..5);
+
+        let caller = tree.get(2).unwrap();
+        assert_eq!(caller.name(), Some("caller"));
+        assert_eq!(
+            caller.attribute(DW_AT_low_pc),
+            Some(&AttributeValue::Address(FileAddr(0x1000)))
+        );
+        assert_eq!(
+            caller.attribute(DW_AT_decl_line),
+            Some(&AttributeValue::Signed(7))
+        );
+        assert_eq!(
+            caller.attribute(DW_AT_external),
+            Some(&AttributeValue::Flag(true))
+        );
+        assert_eq!(
+            info.ranges(unit, caller),
+            [FileAddr(0x1000)..FileAddr(0x1100)]
+        );
+
+        // The inlined instance is named by its abstract origin.
+        let (_, inlined) = info.entry_at_offset(63).unwrap();
+        assert_eq!(inlined.tag, DW_TAG_inlined_subroutine);
+        assert_eq!(inlined.name(), None);
+        assert_eq!(info.name(inlined).as_deref(), Some("helper"));
+        assert_eq!(
+            inlined.attribute(DW_AT_call_column),
+            Some(&AttributeValue::Unsigned(5))
+        );
+        assert_eq!(
+            info.ranges(unit, inlined),
+            [
+                FileAddr(0x1040)..FileAddr(0x1060),
+                FileAddr(0x1070)..FileAddr(0x1080),
+            ]
+        );
+
+        let local = tree.get(4).unwrap();
+        assert_eq!(local.name(), Some("local"));
+        assert_eq!(
+            local.attribute(DW_AT_location),
+            Some(&AttributeValue::SectionOffset(16))
+        );
+        assert_eq!(
+            local.attribute(DW_AT_byte_size),
+            Some(&AttributeValue::Signed(-8))
+        );
+        assert_eq!(
+            local.attribute(0x1c),
+            Some(&AttributeValue::Block(vec![0xab; 16]))
+        );
+
+        // Entries are only found at their exact offset.
+        assert!(info.entry_at_offset(64).is_none());
+        assert!(info.entry_at_offset(200).is_none());
+
+        let scopes = info.scopes_at(FileAddr(0x1050));
+        let names: Vec<_> = scopes.iter().map(|scope| scope.name.as_deref()).collect();
+        assert_eq!(names, [Some("caller"), Some("helper")]);
+        assert!(!scopes[0].is_inlined());
+        assert!(scopes[1].is_inlined());
+        assert_eq!(scopes[0].call_site(), None);
+        assert_eq!(
+            scopes[1].call_site(),
+            Some(CallSite {
+                file: 1,
+                line: 12,
+                column: 5,
+            })
+        );
+
+        let names: Vec<_> = info
+            .scopes_at(FileAddr(0x1065))
+            .into_iter()
+            .map(|scope| scope.name)
+            .collect();
+        assert_eq!(names, [Some("caller".to_string())]);
+        assert!(info.scopes_at(FileAddr(0x2008)).is_empty());
+        assert!(info.scopes_at(FileAddr(0x3000)).is_empty());
+    }
+
+    #[test]
+    fn debug_info_malformed() {
+        #[rustfmt::skip]
+        let debug_abbrev = [
+            0x01, DW_TAG_compile_unit as u8, DW_CHILDREN_yes,
+            DW_AT_name as u8, DW_FORM_string as u8,
+            DW_AT_low_pc as u8, DW_FORM_addr as u8,
+            DW_AT_high_pc as u8, DW_FORM_data8 as u8,
+            0x00, 0x00,
+            0x02, DW_TAG_compile_unit as u8, DW_CHILDREN_yes,
+            DW_AT_name as u8, DW_FORM_string as u8,
+            0x00, 0x00,
+            0x03, DW_TAG_subprogram as u8, 0x00,
+            DW_AT_low_pc as u8, DW_FORM_addr as u8,
+            DW_AT_high_pc as u8, DW_FORM_data8 as u8,
+            0x00, 0x00,
+            0x00,
+        ];
+
+        #[rustfmt::skip]
+        let debug_info = [
+            // Unsupported version, skipped.
+            with_length(&[0x06, 0x00]),
+            // Unit `b`, at offset 6, at address zero and with an unknown
+            // abbreviation code after its root entry.
+            with_length(&[
+                0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08,
+                0x01, b'b', 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x09,
+            ]),
+            // Unit `c`, at offset 37, sharing the abbreviation table.
+            with_length(&[
+                0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08,
+                0x01, b'c', 0x00,
+                0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00,
+            ]),
+            // Unit `d`, at offset 68, without ranges but with a function.
+            with_length(&[
+                0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08,
+                0x02, b'd', 0x00,
+                0x03,
+                0x00, 0x50, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00,
+            ]),
+            // Unit truncated by the end of the section.
+            vec![0x64, 0x00, 0x00, 0x00, 0x04, 0x00],
+        ]
+        .concat();
+
+        let sections = DwarfSections {
+            debug_info: &debug_info,
+            debug_abbrev: &debug_abbrev,
+            ..DwarfSections::default()
+        };
+        let cache = DebugInfoCache::new();
+        let info = DebugInfo::new(sections, &cache);
+
+        let [b, c, d] = info.units() else {
+            panic!("expected three units");
+        };
+        assert_eq!(b.name.as_deref(), Some("b"));
+        assert_eq!(b.offsets(), 6..37);
+        assert!(b.ranges.is_empty());
+        assert_eq!(c.name.as_deref(), Some("c"));
+        assert_eq!(c.ranges, [FileAddr(0x4000)..FileAddr(0x4010)]);
+        assert!(Rc::ptr_eq(&b.abbreviations, &c.abbreviations));
+
+        // Entries are kept up to the first malformed one.
+        assert_eq!(info.entries(b).entries().len(), 1);
+        assert_eq!(info.entries(c).entries().len(), 1);
+
+        assert_eq!(info.unit_at_offset(0).map(|unit| unit.offset), None);
+        assert_eq!(info.unit_at_offset(40).map(|unit| unit.offset), Some(37));
+        assert_eq!(info.unit_at_offset(100).map(|unit| unit.offset), None);
+        assert_eq!(
+            info.unit_containing(FileAddr(0x4008))
+                .map(|unit| unit.offset),
+            Some(37)
+        );
+        assert!(info.unit_containing(FileAddr(0x8)).is_none());
+
+        // Units without ranges are found by the code of their functions.
+        assert!(d.ranges.is_empty());
+        assert_eq!(
+            info.unit_containing(FileAddr(0x5008))
+                .map(|unit| unit.offset),
+            Some(68)
+        );
+        assert_eq!(info.scopes_at(FileAddr(0x5008)).len(), 1);
+        assert!(info.unit_containing(FileAddr(0x5010)).is_none());
+    }
+
+    #[test]
+    fn debug_info_inlined_functions() {
+        let elf = Elf::open("target/debug/inlining").unwrap();
+        let info = elf.debug_info();
+
+        let main = elf.symbol_index().find_by_name("inlining::main")[0];
+        let address = (main.address.0..main.address.0 + main.size)
+            .map(FileAddr)
+            .find(|address| info.scopes_at(*address).len() == 3)
+            .unwrap();
+        let scopes = info.scopes_at(address);
+
+        let unit = scopes[0].unit;
+        assert!(unit.producer.as_deref().unwrap().contains("rustc"));
+        assert!(unit.comp_dir.is_some());
+
+        let names: Vec<_> = scopes.iter().map(|scope| scope.name.as_deref()).collect();
+        assert_eq!(
+            names,
+            [
+                Some("inlining::main"),
+                Some("inlining::add_two"),
+                Some("inlining::add_one"),
+            ]
+        );
+        assert!(!scopes[0].is_inlined());
+
+        // `add_two` is called on line 18, and calls `add_one` on line 14.
+        let lines: Vec<_> = scopes[1..]
+            .iter()
+            .map(|scope| scope.call_site().unwrap().line)
+            .collect();
+        assert_eq!(lines, [18, 14]);
+
+        let call = scopes[1].call_site().unwrap();
+        let file = elf
+            .line_table()
+            .program_file(unit.stmt_list.unwrap(), call.file)
+            .unwrap();
+        assert!(file.path.ends_with("inlining.rs"));
+    }
+}
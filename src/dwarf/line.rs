@@ -0,0 +1,815 @@
+//! Line number information of the `.debug_line` section, mapping addresses to
+//! source locations and back.
+//!
+//! Each unit of the section holds a line number program, which is run by a
+//! state machine to build the rows of a matrix of addresses and locations.
+//! Rows are grouped into sequences of contiguous, increasing addresses (e.g.,
+//! a single function), each ending with a row past its last address.
+
+use std::ffi::OsStr;
+use std::ops::Range;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use super::{Unit, constants::*, unit_at};
+use crate::elf::FileAddr;
+use crate::elf::reader::{Reader, c_str};
+
+/// A source file referenced by a line number program.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SourceFile {
+    /// Path of the file, joined to its include directory. Relative paths are
+    /// relative to the compilation directory, which DWARF 4 line number
+    /// programs do not record.
+    pub path: PathBuf,
+    /// MD5 digest of the contents of the file, recorded by DWARF 5 producers.
+    pub md5: Option<[u8; 16]>,
+}
+
+/// A row of the line number matrix, giving the source location of the
+/// instructions starting at its address.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct LineRow {
+    /// Address of the first instruction described by the row.
+    pub address: FileAddr,
+    /// Index of the source file within the [`LineTable`], or `usize::MAX` if
+    /// the line number program referenced an undefined file.
+    pub file: usize,
+    /// Line number, starting from 1, or zero if the instructions do not
+    /// correspond to any source line.
+    pub line: u64,
+    /// Column number, starting from 1, or zero for the whole line.
+    pub column: u64,
+    /// Whether the address is a recommended breakpoint location (i.e., the
+    /// start of a statement).
+    pub is_stmt: bool,
+    /// Whether the address is where a breakpoint should be placed to stop
+    /// after the prologue of a function.
+    pub prologue_end: bool,
+    /// Whether the address is where the epilogue of a function begins.
+    pub epilogue_begin: bool,
+    /// Whether the row ends a sequence, in which case its address is the
+    /// first byte after the last instruction of the sequence.
+    pub end_sequence: bool,
+}
+
+/// The rows of every line number program of an ELF object.
+#[derive(Debug, Default)]
+pub struct LineTable {
+    /// Source files of every line number program.
+    files: Vec<SourceFile>,
+    /// Rows of every sequence, in the order of the line number programs.
+    rows: Vec<LineRow>,
+    /// Sequences of rows, sorted by start address.
+    sequences: Vec<Sequence>,
+    /// Line number programs whose header could be parsed, in order.
+    programs: Vec<Program>,
+}
+
+/// The files of a line number program within [`LineTable::files`].
+#[derive(Debug)]
+struct Program {
+    /// Offset of the program within `.debug_line`.
+    offset: u64,
+    /// Index of the first file of the program within the table.
+    first_file: usize,
+    /// Number of the first file within the program: 0 for DWARF 5, and 1 for
+    /// earlier versions.
+    file_base: u64,
+}
+
+/// A sequence of rows describing contiguous, increasing addresses.
+#[derive(Debug)]
+struct Sequence {
+    /// Address of the first row.
+    start: FileAddr,
+    /// Address of the end-of-sequence row, one past the last instruction.
+    end: FileAddr,
+    /// Range of the rows of the sequence within [`LineTable::rows`].
+    rows: Range<usize>,
+}
+
+/// String sections referenced by line number program headers.
+#[derive(Debug, Clone, Copy)]
+struct Strings<'a> {
+    /// Contents of `.debug_str`.
+    debug_str: &'a [u8],
+    /// Contents of `.debug_line_str`.
+    debug_line_str: &'a [u8],
+}
+
+/// Value of an attribute of a directory or file entry.
+#[derive(Debug)]
+enum Value<'a> {
+    String(&'a [u8]),
+    Unsigned(u64),
+    Block(&'a [u8]),
+    /// Value which cannot be resolved from the line number program alone
+    /// (e.g., an index into `.debug_str_offsets`).
+    Unresolved,
+}
+
+/// A directory or file entry of a DWARF 5 line number program header.
+#[derive(Debug, Default)]
+struct Entry {
+    path: PathBuf,
+    directory: u64,
+    md5: Option<[u8; 16]>,
+}
+
+impl LineTable {
+    /// Run every line number program of the `debug_line` section, whose
+    /// headers may reference strings of the `debug_str` and `debug_line_str`
+    /// sections.
+    ///
+    /// Programs with a malformed header are skipped, as are incomplete
+    /// sequences. Sequences starting at address zero describe functions
+    /// discarded by the linker, and are dropped.
+    pub fn parse(debug_line: &[u8], debug_str: &[u8], debug_line_str: &[u8]) -> Self {
+        let strings = Strings {
+            debug_str,
+            debug_line_str,
+        };
+
+        let mut table = Self::default();
+        let mut offset = 0;
+
+        while offset < debug_line.len() {
+            let Some(unit) = unit_at(debug_line, offset) else {
+                break;
+            };
+
+            table.run_program(offset as u64, &unit, strings);
+            offset = unit.end;
+        }
+
+        table.sequences.sort_by_key(|sequence| sequence.start);
+        table
+    }
+
+    /// Return `true` if no line number program described any address.
+    pub fn is_empty(&self) -> bool {
+        self.sequences.is_empty()
+    }
+
+    /// Return the source files of every line number program.
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// Return the source file of `row`.
+    pub fn file(&self, row: &LineRow) -> Option<&SourceFile> {
+        self.files.get(row.file)
+    }
+
+    /// Look up file number `file` of the line number program at `offset` of
+    /// `.debug_line` (e.g., the [`DW_AT_call_file`] of an inlined function,
+    /// within the program given by the [`DW_AT_stmt_list`] of its unit).
+    pub fn program_file(&self, offset: u64, file: u64) -> Option<&SourceFile> {
+        let idx = self
+            .programs
+            .binary_search_by_key(&offset, |program| program.offset)
+            .ok()?;
+        let program = &self.programs[idx];
+
+        // Files of the program are followed by those of the next.
+        let end = self
+            .programs
+            .get(idx + 1)
+            .map_or(self.files.len(), |next| next.first_file);
+
+        let index = usize::try_from(file.checked_sub(program.file_base)?)
+            .ok()?
+            .checked_add(program.first_file)
+            .filter(|&index| index < end)?;
+
+        self.files.get(index)
+    }
+
+    /// Return the offset within `.debug_line` of the line number program
+    /// naming the source file at index `file` of the table (e.g., to find the
+    /// unit, and so the compilation directory, of the file).
+    pub fn program_offset(&self, file: usize) -> Option<u64> {
+        if file >= self.files.len() {
+            return None;
+        }
+
+        let idx = self
+            .programs
+            .partition_point(|program| program.first_file <= file);
+
+        Some(self.programs.get(idx.checked_sub(1)?)?.offset)
+    }
+
+    /// Look up the row describing the instruction at `address`.
+    pub fn find_by_address(&self, address: FileAddr) -> Option<&LineRow> {
+        let sequence = self.sequence_containing(address)?;
+        let rows = &self.rows[sequence.rows.clone()];
+
+        let idx = rows.partition_point(|row| row.address <= address);
+
+        rows[..idx].last().filter(|row| !row.end_sequence)
+    }
+
+    /// Look up the rows at which the code of `line` of the source files whose
+    /// path ends with `path` begins. A line may have several such rows within
+    /// a sequence (e.g., a loop condition, or cleanup code), each following a
+    /// row of a different line. If no code corresponds to `line`, the rows of
+    /// the closest following line with code are given instead.
+    pub fn find_by_line(&self, path: &Path, line: u64) -> Vec<&LineRow> {
+        let matching: Vec<bool> = self
+            .files
+            .iter()
+            .map(|file| file.path.ends_with(path))
+            .collect();
+
+        let candidate = |row: &&LineRow| {
+            row.is_stmt && !row.end_sequence && matching.get(row.file) == Some(&true)
+        };
+
+        let Some(best) = self
+            .rows
+            .iter()
+            .filter(candidate)
+            .map(|row| row.line)
+            .filter(|&row_line| row_line >= line)
+            .min()
+        else {
+            return Vec::new();
+        };
+
+        let mut rows = Vec::new();
+
+        for sequence in &self.sequences {
+            let mut previous: Option<&LineRow> = None;
+
+            for row in &self.rows[sequence.rows.clone()] {
+                let starts_run =
+                    previous.is_none_or(|prev| (prev.file, prev.line) != (row.file, row.line));
+
+                if starts_run && candidate(&row) && row.line == best {
+                    rows.push(row);
+                }
+
+                previous = Some(row);
+            }
+        }
+
+        rows
+    }
+
+    /// Return the address at which the prologue of the function whose code
+    /// spans `function` ends: the first row marked as the end of a prologue,
+    /// or otherwise the second address of the function with a row, at which
+    /// its body is assumed to begin.
+    pub fn prologue_end(&self, function: Range<FileAddr>) -> Option<FileAddr> {
+        let sequence = self.sequence_containing(function.start)?;
+
+        let mut rows = self.rows[sequence.rows.clone()]
+            .iter()
+            .filter(|row| function.contains(&row.address) && !row.end_sequence);
+
+        let first = rows.clone().next()?;
+
+        match rows.clone().find(|row| row.prologue_end) {
+            Some(row) => Some(row.address),
+            None => rows
+                .find(|row| row.address > first.address)
+                .map(|row| row.address),
+        }
+    }
+
+    /// Return the sequence whose addresses contain `address`.
+    fn sequence_containing(&self, address: FileAddr) -> Option<&Sequence> {
+        let idx = self
+            .sequences
+            .partition_point(|sequence| sequence.start <= address);
+
+        // Sequences may overlap if the object is malformed, so earlier
+        // sequences are searched as well.
+        self.sequences[..idx]
+            .iter()
+            .rev()
+            .find(|sequence| address < sequence.end)
+    }
+
+    /// Run the line number program of `unit`, at `offset` of `.debug_line`,
+    /// appending its files and sequences to the table. Returns `None` if the
+    /// program is malformed.
+    fn run_program(&mut self, offset: u64, unit: &Unit<'_>, strings: Strings<'_>) -> Option<()> {
+        let mut reader = Reader::new(unit.data, 0);
+
+        let version = reader.u16()?;
+
+        if !(2..=5).contains(&version) {
+            return None;
+        }
+
+        if version >= 5 {
+            // Address and segment selector sizes, which are implied by the
+            // object on x86-64.
+            reader.bytes(2)?;
+        }
+
+        let header_length = usize::try_from(reader.uint(unit.offset_size)?).ok()?;
+        let program = reader.offset().checked_add(header_length)?;
+
+        let min_instruction_length = u64::from(reader.u8()?);
+
+        if version >= 4 {
+            // Maximum operations per instruction, which is 1 on non-VLIW
+            // architectures.
+            reader.u8()?;
+        }
+
+        let default_is_stmt = reader.u8()? != 0;
+        let line_base = i64::from(reader.u8()? as i8);
+        let line_range = reader.u8()?;
+        let opcode_base = reader.u8()?;
+        let opcode_lengths = reader.bytes(usize::from(opcode_base.saturating_sub(1)))?;
+
+        if line_range == 0 {
+            return None;
+        }
+
+        // Files are numbered from 0 by DWARF 5, and from 1 by earlier versions.
+        let first_file = self.files.len();
+        let file_base = if version >= 5 { 0 } else { 1 };
+
+        let directories = if version >= 5 {
+            let directories = parse_entries(&mut reader, unit.offset_size, strings)?;
+            let files = parse_entries(&mut reader, unit.offset_size, strings)?;
+
+            // Directory 0 is the compilation directory, to which the others
+            // are relative.
+            let mut directories: Vec<PathBuf> =
+                directories.into_iter().map(|dir| dir.path).collect();
+
+            if let [compilation, rest @ ..] = directories.as_mut_slice() {
+                for dir in rest {
+                    *dir = compilation.join(&*dir);
+                }
+            }
+
+            for file in files {
+                self.files.push(SourceFile {
+                    path: join(&directories, file.directory, &file.path, 0),
+                    md5: file.md5,
+                });
+            }
+
+            directories
+        } else {
+            let mut directories = Vec::new();
+
+            while let Some(dir) = reader.c_str().filter(|dir| !dir.is_empty()) {
+                directories.push(path_from_bytes(dir));
+            }
+
+            while let Some(name) = reader.c_str().filter(|name| !name.is_empty()) {
+                let file = parse_file_entry(&mut reader, name, &directories)?;
+                self.files.push(file);
+            }
+
+            directories
+        };
+
+        self.programs.push(Program {
+            offset,
+            first_file,
+            file_base,
+        });
+
+        let file_index = |register: u64| {
+            register
+                .checked_sub(file_base)
+                .and_then(|idx| usize::try_from(idx).ok())
+                .and_then(|idx| idx.checked_add(first_file))
+                .unwrap_or(usize::MAX)
+        };
+
+        let initial = LineRow {
+            address: FileAddr(0),
+            file: 0,
+            line: 1,
+            column: 0,
+            is_stmt: default_is_stmt,
+            prologue_end: false,
+            epilogue_begin: false,
+            end_sequence: false,
+        };
+
+        let mut state = initial;
+        let mut file_register = 1;
+        let mut sequence_start = self.rows.len();
+
+        let mut reader = Reader::new(unit.data, program);
+
+        while !reader.is_empty() {
+            let opcode = reader.u8()?;
+            let mut emit = false;
+
+            if opcode >= opcode_base {
+                // Special opcodes advance both the address and line, then
+                // append a row.
+                let adjusted = opcode - opcode_base;
+                let advance = u64::from(adjusted / line_range) * min_instruction_length;
+
+                state.address.0 = state.address.0.wrapping_add(advance);
+                state.line = state
+                    .line
+                    .wrapping_add_signed(line_base + i64::from(adjusted % line_range));
+                emit = true;
+            } else {
+                match opcode {
+                    0 => {
+                        let len = usize::try_from(reader.uleb128()?).ok()?;
+                        let mut operands = Reader::new(reader.bytes(len)?, 0);
+
+                        match operands.u8()? {
+                            DW_LNE_end_sequence => {
+                                state.end_sequence = true;
+                                emit = true;
+                            }
+                            DW_LNE_set_address => {
+                                state.address = FileAddr(operands.uint(len - 1)?);
+                            }
+                            DW_LNE_define_file => {
+                                let name = operands.c_str()?;
+                                let file = parse_file_entry(&mut operands, name, &directories)?;
+                                self.files.push(file);
+                            }
+                            // Discriminators and vendor extensions are ignored.
+                            _ => {}
+                        }
+                    }
+                    DW_LNS_copy => emit = true,
+                    DW_LNS_advance_pc => {
+                        let advance = reader.uleb128()?.wrapping_mul(min_instruction_length);
+                        state.address.0 = state.address.0.wrapping_add(advance);
+                    }
+                    DW_LNS_advance_line => {
+                        state.line = state.line.wrapping_add_signed(reader.sleb128()?);
+                    }
+                    DW_LNS_set_file => file_register = reader.uleb128()?,
+                    DW_LNS_set_column => state.column = reader.uleb128()?,
+                    DW_LNS_negate_stmt => state.is_stmt = !state.is_stmt,
+                    DW_LNS_const_add_pc => {
+                        let advance =
+                            u64::from((255 - opcode_base) / line_range) * min_instruction_length;
+                        state.address.0 = state.address.0.wrapping_add(advance);
+                    }
+                    DW_LNS_fixed_advance_pc => {
+                        state.address.0 = state.address.0.wrapping_add(reader.u16()?.into());
+                    }
+                    DW_LNS_set_prologue_end => state.prologue_end = true,
+                    DW_LNS_set_epilogue_begin => state.epilogue_begin = true,
+                    // Unknown standard opcodes are skipped using the number of
+                    // LEB128 operands given by the header.
+                    _ => {
+                        for _ in 0..opcode_lengths[usize::from(opcode - 1)] {
+                            reader.uleb128()?;
+                        }
+                    }
+                }
+            }
+
+            if !emit {
+                continue;
+            }
+
+            self.rows.push(LineRow {
+                file: file_index(file_register),
+                ..state
+            });
+
+            if state.end_sequence {
+                let rows = sequence_start..self.rows.len();
+                let start = self.rows[sequence_start].address;
+
+                if start.0 == 0 {
+                    self.rows.truncate(sequence_start);
+                } else {
+                    self.sequences.push(Sequence {
+                        start,
+                        end: state.address,
+                        rows,
+                    });
+                }
+
+                sequence_start = self.rows.len();
+                state = initial;
+                file_register = 1;
+            } else {
+                state.prologue_end = false;
+                state.epilogue_begin = false;
+            }
+        }
+
+        // Rows of a sequence cut short by the end of the program are dropped.
+        self.rows.truncate(sequence_start);
+
+        Some(())
+    }
+}
+
+/// Parse the entry format, then the directory or file entries, of a DWARF 5
+/// line number program header.
+fn parse_entries<'a>(
+    reader: &mut Reader<'a>,
+    offset_size: usize,
+    strings: Strings<'a>,
+) -> Option<Vec<Entry>> {
+    let format_count = reader.u8()?;
+    let mut format = Vec::new();
+
+    for _ in 0..format_count {
+        format.push((reader.uleb128()?, reader.uleb128()?));
+    }
+
+    let count = reader.uleb128()?;
+
+    // Entries without any attributes occupy no space, so a bogus count could
+    // otherwise cause an excessive allocation.
+    if format.is_empty() && count > 0 {
+        return None;
+    }
+
+    let mut entries = Vec::new();
+
+    for _ in 0..count {
+        let mut entry = Entry::default();
+
+        for &(content, form) in &format {
+            match (content, read_value(reader, form, offset_size, strings)?) {
+                (DW_LNCT_path, Value::String(path)) => entry.path = path_from_bytes(path),
+                (DW_LNCT_directory_index, Value::Unsigned(idx)) => entry.directory = idx,
+                (DW_LNCT_MD5, Value::Block(digest)) => entry.md5 = digest.try_into().ok(),
+                _ => {}
+            }
+        }
+
+        entries.push(entry);
+    }
+
+    Some(entries)
+}
+
+/// Read an attribute value of the given `form` from a DWARF 5 entry.
+fn read_value<'a>(
+    reader: &mut Reader<'a>,
+    form: u64,
+    offset_size: usize,
+    strings: Strings<'a>,
+) -> Option<Value<'a>> {
+    let value = match form {
+        DW_FORM_string => Value::String(reader.c_str()?),
+        DW_FORM_line_strp => {
+            Value::String(c_str(strings.debug_line_str, reader.uint(offset_size)?)?)
+        }
+        DW_FORM_strp => Value::String(c_str(strings.debug_str, reader.uint(offset_size)?)?),
+        DW_FORM_strx => {
+            reader.uleb128()?;
+            Value::Unresolved
+        }
+        DW_FORM_strx1 | DW_FORM_strx2 | DW_FORM_strx3 | DW_FORM_strx4 => {
+            reader.bytes(usize::try_from(form - DW_FORM_strx1 + 1).ok()?)?;
+            Value::Unresolved
+        }
+        DW_FORM_udata => Value::Unsigned(reader.uleb128()?),
+        DW_FORM_data1 => Value::Unsigned(reader.u8()?.into()),
+        DW_FORM_data2 => Value::Unsigned(reader.u16()?.into()),
+        DW_FORM_data4 => Value::Unsigned(reader.u32()?.into()),
+        DW_FORM_data8 => Value::Unsigned(reader.u64()?),
+        DW_FORM_data16 => Value::Block(reader.bytes(16)?),
+        DW_FORM_block | DW_FORM_block1 | DW_FORM_block2 | DW_FORM_block4 => {
+            let len = match form {
+                DW_FORM_block1 => reader.u8()?.into(),
+                DW_FORM_block2 => reader.u16()?.into(),
+                DW_FORM_block4 => reader.u32()?.into(),
+                _ => reader.uleb128()?,
+            };
+
+            Value::Block(reader.bytes(usize::try_from(len).ok()?)?)
+        }
+        // The size of values of other forms is unknown, so the rest of the
+        // header cannot be parsed.
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/// Parse the rest of a DWARF 4 (or earlier) file entry named `name`, given
+/// either in the header or by `DW_LNE_define_file`.
+fn parse_file_entry(
+    reader: &mut Reader<'_>,
+    name: &[u8],
+    directories: &[PathBuf],
+) -> Option<SourceFile> {
+    let directory = reader.uleb128()?;
+
+    // Modification time and size of the file.
+    reader.uleb128()?;
+    reader.uleb128()?;
+
+    // Directory 0 is the compilation directory, which is not listed.
+    Some(SourceFile {
+        path: join(directories, directory, &path_from_bytes(name), 1),
+        md5: None,
+    })
+}
+
+/// Join `path` to entry `directory` of `directories`, whose first entry has
+/// index `base`. Paths are left as is if they reference no listed directory.
+fn join(directories: &[PathBuf], directory: u64, path: &Path, base: u64) -> PathBuf {
+    directory
+        .checked_sub(base)
+        .and_then(|idx| directories.get(usize::try_from(idx).ok()?))
+        .map_or_else(|| path.to_path_buf(), |dir| dir.join(path))
+}
+
+/// Return the path given by the raw bytes of a string.
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(OsStr::from_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elf::Elf;
+
+    /// Contents of `.debug_line_str` referenced by [`line_program_v5`].
+    const LINE_STR: &[u8] = b"/work\0lib\0main.c\0util.h\0";
+
+    /// Return a DWARF 5 line number program with two sequences, one of which
+    /// starts at address zero.
+    fn line_program_v5() -> Vec<u8> {
+        let mut header = vec![
+            1,    // minimum_instruction_length
+            1,    // maximum_operations_per_instruction
+            1,    // default_is_stmt
+            0xfb, // line_base (-5)
+            14,   // line_range
+            13,   // opcode_base
+            0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1, // standard_opcode_lengths
+        ];
+
+        // Directories: `/work` and `lib`.
+        header.extend([1, 0x1, 0x1f, 2, 0, 0, 0, 0, 6, 0, 0, 0]);
+        // Files: `main.c` in `/work`, and `util.h` in `lib`, with digests.
+        header.extend([3, 0x1, 0x1f, 0x2, 0x0f, 0x5, 0x1e, 2]);
+        header.extend([10, 0, 0, 0, 0]);
+        header.extend([1; 16]);
+        header.extend([17, 0, 0, 0, 1]);
+        header.extend([2; 16]);
+
+        let mut program = vec![0, 9, DW_LNE_set_address];
+        program.extend(0x1000u64.to_le_bytes());
+        program.extend([
+            DW_LNS_set_file,
+            0,
+            DW_LNS_set_column,
+            5,
+            DW_LNS_advance_line,
+            9,
+            DW_LNS_copy, // 0x1000: main.c:10:5
+            75,          // 0x1004: main.c:11:5
+            DW_LNS_set_prologue_end,
+            46, // 0x1006: main.c:11:5, prologue end
+            DW_LNS_set_file,
+            1,
+            DW_LNS_advance_line,
+            0x78, // -8
+            DW_LNS_advance_pc,
+            3,
+            DW_LNS_copy, // 0x1009: util.h:3:5
+            DW_LNS_advance_pc,
+            5,
+            0,
+            1,
+            DW_LNE_end_sequence, // 0x100e
+        ]);
+
+        // A function discarded by the linker.
+        program.extend([0, 9, DW_LNE_set_address]);
+        program.extend(0u64.to_le_bytes());
+        program.extend([DW_LNS_copy, DW_LNS_advance_pc, 4, 0, 1, DW_LNE_end_sequence]);
+
+        let mut unit = vec![5, 0, 8, 0];
+        unit.extend((header.len() as u32).to_le_bytes());
+        unit.extend(header);
+        unit.extend(program);
+
+        let mut data = (unit.len() as u32).to_le_bytes().to_vec();
+        data.extend(unit);
+        data
+    }
+
+    #[test]
+    fn line_table_v5() {
+        let table = LineTable::parse(&line_program_v5(), &[], LINE_STR);
+        assert!(!table.is_empty());
+
+        let paths: Vec<&Path> = table
+            .files()
+            .iter()
+            .map(|file| file.path.as_path())
+            .collect();
+        assert_eq!(
+            paths,
+            [Path::new("/work/main.c"), Path::new("/work/lib/util.h")]
+        );
+        assert_eq!(table.files()[0].md5, Some([1; 16]));
+        assert_eq!(table.files()[1].md5, Some([2; 16]));
+
+        // Files of DWARF 5 programs are numbered from 0.
+        let file = table.program_file(0, 1).unwrap();
+        assert_eq!(file.path, Path::new("/work/lib/util.h"));
+        assert_eq!(table.program_file(0, 2), None);
+        assert_eq!(table.program_file(1, 0), None);
+        assert_eq!(table.program_offset(1), Some(0));
+        assert_eq!(table.program_offset(2), None);
+
+        let row = table.find_by_address(FileAddr(0x1000)).unwrap();
+        assert_eq!((row.file, row.line, row.column), (0, 10, 5));
+        assert!(row.is_stmt);
+
+        let row = table.find_by_address(FileAddr(0x1005)).unwrap();
+        assert_eq!((row.address, row.line), (FileAddr(0x1004), 11));
+        assert!(!row.prologue_end);
+
+        let row = table.find_by_address(FileAddr(0x1006)).unwrap();
+        assert!(row.prologue_end);
+
+        let row = table.find_by_address(FileAddr(0x100d)).unwrap();
+        assert_eq!(table.file(row).unwrap().path, Path::new("/work/lib/util.h"));
+        assert_eq!(row.line, 3);
+
+        let function = FileAddr(0x1000)..FileAddr(0x1009);
+        assert_eq!(table.prologue_end(function), Some(FileAddr(0x1006)));
+        let function = FileAddr(0x1000)..FileAddr(0x1006);
+        assert_eq!(table.prologue_end(function), Some(FileAddr(0x1004)));
+        let function = FileAddr(0x1009)..FileAddr(0x100e);
+        assert_eq!(table.prologue_end(function), None);
+
+        assert_eq!(table.find_by_address(FileAddr(0x0fff)), None);
+        assert_eq!(table.find_by_address(FileAddr(0x100e)), None);
+        assert_eq!(table.find_by_address(FileAddr(0x2)), None);
+
+        let addresses = |path: &str, line| -> Vec<u64> {
+            table
+                .find_by_line(Path::new(path), line)
+                .iter()
+                .map(|row| row.address.0)
+                .collect()
+        };
+
+        assert_eq!(addresses("main.c", 11), [0x1004]);
+        assert_eq!(addresses("/work/main.c", 1), [0x1000]);
+        assert_eq!(addresses("lib/util.h", 3), [0x1009]);
+        assert!(addresses("main.c", 12).is_empty());
+        assert!(addresses("ain.c", 10).is_empty());
+    }
+
+    #[test]
+    fn line_table_malformed() {
+        let data = line_program_v5();
+
+        // Truncated or corrupted programs never panic, and never yield rows of
+        // incomplete sequences.
+        for len in 0..data.len() {
+            let table = LineTable::parse(&data[..len], &[], LINE_STR);
+            assert_eq!(table.find_by_address(FileAddr(0x1000)), None);
+        }
+
+        for idx in 0..data.len() {
+            for byte in [0x00, 0x7f, 0x80, 0xff] {
+                let mut corrupted = data.clone();
+                corrupted[idx] = byte;
+                let _ = LineTable::parse(&corrupted, &[], LINE_STR);
+            }
+        }
+
+        assert!(LineTable::parse(&[], &[], &[]).is_empty());
+    }
+
+    #[test]
+    fn line_table_executable() {
+        let elf = Elf::open("target/debug/stepping").unwrap();
+        let table = elf.line_table();
+        assert!(!table.is_empty());
+
+        // The first line of `main` with code is the `asm!` invocation.
+        let rows = table.find_by_line(Path::new("src/bin/stepping.rs"), 21);
+        let row = rows[0];
+        assert_eq!(row.line, 22);
+        assert!(table.file(row).unwrap().path.ends_with("stepping.rs"));
+
+        let (symbol, _) = elf.symbol_index().find_by_address(row.address).unwrap();
+        assert_eq!(symbol.name, "stepping::main");
+
+        let found = table.find_by_address(FileAddr(row.address.0 + 1)).unwrap();
+        assert_eq!(found.line, 22);
+    }
+}
@@ -0,0 +1,63 @@
+//! Parser of the DWARF debugging information of ELF objects: the line number
+//! information of `.debug_line`, the debugging information entries of
+//! `.debug_info`, and the call frame information of `.eh_frame`.
+//!
+//! Sections are read directly from the bytes of an [`Elf`](crate::elf::Elf),
+//! and every access is bounds-checked, so malformed units are skipped (or
+//! yield missing data) rather than causing a panic.
+
+// Constants keep the names given by the DWARF standard (e.g., `DW_FORM_data1`).
+#![allow(non_upper_case_globals)]
+
+use crate::elf::reader::Reader;
+
+mod constants;
+pub use constants::*;
+
+mod abbrev;
+pub use abbrev::{Abbreviation, Abbreviations, AttributeSpec};
+
+mod die;
+pub use die::{Attribute, AttributeValue, Die, DieTree};
+
+mod frame;
+pub use frame::{CallFrameInfo, CfaRule};
+
+mod info;
+pub use info::{CallSite, CompileUnit, DebugInfo, DebugInfoCache, DwarfSections, Scope};
+
+mod line;
+pub use line::{LineRow, LineTable, SourceFile};
+
+/// A unit of a DWARF section (e.g., a line number program, or a compilation
+/// unit), as delimited by its initial length.
+#[derive(Debug)]
+struct Unit<'a> {
+    /// Contents of the unit, following its initial length.
+    data: &'a [u8],
+    /// Size in bytes of section offsets within the unit: 4 in the 32-bit
+    /// DWARF format, or 8 in the 64-bit format.
+    offset_size: usize,
+    /// Offset of the following unit within the section.
+    end: usize,
+}
+
+/// Split the unit at `offset` of `section`, or return `None` if its initial
+/// length is reserved or extends past the end of the section.
+fn unit_at(section: &[u8], offset: usize) -> Option<Unit<'_>> {
+    let mut reader = Reader::new(section, offset);
+
+    let (len, offset_size) = match reader.u32()? {
+        DW_LENGTH_64 => (reader.u64()?, 8),
+        len if len >= DW_LENGTH_RESERVED => return None,
+        len => (len.into(), 4),
+    };
+
+    let data = reader.bytes(usize::try_from(len).ok()?)?;
+
+    Some(Unit {
+        data,
+        offset_size,
+        end: reader.offset(),
+    })
+}
@@ -0,0 +1,86 @@
+//! Addresses within an ELF object, and within the memory of a tracee.
+//!
+//! Position-independent objects are loaded at an address chosen at runtime,
+//! so the addresses given by their ELF (and DWARF) data differ from the
+//! addresses at which the code and data actually reside. The difference is
+//! the load bias of the object, which is zero only for objects loaded at a
+//! fixed address.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Sub};
+
+use super::Elf;
+
+/// Address as given by the data of an ELF object (e.g., the value of a
+/// symbol), before any load bias is applied.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Default)]
+pub struct FileAddr(pub u64);
+
+/// Address within the virtual memory of a tracee.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Default)]
+pub struct VirtAddr(pub u64);
+
+impl FileAddr {
+    /// Return the address in memory of the given [`FileAddr`] of `elf`,
+    /// according to its load bias.
+    pub fn to_virt(self, elf: &Elf) -> VirtAddr {
+        VirtAddr(self.0.wrapping_add(elf.load_bias()))
+    }
+}
+
+impl VirtAddr {
+    /// Return the address within `elf` of the given [`VirtAddr`], or `None` if
+    /// it does not lie within one of the loadable segments of `elf`.
+    pub fn to_file(self, elf: &Elf) -> Option<FileAddr> {
+        let address = FileAddr(self.0.wrapping_sub(elf.load_bias()));
+        elf.segment_containing(address)?;
+
+        Some(address)
+    }
+}
+
+impl From<u64> for VirtAddr {
+    fn from(address: u64) -> Self {
+        Self(address)
+    }
+}
+
+impl From<VirtAddr> for u64 {
+    fn from(address: VirtAddr) -> Self {
+        address.0
+    }
+}
+
+impl Add<u64> for VirtAddr {
+    type Output = Self;
+
+    fn add(self, offset: u64) -> Self {
+        Self(self.0.wrapping_add(offset))
+    }
+}
+
+impl AddAssign<u64> for VirtAddr {
+    fn add_assign(&mut self, offset: u64) {
+        *self = *self + offset;
+    }
+}
+
+impl Sub<u64> for VirtAddr {
+    type Output = Self;
+
+    fn sub(self, offset: u64) -> Self {
+        Self(self.0.wrapping_sub(offset))
+    }
+}
+
+impl fmt::LowerHex for FileAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl fmt::LowerHex for VirtAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
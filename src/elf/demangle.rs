@@ -0,0 +1,763 @@
+//! Demangling of Rust symbol names, in both the legacy (`_ZN...E`) and `v0`
+//! (`_R...`) schemes.
+//!
+//! Names are demangled without hashes or crate disambiguators (e.g.,
+//! `mycrate::parser::parse::<u8>`), matching how they are written in source.
+
+use std::fmt::Write;
+use std::mem;
+
+/// Maximum nesting of paths and types within a `v0` symbol, bounding recursion
+/// on malformed (or malicious) input.
+const MAX_DEPTH: u32 = 256;
+
+/// Maximum length of a demangled name. Back-references allow a short symbol
+/// to expand exponentially, so longer names are rejected.
+const MAX_LEN: usize = 4096;
+
+/// Demangle the Rust symbol `name`, returning `None` if it is not mangled, or
+/// is malformed.
+pub fn demangle(name: &str) -> Option<String> {
+    if let Some(mangled) = name.strip_prefix("_R") {
+        // Suffixes added by LLVM (e.g., `.llvm.123`) are not part of the name.
+        let mangled = mangled
+            .split_once('.')
+            .map_or(mangled, |(mangled, _)| mangled);
+        return V0::new(mangled).demangle();
+    }
+
+    name.strip_prefix("_ZN").and_then(demangle_legacy)
+}
+
+/// Demangle a legacy symbol, given the path following its `_ZN` prefix. The
+/// path consists of length-prefixed components, ending with `E`.
+fn demangle_legacy(mut mangled: &str) -> Option<String> {
+    let mut components = Vec::new();
+
+    while !mangled.starts_with('E') {
+        let digits = mangled.find(|c: char| !c.is_ascii_digit())?;
+        let len: usize = mangled[..digits].parse().ok()?;
+        let component = mangled.get(digits..digits.checked_add(len)?)?;
+
+        components.push(component);
+        mangled = &mangled[digits + len..];
+    }
+
+    // Suffixes added by LLVM (e.g., `.llvm.123`) follow the path.
+    if !(mangled == "E" || mangled.starts_with("E.")) || components.is_empty() {
+        return None;
+    }
+
+    // The final component is a hash of the form `h` followed by 16 hex digits.
+    if let Some(hash) = components.last()?.strip_prefix('h')
+        && hash.len() == 16
+        && hash.bytes().all(|byte| byte.is_ascii_hexdigit())
+    {
+        components.pop();
+    }
+
+    let mut demangled = String::new();
+
+    for (idx, component) in components.iter().enumerate() {
+        if idx > 0 {
+            demangled.push_str("::");
+        }
+
+        unescape_legacy(component, &mut demangled)?;
+    }
+
+    Some(demangled)
+}
+
+/// Unescape a `component` of a legacy symbol (e.g., `$LT$impl$GT$`) into
+/// `out`.
+fn unescape_legacy(component: &str, out: &mut String) -> Option<()> {
+    // Components starting with an escape are prefixed by an underscore.
+    let mut rest = match component.strip_prefix('_') {
+        Some(rest) if rest.starts_with('$') => rest,
+        _ => component,
+    };
+
+    while !rest.is_empty() {
+        if let Some(escaped) = rest.strip_prefix('$') {
+            let end = escaped.find('$')?;
+
+            let c = match &escaped[..end] {
+                "SP" => '@',
+                "BP" => '*',
+                "RF" => '&',
+                "LT" => '<',
+                "GT" => '>',
+                "LP" => '(',
+                "RP" => ')',
+                "C" => ',',
+                code => {
+                    let hex = code.strip_prefix('u')?;
+                    char::from_u32(u32::from_str_radix(hex, 16).ok()?)?
+                }
+            };
+
+            out.push(c);
+            rest = &escaped[end + 1..];
+        } else if let Some(stripped) = rest.strip_prefix("..") {
+            out.push_str("::");
+            rest = stripped;
+        } else {
+            let c = rest.chars().next()?;
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    Some(())
+}
+
+/// Demangler of a `v0` symbol, printing each production as it is parsed.
+struct V0<'a> {
+    /// The symbol, following its `_R` prefix.
+    mangled: &'a [u8],
+    /// Offset of the next byte to parse.
+    pos: usize,
+    /// The demangled name.
+    out: String,
+    /// Current nesting of paths and types.
+    depth: u32,
+    /// Number of lifetimes bound by enclosing binders (e.g., `for<'a>`).
+    bound_lifetimes: u64,
+}
+
+impl<'a> V0<'a> {
+    /// Creates a new [`V0`] demangler of `mangled`.
+    fn new(mangled: &'a str) -> Self {
+        Self {
+            mangled: mangled.as_bytes(),
+            pos: 0,
+            out: String::new(),
+            depth: 0,
+            bound_lifetimes: 0,
+        }
+    }
+
+    /// Demangle the symbol, ignoring its instantiating crate.
+    fn demangle(mut self) -> Option<String> {
+        // Skip the optional encoding version.
+        self.decimal();
+        self.path(true)?;
+
+        Some(self.out)
+    }
+
+    /// Return the next byte without consuming it.
+    fn peek(&self) -> Option<u8> {
+        self.mangled.get(self.pos).copied()
+    }
+
+    /// Consume the next byte.
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+
+        Some(byte)
+    }
+
+    /// Consume the next byte if it is `byte`.
+    fn eat(&mut self, byte: u8) -> bool {
+        let matches = self.peek() == Some(byte);
+        self.pos += usize::from(matches);
+
+        matches
+    }
+
+    /// Append `s` to the demangled name, failing once it grows too long.
+    fn print(&mut self, s: &str) -> Option<()> {
+        self.out.push_str(s);
+        (self.out.len() <= MAX_LEN).then_some(())
+    }
+
+    /// Parse a decimal number. Numbers have no leading zeros, so a zero is
+    /// always a number on its own.
+    fn decimal(&mut self) -> Option<u64> {
+        if self.eat(b'0') {
+            return Some(0);
+        }
+
+        let start = self.pos;
+
+        while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        std::str::from_utf8(&self.mangled[start..self.pos])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    /// Parse a base-62 number terminated by `_`, where `_` alone is zero and
+    /// other numbers are offset by one.
+    fn base62(&mut self) -> Option<u64> {
+        if self.eat(b'_') {
+            return Some(0);
+        }
+
+        let mut value = 0u64;
+
+        loop {
+            let digit = match self.next()? {
+                byte @ b'0'..=b'9' => byte - b'0',
+                byte @ b'a'..=b'z' => byte - b'a' + 10,
+                byte @ b'A'..=b'Z' => byte - b'A' + 36,
+                b'_' => return value.checked_add(1),
+                _ => return None,
+            };
+
+            value = value.checked_mul(62)?.checked_add(digit.into())?;
+        }
+    }
+
+    /// Parse an optional disambiguator (`s` followed by a base-62 number).
+    fn disambiguator(&mut self) -> Option<u64> {
+        if self.eat(b's') {
+            self.base62()?.checked_add(1)
+        } else {
+            Some(0)
+        }
+    }
+
+    /// Parse an identifier without its disambiguator, returning its bytes.
+    fn ident(&mut self) -> Option<String> {
+        let punycode = self.eat(b'u');
+        let len = usize::try_from(self.decimal()?).ok()?;
+        self.eat(b'_');
+
+        let bytes = self.mangled.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+
+        let ident = std::str::from_utf8(bytes).ok()?;
+
+        // Decoding punycode is not supported, so such identifiers are shown
+        // in their encoded form.
+        Some(if punycode {
+            format!("punycode{{{ident}}}")
+        } else {
+            ident.to_string()
+        })
+    }
+
+    /// Enter a nested path or type, failing if nested too deeply.
+    fn enter(&mut self) -> Option<()> {
+        self.depth += 1;
+        (self.depth <= MAX_DEPTH).then_some(())
+    }
+
+    /// Parse a back-reference (`B` followed by a base-62 number, already
+    /// consumed), returning the offset it refers to.
+    fn backref(&mut self) -> Option<usize> {
+        // The `B` tag has already been consumed.
+        let start = self.pos - 1;
+        let target = usize::try_from(self.base62()?).ok()?;
+
+        // References may only point backwards.
+        (target < start).then_some(target)
+    }
+
+    /// Print the production at the back-referenced offset using `print`,
+    /// then resume parsing after the back-reference.
+    fn print_backref(&mut self, print: fn(&mut Self) -> Option<()>) -> Option<()> {
+        let target = self.backref()?;
+        let resume = mem::replace(&mut self.pos, target);
+
+        print(self)?;
+        self.pos = resume;
+
+        Some(())
+    }
+
+    /// Parse and print a path. Generic arguments of paths in value position
+    /// are introduced by `::` (e.g., `size_of::<u8>`).
+    fn path(&mut self, in_value: bool) -> Option<()> {
+        self.enter()?;
+
+        match self.next()? {
+            b'C' => {
+                self.disambiguator()?;
+                let name = self.ident()?;
+                self.print(&name)?;
+            }
+            b'N' => {
+                let namespace = self.next()?;
+                self.path(in_value)?;
+
+                let disambiguator = self.disambiguator()?;
+                let name = self.ident()?;
+
+                if namespace.is_ascii_uppercase() {
+                    // Special namespaces (e.g., closures) are printed with
+                    // their disambiguator, as they are usually unnamed.
+                    let kind = match namespace {
+                        b'C' => "closure".to_string(),
+                        b'S' => "shim".to_string(),
+                        other => (other as char).to_string(),
+                    };
+
+                    let mut special = format!("::{{{kind}");
+                    if !name.is_empty() {
+                        let _ = write!(special, ":{name}");
+                    }
+                    let _ = write!(special, "#{disambiguator}}}");
+                    self.print(&special)?;
+                } else if namespace.is_ascii_lowercase() {
+                    self.print("::")?;
+                    self.print(&name)?;
+                } else {
+                    return None;
+                }
+            }
+            b'M' => {
+                self.impl_path()?;
+                self.print("<")?;
+                self.ty()?;
+                self.print(">")?;
+            }
+            b'X' => {
+                self.impl_path()?;
+                self.print("<")?;
+                self.ty()?;
+                self.print(" as ")?;
+                self.path(false)?;
+                self.print(">")?;
+            }
+            b'Y' => {
+                self.print("<")?;
+                self.ty()?;
+                self.print(" as ")?;
+                self.path(false)?;
+                self.print(">")?;
+            }
+            b'I' => {
+                self.path(in_value)?;
+
+                if in_value {
+                    self.print("::")?;
+                }
+
+                self.print("<")?;
+                self.sequence(b'E', Self::generic_arg)?;
+                self.print(">")?;
+            }
+            b'B' => {
+                let print: fn(&mut Self) -> Option<()> = if in_value {
+                    |this: &mut Self| this.path(true)
+                } else {
+                    |this: &mut Self| this.path(false)
+                };
+
+                self.print_backref(print)?;
+            }
+            _ => return None,
+        }
+
+        self.depth -= 1;
+
+        Some(())
+    }
+
+    /// Parse the path of an `impl` block, which is not printed.
+    fn impl_path(&mut self) -> Option<()> {
+        self.disambiguator()?;
+
+        // Parse the path into a scratch buffer, then discard it.
+        let len = self.out.len();
+        self.path(false)?;
+        self.out.truncate(len);
+
+        Some(())
+    }
+
+    /// Parse and print items using `item` until the `end` tag, separating
+    /// them with commas.
+    fn sequence(&mut self, end: u8, item: fn(&mut Self) -> Option<()>) -> Option<usize> {
+        let mut count = 0;
+
+        while !self.eat(end) {
+            if count > 0 {
+                self.print(", ")?;
+            }
+
+            item(self)?;
+            count += 1;
+        }
+
+        Some(count)
+    }
+
+    /// Parse and print a generic argument (a lifetime, type, or constant).
+    fn generic_arg(&mut self) -> Option<()> {
+        if self.eat(b'L') {
+            let lifetime = self.base62()?;
+            self.lifetime(lifetime)
+        } else if self.eat(b'K') {
+            self.constant()
+        } else {
+            self.ty()
+        }
+    }
+
+    /// Print the lifetime with the given de Bruijn index, where zero is the
+    /// erased lifetime `'_`.
+    fn lifetime(&mut self, lifetime: u64) -> Option<()> {
+        if lifetime == 0 {
+            return self.print("'_");
+        }
+
+        let depth = self.bound_lifetimes.checked_sub(lifetime)?;
+
+        if depth < 26 {
+            let name = format!("'{}", (b'a' + depth as u8) as char);
+            self.print(&name)
+        } else {
+            self.print(&format!("'_{depth}"))
+        }
+    }
+
+    /// Parse an optional binder of lifetimes (e.g., `for<'a>`), printing it
+    /// and calling `inner` with the lifetimes bound.
+    fn binder(&mut self, inner: impl FnOnce(&mut Self) -> Option<()>) -> Option<()> {
+        let bound = if self.eat(b'G') {
+            self.base62()?.checked_add(1)?
+        } else {
+            0
+        };
+
+        if bound > 0 {
+            self.print("for<")?;
+
+            for idx in 0..bound {
+                if idx > 0 {
+                    self.print(", ")?;
+                }
+
+                self.bound_lifetimes += 1;
+                self.lifetime(1)?;
+            }
+
+            self.print("> ")?;
+        }
+
+        inner(self)?;
+        self.bound_lifetimes -= bound;
+
+        Some(())
+    }
+
+    /// Parse and print a type.
+    fn ty(&mut self) -> Option<()> {
+        if let Some(name) = self.peek().and_then(basic_type) {
+            self.pos += 1;
+            return self.print(name);
+        }
+
+        self.enter()?;
+
+        match self.next()? {
+            tag @ (b'R' | b'Q') => {
+                let mutable = tag == b'Q';
+                self.print("&")?;
+
+                if self.eat(b'L') {
+                    let lifetime = self.base62()?;
+
+                    if lifetime != 0 {
+                        self.lifetime(lifetime)?;
+                        self.print(" ")?;
+                    }
+                }
+
+                if mutable {
+                    self.print("mut ")?;
+                }
+
+                self.ty()?;
+            }
+            b'P' => {
+                self.print("*const ")?;
+                self.ty()?;
+            }
+            b'O' => {
+                self.print("*mut ")?;
+                self.ty()?;
+            }
+            b'A' => {
+                self.print("[")?;
+                self.ty()?;
+                self.print("; ")?;
+                self.constant()?;
+                self.print("]")?;
+            }
+            b'S' => {
+                self.print("[")?;
+                self.ty()?;
+                self.print("]")?;
+            }
+            b'T' => {
+                self.print("(")?;
+                let count = self.sequence(b'E', Self::ty)?;
+
+                if count == 1 {
+                    self.print(",")?;
+                }
+
+                self.print(")")?;
+            }
+            b'F' => self.binder(Self::fn_sig)?,
+            b'D' => {
+                self.print("dyn ")?;
+                self.binder(|this| {
+                    let mut count = 0;
+
+                    while !this.eat(b'E') {
+                        if count > 0 {
+                            this.print(" + ")?;
+                        }
+
+                        this.dyn_trait()?;
+                        count += 1;
+                    }
+
+                    Some(())
+                })?;
+
+                // The lifetime bound of the trait object.
+                if !self.eat(b'L') {
+                    return None;
+                }
+
+                let lifetime = self.base62()?;
+
+                if lifetime != 0 {
+                    self.print(" + ")?;
+                    self.lifetime(lifetime)?;
+                }
+            }
+            b'B' => self.print_backref(Self::ty)?,
+            _ => {
+                // Any other type is a path.
+                self.pos -= 1;
+                self.path(false)?;
+            }
+        }
+
+        self.depth -= 1;
+
+        Some(())
+    }
+
+    /// Parse and print a function signature, following its binder.
+    fn fn_sig(&mut self) -> Option<()> {
+        if self.eat(b'U') {
+            self.print("unsafe ")?;
+        }
+
+        if self.eat(b'K') {
+            let abi = if self.eat(b'C') {
+                "C".to_string()
+            } else {
+                self.ident()?.replace('_', "-")
+            };
+
+            self.print(&format!("extern \"{abi}\" "))?;
+        }
+
+        self.print("fn(")?;
+        self.sequence(b'E', Self::ty)?;
+        self.print(")")?;
+
+        // Functions returning `()` do not show their return type.
+        if self.eat(b'u') {
+            return Some(());
+        }
+
+        self.print(" -> ")?;
+        self.ty()
+    }
+
+    /// Parse and print a trait of a trait object, along with its associated
+    /// type bindings (e.g., `Iterator<Item = u8>`).
+    fn dyn_trait(&mut self) -> Option<()> {
+        let len = self.out.len();
+        self.path(false)?;
+
+        let mut bindings = 0;
+
+        while self.eat(b'p') {
+            // Bindings are added to the generic arguments of the trait, if any.
+            if bindings == 0 {
+                if self.out.ends_with('>') && self.out.len() > len {
+                    self.out.pop();
+                    self.print(", ")?;
+                } else {
+                    self.print("<")?;
+                }
+            } else {
+                self.print(", ")?;
+            }
+
+            let name = self.ident()?;
+            self.print(&name)?;
+            self.print(" = ")?;
+            self.ty()?;
+            bindings += 1;
+        }
+
+        if bindings > 0 {
+            self.print(">")?;
+        }
+
+        Some(())
+    }
+
+    /// Parse and print a constant generic argument. Only integers, `bool`,
+    /// and `char` values are supported.
+    fn constant(&mut self) -> Option<()> {
+        if self.eat(b'B') {
+            return self.print_backref(Self::constant);
+        }
+
+        // Placeholder for an unknown constant.
+        if self.eat(b'p') {
+            return self.print("_");
+        }
+
+        let ty = self.next()?;
+        let negative = self.eat(b'n');
+
+        let start = self.pos;
+        while matches!(self.peek()?, b'0'..=b'9' | b'a'..=b'f') {
+            self.pos += 1;
+        }
+
+        let hex = std::str::from_utf8(&self.mangled[start..self.pos]).ok()?;
+        if !self.eat(b'_') {
+            return None;
+        }
+
+        let value = if hex.is_empty() {
+            0
+        } else {
+            u128::from_str_radix(hex, 16).ok()?
+        };
+
+        let literal = match ty {
+            b'a' | b's' | b'l' | b'x' | b'n' | b'i' if negative => format!("-{value}"),
+            b'a' | b's' | b'l' | b'x' | b'n' | b'i' | b'h' | b't' | b'm' | b'y' | b'o' | b'j'
+                if !negative =>
+            {
+                value.to_string()
+            }
+            b'b' if !negative => match value {
+                0 => "false".to_string(),
+                1 => "true".to_string(),
+                _ => return None,
+            },
+            b'c' if !negative => {
+                let c = char::from_u32(u32::try_from(value).ok()?)?;
+                format!("{c:?}")
+            }
+            _ => return None,
+        };
+
+        self.print(&literal)
+    }
+}
+
+/// Return the name of the basic type with the given `tag`.
+fn basic_type(tag: u8) -> Option<&'static str> {
+    Some(match tag {
+        b'a' => "i8",
+        b'b' => "bool",
+        b'c' => "char",
+        b'd' => "f64",
+        b'e' => "str",
+        b'f' => "f32",
+        b'h' => "u8",
+        b'i' => "isize",
+        b'j' => "usize",
+        b'l' => "i32",
+        b'm' => "u32",
+        b'n' => "i128",
+        b'o' => "u128",
+        b'p' => "_",
+        b's' => "i16",
+        b't' => "u16",
+        b'u' => "()",
+        b'v' => "...",
+        b'x' => "i64",
+        b'y' => "u64",
+        b'z' => "!",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn demangle_legacy_symbols() {
+        assert_eq!(
+            demangle("_ZN7mycrate6parser5parse17h0123456789abcdefE").as_deref(),
+            Some("mycrate::parser::parse")
+        );
+        assert_eq!(
+            demangle("_ZN4core3ptr85drop_in_place$LT$std..rt..lang_start$LT$$LP$$RP$$GT$..$u7b$$u7b$closure$u7d$$u7d$$GT$17h0123456789abcdefE.llvm.42")
+                .as_deref(),
+            Some("core::ptr::drop_in_place<std::rt::lang_start<()>::{{closure}}>")
+        );
+        assert_eq!(
+            demangle("_ZN66_$LT$alloc..vec..Vec$LT$T$GT$$u20$as$u20$core..ops..drop..Drop$GT$4drop17h0123456789abcdefE")
+                .as_deref(),
+            Some("<alloc::vec::Vec<T> as core::ops::drop::Drop>::drop")
+        );
+
+        assert_eq!(demangle("main"), None);
+        assert_eq!(demangle("_ZN5abc"), None);
+        assert_eq!(demangle("_ZN99abcE"), None);
+    }
+
+    #[test]
+    fn demangle_v0_symbols() {
+        assert_eq!(
+            demangle("_RNvNtCs1234_7mycrate6parser5parse").as_deref(),
+            Some("mycrate::parser::parse")
+        );
+        assert_eq!(
+            demangle("_RINvMNtCsgEmfK2I1SDS_4core5sliceSh11copy_withinINtNtNtB5_3ops5range14RangeInclusivejEECsfEOYDRpO4Ta_11miniz_oxide")
+                .as_deref(),
+            Some("<[u8]>::copy_within::<core::ops::range::RangeInclusive<usize>>")
+        );
+        assert_eq!(
+            demangle("_RNCNvCs1234_7mycrate4main0B3_").as_deref(),
+            Some("mycrate::main::{closure#0}")
+        );
+        assert_eq!(
+            demangle("_RNvXs_Cs1234_7mycrateNtB4_3FooNtNtCs5678_4core3fmt7Display3fmt").as_deref(),
+            Some("<mycrate::Foo as core::fmt::Display>::fmt")
+        );
+        assert_eq!(
+            demangle("_RINvCs1234_7mycrate3fooKj2a_Kanb_EB2_").as_deref(),
+            Some("mycrate::foo::<42, -11>")
+        );
+        assert_eq!(
+            demangle("_RINvCs1234_7mycrate3fooFG_KCRL0_hEuEB2_").as_deref(),
+            Some("mycrate::foo::<for<'a> extern \"C\" fn(&'a u8)>")
+        );
+
+        // Malformed symbols, including back-references which do not point
+        // backwards, are rejected.
+        assert_eq!(demangle("_R"), None);
+        assert_eq!(demangle("_RNvCs1234_7mycrate"), None);
+        assert_eq!(demangle("_RB_"), None);
+        assert_eq!(demangle("_RNvB0_3foo"), None);
+        assert_eq!(demangle(&format!("_R{}", "I".repeat(1000))), None);
+    }
+}
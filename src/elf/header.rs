@@ -0,0 +1,256 @@
+//! ELF64 file, program, and section headers.
+
+use super::reader::Reader;
+use crate::Result;
+
+/// Size in bytes of the ELF64 file header.
+pub const FILE_HEADER_SIZE: usize = 64;
+/// Size in bytes of an ELF64 program header.
+pub const PROGRAM_HEADER_SIZE: usize = 56;
+/// Size in bytes of an ELF64 section header.
+pub const SECTION_HEADER_SIZE: usize = 64;
+
+/// Magic number at the start of every ELF file.
+pub const ELF_MAGIC: [u8; 4] = *b"\x7fELF";
+/// `e_ident[EI_CLASS]` of 64-bit objects.
+pub const ELFCLASS64: u8 = 2;
+/// `e_ident[EI_DATA]` of little-endian objects.
+pub const ELFDATA2LSB: u8 = 1;
+
+/// Relocatable file.
+pub const ET_REL: u16 = 1;
+/// Executable file, loaded at a fixed address.
+pub const ET_EXEC: u16 = 2;
+/// Shared object, or position-independent executable.
+pub const ET_DYN: u16 = 3;
+/// Core file.
+pub const ET_CORE: u16 = 4;
+
+/// Machine type of x86-64 objects.
+pub const EM_X86_64: u16 = 62;
+
+/// Loadable segment.
+pub const PT_LOAD: u32 = 1;
+/// Dynamic linking information.
+pub const PT_DYNAMIC: u32 = 2;
+/// Path of the program interpreter.
+pub const PT_INTERP: u32 = 3;
+/// Auxiliary information, in the form of notes.
+pub const PT_NOTE: u32 = 4;
+/// Location of the program header table itself.
+pub const PT_PHDR: u32 = 6;
+/// Thread-local storage template.
+pub const PT_TLS: u32 = 7;
+
+/// Executable segment.
+pub const PF_X: u32 = 0x1;
+/// Writable segment.
+pub const PF_W: u32 = 0x2;
+/// Readable segment.
+pub const PF_R: u32 = 0x4;
+
+/// Inactive section header.
+pub const SHT_NULL: u32 = 0;
+/// Section of program-defined data (e.g., `.text` or `.debug_info`).
+pub const SHT_PROGBITS: u32 = 1;
+/// Symbol table used for link editing (`.symtab`).
+pub const SHT_SYMTAB: u32 = 2;
+/// String table.
+pub const SHT_STRTAB: u32 = 3;
+/// Section of notes.
+pub const SHT_NOTE: u32 = 7;
+/// Section which occupies no space in the file (e.g., `.bss`).
+pub const SHT_NOBITS: u32 = 8;
+/// Symbol table used for dynamic linking (`.dynsym`).
+pub const SHT_DYNSYM: u32 = 11;
+
+/// Undefined section index.
+pub const SHN_UNDEF: u16 = 0;
+/// Section index stored elsewhere, as the real index is too large.
+pub const SHN_XINDEX: u16 = 0xffff;
+
+/// Section occupies memory during execution.
+pub const SHF_ALLOC: u64 = 0x2;
+/// Section contains executable instructions.
+pub const SHF_EXECINSTR: u64 = 0x4;
+/// Section holds compressed data (e.g., zlib-compressed DWARF).
+pub const SHF_COMPRESSED: u64 = 0x800;
+
+/// The ELF64 file header (`Elf64_Ehdr`).
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct FileHeader {
+    /// Operating system ABI (`e_ident[EI_OSABI]`).
+    pub os_abi: u8,
+    /// Object file type (e.g., [`ET_DYN`]).
+    pub file_type: u16,
+    /// Target machine (e.g., [`EM_X86_64`]).
+    pub machine: u16,
+    /// Object file version.
+    pub version: u32,
+    /// Virtual address of the entry point.
+    pub entry: u64,
+    /// File offset of the program header table.
+    pub phoff: u64,
+    /// File offset of the section header table.
+    pub shoff: u64,
+    /// Processor-specific flags.
+    pub flags: u32,
+    /// Size of the file header.
+    pub ehsize: u16,
+    /// Size of each program header.
+    pub phentsize: u16,
+    /// Number of program headers.
+    pub phnum: u16,
+    /// Size of each section header.
+    pub shentsize: u16,
+    /// Number of section headers, or zero if stored in the first section.
+    pub shnum: u16,
+    /// Section index of the section name string table.
+    pub shstrndx: u16,
+}
+
+impl FileHeader {
+    /// Parse the file header at the start of `data`, validating that it is a
+    /// little-endian ELF64 object.
+    pub(crate) fn parse(data: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(data, 0);
+        let ident = reader
+            .bytes(16)
+            .ok_or("file is too small to contain an ELF header")?;
+
+        if ident[..4] != ELF_MAGIC {
+            return Err("file is not an ELF object (bad magic number)".into());
+        }
+
+        if ident[4] != ELFCLASS64 {
+            return Err(format!("unsupported ELF class {}: expected ELF64", ident[4]).into());
+        }
+
+        if ident[5] != ELFDATA2LSB {
+            return Err(format!(
+                "unsupported ELF data encoding {}: expected little-endian",
+                ident[5]
+            )
+            .into());
+        }
+
+        let os_abi = ident[7];
+
+        let mut parse = || {
+            Some(FileHeader {
+                os_abi,
+                file_type: reader.u16()?,
+                machine: reader.u16()?,
+                version: reader.u32()?,
+                entry: reader.u64()?,
+                phoff: reader.u64()?,
+                shoff: reader.u64()?,
+                flags: reader.u32()?,
+                ehsize: reader.u16()?,
+                phentsize: reader.u16()?,
+                phnum: reader.u16()?,
+                shentsize: reader.u16()?,
+                shnum: reader.u16()?,
+                shstrndx: reader.u16()?,
+            })
+        };
+
+        parse().ok_or_else(|| "truncated ELF header".into())
+    }
+}
+
+/// An ELF64 program header (`Elf64_Phdr`), describing a segment.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct ProgramHeader {
+    /// Segment type (e.g., [`PT_LOAD`]).
+    pub segment_type: u32,
+    /// Segment permissions (e.g., [`PF_R`] | [`PF_X`]).
+    pub flags: u32,
+    /// File offset of the segment.
+    pub offset: u64,
+    /// Virtual address of the segment.
+    pub vaddr: u64,
+    /// Physical address of the segment (unused on Linux).
+    pub paddr: u64,
+    /// Size of the segment in the file.
+    pub filesz: u64,
+    /// Size of the segment in memory.
+    pub memsz: u64,
+    /// Alignment of the segment.
+    pub align: u64,
+}
+
+impl ProgramHeader {
+    /// Parse the program header starting at the current offset of `reader`.
+    pub(crate) fn parse(reader: &mut Reader<'_>) -> Option<Self> {
+        Some(Self {
+            segment_type: reader.u32()?,
+            flags: reader.u32()?,
+            offset: reader.u64()?,
+            vaddr: reader.u64()?,
+            paddr: reader.u64()?,
+            filesz: reader.u64()?,
+            memsz: reader.u64()?,
+            align: reader.u64()?,
+        })
+    }
+
+    /// Return `true` if the segment's virtual memory contains `address`.
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.vaddr && address - self.vaddr < self.memsz
+    }
+}
+
+/// An ELF64 section header (`Elf64_Shdr`), along with its resolved name.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SectionHeader {
+    /// Name of the section (e.g., `.text`), or empty if it could not be
+    /// resolved.
+    pub name: String,
+    /// Offset of the name within the section name string table.
+    pub name_offset: u32,
+    /// Section type (e.g., [`SHT_SYMTAB`]).
+    pub section_type: u32,
+    /// Section attributes (e.g., [`SHF_ALLOC`]).
+    pub flags: u64,
+    /// Virtual address of the section in memory, or zero.
+    pub addr: u64,
+    /// File offset of the section.
+    pub offset: u64,
+    /// Size of the section.
+    pub size: u64,
+    /// Index of an associated section (e.g., the string table of a symbol
+    /// table).
+    pub link: u32,
+    /// Extra type-dependent information.
+    pub info: u32,
+    /// Alignment of the section.
+    pub addralign: u64,
+    /// Size of each entry, for sections holding a table of entries.
+    pub entsize: u64,
+}
+
+impl SectionHeader {
+    /// Parse the section header starting at the current offset of `reader`,
+    /// leaving its name unresolved.
+    pub(crate) fn parse(reader: &mut Reader<'_>) -> Option<Self> {
+        Some(Self {
+            name: String::new(),
+            name_offset: reader.u32()?,
+            section_type: reader.u32()?,
+            flags: reader.u64()?,
+            addr: reader.u64()?,
+            offset: reader.u64()?,
+            size: reader.u64()?,
+            link: reader.u32()?,
+            info: reader.u32()?,
+            addralign: reader.u64()?,
+            entsize: reader.u64()?,
+        })
+    }
+
+    /// Return `true` if the section's virtual memory contains `address`.
+    pub fn contains(&self, address: u64) -> bool {
+        self.flags & SHF_ALLOC != 0 && address >= self.addr && address - self.addr < self.size
+    }
+}
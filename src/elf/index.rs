@@ -0,0 +1,252 @@
+//! Index of the symbols of an ELF object, by address and by name.
+
+use std::collections::HashMap;
+
+use super::demangle::demangle;
+use super::{
+    FileAddr, STB_GLOBAL, STB_WEAK, STT_FUNC, STT_GNU_IFUNC, STT_NOTYPE, STT_OBJECT, Symbol,
+};
+
+/// A defined symbol of an [`SymbolIndex`], with its name demangled.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IndexedSymbol {
+    /// Demangled name of the symbol (e.g., `mycrate::parser::parse`), or its
+    /// linkage name if it is not mangled.
+    pub name: String,
+    /// Name of the symbol as written in the symbol table.
+    pub linkage_name: String,
+    /// Address of the symbol, as given by the object file.
+    pub address: FileAddr,
+    /// Size of the object the symbol refers to, or zero if unknown.
+    pub size: u64,
+    /// Type of the symbol (e.g., [`STT_FUNC`]).
+    pub kind: u8,
+    /// Binding of the symbol (e.g., [`STB_GLOBAL`]).
+    pub binding: u8,
+}
+
+impl IndexedSymbol {
+    /// Return `true` if the symbol refers to executable code.
+    pub fn is_function(&self) -> bool {
+        matches!(self.kind, STT_FUNC | STT_GNU_IFUNC)
+    }
+}
+
+/// Index of the defined function and data symbols of an object, supporting
+/// lookups by address and by (linkage or demangled) name in sub-linear time.
+#[derive(Debug, Default)]
+pub struct SymbolIndex {
+    /// Symbols, sorted by address.
+    symbols: Vec<IndexedSymbol>,
+    /// Indices into `symbols` of the preferred symbol at each distinct
+    /// address, sorted by address.
+    by_address: Vec<usize>,
+    /// Indices into `symbols` of the symbols with a given name, either
+    /// demangled or as written in the symbol table.
+    by_name: HashMap<String, Vec<usize>>,
+}
+
+impl SymbolIndex {
+    /// Creates a new [`SymbolIndex`] of the defined symbols among `symbols`
+    /// (e.g., the combined `.symtab` and `.dynsym` entries of an object).
+    /// Symbols appearing in several tables are indexed once.
+    pub fn new<'a, I>(symbols: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Symbol>,
+    {
+        let mut symbols: Vec<IndexedSymbol> = symbols
+            .into_iter()
+            .filter(|symbol| {
+                !symbol.is_undefined()
+                    && !symbol.name.is_empty()
+                    && matches!(
+                        symbol.kind(),
+                        STT_NOTYPE | STT_OBJECT | STT_FUNC | STT_GNU_IFUNC
+                    )
+            })
+            .map(|symbol| IndexedSymbol {
+                name: demangle(&symbol.name).unwrap_or_else(|| symbol.name.clone()),
+                linkage_name: symbol.name.clone(),
+                address: FileAddr(symbol.value),
+                size: symbol.size,
+                kind: symbol.kind(),
+                binding: symbol.binding(),
+            })
+            .collect();
+
+        // Order the symbols at each address by preference, so the first is the
+        // one used to describe it: functions, then global (or weak) symbols,
+        // then those of known size, then public names over internal aliases
+        // (e.g., `malloc` over `__libc_malloc`).
+        symbols.sort_by(|a, b| {
+            a.address
+                .cmp(&b.address)
+                .then_with(|| b.is_function().cmp(&a.is_function()))
+                .then_with(|| binding_rank(b).cmp(&binding_rank(a)))
+                .then_with(|| b.size.cmp(&a.size))
+                .then_with(|| leading_underscores(a).cmp(&leading_underscores(b)))
+                .then_with(|| a.linkage_name.cmp(&b.linkage_name))
+        });
+        symbols.dedup_by(|a, b| a.address == b.address && a.linkage_name == b.linkage_name);
+
+        let mut by_address = Vec::new();
+        let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for (idx, symbol) in symbols.iter().enumerate() {
+            if idx == 0 || symbols[idx - 1].address != symbol.address {
+                by_address.push(idx);
+            }
+
+            by_name.entry(symbol.name.clone()).or_default().push(idx);
+
+            if symbol.linkage_name != symbol.name {
+                by_name
+                    .entry(symbol.linkage_name.clone())
+                    .or_default()
+                    .push(idx);
+            }
+        }
+
+        Self {
+            symbols,
+            by_address,
+            by_name,
+        }
+    }
+
+    /// Return the number of indexed symbols.
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Return `true` if no symbols are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Return an iterator over the indexed symbols, in order of address.
+    pub fn iter(&self) -> impl Iterator<Item = &IndexedSymbol> {
+        self.symbols.iter()
+    }
+
+    /// Look up the symbol containing `address`, returning it along with the
+    /// offset of `address` from its start. Symbols of unknown size (e.g.,
+    /// labels in assembly) are taken to extend up to the next symbol.
+    pub fn find_by_address(&self, address: FileAddr) -> Option<(&IndexedSymbol, u64)> {
+        // Find the last address at or before `address`.
+        let idx = self
+            .by_address
+            .partition_point(|&idx| self.symbols[idx].address <= address)
+            .checked_sub(1)?;
+
+        let symbol = &self.symbols[self.by_address[idx]];
+        let offset = address.0 - symbol.address.0;
+
+        (symbol.size == 0 || offset < symbol.size).then_some((symbol, offset))
+    }
+
+    /// Look up the symbols with the given `name`, which may be either
+    /// demangled (e.g., `mycrate::main`) or as written in the symbol table.
+    pub fn find_by_name(&self, name: &str) -> Vec<&IndexedSymbol> {
+        self.by_name
+            .get(name)
+            .map(|indices| indices.iter().map(|&idx| &self.symbols[idx]).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Return the preference of the binding of `symbol` when describing an
+/// address, where global symbols are preferred over weak and local ones.
+fn binding_rank(symbol: &IndexedSymbol) -> u8 {
+    match symbol.binding {
+        STB_GLOBAL => 2,
+        STB_WEAK => 1,
+        _ => 0,
+    }
+}
+
+/// Return the number of leading underscores of the linkage name of `symbol`,
+/// which usually mark internal names.
+fn leading_underscores(symbol: &IndexedSymbol) -> usize {
+    symbol.linkage_name.len() - symbol.linkage_name.trim_start_matches('_').len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elf::STB_LOCAL;
+
+    /// Creates a new defined [`Symbol`] with the given `info` (type and
+    /// binding).
+    fn symbol(name: &str, value: u64, size: u64, kind: u8, binding: u8) -> Symbol {
+        Symbol {
+            name: name.to_string(),
+            name_offset: 0,
+            info: (binding << 4) | kind,
+            other: 0,
+            shndx: 1,
+            value,
+            size,
+        }
+    }
+
+    #[test]
+    fn symbol_index_lookups() {
+        let mut undefined = symbol("printf", 0, 0, STT_FUNC, STB_GLOBAL);
+        undefined.shndx = 0;
+
+        let symbols = [
+            symbol("main", 0x1100, 0x20, STT_FUNC, STB_GLOBAL),
+            symbol(
+                "_RNvCs1234_7mycrate4main",
+                0x1000,
+                0x40,
+                STT_FUNC,
+                STB_LOCAL,
+            ),
+            symbol("data_start", 0x4000, 0, STT_NOTYPE, STB_WEAK),
+            symbol("__data_start", 0x4000, 0, STT_NOTYPE, STB_GLOBAL),
+            symbol("COUNTER", 0x4008, 8, STT_OBJECT, STB_LOCAL),
+            symbol("main", 0x1100, 0x20, STT_FUNC, STB_GLOBAL),
+            symbol("__libc_malloc", 0x1200, 0x10, STT_FUNC, STB_GLOBAL),
+            symbol("malloc", 0x1200, 0x10, STT_FUNC, STB_GLOBAL),
+            undefined,
+        ];
+
+        let index = SymbolIndex::new(&symbols);
+        assert_eq!(index.len(), 7);
+
+        let (main, offset) = index.find_by_address(FileAddr(0x111c)).unwrap();
+        assert_eq!((main.name.as_str(), offset), ("main", 0x1c));
+        assert!(index.find_by_address(FileAddr(0x1120)).is_none());
+
+        let (symbol, offset) = index.find_by_address(FileAddr(0x1000)).unwrap();
+        assert_eq!((symbol.name.as_str(), offset), ("mycrate::main", 0));
+
+        // Sizeless symbols extend up to the next symbol, preferring global
+        // symbols over weak ones.
+        let (symbol, offset) = index.find_by_address(FileAddr(0x4004)).unwrap();
+        assert_eq!((symbol.name.as_str(), offset), ("__data_start", 4));
+
+        // Public names are preferred over internal aliases.
+        let (symbol, _) = index.find_by_address(FileAddr(0x1200)).unwrap();
+        assert_eq!(symbol.name, "malloc");
+        assert_eq!(
+            index.find_by_address(FileAddr(0x400f)).unwrap().0.name,
+            "COUNTER"
+        );
+        assert!(index.find_by_address(FileAddr(0x4010)).is_none());
+        assert!(index.find_by_address(FileAddr(0xfff)).is_none());
+
+        assert_eq!(index.find_by_name("main").len(), 1);
+        assert_eq!(
+            index.find_by_name("mycrate::main")[0].address,
+            FileAddr(0x1000)
+        );
+        assert_eq!(
+            index.find_by_name("_RNvCs1234_7mycrate4main")[0].name,
+            "mycrate::main"
+        );
+        assert!(index.find_by_name("printf").is_empty());
+    }
+}
@@ -0,0 +1,611 @@
+//! Minimal parser of ELF64 objects, such as the executable of a tracee.
+//!
+//! The file is memory-mapped and its headers, symbol tables, and notes are
+//! parsed eagerly. Every access to the underlying bytes is bounds-checked, so
+//! malformed or truncated files result in an error (or missing data) rather
+//! than a panic.
+
+use std::cell::OnceCell;
+use std::fs::File;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::{fmt, ptr, slice};
+
+use crate::Result;
+use crate::dwarf::{CallFrameInfo, DebugInfo, DebugInfoCache, DwarfSections, LineTable};
+use crate::utils::errno;
+
+use libc::{MAP_FAILED, MAP_PRIVATE, PROT_READ, c_void};
+
+mod address;
+pub use address::{FileAddr, VirtAddr};
+
+mod demangle;
+pub use demangle::demangle;
+
+mod header;
+pub use header::*;
+
+mod index;
+pub use index::{IndexedSymbol, SymbolIndex};
+
+mod note;
+pub use note::{GNU_NOTE_NAME, NT_GNU_BUILD_ID, Note};
+
+pub(crate) mod reader;
+use reader::{Reader, c_str, slice};
+
+mod symbol;
+pub use symbol::*;
+
+/// A parsed ELF64 object.
+#[derive(Debug)]
+pub struct Elf {
+    /// Path of the object, or empty if it was parsed from memory.
+    path: PathBuf,
+    /// Raw bytes of the object.
+    data: Storage,
+    /// The file header.
+    header: FileHeader,
+    /// Program headers, describing the segments of the object.
+    program_headers: Vec<ProgramHeader>,
+    /// Section headers, with their names resolved.
+    section_headers: Vec<SectionHeader>,
+    /// Entries of the `.symtab` section.
+    symbols: Vec<Symbol>,
+    /// Entries of the `.dynsym` section.
+    dynamic_symbols: Vec<Symbol>,
+    /// Notes of the object (e.g., its build ID).
+    notes: Vec<Note>,
+    /// Index of the symbols of both symbol tables, built on first use.
+    symbol_index: OnceCell<SymbolIndex>,
+    /// Rows of the DWARF line number programs, built on first use.
+    line_table: OnceCell<LineTable>,
+    /// Units and abbreviation tables of `.debug_info`, parsed on first use.
+    debug_info: DebugInfoCache,
+    /// Difference between the addresses at which the object is loaded and
+    /// those given by its data, or zero if it is not loaded.
+    load_bias: u64,
+}
+
+impl Elf {
+    /// Memory-map and parse the ELF object at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        let file = File::open(path)
+            .map_err(|err| format!("failed to open '{}': {err}", path.display()))?;
+        let len = file
+            .metadata()
+            .map_err(|err| format!("failed to stat '{}': {err}", path.display()))?
+            .len();
+
+        // Mapping an empty file fails, and it could not be parsed anyway.
+        let data = if len == 0 {
+            Storage::Owned(Vec::new())
+        } else {
+            Storage::Mapped(Mapping::new(&file, len)?)
+        };
+
+        Self::from_storage(path.to_path_buf(), data)
+            .map_err(|err| format!("failed to parse '{}': {err}", path.display()).into())
+    }
+
+    /// Parse the ELF object held in `data`.
+    pub fn parse(data: Vec<u8>) -> Result<Self> {
+        Self::from_storage(PathBuf::new(), Storage::Owned(data))
+    }
+
+    /// Parse the ELF object held in `data`, read from `path`.
+    fn from_storage(path: PathBuf, data: Storage) -> Result<Self> {
+        let bytes = data.bytes();
+
+        let header = FileHeader::parse(bytes)?;
+        let program_headers = parse_program_headers(bytes, &header)?;
+        let section_headers = parse_section_headers(bytes, &header)?;
+
+        let mut elf = Self {
+            path,
+            header,
+            program_headers,
+            section_headers,
+            symbols: Vec::new(),
+            dynamic_symbols: Vec::new(),
+            notes: Vec::new(),
+            symbol_index: OnceCell::new(),
+            line_table: OnceCell::new(),
+            debug_info: DebugInfoCache::new(),
+            load_bias: 0,
+            data,
+        };
+
+        elf.symbols = elf.parse_symbols(SHT_SYMTAB);
+        elf.dynamic_symbols = elf.parse_symbols(SHT_DYNSYM);
+        elf.notes = elf.parse_notes();
+
+        Ok(elf)
+    }
+
+    /// Parse the entries of the first symbol table of the given `section_type`
+    /// (i.e., [`SHT_SYMTAB`] or [`SHT_DYNSYM`]).
+    fn parse_symbols(&self, section_type: u32) -> Vec<Symbol> {
+        let Some(section) = self
+            .section_headers
+            .iter()
+            .find(|section| section.section_type == section_type)
+        else {
+            return Vec::new();
+        };
+
+        let Some(table) = self.section_data(section) else {
+            return Vec::new();
+        };
+
+        let strtab = usize::try_from(section.link)
+            .ok()
+            .and_then(|link| self.section_headers.get(link))
+            .and_then(|strtab| self.section_data(strtab))
+            .unwrap_or_default();
+
+        let entsize = usize::try_from(section.entsize)
+            .unwrap_or(0)
+            .max(symbol::SYMBOL_SIZE);
+
+        table
+            .chunks_exact(entsize)
+            .filter_map(|entry| Symbol::parse(&mut Reader::new(entry, 0), strtab))
+            .collect()
+    }
+
+    /// Parse the notes of every `SHT_NOTE` section, falling back to the
+    /// `PT_NOTE` segments if the section headers have been stripped.
+    fn parse_notes(&self) -> Vec<Note> {
+        let bytes = self.data();
+
+        if !self.section_headers.is_empty() {
+            return self
+                .section_headers
+                .iter()
+                .filter(|section| section.section_type == SHT_NOTE)
+                .filter_map(|section| Some((self.section_data(section)?, section.addralign)))
+                .flat_map(|(data, align)| Note::parse_all(data, align))
+                .collect();
+        }
+
+        self.program_headers
+            .iter()
+            .filter(|segment| segment.segment_type == PT_NOTE)
+            .filter_map(|segment| {
+                Some((slice(bytes, segment.offset, segment.filesz)?, segment.align))
+            })
+            .flat_map(|(data, align)| Note::parse_all(data, align))
+            .collect()
+    }
+
+    /// Return the path of the object, which is empty if it was parsed from
+    /// memory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Return the raw bytes of the object.
+    pub fn data(&self) -> &[u8] {
+        self.data.bytes()
+    }
+
+    /// Return the file header of the object.
+    pub fn header(&self) -> &FileHeader {
+        &self.header
+    }
+
+    /// Return the program headers of the object.
+    pub fn program_headers(&self) -> &[ProgramHeader] {
+        &self.program_headers
+    }
+
+    /// Return the section headers of the object.
+    pub fn section_headers(&self) -> &[SectionHeader] {
+        &self.section_headers
+    }
+
+    /// Return the entries of the `.symtab` section, which is absent from
+    /// stripped objects.
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Return the entries of the `.dynsym` section.
+    pub fn dynamic_symbols(&self) -> &[Symbol] {
+        &self.dynamic_symbols
+    }
+
+    /// Return the [`SymbolIndex`] of the `.symtab` and `.dynsym` entries,
+    /// building it on first use.
+    pub fn symbol_index(&self) -> &SymbolIndex {
+        self.symbol_index
+            .get_or_init(|| SymbolIndex::new(self.symbols.iter().chain(&self.dynamic_symbols)))
+    }
+
+    /// Return the contents of the DWARF sections of the object. Sections which
+    /// are absent or compressed are empty.
+    pub fn dwarf_sections(&self) -> DwarfSections<'_> {
+        let section = |name| {
+            self.section_by_name(name)
+                .filter(|section| section.flags & SHF_COMPRESSED == 0)
+                .and_then(|section| self.section_data(section))
+                .unwrap_or_default()
+        };
+
+        DwarfSections {
+            debug_info: section(".debug_info"),
+            debug_abbrev: section(".debug_abbrev"),
+            debug_str: section(".debug_str"),
+            debug_str_offsets: section(".debug_str_offsets"),
+            debug_line: section(".debug_line"),
+            debug_line_str: section(".debug_line_str"),
+            debug_addr: section(".debug_addr"),
+            debug_ranges: section(".debug_ranges"),
+            debug_rnglists: section(".debug_rnglists"),
+            debug_loclists: section(".debug_loclists"),
+        }
+    }
+
+    /// Return the [`LineTable`] of the `.debug_line` section, building it on
+    /// first use. The table is empty if the object has no (uncompressed) line
+    /// number information.
+    pub fn line_table(&self) -> &LineTable {
+        self.line_table.get_or_init(|| {
+            let sections = self.dwarf_sections();
+
+            LineTable::parse(
+                sections.debug_line,
+                sections.debug_str,
+                sections.debug_line_str,
+            )
+        })
+    }
+
+    /// Return a [`DebugInfo`] reader of the `.debug_info` section, which keeps
+    /// the units and entries it parses for later calls.
+    pub fn debug_info(&self) -> DebugInfo<'_> {
+        DebugInfo::new(self.dwarf_sections(), &self.debug_info)
+    }
+
+    /// Return a [`CallFrameInfo`] reader of the `.eh_frame` section, which is
+    /// empty if the object has none.
+    pub fn call_frame_info(&self) -> CallFrameInfo<'_> {
+        match self.section_by_name(".eh_frame") {
+            Some(section) => {
+                CallFrameInfo::new(self.section_data(section).unwrap_or_default(), section.addr)
+            }
+            None => CallFrameInfo::new(&[], 0),
+        }
+    }
+
+    /// Return the notes of the object.
+    pub fn notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    /// Return the GNU build ID of the object as a hexadecimal string.
+    pub fn build_id(&self) -> Option<String> {
+        self.notes.iter().find_map(Note::build_id)
+    }
+
+    /// Return the load bias of the object, which is added to its addresses to
+    /// give their addresses in memory (see [`FileAddr::to_virt`]).
+    pub fn load_bias(&self) -> u64 {
+        self.load_bias
+    }
+
+    /// Set the load bias of the object, once the address it has been loaded
+    /// at is known.
+    pub fn set_load_bias(&mut self, load_bias: u64) {
+        self.load_bias = load_bias;
+    }
+
+    /// Return the load bias of the object, given the `address` in memory of
+    /// the start of its first loadable segment. Segments are mapped in whole
+    /// pages, so the segment's address is rounded down to a page boundary.
+    pub fn load_bias_from(&self, address: VirtAddr, page_size: u64) -> Option<u64> {
+        let segment = self
+            .program_headers
+            .iter()
+            .find(|segment| segment.segment_type == PT_LOAD)?;
+
+        let start = segment.vaddr.wrapping_sub(segment.offset) & !(page_size - 1);
+
+        Some(address.0.wrapping_sub(start))
+    }
+
+    /// Look up the loadable segment containing `address`.
+    pub fn segment_containing(&self, address: FileAddr) -> Option<&ProgramHeader> {
+        self.program_headers
+            .iter()
+            .find(|segment| segment.segment_type == PT_LOAD && segment.contains(address.0))
+    }
+
+    /// Look up the first section with the given `name` (e.g., `.text`).
+    pub fn section_by_name(&self, name: &str) -> Option<&SectionHeader> {
+        self.section_headers
+            .iter()
+            .find(|section| section.name == name)
+    }
+
+    /// Return the contents of `section`, or `None` if it occupies no space in
+    /// the file or lies outside of it.
+    pub fn section_data(&self, section: &SectionHeader) -> Option<&[u8]> {
+        if section.section_type == SHT_NOBITS {
+            return None;
+        }
+
+        slice(self.data(), section.offset, section.size)
+    }
+}
+
+/// Parse the program header table described by `header`.
+fn parse_program_headers(data: &[u8], header: &FileHeader) -> Result<Vec<ProgramHeader>> {
+    if header.phnum == 0 {
+        return Ok(Vec::new());
+    }
+
+    if usize::from(header.phentsize) < PROGRAM_HEADER_SIZE {
+        return Err(format!("invalid program header size {}", header.phentsize).into());
+    }
+
+    (0..header.phnum)
+        .map(|idx| {
+            let offset = table_entry(header.phoff, idx.into(), header.phentsize)?;
+            ProgramHeader::parse(&mut Reader::new(data, offset))
+        })
+        .collect::<Option<_>>()
+        .ok_or_else(|| "program header table is out of bounds".into())
+}
+
+/// Parse the section header table described by `header`, resolving the name
+/// of each section.
+fn parse_section_headers(data: &[u8], header: &FileHeader) -> Result<Vec<SectionHeader>> {
+    if header.shoff == 0 {
+        return Ok(Vec::new());
+    }
+
+    if usize::from(header.shentsize) < SECTION_HEADER_SIZE {
+        return Err(format!("invalid section header size {}", header.shentsize).into());
+    }
+
+    let out_of_bounds = || "section header table is out of bounds";
+
+    let parse_at = |idx: u64| {
+        let offset = table_entry(header.shoff, idx, header.shentsize)?;
+        SectionHeader::parse(&mut Reader::new(data, offset))
+    };
+
+    // When there are too many sections for the file header, their number (and
+    // the index of the section name string table) is held by the first.
+    let first = parse_at(0).ok_or_else(out_of_bounds)?;
+
+    let count = match header.shnum {
+        0 => first.size,
+        shnum => shnum.into(),
+    };
+
+    let shstrndx = match header.shstrndx {
+        SHN_XINDEX => first.link.into(),
+        shstrndx => u64::from(shstrndx),
+    };
+
+    // Each header is checked to be within the file before the next is parsed,
+    // so a bogus count cannot cause an excessive allocation.
+    let mut sections = Vec::new();
+
+    for idx in 0..count {
+        sections.push(parse_at(idx).ok_or_else(out_of_bounds)?);
+    }
+
+    let shstrtab = usize::try_from(shstrndx)
+        .ok()
+        .and_then(|idx| sections.get(idx))
+        .filter(|section| section.section_type != SHT_NOBITS)
+        .and_then(|section| slice(data, section.offset, section.size))
+        .unwrap_or_default();
+
+    for section in &mut sections {
+        if let Some(name) = c_str(shstrtab, section.name_offset.into()) {
+            section.name = String::from_utf8_lossy(name).into_owned();
+        }
+    }
+
+    Ok(sections)
+}
+
+/// Return the file offset of entry `idx` of a table at `offset` with entries
+/// of `entsize` bytes, or `None` on overflow.
+fn table_entry(offset: u64, idx: u64, entsize: u16) -> Option<usize> {
+    let offset = idx.checked_mul(entsize.into())?.checked_add(offset)?;
+
+    usize::try_from(offset).ok()
+}
+
+/// Backing storage of the bytes of an [`Elf`].
+enum Storage {
+    /// Read-only, private mapping of a file.
+    Mapped(Mapping),
+    /// Bytes held in memory.
+    Owned(Vec<u8>),
+}
+
+impl Storage {
+    /// Return the bytes held by the storage.
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Storage::Mapped(mapping) => mapping.bytes(),
+            Storage::Owned(data) => data,
+        }
+    }
+}
+
+impl fmt::Debug for Storage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Storage::Mapped(mapping) => write!(f, "Mapped({} bytes)", mapping.len),
+            Storage::Owned(data) => write!(f, "Owned({} bytes)", data.len()),
+        }
+    }
+}
+
+/// Read-only, private memory mapping of an entire file, unmapped on drop.
+struct Mapping {
+    /// Start of the mapping.
+    addr: *mut c_void,
+    /// Length of the mapping in bytes.
+    len: usize,
+}
+
+impl Mapping {
+    /// Map the first `len` bytes of `file` into memory.
+    fn new(file: &File, len: u64) -> Result<Self> {
+        let len = usize::try_from(len).map_err(|_| "file is too large to map")?;
+
+        // Maps the file read-only into memory chosen by the kernel. The file
+        // descriptor may be closed once the mapping is created.
+        let addr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                PROT_READ,
+                MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+
+        if addr == MAP_FAILED {
+            return Err(errno!("failed to map file"));
+        }
+
+        Ok(Self { addr, len })
+    }
+
+    /// Return the mapped bytes.
+    fn bytes(&self) -> &[u8] {
+        // The mapping is readable for `len` bytes until it is dropped.
+        unsafe { slice::from_raw_parts(self.addr as *const u8, self.len) }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // Unmapping can only fail for invalid arguments, which would be a bug.
+        unsafe { libc::munmap(self.addr, self.len) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn elf_parse_executable() {
+        let elf = Elf::open("target/debug/breakpoints").unwrap();
+        assert_eq!(elf.path(), Path::new("target/debug/breakpoints"));
+
+        let header = elf.header();
+        assert_eq!(header.file_type, ET_DYN);
+        assert_eq!(header.machine, EM_X86_64);
+        assert_eq!(usize::from(header.phnum), elf.program_headers().len());
+
+        let text = elf.section_by_name(".text").unwrap();
+        assert!(text.flags & SHF_EXECINSTR != 0);
+        assert!(text.contains(header.entry));
+        assert_eq!(elf.section_data(text).unwrap().len() as u64, text.size);
+        assert!(
+            elf.section_data(elf.section_by_name(".bss").unwrap())
+                .is_none()
+        );
+
+        assert!(
+            elf.program_headers()
+                .iter()
+                .any(|segment| segment.segment_type == PT_LOAD && segment.contains(header.entry))
+        );
+
+        let main = elf
+            .symbols()
+            .iter()
+            .find(|symbol| symbol.name == "main")
+            .cloned()
+            .unwrap();
+        assert_eq!(main.kind(), STT_FUNC);
+        assert_eq!(main.binding(), STB_GLOBAL);
+        assert!(text.contains(main.value));
+        assert!(elf.dynamic_symbols().iter().any(Symbol::is_undefined));
+
+        let index = elf.symbol_index();
+        let address = FileAddr(main.value);
+        assert_eq!(index.find_by_name("main")[0].address, address);
+        assert_eq!(index.find_by_name("breakpoints::main").len(), 1);
+
+        let (symbol, offset) = index.find_by_address(FileAddr(main.value + 4)).unwrap();
+        assert_eq!((symbol.name.as_str(), offset), ("main", 4));
+        assert!(elf.segment_containing(address).is_some());
+
+        // Addresses are only translated once the object is loaded.
+        let mut elf = elf;
+        assert_eq!(address.to_virt(&elf), VirtAddr(main.value));
+
+        let load_bias = elf
+            .load_bias_from(VirtAddr(0x5555_5555_4000), 4096)
+            .unwrap();
+        assert_eq!(load_bias, 0x5555_5555_4000);
+        elf.set_load_bias(load_bias);
+
+        let virt = address.to_virt(&elf);
+        assert_eq!(virt, VirtAddr(main.value + load_bias));
+        assert_eq!(virt.to_file(&elf), Some(address));
+        assert_eq!(VirtAddr(main.value).to_file(&elf), None);
+
+        let build_id = elf.build_id().unwrap();
+        assert_eq!(build_id.len(), 40);
+
+        // Parsing from memory yields the same object.
+        let parsed = Elf::parse(fs::read("target/debug/breakpoints").unwrap()).unwrap();
+        assert_eq!(parsed.section_headers(), elf.section_headers());
+        assert_eq!(parsed.symbols(), elf.symbols());
+        assert_eq!(parsed.build_id(), Some(build_id));
+    }
+
+    #[test]
+    fn elf_parse_malformed() {
+        assert!(Elf::parse(Vec::new()).is_err());
+        assert!(Elf::parse(b"\x7fELF".to_vec()).is_err());
+        assert!(Elf::parse(vec![0; FILE_HEADER_SIZE]).is_err());
+        assert!(Elf::open("target/debug/does-not-exist").is_err());
+
+        let data = fs::read("target/debug/breakpoints").unwrap();
+
+        let mut header = data[..FILE_HEADER_SIZE].to_vec();
+        header[4] = 1;
+        assert!(Elf::parse(header).is_err());
+
+        // Truncated files never panic, failing once the header tables are cut.
+        for len in (0..data.len()).step_by(997).chain([FILE_HEADER_SIZE - 1]) {
+            let _ = Elf::parse(data[..len].to_vec());
+        }
+
+        assert!(Elf::parse(data[..FILE_HEADER_SIZE].to_vec()).is_err());
+
+        // Neither do corrupted header fields, or offsets and sizes within the
+        // section headers.
+        let shoff = u64::from_le_bytes(data[0x28..0x30].try_into().unwrap()) as usize;
+
+        for idx in (0x10..FILE_HEADER_SIZE).chain(shoff..shoff + 8 * SECTION_HEADER_SIZE) {
+            for byte in [0x00, 0x7f, 0xff] {
+                let mut corrupted = data.clone();
+                corrupted[idx] = byte;
+                let _ = Elf::parse(corrupted);
+            }
+        }
+    }
+}
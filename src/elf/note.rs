@@ -0,0 +1,73 @@
+//! ELF notes, as found in `PT_NOTE` segments and `SHT_NOTE` sections.
+
+use super::reader::Reader;
+
+/// Note type of the GNU build ID, a unique identifier of the built object.
+pub const NT_GNU_BUILD_ID: u32 = 3;
+
+/// Owner name of notes defined by GNU.
+pub const GNU_NOTE_NAME: &str = "GNU";
+
+/// An ELF note (`Elf64_Nhdr`), along with its name and descriptor.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Note {
+    /// Owner of the note (e.g., `GNU`), without its NUL terminator.
+    pub name: String,
+    /// Type of the note, interpreted according to its owner.
+    pub note_type: u32,
+    /// Contents of the note.
+    pub desc: Vec<u8>,
+}
+
+impl Note {
+    /// Parse every note of `data`, where each name and descriptor is padded to
+    /// `align` bytes (four or eight). Parsing stops at the first truncated
+    /// note.
+    pub(crate) fn parse_all(data: &[u8], align: u64) -> Vec<Self> {
+        let align = if align == 8 { 8 } else { 4 };
+        let mut reader = Reader::new(data, 0);
+        let mut notes = Vec::new();
+
+        while let Some(note) = Self::parse(&mut reader, align) {
+            notes.push(note);
+        }
+
+        notes
+    }
+
+    /// Parse the note starting at the current offset of `reader`.
+    fn parse(reader: &mut Reader<'_>, align: usize) -> Option<Self> {
+        let namesz = usize::try_from(reader.u32()?).ok()?;
+        let descsz = usize::try_from(reader.u32()?).ok()?;
+        let note_type = reader.u32()?;
+
+        let name = reader.bytes(namesz)?;
+        reader.bytes(padding(reader.offset(), align))?;
+
+        let desc = reader.bytes(descsz)?;
+        // The final note of a section need not be padded.
+        let _ = reader.bytes(padding(reader.offset(), align));
+
+        let name = name.strip_suffix(b"\0").unwrap_or(name);
+
+        Some(Self {
+            name: String::from_utf8_lossy(name).into_owned(),
+            note_type,
+            desc: desc.to_vec(),
+        })
+    }
+
+    /// Return the GNU build ID as a hexadecimal string, if the note holds one.
+    pub fn build_id(&self) -> Option<String> {
+        if self.name != GNU_NOTE_NAME || self.note_type != NT_GNU_BUILD_ID {
+            return None;
+        }
+
+        Some(self.desc.iter().map(|byte| format!("{byte:02x}")).collect())
+    }
+}
+
+/// Return the number of bytes needed to pad `offset` to a multiple of `align`.
+fn padding(offset: usize, align: usize) -> usize {
+    offset.next_multiple_of(align) - offset
+}
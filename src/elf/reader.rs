@@ -0,0 +1,141 @@
+//! Bounds-checked access to the raw bytes of an ELF file, including its DWARF
+//! sections.
+
+/// Cursor over a byte slice, reading little-endian integers. Every read is
+/// bounds-checked, returning `None` once the end of the slice is reached.
+#[derive(Debug, Clone)]
+pub(crate) struct Reader<'a> {
+    /// Bytes being read.
+    data: &'a [u8],
+    /// Offset of the next byte to read.
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Creates a new [`Reader`] over `data`, starting at `offset`.
+    pub(crate) fn new(data: &'a [u8], offset: usize) -> Self {
+        Self { data, offset }
+    }
+
+    /// Return the offset of the next byte to read.
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Read the next `len` bytes.
+    pub(crate) fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+
+        Some(bytes)
+    }
+
+    /// Read the next `N` bytes as an array.
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.bytes(N)?.try_into().ok()
+    }
+
+    /// Read the next byte.
+    pub(crate) fn u8(&mut self) -> Option<u8> {
+        self.array::<1>().map(|[byte]| byte)
+    }
+
+    /// Read the next little-endian `u16`.
+    pub(crate) fn u16(&mut self) -> Option<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    /// Read the next little-endian `u32`.
+    pub(crate) fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    /// Read the next little-endian `u64`.
+    pub(crate) fn u64(&mut self) -> Option<u64> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    /// Read the next little-endian integer of `size` bytes (at most 8).
+    pub(crate) fn uint(&mut self, size: usize) -> Option<u64> {
+        let mut bytes = [0; 8];
+        bytes.get_mut(..size)?.copy_from_slice(self.bytes(size)?);
+
+        Some(u64::from_le_bytes(bytes))
+    }
+
+    /// Read the next unsigned LEB128-encoded integer, ignoring any bits which
+    /// do not fit in a `u64`.
+    pub(crate) fn uleb128(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.u8()?;
+
+            if shift < 64 {
+                value |= u64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+    }
+
+    /// Read the next signed LEB128-encoded integer, ignoring any bits which do
+    /// not fit in an `i64`.
+    pub(crate) fn sleb128(&mut self) -> Option<i64> {
+        let mut value = 0i64;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.u8()?;
+
+            if shift < 64 {
+                value |= i64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                // Sign-extend from the last bit read.
+                if shift < 64 && byte & 0x40 != 0 {
+                    value |= -1 << shift;
+                }
+
+                return Some(value);
+            }
+        }
+    }
+
+    /// Read the next NUL-terminated string, excluding its terminator.
+    pub(crate) fn c_str(&mut self) -> Option<&'a [u8]> {
+        let string = c_str(self.data, self.offset as u64)?;
+        self.offset += string.len() + 1;
+
+        Some(string)
+    }
+
+    /// Return `true` if every byte has been read.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+}
+
+/// Return the `len` bytes of `data` at `offset`, or `None` if the range is out
+/// of bounds.
+pub(crate) fn slice(data: &[u8], offset: u64, len: u64) -> Option<&[u8]> {
+    let start = usize::try_from(offset).ok()?;
+    let end = start.checked_add(usize::try_from(len).ok()?)?;
+
+    data.get(start..end)
+}
+
+/// Return the NUL-terminated string of `table` at `offset`, or `None` if the
+/// offset is out of bounds or the string is unterminated.
+pub(crate) fn c_str(table: &[u8], offset: u64) -> Option<&[u8]> {
+    let bytes = table.get(usize::try_from(offset).ok()?..)?;
+    let len = bytes.iter().position(|&byte| byte == 0)?;
+
+    Some(&bytes[..len])
+}
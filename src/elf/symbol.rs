@@ -0,0 +1,98 @@
+//! ELF64 symbol table entries.
+
+use super::header::SHN_UNDEF;
+use super::reader::{Reader, c_str};
+
+/// Size in bytes of an ELF64 symbol table entry.
+pub const SYMBOL_SIZE: usize = 24;
+
+/// Symbol of unspecified type.
+pub const STT_NOTYPE: u8 = 0;
+/// Data object (e.g., a variable or array).
+pub const STT_OBJECT: u8 = 1;
+/// Function or other executable code.
+pub const STT_FUNC: u8 = 2;
+/// Section, used for relocation.
+pub const STT_SECTION: u8 = 3;
+/// Source file of the object.
+pub const STT_FILE: u8 = 4;
+/// Thread-local storage entity.
+pub const STT_TLS: u8 = 6;
+/// Indirect function, resolved at load time (e.g., `memcpy` in glibc).
+pub const STT_GNU_IFUNC: u8 = 10;
+
+/// Symbol local to the object file.
+pub const STB_LOCAL: u8 = 0;
+/// Symbol visible to all object files being combined.
+pub const STB_GLOBAL: u8 = 1;
+/// Global symbol of lower precedence.
+pub const STB_WEAK: u8 = 2;
+
+/// An ELF64 symbol table entry (`Elf64_Sym`), along with its resolved name.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Symbol {
+    /// Name of the symbol, or empty if it could not be resolved. Invalid UTF-8
+    /// is replaced.
+    pub name: String,
+    /// Offset of the name within the associated string table.
+    pub name_offset: u32,
+    /// Type and binding of the symbol (see [`Symbol::kind`] and
+    /// [`Symbol::binding`]).
+    pub info: u8,
+    /// Visibility of the symbol.
+    pub other: u8,
+    /// Index of the section the symbol is defined in, or [`SHN_UNDEF`].
+    pub shndx: u16,
+    /// Value of the symbol, usually its virtual address.
+    pub value: u64,
+    /// Size of the object the symbol refers to, or zero if unknown.
+    pub size: u64,
+}
+
+impl Symbol {
+    /// Parse the symbol starting at the current offset of `reader`, resolving
+    /// its name within `strtab`.
+    pub(crate) fn parse(reader: &mut Reader<'_>, strtab: &[u8]) -> Option<Self> {
+        let name_offset = reader.u32()?;
+        let info = reader.u8()?;
+        let other = reader.u8()?;
+        let shndx = reader.u16()?;
+        let value = reader.u64()?;
+        let size = reader.u64()?;
+
+        let name = c_str(strtab, name_offset.into())
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .unwrap_or_default();
+
+        Some(Self {
+            name,
+            name_offset,
+            info,
+            other,
+            shndx,
+            value,
+            size,
+        })
+    }
+
+    /// Return the type of the symbol (e.g., [`STT_FUNC`]).
+    pub fn kind(&self) -> u8 {
+        self.info & 0xf
+    }
+
+    /// Return the binding of the symbol (e.g., [`STB_GLOBAL`]).
+    pub fn binding(&self) -> u8 {
+        self.info >> 4
+    }
+
+    /// Return `true` if the symbol is referenced, but not defined, by the
+    /// object.
+    pub fn is_undefined(&self) -> bool {
+        self.shndx == SHN_UNDEF
+    }
+
+    /// Return `true` if the object the symbol refers to contains `address`.
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.value && address - self.value < self.size
+    }
+}
@@ -4,6 +4,9 @@
 #![warn(rust_2018_idioms)]
 
 pub mod core;
+pub mod disasm;
+pub mod dwarf;
+pub mod elf;
 pub mod utils;
 
 mod config;